@@ -11,6 +11,10 @@ pushd macros/component-definition-derive
 cargo clippy --all-targets -- -D warnings
 popd
 
+pushd macros/fsm-derive
+cargo clippy --all-targets -- -D warnings
+popd
+
 pushd feature-tests/protobuf-test
 cargo clippy --all-targets -- -D warnings
 popd
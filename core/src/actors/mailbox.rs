@@ -0,0 +1,861 @@
+use super::*;
+
+use crate::{
+    component::CoreContainer,
+    config::{ConfigError, HoconExt},
+    runtime::keys,
+};
+use hocon::Hocon;
+use std::{
+    error::Error,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// What to do with a message that is sent to a full mailbox
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowStrategy {
+    /// Drop the new message
+    DropNew,
+    /// Drop the oldest message in the mailbox to make room for the new one
+    DropOldest,
+    /// Redirect the new message to the system's deadletter box
+    ///
+    /// Network messages are forwarded to the deadletter box,
+    /// while local messages are logged in the same manner, since the deadletter box can't accept them.
+    Deadletters,
+    /// Reject the new message, leaving it to the sender to handle full mailboxes
+    ///
+    /// Senders are expected to use [try_tell](ActorRef::try_tell), which hands back the message.
+    /// Messages sent with `tell` are dropped with a warning,
+    /// and network messages are forwarded to the deadletter box, as the sender is remote.
+    Fail,
+}
+
+impl Default for OverflowStrategy {
+    fn default() -> Self {
+        OverflowStrategy::DropNew
+    }
+}
+
+impl OverflowStrategy {
+    /// All legal values for the [MAILBOX_OVERFLOW](crate::config_keys::system::MAILBOX_OVERFLOW) key
+    pub const CONFIG_VALUES: [&'static str; 4] = ["drop-new", "drop-oldest", "deadletters", "fail"];
+}
+
+impl FromStr for OverflowStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop-new" => Ok(OverflowStrategy::DropNew),
+            "drop-oldest" => Ok(OverflowStrategy::DropOldest),
+            "deadletters" => Ok(OverflowStrategy::Deadletters),
+            "fail" => Ok(OverflowStrategy::Fail),
+            _ => Err(format!("Illegal overflow strategy: {}", s)),
+        }
+    }
+}
+
+/// The urgency of a message in a [prioritised](MailboxConfig::prioritised) mailbox
+///
+/// Messages of higher priority are handled before any messages of lower priority,
+/// while messages of the same priority are handled in the order they were sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Bulk work, that is only handled when nothing else is waiting
+    Low,
+    /// The priority of all messages, unless specified otherwise
+    Normal,
+    /// Urgent messages, like cancellations or control commands
+    High,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+impl Priority {
+    const LEVELS: usize = 3;
+
+    fn lane(self) -> usize {
+        match self {
+            Priority::Low => 0,
+            Priority::Normal => 1,
+            Priority::High => 2,
+        }
+    }
+}
+
+/// The configuration of a component's mailbox
+///
+/// By default mailboxes are unbounded. A bounded mailbox holds at most `capacity` messages,
+/// and handles any further messages according to its [OverflowStrategy],
+/// until the component has caught up.
+///
+/// The system-wide default is configured via
+/// [MAILBOX_CAPACITY](crate::config_keys::system::MAILBOX_CAPACITY) and
+/// [MAILBOX_OVERFLOW](crate::config_keys::system::MAILBOX_OVERFLOW),
+/// and individual components can override it with
+/// [create_with_mailbox](crate::prelude::KompactSystem::create_with_mailbox).
+///
+/// The same limits apply to each of the component's port queues separately,
+/// with events that are dropped instead of redirected to deadletters or rejected,
+/// as ports have neither.
+///
+/// Mailboxes are FIFO by default. A [prioritised](MailboxConfig::prioritised) mailbox
+/// instead handles messages in the order of their [Priority], as assigned by
+/// [Actor::priority] or [tell_with_priority](ActorRef::tell_with_priority).
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+///
+/// #[derive(ComponentDefinition)]
+/// struct Slow {
+///     ctx: ComponentContext<Self>,
+/// }
+/// ignore_lifecycle!(Slow);
+/// impl Actor for Slow {
+///     type Message = ();
+///
+///     fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+///         Handled::Ok
+///     }
+/// #    fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+/// #        unimplemented!("We don't care about this.");
+/// #    }
+/// }
+///
+/// let system = KompactConfig::default().build().expect("system");
+/// let slow = system.create_with_mailbox(
+///     || Slow { ctx: ComponentContext::uninitialised() },
+///     MailboxConfig::bounded(2, OverflowStrategy::DropNew),
+/// );
+/// let slow_ref = slow.actor_ref();
+/// // the component isn't started, so nothing is taken out of the mailbox
+/// assert!(slow_ref.try_tell(()).is_ok());
+/// assert!(slow_ref.try_tell(()).is_ok());
+/// assert!(matches!(slow_ref.try_tell(()), Err(TryTellError::MailboxFull(()))));
+/// # system.shutdown().expect("shutdown");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MailboxConfig {
+    capacity: Option<usize>,
+    overflow: OverflowStrategy,
+    prioritised: bool,
+}
+
+impl MailboxConfig {
+    /// A mailbox without a capacity limit
+    pub fn unbounded() -> Self {
+        MailboxConfig::default()
+    }
+
+    /// A mailbox that holds at most `capacity` messages
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn bounded(capacity: usize, overflow: OverflowStrategy) -> Self {
+        assert!(
+            capacity > 0,
+            "Mailbox capacity must be at least one message"
+        );
+        MailboxConfig {
+            capacity: Some(capacity),
+            overflow,
+            prioritised: false,
+        }
+    }
+
+    /// Handle messages in the order of their [Priority], instead of FIFO
+    ///
+    /// In a bounded mailbox with [DropOldest](OverflowStrategy::DropOldest),
+    /// the oldest message of the lowest priority is dropped,
+    /// unless the new message has a lower priority than everything in the mailbox.
+    pub fn prioritised(mut self) -> Self {
+        self.prioritised = true;
+        self
+    }
+
+    /// The maximum number of messages in the mailbox, or `None` if it is unbounded
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// What happens to messages sent to the full mailbox
+    pub fn overflow_strategy(&self) -> OverflowStrategy {
+        self.overflow
+    }
+
+    /// Whether messages are handled in the order of their [Priority]
+    pub fn is_prioritised(&self) -> bool {
+        self.prioritised
+    }
+
+    pub(crate) fn from_config(conf: &Hocon) -> Result<Self, ConfigError> {
+        let capacity = conf.get_or_default(&keys::MAILBOX_CAPACITY)?;
+        let overflow_option = conf.get_or_default(&keys::MAILBOX_OVERFLOW)?;
+        let overflow = overflow_option
+            .parse()
+            .unwrap_or_else(|e| unreachable!("Options should be checked by the validator! {}", e));
+        if capacity == 0 {
+            Ok(MailboxConfig {
+                capacity: None,
+                overflow,
+                prioritised: false,
+            })
+        } else {
+            Ok(MailboxConfig::bounded(capacity, overflow))
+        }
+    }
+}
+
+/// The error returned by [try_tell](ActorRef::try_tell), holding the message that could not be sent
+#[derive(Debug, PartialEq, Eq)]
+pub enum TryTellError<M> {
+    /// The target's mailbox is at capacity
+    MailboxFull(M),
+    /// The target component has been deallocated
+    Unavailable(M),
+}
+
+impl<M> TryTellError<M> {
+    /// Take back the message that could not be sent
+    pub fn into_inner(self) -> M {
+        match self {
+            TryTellError::MailboxFull(msg) | TryTellError::Unavailable(msg) => msg,
+        }
+    }
+}
+
+impl<M> fmt::Display for TryTellError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryTellError::MailboxFull(_) => write!(f, "The target's mailbox is full"),
+            TryTellError::Unavailable(_) => write!(f, "The target component is unavailable"),
+        }
+    }
+}
+
+impl<M: fmt::Debug> Error for TryTellError<M> {}
+
+/// The message queue of a component, i.e. its mailbox
+#[derive(Debug)]
+pub struct TypedMsgQueue<M: MessageBounds> {
+    // a single lane in a FIFO queue, or one lane per priority level, from low to high
+    lanes: Box<[ConcurrentQueue<MsgEnvelope<M>>]>,
+    // stashed messages that are re-delivered ahead of all lanes
+    unstashed: ConcurrentQueue<MsgEnvelope<M>>,
+    priority: fn(&M) -> Priority,
+    config: MailboxConfig,
+    // slots taken in a bounded queue, including messages that are about to be pushed
+    reserved: AtomicUsize,
+}
+impl<M: MessageBounds> TypedMsgQueue<M> {
+    pub(crate) fn new() -> TypedMsgQueue<M> {
+        TypedMsgQueue::with_config(MailboxConfig::unbounded(), |_| Priority::Normal)
+    }
+
+    pub(crate) fn with_config(
+        config: MailboxConfig,
+        priority: fn(&M) -> Priority,
+    ) -> TypedMsgQueue<M> {
+        let num_lanes = if config.prioritised {
+            Priority::LEVELS
+        } else {
+            1
+        };
+        TypedMsgQueue {
+            lanes: (0..num_lanes).map(|_| ConcurrentQueue::new()).collect(),
+            unstashed: ConcurrentQueue::new(),
+            priority,
+            config,
+            reserved: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn pop(&self) -> Option<MsgEnvelope<M>> {
+        if let Ok(env) = self.unstashed.pop() {
+            // unstashed messages were already admitted once, so they hold no slot
+            return Some(env);
+        }
+        let res = self.lanes.iter().rev().find_map(|lane| lane.pop().ok());
+        if res.is_some() && self.config.capacity.is_some() {
+            self.reserved.fetch_sub(1, Ordering::AcqRel);
+        }
+        res
+    }
+
+    #[allow(unused)]
+    pub(crate) fn into_dyn(q: Arc<Self>) -> Arc<dyn DynMsgQueue> {
+        q as Arc<dyn DynMsgQueue>
+    }
+
+    /// Try to take a slot for a new message, which must be filled by pushing to a lane
+    fn try_reserve(&self) -> bool {
+        match self.config.capacity {
+            Some(capacity) => self
+                .reserved
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |taken| {
+                    if taken < capacity {
+                        Some(taken + 1)
+                    } else {
+                        None
+                    }
+                })
+                .is_ok(),
+            None => true,
+        }
+    }
+
+    /// The lane `value` belongs in, where an explicit `priority` overrides the queue's function
+    fn lane_for(&self, value: &MsgEnvelope<M>, priority: Option<Priority>) -> usize {
+        if self.lanes.len() == 1 {
+            return 0;
+        }
+        let priority = priority.unwrap_or_else(|| match value {
+            MsgEnvelope::Typed(msg) => (self.priority)(msg),
+            MsgEnvelope::Net(_) => Priority::Normal,
+        });
+        priority.lane()
+    }
+
+    /// Replace the oldest message of the lowest priority with `value`, keeping its slot
+    ///
+    /// Only messages with at most the priority of `lane` are replaced. If all queued messages
+    /// are more urgent, `value` itself is dropped and returned as the replaced message.
+    /// Returns `value` as [Vacant](Replacement::Vacant), if the queue was emptied concurrently.
+    fn replace_oldest(&self, lane: usize, value: MsgEnvelope<M>) -> Replacement<M> {
+        if let Some(oldest) = self.lanes[..=lane].iter().find_map(|l| l.pop().ok()) {
+            self.lanes[lane].push(value);
+            Replacement::Dropped(oldest)
+        } else if self.lanes[lane + 1..].iter().any(|l| !l.is_empty()) {
+            Replacement::Dropped(value)
+        } else {
+            Replacement::Vacant(value)
+        }
+    }
+
+    /// Add `value` to the queue of `component` and schedule it, if necessary
+    ///
+    /// If the queue is full, `value` is returned when `reject_when_full` is set,
+    /// and otherwise handled according to the queue's [OverflowStrategy].
+    pub(crate) fn enqueue<C>(
+        &self,
+        component: &C,
+        value: MsgEnvelope<M>,
+        reject_when_full: bool,
+    ) -> Result<(), Box<MsgEnvelope<M>>>
+    where
+        C: CoreContainer + ?Sized,
+    {
+        self.enqueue_with_priority(component, value, None, reject_when_full)
+    }
+
+    /// Like [enqueue](TypedMsgQueue::enqueue), but with an explicit `priority` for `value`
+    ///
+    /// If `priority` is `None`, the priority function of the queue is used instead.
+    pub(crate) fn enqueue_with_priority<C>(
+        &self,
+        component: &C,
+        mut value: MsgEnvelope<M>,
+        priority: Option<Priority>,
+        reject_when_full: bool,
+    ) -> Result<(), Box<MsgEnvelope<M>>>
+    where
+        C: CoreContainer + ?Sized,
+    {
+        let lane = self.lane_for(&value, priority);
+        loop {
+            if self.try_reserve() {
+                // must count the work before pushing, to maintain counting guarantees
+                let sd = component.core().increment_work();
+                self.lanes[lane].push(value);
+                if let SchedulingDecision::Schedule = sd {
+                    component.schedule();
+                }
+                return Ok(());
+            } else if reject_when_full {
+                return Err(Box::new(value));
+            }
+            match self.config.overflow {
+                OverflowStrategy::DropNew => {
+                    Self::log_dropped(component, &value);
+                    return Ok(());
+                }
+                OverflowStrategy::DropOldest => match self.replace_oldest(lane, value) {
+                    Replacement::Dropped(oldest) => {
+                        // the new message took the place of the oldest, so the work is unchanged
+                        Self::log_dropped(component, &oldest);
+                        return Ok(());
+                    }
+                    Replacement::Vacant(v) => value = v, // there is room again
+                },
+                OverflowStrategy::Deadletters => {
+                    Self::redirect_to_deadletters(component, value);
+                    return Ok(());
+                }
+                OverflowStrategy::Fail => {
+                    // never panic on the delivering side, which may well be the network
+                    Self::reject(component, value);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Re-deliver a message that `component` had stashed, ahead of all other messages
+    ///
+    /// As the message was already admitted to the mailbox once, it is not subject to the capacity.
+    pub(crate) fn unstash<C>(&self, component: &C, value: MsgEnvelope<M>)
+    where
+        C: CoreContainer + ?Sized,
+    {
+        // must count the work before pushing, to maintain counting guarantees
+        let sd = component.core().increment_work();
+        self.unstashed.push(value);
+        if let SchedulingDecision::Schedule = sd {
+            component.schedule();
+        }
+    }
+
+    fn log_dropped<C: CoreContainer + ?Sized>(component: &C, value: &MsgEnvelope<M>) {
+        debug!(
+            component.system().logger(),
+            "Dropping message, as the mailbox of component {} is full: {:?}",
+            component.id(),
+            value
+        );
+    }
+
+    fn reject<C: CoreContainer + ?Sized>(component: &C, value: MsgEnvelope<M>) {
+        match value {
+            MsgEnvelope::Net(_) => Self::redirect_to_deadletters(component, value),
+            MsgEnvelope::Typed(msg) => warn!(
+                component.system().logger(),
+                "Rejecting message, as the mailbox of component {} is full. Use try_tell to handle this: {:?}",
+                component.id(),
+                msg
+            ),
+        }
+    }
+
+    fn redirect_to_deadletters<C: CoreContainer + ?Sized>(component: &C, value: MsgEnvelope<M>) {
+        match value {
+            MsgEnvelope::Net(msg) => component.system().actor_ref().dyn_ref().tell(msg),
+            MsgEnvelope::Typed(msg) => info!(
+                component.system().logger(),
+                "Deadletter, as the mailbox of component {} is full: {:?}",
+                component.id(),
+                msg
+            ),
+        }
+    }
+
+    /// Returns the number of messages currently in the queue
+    ///
+    /// As the queue is concurrently modified, the result is only a snapshot.
+    pub fn len(&self) -> usize {
+        self.unstashed.len() + self.lanes.iter().map(ConcurrentQueue::len).sum::<usize>()
+    }
+
+    /// Returns `true` if there are currently no messages in the queue
+    pub fn is_empty(&self) -> bool {
+        self.unstashed.is_empty() && self.lanes.iter().all(ConcurrentQueue::is_empty)
+    }
+
+    /// Returns the configuration of this mailbox
+    pub fn config(&self) -> MailboxConfig {
+        self.config
+    }
+
+    pub(crate) fn create_adapter<In: 'static>(
+        component: Weak<dyn MsgQueueContainer<Message = M>>,
+        convert: fn(In) -> M,
+    ) -> Box<dyn AdaptedQueueContainer<In>> {
+        let converting_container = Box::new(ConvertingMsgQueueContainer::from(component, convert));
+        converting_container as Box<dyn AdaptedQueueContainer<In>>
+    }
+}
+impl<M: MessageBounds> DynMsgQueue for TypedMsgQueue<M> {
+    fn push_net(&self, value: NetMessage) {
+        if self.config.capacity.is_some() {
+            // take a slot past the capacity, so that popping the message frees it again
+            self.reserved.fetch_add(1, Ordering::AcqRel);
+        }
+        let env = MsgEnvelope::Net(value);
+        let lane = self.lane_for(&env, None);
+        self.lanes[lane].push(env);
+    }
+
+    fn enqueue_net(&self, component: &dyn CoreContainer, value: NetMessage) {
+        // network messages never reject, so the error case can't happen
+        let _ = self.enqueue(component, MsgEnvelope::Net(value), false);
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(TypedMsgQueue::len(self))
+    }
+
+    fn config(&self) -> MailboxConfig {
+        TypedMsgQueue::config(self)
+    }
+}
+
+/// The outcome of [replace_oldest](TypedMsgQueue::replace_oldest)
+enum Replacement<M: MessageBounds> {
+    /// The message that was dropped to make room, which may be the new message itself
+    Dropped(MsgEnvelope<M>),
+    /// The queue had room again, so the new message was not enqueued
+    Vacant(MsgEnvelope<M>),
+}
+
+/// A message queue handle that only deals with
+/// net messages.
+pub trait DynMsgQueue: fmt::Debug + Sync + Send {
+    /// Add a network message to the queue, without counting or scheduling any work
+    ///
+    /// This bypasses the capacity of bounded queues,
+    /// so prefer [enqueue_net](DynMsgQueue::enqueue_net) where possible.
+    fn push_net(&self, value: NetMessage);
+    /// Add a network message to the queue of `component` and schedule it, if necessary
+    ///
+    /// The default implementation [pushes](DynMsgQueue::push_net) the message unconditionally.
+    fn enqueue_net(&self, component: &dyn CoreContainer, value: NetMessage) {
+        // must count the work before pushing, to maintain counting guarantees
+        let sd = component.core().increment_work();
+        self.push_net(value);
+        if let SchedulingDecision::Schedule = sd {
+            component.schedule();
+        }
+    }
+    /// Returns the number of messages currently in the queue, if the queue keeps track of it
+    ///
+    /// The default implementation returns `None`.
+    fn len(&self) -> Option<usize> {
+        None
+    }
+    /// Returns the configuration of the queue, which also bounds the owner's port queues
+    ///
+    /// The default implementation returns an [unbounded](MailboxConfig::unbounded) configuration.
+    fn config(&self) -> MailboxConfig {
+        MailboxConfig::unbounded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+    const CAPACITY: usize = 3;
+
+    #[derive(Debug)]
+    enum CollectorMsg {
+        Num(u64),
+        Get(Ask<(), Vec<u64>>),
+    }
+    impl From<u64> for CollectorMsg {
+        fn from(num: u64) -> Self {
+            CollectorMsg::Num(num)
+        }
+    }
+
+    struct NumPort;
+    impl Port for NumPort {
+        type Indication = ();
+        type Request = u64;
+    }
+
+    #[derive(ComponentDefinition)]
+    struct Collector {
+        ctx: ComponentContext<Self>,
+        num_port: ProvidedPort<NumPort>,
+        received: Vec<u64>,
+    }
+    impl Collector {
+        fn new() -> Self {
+            Collector {
+                ctx: ComponentContext::uninitialised(),
+                num_port: ProvidedPort::uninitialised(),
+                received: Vec::new(),
+            }
+        }
+    }
+    ignore_lifecycle!(Collector);
+    impl Provide<NumPort> for Collector {
+        fn handle(&mut self, num: u64) -> Handled {
+            self.received.push(num);
+            Handled::Ok
+        }
+    }
+    impl Actor for Collector {
+        type Message = CollectorMsg;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            match msg {
+                CollectorMsg::Num(num) => self.received.push(num),
+                CollectorMsg::Get(ask) => ask.reply(self.received.clone()).expect("reply"),
+            }
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+            unimplemented!("Only used locally");
+        }
+
+        fn priority(msg: &Self::Message) -> Priority {
+            match msg {
+                CollectorMsg::Num(num) if *num >= 100 => Priority::High,
+                CollectorMsg::Num(_) => Priority::Normal,
+                CollectorMsg::Get(_) => Priority::Low,
+            }
+        }
+    }
+
+    fn start_and_collect(
+        system: &KompactSystem,
+        collector: &Arc<Component<Collector>>,
+    ) -> Vec<u64> {
+        system
+            .start_notify(collector)
+            .wait_timeout(TIMEOUT)
+            .expect("collector never started");
+        collector
+            .actor_ref()
+            .ask_with(|promise| CollectorMsg::Get(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("received")
+    }
+
+    /// Fill a stopped collector with twice its capacity, then start it and return what it received
+    fn overflow_with(strategy: OverflowStrategy) -> Vec<u64> {
+        let system = KompactConfig::default().build().expect("system");
+        let collector =
+            system.create_with_mailbox(Collector::new, MailboxConfig::bounded(CAPACITY, strategy));
+        let collector_ref = collector.actor_ref();
+        for i in 0..(2 * CAPACITY as u64) {
+            collector_ref.tell(i);
+        }
+        assert_eq!(collector_ref.mailbox_len(), Some(CAPACITY));
+        system
+            .start_notify(&collector)
+            .wait_timeout(TIMEOUT)
+            .expect("collector never started");
+        // messages are accepted again after the mailbox was drained
+        collector_ref.tell(100u64);
+        let received = collector_ref
+            .ask_with(|promise| CollectorMsg::Get(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("received");
+        system.shutdown().expect("shutdown");
+        received
+    }
+
+    #[test]
+    fn bounded_mailbox_drops_new_messages() {
+        assert_eq!(overflow_with(OverflowStrategy::DropNew), vec![0, 1, 2, 100]);
+    }
+
+    #[test]
+    fn bounded_mailbox_drops_oldest_messages() {
+        assert_eq!(
+            overflow_with(OverflowStrategy::DropOldest),
+            vec![3, 4, 5, 100]
+        );
+    }
+
+    #[test]
+    fn bounded_mailbox_redirects_to_deadletters() {
+        assert_eq!(
+            overflow_with(OverflowStrategy::Deadletters),
+            vec![0, 1, 2, 100]
+        );
+    }
+
+    #[test]
+    fn bounded_mailbox_rejects_tell_without_panicking() {
+        assert_eq!(overflow_with(OverflowStrategy::Fail), vec![0, 1, 2, 100]);
+    }
+
+    #[test]
+    fn try_tell_rejects_when_full() {
+        let system = KompactConfig::default().build().expect("system");
+        let collector = system.create_with_mailbox(
+            Collector::new,
+            MailboxConfig::bounded(CAPACITY, OverflowStrategy::Fail),
+        );
+        let strong_ref = collector.actor_ref().hold().expect("live ref");
+        for i in 0..(CAPACITY as u64) {
+            strong_ref.try_tell(i).expect("room in the mailbox");
+        }
+        match collector.actor_ref().try_tell(42u64) {
+            Err(TryTellError::MailboxFull(CollectorMsg::Num(42))) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        assert!(matches!(
+            strong_ref.try_tell(43u64),
+            Err(TryTellError::MailboxFull(_))
+        ));
+        // network messages to the full mailbox go to the deadletter box, instead of panicking
+        let net_msg = NetMessage::with_box(
+            <u64 as Deserialiser<u64>>::SER_ID,
+            system.deadletter_path(),
+            system.deadletter_path(),
+            Box::new(43u64),
+        );
+        collector.actor_ref().dyn_ref().enqueue(net_msg);
+        assert_eq!(collector.actor_ref().mailbox_len(), Some(CAPACITY));
+        system
+            .start_notify(&collector)
+            .wait_timeout(TIMEOUT)
+            .expect("collector never started");
+        let received = collector
+            .actor_ref()
+            .ask_with(|promise| CollectorMsg::Get(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("received");
+        assert_eq!(received, vec![0, 1, 2]);
+
+        let collector_ref = collector.actor_ref();
+        drop(strong_ref);
+        system
+            .kill_notify(collector)
+            .wait_timeout(TIMEOUT)
+            .expect("collector never died");
+        let res = collector_ref.try_tell(44u64);
+        assert!(matches!(res, Err(TryTellError::Unavailable(_))));
+        system.shutdown().expect("shutdown");
+    }
+
+    /// Fill the port of a stopped collector with twice its capacity, then start it and return what it received
+    fn port_overflow_with(strategy: OverflowStrategy) -> Vec<u64> {
+        let system = KompactConfig::default().build().expect("system");
+        let collector =
+            system.create_with_mailbox(Collector::new, MailboxConfig::bounded(CAPACITY, strategy));
+        let port = collector.on_definition(|c| c.num_port.share());
+        for i in 0..(2 * CAPACITY as u64) {
+            system.trigger_r(i, &port);
+        }
+        let received = start_and_collect(&system, &collector);
+        system.shutdown().expect("shutdown");
+        received
+    }
+
+    #[test]
+    fn bounded_port_queues_overflow_like_the_mailbox() {
+        assert_eq!(
+            port_overflow_with(OverflowStrategy::DropNew),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            port_overflow_with(OverflowStrategy::DropOldest),
+            vec![3, 4, 5]
+        );
+        assert_eq!(port_overflow_with(OverflowStrategy::Fail), vec![0, 1, 2]);
+    }
+
+    /// Send a mix of priorities to a stopped collector, then start it and return what it received
+    fn mixed_priorities_with(mailbox: MailboxConfig) -> Vec<u64> {
+        let system = KompactConfig::default().build().expect("system");
+        let collector = system.create_with_mailbox(Collector::new, mailbox);
+        let collector_ref = collector.actor_ref();
+        collector_ref.tell(1u64);
+        collector_ref.tell(100u64);
+        collector_ref.tell(2u64);
+        collector_ref.tell_with_priority(3u64, Priority::Low);
+        let strong_ref = collector_ref.hold().expect("live ref");
+        strong_ref.tell_with_priority(4u64, Priority::High);
+        strong_ref.tell(101u64);
+        assert_eq!(collector_ref.mailbox_len(), Some(6));
+        let received = start_and_collect(&system, &collector);
+        system.shutdown().expect("shutdown");
+        received
+    }
+
+    #[test]
+    fn prioritised_mailbox_handles_urgent_messages_first() {
+        assert_eq!(
+            mixed_priorities_with(MailboxConfig::unbounded().prioritised()),
+            vec![100, 4, 101, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn fifo_mailbox_ignores_priorities() {
+        assert_eq!(
+            mixed_priorities_with(MailboxConfig::unbounded()),
+            vec![1, 100, 2, 3, 4, 101]
+        );
+    }
+
+    #[test]
+    fn prioritised_mailbox_drops_least_urgent_messages() {
+        let system = KompactConfig::default().build().expect("system");
+        let mailbox = MailboxConfig::bounded(CAPACITY, OverflowStrategy::DropOldest).prioritised();
+        assert!(mailbox.is_prioritised());
+        let collector = system.create_with_mailbox(Collector::new, mailbox);
+        let collector_ref = collector.actor_ref();
+        collector_ref.tell(1u64);
+        collector_ref.tell_with_priority(2u64, Priority::Low);
+        collector_ref.tell(100u64);
+        // replaces 2, as the oldest message of the lowest priority
+        collector_ref.tell(3u64);
+        // everything in the mailbox is more urgent, so this is dropped instead
+        collector_ref.tell_with_priority(4u64, Priority::Low);
+        assert_eq!(collector_ref.mailbox_len(), Some(CAPACITY));
+        let received = start_and_collect(&system, &collector);
+        assert_eq!(received, vec![100, 1, 3]);
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn default_mailbox_from_config() {
+        let mut cfg = KompactConfig::default();
+        cfg.set_config_value(&crate::config_keys::system::MAILBOX_CAPACITY, 2usize);
+        cfg.load_config_str(r#"kompact.runtime.mailbox-overflow = "drop-oldest""#);
+        let system = cfg.build().expect("system");
+        let expected = MailboxConfig::bounded(2, OverflowStrategy::DropOldest);
+        assert_eq!(system.default_mailbox(), expected);
+        let collector = system.create(Collector::new);
+        assert_eq!(collector.message_queue().config(), expected);
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn mailbox_config_from_hocon() {
+        let conf = hocon::HoconLoader::new()
+            .load_str(
+                r#"
+                kompact.runtime.mailbox-capacity = 16
+                kompact.runtime.mailbox-overflow = "drop-oldest"
+                "#,
+            )
+            .expect("config")
+            .hocon()
+            .expect("hocon");
+        let mailbox = MailboxConfig::from_config(&conf).expect("mailbox");
+        assert_eq!(
+            mailbox,
+            MailboxConfig::bounded(16, OverflowStrategy::DropOldest)
+        );
+
+        let empty = hocon::HoconLoader::new().hocon().expect("hocon");
+        let mailbox = MailboxConfig::from_config(&empty).expect("mailbox");
+        assert_eq!(mailbox, MailboxConfig::unbounded());
+    }
+
+    #[test]
+    fn overflow_strategies_parse() {
+        for value in OverflowStrategy::CONFIG_VALUES.iter() {
+            assert!(value.parse::<OverflowStrategy>().is_ok());
+        }
+        assert!("drop".parse::<OverflowStrategy>().is_err());
+    }
+}
@@ -2,15 +2,17 @@ use super::*;
 use crate::{
     component::Handled,
     messaging::{DispatchEnvelope, MsgEnvelope, NetMessage, UnpackError},
-    prelude::NetworkStatusPort,
+    prelude::{NetworkBackend, NetworkStatusPort},
 };
 use std::{
     fmt,
     sync::{Arc, Weak},
 };
 
+mod mailbox;
 mod paths;
 mod refs;
+pub use mailbox::*;
 pub use paths::*;
 pub use refs::*;
 
@@ -76,6 +78,20 @@ pub trait ActorRaw {
     /// so, just like for [handle](Provide::handle) implementations,
     /// you shouldn't ever block in this method unless you know what you are doing.
     fn receive(&mut self, env: MsgEnvelope<Self::Message>) -> Handled;
+
+    /// The priority of a local message in a [prioritised](MailboxConfig::prioritised) mailbox
+    ///
+    /// This is called by the sender, before the message is enqueued,
+    /// and thus can't access the component's state.
+    /// Network messages always have [Normal](Priority::Normal) priority.
+    ///
+    /// The default implementation assigns [Normal](Priority::Normal) priority to all messages.
+    fn priority(_msg: &Self::Message) -> Priority
+    where
+        Self: Sized,
+    {
+        Priority::Normal
+    }
 }
 
 /// A slightly higher level Actor API that handles both local and networked messages
@@ -139,6 +155,64 @@ pub trait Actor {
     /// so, just like for [handle](Provide::handle) implementations,
     /// you shouldn't ever block in this method unless you know what you are doing.
     fn receive_network(&mut self, msg: NetMessage) -> Handled;
+
+    /// The priority of a local message in a [prioritised](MailboxConfig::prioritised) mailbox
+    ///
+    /// This is called by the sender, before the message is enqueued,
+    /// and thus can't access the component's state.
+    /// Network messages always have [Normal](Priority::Normal) priority.
+    ///
+    /// The default implementation assigns [Normal](Priority::Normal) priority to all messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    ///
+    /// #[derive(Debug)]
+    /// enum Work {
+    ///     Chunk(Vec<u8>),
+    ///     Cancel,
+    /// }
+    ///
+    /// #[derive(ComponentDefinition)]
+    /// struct Worker {
+    ///    ctx: ComponentContext<Self>
+    /// }
+    /// ignore_lifecycle!(Worker);
+    /// impl Actor for Worker {
+    ///     type Message = Work;
+    ///
+    ///     fn receive_local(&mut self, msg: Self::Message) -> Handled {
+    ///         info!(self.log(), "Got a local message: {:?}", msg);
+    ///         Handled::Ok
+    ///     }
+    ///
+    ///     fn receive_network(&mut self, msg: NetMessage) -> Handled {
+    ///         info!(self.log(), "Got a network message: {:?}", msg);
+    ///         Handled::Ok
+    ///     }
+    ///
+    ///     fn priority(msg: &Self::Message) -> Priority {
+    ///         match msg {
+    ///             Work::Cancel => Priority::High,
+    ///             Work::Chunk(_) => Priority::Low,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let system = KompactConfig::default().build().expect("system");
+    /// let worker = system.create_with_mailbox(
+    ///     || Worker { ctx: ComponentContext::uninitialised() },
+    ///     MailboxConfig::unbounded().prioritised(),
+    /// );
+    /// worker.actor_ref().tell(Work::Chunk(vec![1, 2, 3]));
+    /// worker.actor_ref().tell(Work::Cancel); // handled first, once the worker is started
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn priority(_msg: &Self::Message) -> Priority {
+        Priority::Normal
+    }
 }
 
 /// A dispatcher is a system component that knows how to route messages and create system paths
@@ -153,6 +227,10 @@ pub trait Dispatcher: ActorRaw<Message = DispatchEnvelope> {
     /// Returns a mutable pointer to the dispatchers provided NetworkStatusPort
     /// Can be unimplemented in systems where the NetworkStatusPort is not used
     fn network_status_port(&mut self) -> &mut ProvidedPort<NetworkStatusPort>;
+    /// Returns the backend driving this dispatcher's network, if it has one and it is running
+    fn network_backend(&mut self) -> Option<NetworkBackend> {
+        None
+    }
 }
 
 impl<A, M: MessageBounds> ActorRaw for A
@@ -167,6 +245,10 @@ where
             MsgEnvelope::Net(nm) => self.receive_network(nm),
         }
     }
+
+    fn priority(msg: &M) -> Priority {
+        <A as Actor>::priority(msg)
+    }
 }
 
 /// A trait for things that have associated [actor references](ActorRef)
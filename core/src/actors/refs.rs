@@ -6,53 +6,9 @@ use uuid::Uuid;
 /// The type of actor references for [dispatcher](Dispatcher) implementations
 pub type DispatcherRef = ActorRefStrong<DispatchEnvelope>;
 
-#[derive(Debug)]
-pub struct TypedMsgQueue<M: MessageBounds> {
-    inner: ConcurrentQueue<MsgEnvelope<M>>,
-}
-impl<M: MessageBounds> TypedMsgQueue<M> {
-    pub(crate) fn new() -> TypedMsgQueue<M> {
-        TypedMsgQueue {
-            inner: ConcurrentQueue::new(),
-        }
-    }
-
-    pub(crate) fn pop(&self) -> Option<MsgEnvelope<M>> {
-        self.inner.pop().ok()
-    }
-
-    #[allow(unused)]
-    pub(crate) fn into_dyn(q: Arc<Self>) -> Arc<dyn DynMsgQueue> {
-        q as Arc<dyn DynMsgQueue>
-    }
-
-    #[inline(always)]
-    pub(crate) fn push(&self, value: MsgEnvelope<M>) {
-        self.inner.push(value)
-    }
-
-    pub(crate) fn create_adapter<In: 'static>(
-        component: Weak<dyn MsgQueueContainer<Message = M>>,
-        convert: fn(In) -> M,
-    ) -> Box<dyn AdaptedQueueContainer<In>> {
-        let converting_container = Box::new(ConvertingMsgQueueContainer::from(component, convert));
-        converting_container as Box<dyn AdaptedQueueContainer<In>>
-    }
-}
-impl<M: MessageBounds> DynMsgQueue for TypedMsgQueue<M> {
-    #[inline(always)]
-    fn push_net(&self, value: NetMessage) {
-        self.push(MsgEnvelope::Net(value));
-    }
-}
-
-/// A message queue handle that only deals with
-/// net messages.
-pub trait DynMsgQueue: fmt::Debug + Sync + Send {
-    fn push_net(&self, value: NetMessage);
-}
 pub(crate) trait AdaptedQueueContainer<M>: fmt::Debug + Sync + Send {
     fn id(&self) -> Option<Uuid>;
+    fn mailbox_len(&self) -> Option<usize>;
     fn enqueue_into(&self, value: M);
     fn box_clone(&self) -> Box<dyn AdaptedQueueContainer<M>>;
 }
@@ -83,12 +39,7 @@ where
         let out = self.convert(value);
         let msg = MsgEnvelope::Typed(out);
         if let Some(c) = self.inner.upgrade() {
-            let q = c.message_queue();
-            let sd = c.core().increment_work();
-            q.push(msg);
-            if let SchedulingDecision::Schedule = sd {
-                c.schedule();
-            }
+            let _ = c.message_queue().enqueue(c.as_ref(), msg, false);
         } else {
             #[cfg(test)]
             println!("Dropping msg as target component is unavailable: {:?}", msg)
@@ -115,6 +66,10 @@ where
         self.inner.upgrade().map(|c| c.id())
     }
 
+    fn mailbox_len(&self) -> Option<usize> {
+        self.inner.upgrade().map(|c| c.message_queue().len())
+    }
+
     fn enqueue_into(&self, value: In) {
         self.push_and_schedule(value)
     }
@@ -143,12 +98,7 @@ pub struct DynActorRef {
 impl DynActorRef {
     pub(crate) fn enqueue(&self, msg: NetMessage) -> () {
         if let Some(c) = self.component.upgrade() {
-            let q = c.dyn_message_queue();
-            let sd = c.core().increment_work();
-            q.push_net(msg);
-            if let SchedulingDecision::Schedule = sd {
-                c.schedule();
-            }
+            c.dyn_message_queue().enqueue_net(c.as_ref(), msg);
         } else {
             #[cfg(test)]
             println!("Dropping msg as target component is unavailable: {:?}", msg)
@@ -163,6 +113,53 @@ impl DynActorRef {
         self.component.upgrade().is_some()
     }
 
+    /// Returns the number of messages waiting in the target actor's mailbox
+    ///
+    /// Returns `None` if the target component was deallocated,
+    /// or its mailbox does not keep track of its length.
+    ///
+    /// This is only a snapshot, as other threads may be enqueueing messages concurrently,
+    /// but it is cheap enough to be called on every message, e.g. by load-aware
+    /// [routing policies](crate::routing::groups::LeastLoadedRouting).
+    pub fn mailbox_len(&self) -> Option<usize> {
+        self.component
+            .upgrade()
+            .and_then(|c| c.dyn_message_queue().len())
+    }
+
+    /// Returns the unique id of the target component, if it is still allocated
+    pub(crate) fn component_id(&self) -> Option<Uuid> {
+        self.component.upgrade().map(|c| c.id())
+    }
+
+    /// Returns `true` if the target component was deallocated, destroyed, or has faulted
+    pub(crate) fn is_terminated(&self) -> bool {
+        self.component
+            .upgrade()
+            .map_or(true, |c| c.core().is_terminated())
+    }
+
+    /// Makes the target component notify the dispatcher once it terminates
+    pub(crate) fn mark_watched(&self) -> () {
+        if let Some(c) = self.component.upgrade() {
+            c.core().mark_watched();
+        }
+    }
+
+    /// Records on the target component that it was registered at `path`
+    pub(crate) fn record_registration(&self, path: &ActorPath) -> () {
+        if let Some(c) = self.component.upgrade() {
+            c.core().record_registration(path);
+        }
+    }
+
+    /// Records on the target component that it is no longer registered at `path`
+    pub(crate) fn forget_registration(&self, path: &ActorPath) -> () {
+        if let Some(c) = self.component.upgrade() {
+            c.core().forget_registration(path);
+        }
+    }
+
     /// Send a network message to the target actor
     pub fn tell<I>(&self, v: I) -> ()
     where
@@ -220,12 +217,8 @@ impl<M: MessageBounds> Clone for ActorRefStrong<M> {
 impl<M: MessageBounds> ActorRefStrong<M> {
     pub(crate) fn enqueue(&self, env: MsgEnvelope<M>) -> () {
         let c = &self.component;
-        let q = c.message_queue();
-        let sd = c.core().increment_work();
-        q.push(env);
-        if let SchedulingDecision::Schedule = sd {
-            c.schedule();
-        }
+        // without rejection, overflowing messages are handled by the mailbox
+        let _ = c.message_queue().enqueue(c.as_ref(), env, false);
     }
 
     /// Send message `v` to the actor instance referenced by this actor reference
@@ -238,6 +231,35 @@ impl<M: MessageBounds> ActorRefStrong<M> {
         self.enqueue(env)
     }
 
+    /// Send message `v` with the given `priority` to the actor instance referenced by this actor reference
+    ///
+    /// See [ActorRef::tell_with_priority] for details.
+    pub fn tell_with_priority<I>(&self, v: I, priority: Priority) -> ()
+    where
+        I: Into<M>,
+    {
+        let c = &self.component;
+        let env = MsgEnvelope::Typed(v.into());
+        // without rejection, overflowing messages are handled by the mailbox
+        let _ = c
+            .message_queue()
+            .enqueue_with_priority(c.as_ref(), env, Some(priority), false);
+    }
+
+    /// Send message `v` to the actor instance referenced by this actor reference,
+    /// unless its mailbox is full
+    ///
+    /// See [ActorRef::try_tell] for details.
+    pub fn try_tell<I>(&self, v: I) -> Result<(), TryTellError<M>>
+    where
+        I: Into<M>,
+    {
+        let c = &self.component;
+        c.message_queue()
+            .enqueue(c.as_ref(), MsgEnvelope::Typed(v.into()), true)
+            .map_err(|env| TryTellError::MailboxFull(unwrap_typed(*env)))
+    }
+
     /// Helper to create messages that expect a response via a future instead of a message
     ///
     /// # Example
@@ -447,18 +469,27 @@ impl<M: MessageBounds> ActorRef<M> {
 
     pub(crate) fn enqueue(&self, env: MsgEnvelope<M>) -> () {
         if let Some(c) = self.component.upgrade() {
-            let q = c.message_queue();
-            let sd = c.core().increment_work();
-            q.push(env);
-            if let SchedulingDecision::Schedule = sd {
-                c.schedule();
-            }
+            // without rejection, overflowing messages are handled by the mailbox
+            let _ = c.message_queue().enqueue(c.as_ref(), env, false);
         } else {
             #[cfg(test)]
             println!("Dropping msg as target component is unavailable: {:?}", env)
         }
     }
 
+    /// Returns the number of messages waiting in the target actor's mailbox
+    ///
+    /// Returns `None` if the target component was deallocated.
+    /// See [DynActorRef::mailbox_len] for details.
+    pub fn mailbox_len(&self) -> Option<usize> {
+        self.component.upgrade().map(|c| c.message_queue().len())
+    }
+
+    /// Returns the unique id of the target component, if it is still allocated
+    pub(crate) fn component_id(&self) -> Option<Uuid> {
+        self.component.upgrade().map(|c| c.id())
+    }
+
     /// Upgrade this reference to a strong reference
     ///
     /// This is only possible if the target actor has not already been
@@ -483,6 +514,50 @@ impl<M: MessageBounds> ActorRef<M> {
         self.enqueue(env);
     }
 
+    /// Send message `v` with the given `priority` to the actor instance referenced by this actor reference
+    ///
+    /// The `priority` overrides the one assigned by the target's [Actor::priority] function.
+    /// It only has an effect if the target has a [prioritised](MailboxConfig::prioritised) mailbox,
+    /// and is ignored by FIFO mailboxes.
+    pub fn tell_with_priority<I>(&self, v: I, priority: Priority) -> ()
+    where
+        I: Into<M>,
+    {
+        let env = MsgEnvelope::Typed(v.into());
+        if let Some(c) = self.component.upgrade() {
+            // without rejection, overflowing messages are handled by the mailbox
+            let _ = c
+                .message_queue()
+                .enqueue_with_priority(c.as_ref(), env, Some(priority), false);
+        } else {
+            #[cfg(test)]
+            println!("Dropping msg as target component is unavailable: {:?}", env)
+        }
+    }
+
+    /// Send message `v` to the actor instance referenced by this actor reference,
+    /// unless its mailbox is full
+    ///
+    /// In contrast to [tell](ActorRef::tell), the mailbox's [OverflowStrategy] is not applied,
+    /// but the message is handed back as [MailboxFull](TryTellError::MailboxFull) instead.
+    /// This way, senders can apply backpressure, or decide on a case-by-case basis what to do
+    /// with the message.
+    ///
+    /// Unbounded mailboxes are never full.
+    pub fn try_tell<I>(&self, v: I) -> Result<(), TryTellError<M>>
+    where
+        I: Into<M>,
+    {
+        let env = MsgEnvelope::Typed(v.into());
+        match self.component.upgrade() {
+            Some(c) => c
+                .message_queue()
+                .enqueue(c.as_ref(), env, true)
+                .map_err(|env| TryTellError::MailboxFull(unwrap_typed(*env))),
+            None => Err(TryTellError::Unavailable(unwrap_typed(env))),
+        }
+    }
+
     /// Helper to create messages that expect a response via a future instead of a message
     ///
     /// # Example
@@ -714,7 +789,7 @@ pub struct Recipient<M> {
 }
 
 impl<M: fmt::Debug> Recipient<M> {
-    fn from(component: Box<dyn AdaptedQueueContainer<M>>) -> Recipient<M> {
+    pub(crate) fn from(component: Box<dyn AdaptedQueueContainer<M>>) -> Recipient<M> {
         Recipient { component }
     }
 
@@ -726,6 +801,19 @@ impl<M: fmt::Debug> Recipient<M> {
         let msg: M = v.into();
         self.component.enqueue_into(msg)
     }
+
+    /// Returns the number of messages waiting in the target actor's mailbox
+    ///
+    /// Returns `None` if the target component was deallocated.
+    /// See [DynActorRef::mailbox_len] for details.
+    pub fn mailbox_len(&self) -> Option<usize> {
+        self.component.mailbox_len()
+    }
+
+    /// Returns the unique id of the target component, if it is still allocated
+    pub(crate) fn component_id(&self) -> Option<Uuid> {
+        self.component.id()
+    }
 }
 // Because the derive macro adds the wrong trait bound for this -.-
 impl<M> Clone for Recipient<M> {
@@ -1002,10 +1090,17 @@ impl<T> Receiver<T> for Recipient<T> {
     }
 }
 
+fn unwrap_typed<M: MessageBounds>(env: MsgEnvelope<M>) -> M {
+    match env {
+        MsgEnvelope::Typed(msg) => msg,
+        MsgEnvelope::Net(_) => unreachable!("Only typed messages are sent via actor references"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::prelude::*;
+    use crate::{component::MsgQueueContainer, prelude::*};
     use std::{convert::From, sync::Arc, time::Duration};
     use synchronoise::CountdownEvent;
 
@@ -1037,6 +1132,28 @@ mod tests {
         assert_eq!(count, 0, "Latch should have triggered by now!");
     }
 
+    #[test]
+    fn test_mailbox_len() {
+        let system = KompactConfig::default().build().expect("KompactSystem");
+        let latch = Arc::new(CountdownEvent::new(3));
+        let latch2 = latch.clone();
+        let ldactor = system.create(move || LatchDropActor::new(latch2));
+        let ldref = ldactor.actor_ref();
+        let dyn_ref = ldref.dyn_ref();
+        assert_eq!(dyn_ref.mailbox_len(), Some(0));
+        assert!(ldactor.message_queue().is_empty());
+        for _i in 0..3 {
+            ldref.tell(CountdownWrapper(Countdown));
+        }
+        assert_eq!(dyn_ref.mailbox_len(), Some(3));
+        assert_eq!(ldactor.message_queue().len(), 3);
+        system.start(&ldactor);
+        let count = latch.wait_timeout(Duration::from_millis(500));
+        assert_eq!(count, 0, "Latch should have triggered by now!");
+        assert_eq!(dyn_ref.mailbox_len(), Some(0));
+        system.shutdown().expect("shutdown");
+    }
+
     #[derive(Debug)]
     struct Countdown;
     #[derive(Debug)]
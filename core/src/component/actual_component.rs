@@ -20,6 +20,7 @@ macro_rules! check_and_handle_blocking {
                 if let Some(ref supervisor) = $self.supervisor {
                     supervisor.enqueue(supervisor_msg);
                 }
+                $self.core.notify_watchers();
                 return SchedulingDecision::NoWork
             }
         }
@@ -56,7 +57,6 @@ pub struct Component<CD: ComponentTraits> {
     // system components don't have supervision
     supervisor: Option<ProvidedRef<SupervisionPort>>,
     logger: KompactLogger,
-    recovery_function: Mutex<Box<RecoveryFunction>>,
 }
 
 impl<CD: ComponentTraits> Component<CD> {
@@ -70,16 +70,16 @@ impl<CD: ComponentTraits> Component<CD> {
             .system
             .logger()
             .new(o!("cid" => format!("{}", core.id)));
+        let mailbox = core.system.default_mailbox();
         let mutable_core = ComponentMutableCore::from(definition);
         Component {
             core,
             custom_scheduler: None,
             mutable_core: Mutex::new(mutable_core),
             ctrl_queue: ConcurrentQueue::new(),
-            msg_queue: TypedMsgQueue::new(),
+            msg_queue: TypedMsgQueue::with_config(mailbox, <CD as ActorRaw>::priority),
             supervisor: Some(supervisor),
             logger,
-            recovery_function: Mutex::new(Box::new(default_recovery_function)),
         }
     }
 
@@ -94,16 +94,16 @@ impl<CD: ComponentTraits> Component<CD> {
             .system
             .logger()
             .new(o!("cid" => format!("{}", core.id)));
+        let mailbox = core.system.default_mailbox();
         let mutable_core = ComponentMutableCore::from(definition);
         Component {
             core,
             custom_scheduler: Some(custom_scheduler),
             mutable_core: Mutex::new(mutable_core),
             ctrl_queue: ConcurrentQueue::new(),
-            msg_queue: TypedMsgQueue::new(),
+            msg_queue: TypedMsgQueue::with_config(mailbox, <CD as ActorRaw>::priority),
             supervisor: Some(supervisor),
             logger,
-            recovery_function: Mutex::new(Box::new(default_recovery_function)),
         }
     }
 
@@ -122,7 +122,6 @@ impl<CD: ComponentTraits> Component<CD> {
             msg_queue: TypedMsgQueue::new(),
             supervisor: None,
             logger,
-            recovery_function: Mutex::new(Box::new(default_recovery_function)),
         }
     }
 
@@ -145,10 +144,15 @@ impl<CD: ComponentTraits> Component<CD> {
             msg_queue: TypedMsgQueue::new(),
             supervisor: None,
             logger,
-            recovery_function: Mutex::new(Box::new(default_recovery_function)),
         }
     }
 
+    /// Replace the (still empty) mailbox of this component with one using `mailbox`
+    pub(crate) fn with_mailbox(mut self, mailbox: MailboxConfig) -> Self {
+        self.msg_queue = TypedMsgQueue::with_config(mailbox, <CD as ActorRaw>::priority);
+        self
+    }
+
     pub(crate) fn enqueue_control(&self, event: ControlEvent) -> () {
         let res = self.core.increment_work(); // must do it in this order to maintain counting guarantees
         self.ctrl_queue.push(event);
@@ -226,6 +230,7 @@ impl<CD: ComponentTraits> Component<CD> {
         if let Some(ref supervisor) = self.supervisor {
             supervisor.enqueue(supervisor_msg);
         }
+        self.core.notify_watchers();
         debug!(self.logger, "Component killed");
     }
 
@@ -249,9 +254,21 @@ impl<CD: ComponentTraits> Component<CD> {
     where
         F: FnOnce(FaultContext) -> RecoveryHandler + Send + 'static,
     {
-        let boxed = Box::new(f);
-        let mut current = self.recovery_function.lock().unwrap();
-        *current = boxed;
+        self.core.set_recovery_function(Box::new(f));
+    }
+
+    /// Set the strategy used when one of this component's children faults
+    ///
+    /// See [SupervisionStrategy](crate::prelude::SupervisionStrategy) for more information.
+    pub fn set_supervision_strategy(&self, strategy: SupervisionStrategy) -> () {
+        self.core.set_supervision_strategy(strategy);
+    }
+
+    /// Set the policy limiting how often and how quickly this component is recovered
+    ///
+    /// See [RestartPolicy](crate::prelude::RestartPolicy) for more information.
+    pub fn set_restart_policy(&self, policy: RestartPolicy) -> () {
+        self.core.set_restart_policy(policy);
     }
 
     fn inner_execute(&self) -> SchedulingDecision {
@@ -337,6 +354,7 @@ impl<CD: ComponentTraits> Component<CD> {
                             if let Some(ref supervisor) = self.supervisor {
                                 supervisor.enqueue(supervisor_msg);
                             }
+                            self.core.notify_watchers();
                             return SchedulingDecision::NoWork;
                         }
                     }
@@ -523,15 +541,10 @@ impl<CD: ComponentTraits> CoreContainer for Component<CD> {
                     );
                 }
                 lifecycle::set_faulty(&self.core.state);
+                self.core.notify_watchers();
                 if let Some(ref supervisor) = self.supervisor {
-                    if let Ok(mut guard) = self.recovery_function.lock() {
-                        let context = FaultContext::new(self.core.id, e);
-                        let mut recovery_function: Box<RecoveryFunction> =
-                            Box::new(default_recovery_function);
-                        // replace fault handler with default, so we can own the correct one
-                        // (it's anyway only going to be called once)
-                        std::mem::swap(guard.deref_mut(), &mut recovery_function);
-                        let handler = recovery_function(context);
+                    let context = FaultContext::new(&self.core, e);
+                    if let Some(handler) = self.core.recovery_handler(context) {
                         supervisor.enqueue(SupervisorMsg::Faulty(handler));
                     } else {
                         error!(
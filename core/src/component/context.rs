@@ -1,7 +1,10 @@
 use super::*;
 
-use crate::net::buffers::{BufferConfig, ChunkAllocator, ChunkRef};
-use std::task::Poll;
+use crate::{
+    messaging::{DispatchEnvelope, MsgEnvelope, WatchEvent},
+    net::buffers::{BufferConfig, ChunkAllocator, ChunkRef},
+};
+use std::{collections::VecDeque, error, task::Poll};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum StateTransition {
@@ -30,6 +33,7 @@ pub struct ComponentContext<CD: ComponentTraits> {
     buffer: RefCell<Option<EncodeBuffer>>,
     blocking_future: Option<BlockingState>,
     pub(super) non_blocking_futures: FxHashMap<Uuid, NonBlockingFuture>,
+    stash: VecDeque<MsgEnvelope<CD::Message>>,
 }
 
 struct ComponentContextInner<CD: ComponentTraits> {
@@ -39,6 +43,7 @@ struct ComponentContextInner<CD: ComponentTraits> {
     actor_ref: ActorRef<CD::Message>,
     config: Arc<Hocon>,
     id: Uuid,
+    stash_capacity: usize,
 }
 
 impl<CD> ComponentContext<CD>
@@ -56,6 +61,7 @@ where
             buffer: RefCell::new(None),
             blocking_future: None,
             non_blocking_futures: FxHashMap::default(),
+            stash: VecDeque::new(),
         }
     }
 
@@ -72,6 +78,7 @@ where
             actor_ref: c.actor_ref(),
             config: system.config_owned(),
             id,
+            stash_capacity: system.stash_capacity(),
         };
         self.inner = Some(inner);
         trace!(self.log(), "Initialised.");
@@ -315,6 +322,63 @@ where
         self.component().enqueue_control(ControlEvent::Kill);
     }
 
+    /// Watches the actor at `target` for termination
+    ///
+    /// Once the target actor is killed or deregistered, or its system is deemed unreachable,
+    /// this component receives a [Terminated](crate::prelude::Terminated) network message
+    /// with the `target` path.
+    ///
+    /// Watching requires a networked dispatcher, such as the
+    /// [NetworkDispatcher](crate::prelude::NetworkDispatcher), and this component must be
+    /// registered with the system in order to receive the notification.
+    pub fn watch(&self, target: ActorPath) -> () {
+        let event = WatchEvent::Watch {
+            watcher: self.actor_path(),
+            target,
+        };
+        self.dispatcher_ref().tell(DispatchEnvelope::Watch(event));
+    }
+
+    /// Stops watching the actor at `target`
+    ///
+    /// See [watch](ComponentContext::watch) for more details.
+    pub fn unwatch(&self, target: ActorPath) -> () {
+        let event = WatchEvent::Unwatch {
+            watcher: self.actor_path(),
+            target,
+        };
+        self.dispatcher_ref().tell(DispatchEnvelope::Watch(event));
+    }
+
+    /// Watches the members of the group under `prefix`, on the local or a remote system
+    ///
+    /// This component first receives a [MemberAdded](crate::prelude::MembershipEvent::MemberAdded)
+    /// network message for every actor currently registered at or below `prefix`,
+    /// and then a [MembershipEvent](crate::prelude::MembershipEvent) whenever an actor
+    /// is registered there, or is deregistered, killed, or becomes unreachable.
+    ///
+    /// Watching requires a networked dispatcher, such as the
+    /// [NetworkDispatcher](crate::prelude::NetworkDispatcher), and this component must be
+    /// registered with the system in order to receive the notifications.
+    pub fn watch_members(&self, prefix: NamedPath) -> () {
+        let event = WatchEvent::WatchMembers {
+            watcher: self.actor_path(),
+            prefix,
+        };
+        self.dispatcher_ref().tell(DispatchEnvelope::Watch(event));
+    }
+
+    /// Stops watching the members of the group under `prefix`
+    ///
+    /// See [watch_members](ComponentContext::watch_members) for more details.
+    pub fn unwatch_members(&self, prefix: NamedPath) -> () {
+        let event = WatchEvent::UnwatchMembers {
+            watcher: self.actor_path(),
+            prefix,
+        };
+        self.dispatcher_ref().tell(DispatchEnvelope::Watch(event));
+    }
+
     pub(crate) fn with_buffer<R>(&self, f: impl FnOnce(&mut EncodeBuffer) -> R) -> R {
         {
             // Scoping the borrow
@@ -422,8 +486,135 @@ where
     {
         self.typed_component().set_recovery_function(f);
     }
+
+    /// Set the strategy used when one of this component's children faults
+    ///
+    /// See [SupervisionStrategy](crate::prelude::SupervisionStrategy) for more information.
+    pub fn set_supervision_strategy(&self, strategy: SupervisionStrategy) -> () {
+        self.typed_component().set_supervision_strategy(strategy);
+    }
+
+    /// Set the policy limiting how often and how quickly this component is recovered
+    ///
+    /// See [RestartPolicy](crate::prelude::RestartPolicy) for more information.
+    pub fn set_restart_policy(&self, policy: RestartPolicy) -> () {
+        self.typed_component().set_restart_policy(policy);
+    }
+
+    /// Put a message aside, that this component can't handle yet
+    ///
+    /// Stashed messages are re-delivered, in the order they were stashed,
+    /// once [unstash_all](ComponentContext::unstash_all) is called.
+    /// This way, a component can defer messages while it is, for example,
+    /// still waiting for an ask in [spawn_local](ComponentDefinition::spawn_local) to complete,
+    /// without blocking entirely.
+    ///
+    /// Stashed messages are lost, if the component is killed or restarted before unstashing them.
+    ///
+    /// Fails with [StashFull](StashFull), handing back `msg`, if the stash already holds
+    /// [STASH_CAPACITY](crate::config_keys::system::STASH_CAPACITY) messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    ///
+    /// #[derive(ComponentDefinition)]
+    /// struct Lazy {
+    ///    ctx: ComponentContext<Self>,
+    ///    ready: bool,
+    /// }
+    /// ignore_lifecycle!(Lazy);
+    /// impl Actor for Lazy {
+    ///     type Message = Option<String>;
+    ///
+    ///     fn receive_local(&mut self, msg: Self::Message) -> Handled {
+    ///         match msg {
+    ///             Some(text) if self.ready => info!(self.log(), "Got: {}", text),
+    ///             Some(text) => self.ctx.stash(MsgEnvelope::Typed(Some(text))).expect("stash"),
+    ///             None => {
+    ///                 self.ready = true;
+    ///                 self.ctx.unstash_all();
+    ///             }
+    ///         }
+    ///         Handled::Ok
+    ///     }
+    ///
+    ///     fn receive_network(&mut self, msg: NetMessage) -> Handled {
+    ///         if self.ready {
+    ///             info!(self.log(), "Got a network message: {:?}", msg);
+    ///         } else {
+    ///             self.ctx.stash(MsgEnvelope::Net(msg)).expect("stash");
+    ///         }
+    ///         Handled::Ok
+    ///     }
+    /// }
+    /// ```
+    pub fn stash(&mut self, msg: MsgEnvelope<CD::Message>) -> Result<(), StashFull<CD::Message>> {
+        if self.stash.len() < self.inner_ref().stash_capacity {
+            self.stash.push_back(msg);
+            Ok(())
+        } else {
+            Err(StashFull(Box::new(msg)))
+        }
+    }
+
+    /// Re-deliver all stashed messages, ahead of any messages waiting in the mailbox
+    ///
+    /// Returns the number of unstashed messages.
+    pub fn unstash_all(&mut self) -> usize {
+        let count = self.stash.len();
+        if count > 0 {
+            let component = self.typed_component();
+            let queue = component.message_queue();
+            for msg in self.stash.drain(..) {
+                queue.unstash(component.as_ref(), msg);
+            }
+        }
+        count
+    }
+
+    /// Returns the number of currently stashed messages
+    pub fn stash_len(&self) -> usize {
+        self.stash.len()
+    }
+
+    /// Create a new component supervised by this component
+    ///
+    /// The child is killed whenever this component is killed or faults,
+    /// and faults of the child are handled according to
+    /// this component's [supervision strategy](ComponentContext::set_supervision_strategy).
+    ///
+    /// As with [create](KompactSystem::create), the child is not started automatically.
+    pub fn create_child<C, F>(&self, f: F) -> Arc<Component<C>>
+    where
+        F: FnOnce() -> C,
+        C: ComponentDefinition + 'static,
+    {
+        let parent = self.component();
+        parent.system().create_child(&parent, f)
+    }
 }
 
+/// The error returned by [stash](ComponentContext::stash), holding the message that could not be stashed
+#[derive(Debug)]
+pub struct StashFull<M: MessageBounds>(Box<MsgEnvelope<M>>);
+
+impl<M: MessageBounds> StashFull<M> {
+    /// Take back the message that could not be stashed
+    pub fn into_inner(self) -> MsgEnvelope<M> {
+        *self.0
+    }
+}
+
+impl<M: MessageBounds> fmt::Display for StashFull<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The stash is full")
+    }
+}
+
+impl<M: MessageBounds> error::Error for StashFull<M> {}
+
 impl<CD> ActorRefFactory for ComponentContext<CD>
 where
     CD: ComponentTraits + ComponentLifecycle,
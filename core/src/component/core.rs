@@ -1,5 +1,7 @@
 use super::*;
 
+use crate::messaging::{DispatchEnvelope, WatchEvent};
+
 /// The core of a Kompact component
 ///
 /// Contains the unique id, as well as references to the Kompact system,
@@ -8,7 +10,24 @@ pub struct ComponentCore {
     pub(super) id: Uuid,
     pub(super) system: KompactSystem,
     pub(super) state: AtomicU64,
+    /// Set once the component is watched, so it notifies the dispatcher when it terminates
+    watched: AtomicBool,
     component: UnsafeCell<Weak<dyn CoreContainer>>,
+    parent: UnsafeCell<Option<(Uuid, Weak<dyn CoreContainer>)>>,
+    supervision_strategy: Mutex<SupervisionStrategy>,
+    restart_policy: Mutex<Option<RestartPolicy>>,
+    snapshot: Mutex<Option<StoredSnapshot>>,
+    registrations: Mutex<Registrations>,
+    recovery_function: Mutex<Box<RecoveryFunction>>,
+}
+
+/// The paths a component is registered under, so they can be moved to its replacement
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Registrations {
+    /// Whether the component is registered under its unique path
+    pub(crate) unique: bool,
+    /// The segments of all named paths the component is registered under
+    pub(crate) named: Vec<Vec<String>>,
 }
 
 impl ComponentCore {
@@ -21,7 +40,14 @@ impl ComponentCore {
             id: Uuid::new_v4(),
             system,
             state: lifecycle::initial_state(),
+            watched: AtomicBool::new(false),
             component: UnsafeCell::new(weak),
+            parent: UnsafeCell::new(None),
+            supervision_strategy: Mutex::new(SupervisionStrategy::default()),
+            restart_policy: Mutex::new(None),
+            snapshot: Mutex::new(None),
+            registrations: Mutex::new(Registrations::default()),
+            recovery_function: Mutex::new(Box::new(default_recovery_function)),
         }
     }
 
@@ -56,6 +82,131 @@ impl ComponentCore {
         }
     }
 
+    pub(crate) unsafe fn set_parent(&self, parent: &Arc<dyn CoreContainer>) -> () {
+        let parent_mut = self.parent.get();
+        *parent_mut = Some((parent.id(), Arc::downgrade(parent)));
+    }
+
+    /// Returns the id of the component supervising this component, if any
+    ///
+    /// Components created via [create](KompactSystem::create) have no parent,
+    /// and are supervised directly by the system.
+    pub fn parent_id(&self) -> Option<Uuid> {
+        unsafe { (*self.parent.get()).as_ref().map(|(id, _)| *id) }
+    }
+
+    /// Returns the component supervising this component, if it is still allocated
+    pub(crate) fn parent(&self) -> Option<Arc<dyn CoreContainer>> {
+        unsafe {
+            (*self.parent.get())
+                .as_ref()
+                .and_then(|(_, parent)| parent.upgrade())
+        }
+    }
+
+    /// Returns the strategy used when one of this component's children faults
+    pub fn supervision_strategy(&self) -> SupervisionStrategy {
+        *self.supervision_strategy.lock().unwrap()
+    }
+
+    pub(super) fn set_supervision_strategy(&self, strategy: SupervisionStrategy) -> () {
+        let mut current = self.supervision_strategy.lock().unwrap();
+        *current = strategy;
+    }
+
+    /// Returns the policy limiting restarts of this component, if it set one
+    pub fn restart_policy(&self) -> Option<RestartPolicy> {
+        *self.restart_policy.lock().unwrap()
+    }
+
+    pub(super) fn set_restart_policy(&self, policy: RestartPolicy) -> () {
+        let mut current = self.restart_policy.lock().unwrap();
+        *current = Some(policy);
+    }
+
+    pub(crate) fn store_snapshot(&self, snapshot: StoredSnapshot) -> () {
+        let mut current = self.snapshot.lock().unwrap();
+        *current = Some(snapshot);
+    }
+
+    /// Returns the last snapshot stored via [store_snapshot](Snapshot::store_snapshot)
+    pub(crate) fn last_snapshot(&self) -> Option<StoredSnapshot> {
+        self.snapshot.lock().unwrap().clone()
+    }
+
+    /// Returns `true` if a [snapshot](Snapshot) of this component has been stored
+    pub fn has_snapshot(&self) -> bool {
+        self.snapshot.lock().unwrap().is_some()
+    }
+
+    /// Records that the dispatcher registered this component at `path`
+    pub(crate) fn record_registration(&self, path: &ActorPath) -> () {
+        let mut registrations = self.registrations.lock().unwrap();
+        match path {
+            ActorPath::Unique(_) => registrations.unique = true,
+            ActorPath::Named(np) => {
+                if !registrations.named.iter().any(|p| p == np.path_ref()) {
+                    registrations.named.push(np.clone_path());
+                }
+            }
+        }
+    }
+
+    /// Records that the dispatcher no longer has this component registered at `path`
+    pub(crate) fn forget_registration(&self, path: &ActorPath) -> () {
+        let mut registrations = self.registrations.lock().unwrap();
+        match path {
+            ActorPath::Unique(_) => registrations.unique = false,
+            ActorPath::Named(np) => registrations.named.retain(|p| p != np.path_ref()),
+        }
+    }
+
+    /// Returns the paths this component is registered under
+    pub(crate) fn registrations(&self) -> Registrations {
+        self.registrations.lock().unwrap().clone()
+    }
+
+    pub(super) fn set_recovery_function(&self, f: Box<RecoveryFunction>) -> () {
+        let mut current = self.recovery_function.lock().unwrap();
+        *current = f;
+    }
+
+    /// Produce the [RecoveryHandler] for `fault` from the current recovery function
+    ///
+    /// The recovery function is replaced with the default,
+    /// as it is anyway only going to be called once.
+    pub(crate) fn recovery_handler(&self, fault: FaultContext) -> Option<RecoveryHandler> {
+        match self.recovery_function.lock() {
+            Ok(mut guard) => {
+                let mut recovery_function: Box<RecoveryFunction> =
+                    Box::new(default_recovery_function);
+                std::mem::swap(guard.deref_mut(), &mut recovery_function);
+                Some(recovery_function(fault))
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Returns `true` if the component was destroyed or has faulted
+    pub(crate) fn is_terminated(&self) -> bool {
+        lifecycle::is_destroyed(&self.state) || lifecycle::is_faulty(&self.state)
+    }
+
+    pub(crate) fn mark_watched(&self) -> () {
+        self.watched.store(true, Ordering::SeqCst);
+    }
+
+    /// Tells the dispatcher to notify the watchers of this component, if there are any
+    ///
+    /// Must be called after the component was marked as destroyed or faulty.
+    pub(super) fn notify_watchers(&self) -> () {
+        if self.watched.load(Ordering::SeqCst) {
+            if let Some(dispatcher) = self.system.active_dispatcher_ref() {
+                dispatcher.tell(DispatchEnvelope::Watch(WatchEvent::Terminated(self.id)));
+            }
+        }
+    }
+
     pub(crate) fn increment_work(&self) -> SchedulingDecision {
         LifecycleState::increment_work(&self.state)
     }
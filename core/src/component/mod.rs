@@ -4,7 +4,12 @@ use std::{
     fmt,
     ops::DerefMut,
     panic,
-    sync::{atomic::AtomicU64, Arc, Mutex, Weak},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+        Mutex,
+        Weak,
+    },
     time::Duration,
 };
 use uuid::Uuid;
@@ -36,6 +41,9 @@ mod core;
 pub use self::core::*;
 mod future_task;
 pub use future_task::*;
+mod snapshot;
+pub use snapshot::Snapshot;
+pub(crate) use snapshot::StoredSnapshot;
 
 /// State transition indication at the end of a message or event handler
 #[must_use = "The Handled value must be returned from a handle or receive function in order to take effect."]
@@ -444,7 +452,14 @@ impl SchedulingDecision {
 mod tests {
     use crate::{component::AbstractComponent, prelude::*};
     use futures::channel::oneshot;
-    use std::{sync::Arc, thread, time::Duration};
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
 
     use std::ops::Deref;
 
@@ -1281,4 +1296,202 @@ mod tests {
 
         system.shutdown().expect("shutdown");
     }
+
+    #[derive(Default)]
+    struct DropCounted {
+        indications: AtomicUsize,
+    }
+    impl PortInterceptor<CounterPort> for DropCounted {
+        fn intercept_request(&self, _event: Counted) -> Intercepted<Counted> {
+            Intercepted::Drop
+        }
+
+        fn intercept_indication(&self, event: CountMe) -> Intercepted<CountMe> {
+            self.indications.fetch_add(1, Ordering::SeqCst);
+            Intercepted::Forward(event)
+        }
+    }
+
+    struct DelayCountMe(Duration);
+    impl PortInterceptor<CounterPort> for DelayCountMe {
+        fn intercept_indication(&self, event: CountMe) -> Intercepted<CountMe> {
+            Intercepted::Delay(self.0, event)
+        }
+    }
+
+    #[test]
+    fn test_channel_interceptors() {
+        let system = KompactConfig::default().build().expect("System");
+        let sender = system.create(CountSender::default);
+        let counter1 = system.create(Counter::default);
+        let counter2 = system.create(Counter::default);
+
+        let dropper = Arc::new(DropCounted::default());
+        let chain1 = InterceptorChain::new().with_shared(dropper.clone());
+        let channel1 =
+            biconnect_components_with_interceptors::<CounterPort, _, _>(&sender, &counter1, chain1)
+                .expect("connection");
+        let delay = Duration::from_millis(300);
+        let chain2 = InterceptorChain::new().with(DelayCountMe(delay));
+        let channel2 =
+            biconnect_components_with_interceptors::<CounterPort, _, _>(&sender, &counter2, chain2)
+                .expect("connection");
+
+        system
+            .start_notify(&sender)
+            .wait_timeout(TIMEOUT)
+            .expect("sender started");
+        system
+            .start_notify(&counter1)
+            .wait_timeout(TIMEOUT)
+            .expect("counter1 started");
+        system
+            .start_notify(&counter2)
+            .wait_timeout(TIMEOUT)
+            .expect("counter2 started");
+
+        sender.actor_ref().tell(SendCount);
+        thread::sleep(delay / 3);
+
+        assert_eq!(1, dropper.indications.load(Ordering::SeqCst));
+        assert_eq!(1, counter1.on_definition(|cd| cd.count));
+        // the indication to counter2 is still being held back
+        assert_eq!(0, counter2.on_definition(|cd| cd.count));
+
+        thread::sleep(TIMEOUT);
+
+        assert_eq!(1, counter2.on_definition(|cd| cd.count));
+        // only counter2's request made it through
+        assert_eq!(1, sender.on_definition(|cd| cd.counted));
+
+        sender.actor_ref().tell(SendCount);
+        thread::sleep(delay / 3);
+
+        channel1.disconnect().expect("disconnected");
+        // the indication held back for counter2 is dropped together with its connection
+        channel2.disconnect().expect("disconnected");
+
+        sender.actor_ref().tell(SendCount);
+        thread::sleep(TIMEOUT);
+
+        assert_eq!(2, dropper.indications.load(Ordering::SeqCst));
+        assert_eq!(2, counter1.on_definition(|cd| cd.count));
+        assert_eq!(1, counter2.on_definition(|cd| cd.count));
+
+        system.shutdown().expect("shutdown");
+    }
+
+    #[derive(Debug)]
+    enum StashMsg {
+        Num(u64),
+        Ready,
+        Get(Ask<(), (Vec<u64>, Vec<u64>)>),
+    }
+
+    #[derive(ComponentDefinition)]
+    struct StashingComponent {
+        ctx: ComponentContext<Self>,
+        ready: bool,
+        received: Vec<u64>,
+        overflowed: Vec<u64>,
+    }
+    impl StashingComponent {
+        fn new() -> Self {
+            StashingComponent {
+                ctx: ComponentContext::uninitialised(),
+                ready: false,
+                received: Vec::new(),
+                overflowed: Vec::new(),
+            }
+        }
+    }
+    ignore_lifecycle!(StashingComponent);
+    impl Actor for StashingComponent {
+        type Message = StashMsg;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            match msg {
+                StashMsg::Num(num) if self.ready => self.received.push(num),
+                StashMsg::Num(num) => {
+                    if let Err(full) = self.ctx.stash(MsgEnvelope::Typed(StashMsg::Num(num))) {
+                        assert!(
+                            matches!(full.into_inner(), MsgEnvelope::Typed(StashMsg::Num(n)) if n == num)
+                        );
+                        self.overflowed.push(num);
+                    }
+                }
+                StashMsg::Ready => {
+                    self.ready = true;
+                    let stashed = self.ctx.stash_len();
+                    assert_eq!(self.ctx.unstash_all(), stashed);
+                    assert_eq!(self.ctx.stash_len(), 0);
+                }
+                StashMsg::Get(ask) => ask
+                    .reply((self.received.clone(), self.overflowed.clone()))
+                    .expect("reply"),
+            }
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, msg: NetMessage) -> Handled {
+            if self.ready {
+                let num = msg.try_deserialise::<u64, u64>().expect("u64");
+                self.received.push(num);
+            } else {
+                self.ctx.stash(MsgEnvelope::Net(msg)).expect("stash");
+            }
+            Handled::Ok
+        }
+    }
+
+    fn start_and_get(
+        system: &KompactSystem,
+        component: &Arc<Component<StashingComponent>>,
+    ) -> (Vec<u64>, Vec<u64>) {
+        system
+            .start_notify(component)
+            .wait_timeout(TIMEOUT)
+            .expect("component never started");
+        component
+            .actor_ref()
+            .ask_with(|promise| StashMsg::Get(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("result")
+    }
+
+    #[test]
+    fn test_stash_redelivers_ahead_of_mailbox() {
+        let system = KompactConfig::default().build().expect("system");
+        let component = system.create(StashingComponent::new);
+        let actor_ref = component.actor_ref();
+        actor_ref.tell(StashMsg::Num(1));
+        let path = system.deadletter_path();
+        let net_msg = NetMessage::with_box(u64::SER_ID, path.clone(), path, Box::new(2u64));
+        actor_ref.dyn_ref().tell(net_msg);
+        actor_ref.tell(StashMsg::Num(3));
+        actor_ref.tell(StashMsg::Ready);
+        actor_ref.tell(StashMsg::Num(4));
+        let (received, overflowed) = start_and_get(&system, &component);
+        assert_eq!(received, vec![1, 2, 3, 4]);
+        assert!(overflowed.is_empty());
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn test_stash_is_bounded() {
+        let mut cfg = KompactConfig::default();
+        cfg.set_config_value(&crate::config_keys::system::STASH_CAPACITY, 2usize);
+        let system = cfg.build().expect("system");
+        assert_eq!(system.stash_capacity(), 2);
+        let component = system.create(StashingComponent::new);
+        let actor_ref = component.actor_ref();
+        for num in 1..=3 {
+            actor_ref.tell(StashMsg::Num(num));
+        }
+        actor_ref.tell(StashMsg::Ready);
+        let (received, overflowed) = start_and_get(&system, &component);
+        assert_eq!(received, vec![1, 2]);
+        assert_eq!(overflowed, vec![3]);
+        system.shutdown().expect("shutdown");
+    }
 }
@@ -0,0 +1,134 @@
+use super::*;
+
+use crate::serialisation::{Deserialiser, SerError, SerId, Serialisable};
+
+/// A component whose state can be preserved across restarts
+///
+/// Components implementing this trait can [store](Snapshot::store_snapshot) a serialised
+/// snapshot of their state, either on demand or [periodically](Snapshot::store_snapshots_periodically).
+/// When the component faults, the recovery handler produced by
+/// [restore_snapshot](crate::prelude::FaultContext::restore_snapshot) creates
+/// the replacement from the last snapshot that was stored successfully, and
+/// registers it under the same paths again.
+///
+/// Note that any changes to the state since the last snapshot are lost.
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+/// use std::time::Duration;
+///
+/// #[derive(ComponentDefinition, Actor)]
+/// struct Counter {
+///     ctx: ComponentContext<Self>,
+///     count: u64,
+/// }
+/// impl ComponentLifecycle for Counter {
+///     fn on_start(&mut self) -> Handled {
+///         self.ctx.set_recovery_function(|fault| fault.restore_snapshot::<Counter>());
+///         self.store_snapshots_periodically(Duration::from_millis(100));
+///         Handled::Ok
+///     }
+/// }
+/// impl Snapshot for Counter {
+///     type State = u64;
+///
+///     fn snapshot(&self) -> Self::State {
+///         self.count
+///     }
+///
+///     fn restore(state: Option<Self::State>) -> Self {
+///         Counter {
+///             ctx: ComponentContext::uninitialised(),
+///             count: state.unwrap_or(0),
+///         }
+///     }
+/// }
+/// # let system = KompactConfig::default().build().expect("system");
+/// let counter = system.create(|| Counter::restore(None));
+/// system.start(&counter);
+/// # system.shutdown().expect("shutdown");
+/// ```
+pub trait Snapshot: ComponentTraits {
+    /// The serialisable state of the component
+    type State: Serialisable + Deserialiser<Self::State>;
+
+    /// Capture the current state of the component
+    fn snapshot(&self) -> Self::State;
+
+    /// Create a new instance of the component from `state`
+    ///
+    /// `state` is `None` if no snapshot was stored before the fault,
+    /// or if the stored snapshot could not be deserialised.
+    fn restore(state: Option<Self::State>) -> Self;
+
+    /// Take a snapshot and store it as the last good snapshot of this component
+    ///
+    /// If the snapshot can't be serialised, the previous snapshot is kept.
+    fn store_snapshot(&self) -> Result<(), SerError> {
+        let snapshot = StoredSnapshot::new(&self.snapshot())?;
+        self.ctx().typed_component().core().store_snapshot(snapshot);
+        Ok(())
+    }
+
+    /// Store a snapshot every `period`
+    ///
+    /// Cancel the returned timer to stop taking snapshots.
+    fn store_snapshots_periodically(&mut self, period: Duration) -> ScheduledTimer {
+        self.schedule_periodic(period, period, |component, _timer| {
+            if let Err(e) = component.store_snapshot() {
+                warn!(component.log(), "Could not store snapshot: {}", e);
+            }
+            Handled::Ok
+        })
+    }
+}
+
+/// The serialised form of a [Snapshot]
+#[derive(Clone, Debug)]
+pub(crate) struct StoredSnapshot {
+    ser_id: SerId,
+    data: Vec<u8>,
+}
+
+impl StoredSnapshot {
+    fn new(state: &dyn Serialisable) -> Result<Self, SerError> {
+        let mut data = Vec::with_capacity(state.size_hint().unwrap_or(0));
+        state.serialise(&mut data)?;
+        Ok(StoredSnapshot {
+            ser_id: state.ser_id(),
+            data,
+        })
+    }
+
+    pub(crate) fn restore<T, D>(&self) -> Result<T, SerError>
+    where
+        D: Deserialiser<T>,
+    {
+        if self.ser_id == D::SER_ID {
+            D::deserialise(&mut self.data.as_slice())
+        } else {
+            Err(SerError::InvalidType(format!(
+                "Snapshot has serialiser id {}, but {} was expected",
+                self.ser_id,
+                D::SER_ID
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_snapshot_roundtrip() {
+        let snapshot = StoredSnapshot::new(&42u64).expect("snapshot");
+        assert_eq!(snapshot.restore::<u64, u64>().expect("restore"), 42u64);
+        assert!(matches!(
+            snapshot.restore::<String, String>(),
+            Err(SerError::InvalidType(_))
+        ));
+    }
+}
@@ -1,19 +1,70 @@
 use super::*;
 
 use crate::{
-    messaging::RegistrationResult,
-    routing::groups::StorePolicy,
+    messaging::{
+        ActorIdentity,
+        DispatchEnvelope,
+        NetMessage,
+        PathResolvable,
+        RegisteredPath,
+        RegistrationEnvelope,
+        RegistrationResult,
+    },
+    routing::{
+        groups::StorePolicy,
+        scatter_gather::{GatherPolicy, Gathered},
+    },
     timer::timer_manager::CanCancelTimers,
 };
 
 /// The [SystemHandle](SystemHandle) provided by a [ComponentContext](ComponentContext)
 pub struct ContextSystemHandle {
     component: Arc<dyn CoreContainer>,
+    recovery: Option<RecoveryScope>,
+}
+
+/// Tracks the replacements created while recovering from a fault
+struct RecoveryScope {
+    // replacements become children of `parent`
+    parent: Option<Arc<dyn CoreContainer>>,
+    replacements: Arc<Mutex<Vec<Uuid>>>,
 }
 
 impl ContextSystemHandle {
     pub(super) fn from(component: Arc<dyn CoreContainer>) -> Self {
-        ContextSystemHandle { component }
+        ContextSystemHandle {
+            component,
+            recovery: None,
+        }
+    }
+
+    /// Create all new components via this handle as children of `parent`,
+    /// and record their ids in `replacements`
+    pub(crate) fn for_recovery(
+        self,
+        parent: Option<Arc<dyn CoreContainer>>,
+        replacements: Arc<Mutex<Vec<Uuid>>>,
+    ) -> Self {
+        let recovery = RecoveryScope {
+            parent,
+            replacements,
+        };
+        ContextSystemHandle {
+            recovery: Some(recovery),
+            ..self
+        }
+    }
+
+    /// Register `c` at `path`, replacing any existing registration
+    ///
+    /// Unlike [update_alias_registration](SystemHandle::update_alias_registration)
+    /// this does not provide any feedback, so it can be used where futures can't be awaited.
+    pub(crate) fn reassign(&self, c: &dyn DynActorRefFactory, path: PathResolvable) -> () {
+        let envelope = RegistrationEnvelope::actor(c.dyn_ref(), path, true);
+        self.component
+            .system()
+            .dispatcher_ref()
+            .tell(DispatchEnvelope::Registration(envelope));
     }
 }
 
@@ -23,7 +74,17 @@ impl SystemHandle for ContextSystemHandle {
         F: FnOnce() -> C,
         C: ComponentDefinition + 'static,
     {
-        self.component.system().create(f)
+        match self.recovery {
+            Some(ref recovery) => {
+                let c = match recovery.parent {
+                    Some(ref parent) => self.component.system().create_child(parent, f),
+                    None => self.component.system().create(f),
+                };
+                recovery.replacements.lock().unwrap().push(c.id());
+                c
+            }
+            None => self.component.system().create(f),
+        }
     }
 
     #[cfg(all(nightly, feature = "type_erasure"))]
@@ -43,7 +104,9 @@ impl SystemHandle for ContextSystemHandle {
         F: FnOnce() -> C,
         C: ComponentDefinition + 'static,
     {
-        self.component.system().create_and_register(f)
+        let c = self.create(f);
+        let r = self.register(&c);
+        (c, r)
     }
 
     fn register_by_alias<A>(
@@ -82,6 +145,44 @@ impl SystemHandle for ContextSystemHandle {
             .set_routing_policy(policy, path, update)
     }
 
+    fn deregister_alias<A>(&self, alias: A) -> KFuture<RegistrationResult>
+    where
+        A: Into<String>,
+    {
+        self.component.system().deregister_alias(alias)
+    }
+
+    fn deregister_unique(&self, c: &dyn UniqueRegistrable) -> KFuture<RegistrationResult> {
+        self.component.system().deregister_unique(c)
+    }
+
+    fn remove_routing_policy(&self, path: &str) -> KFuture<RegistrationResult> {
+        self.component.system().remove_routing_policy(path)
+    }
+
+    fn identify(&self, path: ActorPath) -> KFuture<ActorIdentity> {
+        self.component.system().identify(path)
+    }
+
+    fn list_paths(&self, prefix: NamedPath) -> KFuture<Vec<RegisteredPath>> {
+        self.component.system().list_paths(prefix)
+    }
+
+    fn scatter_gather<B>(
+        &self,
+        target: ActorPath,
+        request: B,
+        policy: GatherPolicy,
+        timeout: Duration,
+    ) -> KFuture<Gathered<ActorPath, NetMessage>>
+    where
+        B: Into<Box<dyn Serialisable>>,
+    {
+        self.component
+            .system()
+            .scatter_gather(target, request, policy, timeout)
+    }
+
     fn start(&self, c: &Arc<impl AbstractComponent + ?Sized>) -> () {
         self.component.system().start(c)
     }
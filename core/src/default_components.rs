@@ -2,6 +2,7 @@ use super::*;
 use crate::{
     dispatch::NetworkStatusPort,
     messaging::{DispatchEnvelope, NetMessage},
+    net::NetworkBackend,
     timer::timer_manager::TimerRefFactory,
 };
 use std::sync::Arc;
@@ -150,6 +151,10 @@ where
             utils::biconnect_ports(d.network_status_port(), required);
         })
     }
+
+    fn network_backend(&self) -> Option<NetworkBackend> {
+        self.dispatcher.on_definition(|d| d.network_backend())
+    }
 }
 
 /// The default deadletter box
@@ -7,8 +7,8 @@
 //!     4. Broadcast to _all_ listeners, ensuring that the route/lookup exists in at least one of them.
 
 use crate::{
-    actors::{ActorPath, DynActorRef, PathParseError},
-    messaging::{NetMessage, PathResolvable},
+    actors::{ActorPath, DynActorRef, PathParseError, PATH_SEP},
+    messaging::{NetMessage, PathEntry, PathResolvable},
     routing::groups::{
         RoutingGroup,
         RoutingPolicy,
@@ -17,13 +17,15 @@ use crate::{
         DEFAULT_SELECT_POLICY,
     },
 };
-use rustc_hash::FxHashMap;
-use std::ops::Deref;
+use rustc_hash::FxHasher;
+use std::{fmt, hash::BuildHasherDefault, ops::Deref};
 use uuid::Uuid;
 
 pub(crate) mod gc;
 pub(crate) mod path_trie;
+pub(crate) mod radix_tree;
 use path_trie::*;
+use radix_tree::ByteSliceMap;
 
 /// Result of an actor path lookup
 #[derive(Debug)]
@@ -150,6 +152,35 @@ pub trait ActorLookup: Clone {
     /// Removes the value at the given key, returning `true` if it existed.
     fn remove_by_named_path(&mut self, path: &[String]) -> bool;
 
+    /// Removes the actor reference at the given key, returning `true` if it existed.
+    ///
+    /// Unlike [remove_by_named_path](ActorLookup::remove_by_named_path),
+    /// this leaves a routing policy at the same key untouched.
+    ///
+    /// The default implementation checks the entry with
+    /// [get_by_named_path](ActorLookup::get_by_named_path) before removing it.
+    fn remove_actor_by_named_path(&mut self, path: &[String]) -> bool {
+        if matches!(self.get_by_named_path(path), LookupResult::Ref(_)) {
+            self.remove_by_named_path(path)
+        } else {
+            false
+        }
+    }
+
+    /// Removes the routing policy at the given key, returning `true` if it existed.
+    ///
+    /// Actor references at the same key are left untouched.
+    ///
+    /// The default implementation checks the entry with
+    /// [get_by_named_path](ActorLookup::get_by_named_path) before removing it.
+    fn remove_routing_policy(&mut self, path: &[String]) -> bool {
+        if matches!(self.get_by_named_path(path), LookupResult::Group(_)) {
+            self.remove_by_named_path(path)
+        } else {
+            false
+        }
+    }
+
     /// Performs cleanup on this lookup table, returning how many entries were affected.
     fn cleanup(&mut self) -> usize {
         0
@@ -162,10 +193,39 @@ enum ActorTreeEntry {
     Policy(StorePolicy),
 }
 
+/// What the radix index needs to know about an [ActorTreeEntry] to answer exact lookups
+#[derive(Debug, Clone)]
+enum IndexEntry {
+    Ref(DynActorRef),
+    Policy,
+}
+
+impl From<&ActorTreeEntry> for IndexEntry {
+    fn from(entry: &ActorTreeEntry) -> Self {
+        match entry {
+            ActorTreeEntry::Ref(actor) => IndexEntry::Ref(actor.clone()),
+            ActorTreeEntry::Policy(_) => IndexEntry::Policy,
+        }
+    }
+}
+
 /// Lookup structure for storing and retrieving `DynActorRef`s.
 ///
-/// UUID-based references are stored in a `HashMap`, and path-based named references
-/// are stored in a Trie structure.
+/// UUID-based references are stored in a persistent hash map, and path-based named references
+/// are stored in a persistent Trie structure.
+/// Both share structure between versions, so cloning an `ActorStore` is O(1)
+/// and an update on the clone costs O(log n), leaving the original untouched.
+/// This keeps copy-on-write updates behind an `ArcSwap` cheap, even for large stores.
+///
+/// Named entries are additionally indexed in a radix tree keyed by their serialised path bytes,
+/// which answers exact lookups without hashing each segment, or straight from the wire
+/// via [get_by_path_bytes](ActorStore::get_by_path_bytes).
+/// Routing lookups, listings, and membership, which need all entries below a path,
+/// go through the Trie, since byte prefixes in the radix tree don't follow segment boundaries
+/// (e.g. `a/b` is a prefix of `a/bc`).
+/// Both hold the same named entries, and the radix index only stores a cheap, weak actor reference
+/// or a marker for policies, so bulk removals run over the index once
+/// and then remove only the affected paths from the Trie.
 ///
 /// # Notes
 /// The sequence trie supports the use case of grouping many [DynActorRef] under the same path,
@@ -174,10 +234,15 @@ enum ActorTreeEntry {
 ///
 /// Ex: Broadcasting a message to actors stored under the system path "tcp://127.0.0.1:8080/pongers/*"
 /// Ex: Selecting one of the actors stored under the system path "tcp://127.0.0.1:8080/pongers/?" for delivery
+///
+/// Reserved top-level names, such as the dispatcher's alias, are kept apart from the Trie,
+/// so they are never part of a routing group, e.g. when routing from the root.
 #[derive(Clone)]
 pub struct ActorStore {
-    uuid_map: FxHashMap<Uuid, DynActorRef>,
+    uuid_map: im::HashMap<Uuid, DynActorRef, BuildHasherDefault<FxHasher>>,
     name_map: PathTrie<ActorTreeEntry>,
+    name_index: ByteSliceMap<IndexEntry>,
+    reserved: im::HashMap<String, DynActorRef, BuildHasherDefault<FxHasher>>,
     deadletter: Option<DynActorRef>,
 }
 
@@ -185,11 +250,214 @@ impl ActorStore {
     /// Return a new, empty instance
     pub fn new() -> Self {
         ActorStore {
-            uuid_map: FxHashMap::default(),
+            uuid_map: im::HashMap::default(),
             name_map: PathTrie::new(),
+            name_index: ByteSliceMap::new(),
+            reserved: im::HashMap::default(),
             deadletter: None,
         }
     }
+
+    /// Looks up a named path in its serialised form, i.e. with its segments joined by `/`
+    ///
+    /// Entries are resolved directly on the bytes, e.g. straight from a received frame,
+    /// without allocating the path's segments.
+    pub fn get_by_path_bytes(&self, path: &[u8]) -> LookupResult<'_> {
+        use crate::actors::{BROADCAST_MARKER, SELECT_MARKER};
+        if path.is_empty() {
+            return self.deadletter.as_ref().into();
+        }
+        if path.first() == Some(&(RESERVED_MARKER as u8)) && !path.contains(&(PATH_SEP as u8)) {
+            return match std::str::from_utf8(path) {
+                Ok(name) => self.reserved.get(name).into(),
+                Err(_) => LookupResult::None,
+            };
+        }
+        let last_segment = path.rsplit(|b| *b == PATH_SEP as u8).next().unwrap_or(path);
+        let routed = matches!(
+            last_segment.first(),
+            Some(&b) if b == BROADCAST_MARKER as u8 || b == SELECT_MARKER as u8
+        );
+        if !routed {
+            match self.name_index.get(path) {
+                Some(IndexEntry::Ref(aref)) => return LookupResult::Ref(aref),
+                Some(IndexEntry::Policy) => (), // the group's members are in the trie
+                None => return LookupResult::None,
+            }
+        }
+        let path = match std::str::from_utf8(path) {
+            Ok(path) => path,
+            Err(e) => return LookupResult::Err(format!("Named path is not valid UTF-8: {}", e)),
+        };
+        // split off the marker segment, like get_by_named_path does on a parsed path
+        let (lookup_path, marker_opt) = if routed {
+            match path.rfind(PATH_SEP) {
+                Some(index) => (Some(&path[..index]), path[index + 1..].chars().next()),
+                None => (None, path.chars().next()),
+            }
+        } else {
+            (Some(path), None)
+        };
+        let segments = lookup_path.into_iter().flat_map(|p| p.split(PATH_SEP));
+        let node = self.name_map.get_node_by_segments(segments);
+        self.resolve_node(node, marker_opt, &path)
+    }
+
+    /// Resolves the trie `node` found at `path`, routing to its children if it holds a policy
+    /// or `marker_opt` requests routing
+    fn resolve_node<'a>(
+        &'a self,
+        node: Option<&'a PathTrie<ActorTreeEntry>>,
+        marker_opt: Option<char>,
+        path: &dyn fmt::Debug,
+    ) -> LookupResult<'a> {
+        use crate::actors::{BROADCAST_MARKER, SELECT_MARKER};
+        match node {
+            Some(node) => {
+                if let Some(entry) = node.value() {
+                    match entry {
+                        ActorTreeEntry::Ref(aref) => {
+                            if let Some(marker) = marker_opt {
+                                LookupResult::Err(format!("Expected a routing policy (marker={}), but found an actor reference at path={:?}", marker, path))
+                            } else {
+                                LookupResult::Ref(aref)
+                            }
+                        }
+                        ActorTreeEntry::Policy(policy) => {
+                            let children: Vec<&'a DynActorRef> = node
+                                .values()
+                                .flat_map(|v| {
+                                    if let ActorTreeEntry::Ref(aref) = v {
+                                        Some(aref)
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect();
+                            if let Some(marker) = marker_opt {
+                                // requested routing
+                                match marker {
+                                    BROADCAST_MARKER => {
+                                        if let Some(broadcast_policy) = policy.broadcast() {
+                                            let group =
+                                                RoutingGroup::new(children, broadcast_policy);
+                                            LookupResult::Group(group)
+                                        } else {
+                                            LookupResult::Err(format!("Expected a broadcast policy (marker={}), but found a non-broadcast policy at path={:?}", marker, path))
+                                        }
+                                    }
+                                    SELECT_MARKER => {
+                                        if let Some(select_policy) = policy.select() {
+                                            let group = RoutingGroup::new(children, select_policy);
+                                            LookupResult::Group(group)
+                                        } else {
+                                            LookupResult::Err(format!("Expected a select policy (marker={}), but found a non-select policy at path={:?}", marker, path))
+                                        }
+                                    }
+                                    _ => unreachable!(
+                                        "Only put marker characters in to marker field!"
+                                    ),
+                                }
+                            } else {
+                                // transparent routing
+                                let group = RoutingGroup::new(children, policy.deref());
+                                LookupResult::Group(group)
+                            }
+                        }
+                    }
+                } else if let Some(marker) = marker_opt {
+                    if cfg!(feature = "implicit_routes") {
+                        // implicit routing
+                        let policy: &(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync) =
+                            match marker {
+                                BROADCAST_MARKER => &DEFAULT_BROADCAST_POLICY,
+                                SELECT_MARKER => &DEFAULT_SELECT_POLICY,
+                                _ => unreachable!("Only put marker characters in to marker field!"),
+                            };
+                        let children: Vec<&'a DynActorRef> = node
+                            .values()
+                            .flat_map(|v| {
+                                if let ActorTreeEntry::Ref(aref) = v {
+                                    Some(aref)
+                                } else {
+                                    None
+                                }
+                            })
+                            .collect();
+                        let group = RoutingGroup::new(children, policy);
+                        LookupResult::Group(group)
+                    } else {
+                        LookupResult::None
+                    }
+                } else {
+                    LookupResult::None
+                }
+            }
+            None => LookupResult::None,
+        }
+    }
+
+    /// Lists all named entries at or below `prefix`, sorted by path
+    ///
+    /// Actors whose component was already deallocated or killed are skipped,
+    /// and so are the system's reserved names when listing from the root.
+    pub fn list(&self, prefix: &[String]) -> Vec<(Vec<String>, PathEntry)> {
+        if let Some(name) = reserved_name(prefix) {
+            return self
+                .reserved
+                .get(name)
+                .filter(|actor| !actor.is_terminated())
+                .and_then(|actor| actor.component_id())
+                .map(|id| vec![(prefix.to_vec(), PathEntry::Actor(id))])
+                .unwrap_or_default();
+        }
+        let node = match self.name_map.get_node(prefix) {
+            Some(node) => node,
+            None => return Vec::new(),
+        };
+        let mut entries: Vec<(Vec<String>, PathEntry)> = node
+            .entries()
+            .into_iter()
+            .filter_map(|(suffix, entry)| {
+                let entry = match entry {
+                    ActorTreeEntry::Ref(actor) if actor.is_terminated() => return None,
+                    ActorTreeEntry::Ref(actor) => PathEntry::Actor(actor.component_id()?),
+                    ActorTreeEntry::Policy(_) => PathEntry::Policy,
+                };
+                let mut path = prefix.to_vec();
+                path.extend(suffix.into_iter().map(String::from));
+                if !is_listed_under(prefix, &path) {
+                    return None;
+                }
+                Some((path, entry))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+}
+
+/// Top-level names starting with this marker are reserved for the system, e.g. the dispatcher's alias
+const RESERVED_MARKER: char = '$';
+
+fn is_reserved(name: &str) -> bool {
+    name.starts_with(RESERVED_MARKER)
+}
+
+/// Returns the reserved name that makes up all of `path`, if any
+/// Returns `true` if [list](ActorStore::list) includes an entry at `path` when listing `prefix`
+///
+/// Reserved names are only listed explicitly, never under the root.
+pub(crate) fn is_listed_under(prefix: &[String], path: &[String]) -> bool {
+    path.starts_with(prefix)
+        && !(prefix.is_empty() && path.first().map_or(false, |name| is_reserved(name)))
+}
+
+fn reserved_name(path: &[String]) -> Option<&str> {
+    match path {
+        [name] if is_reserved(name) => Some(name),
+        _ => None,
+    }
 }
 
 impl Default for ActorStore {
@@ -212,19 +480,19 @@ impl ActorLookup for ActorStore {
                 }
                 ActorPath::Named(np) => {
                     let keys = np.into_path();
-                    let prev = self.name_map.insert_owned(keys, ActorTreeEntry::Ref(actor));
+                    let prev = self.insert_named(keys, ActorTreeEntry::Ref(actor));
                     Ok(prev.into())
                 }
             },
             PathResolvable::Alias(ref alias) => {
                 let path = crate::actors::parse_path(alias);
                 crate::actors::validate_insert_path(&path)?;
-                let prev = self.name_map.insert_owned(path, ActorTreeEntry::Ref(actor));
+                let prev = self.insert_named(path, ActorTreeEntry::Ref(actor));
                 Ok(prev.into())
             }
             PathResolvable::Segments(path) => {
                 crate::actors::validate_insert_path(&path)?;
-                let prev = self.name_map.insert_owned(path, ActorTreeEntry::Ref(actor));
+                let prev = self.insert_named(path, ActorTreeEntry::Ref(actor));
                 Ok(prev.into())
             }
             PathResolvable::ActorId(uuid) => Ok(self.uuid_map.insert(uuid, actor).into()),
@@ -238,9 +506,7 @@ impl ActorLookup for ActorStore {
         policy: StorePolicy,
     ) -> Result<InsertResult, PathParseError> {
         crate::actors::validate_insert_path(path)?;
-        let prev = self
-            .name_map
-            .insert_owned(path.to_vec(), ActorTreeEntry::Policy(policy));
+        let prev = self.insert_named(path.to_vec(), ActorTreeEntry::Policy(policy));
         Ok(prev.into())
     }
 
@@ -255,7 +521,7 @@ impl ActorLookup for ActorStore {
                         "Path contains illegal characters: {:?}",
                         keys
                     );
-                    self.name_map.get(keys).is_some()
+                    self.contains_named(keys)
                 }
             },
             PathResolvable::Alias(ref alias) => {
@@ -265,7 +531,7 @@ impl ActorLookup for ActorStore {
                     "Path contains illegal characters: {:?}",
                     path
                 );
-                self.name_map.get(&path).is_some()
+                self.contains_named(&path)
             }
             PathResolvable::Segments(ref path) => {
                 debug_assert!(
@@ -273,7 +539,7 @@ impl ActorLookup for ActorStore {
                     "Path contains illegal characters: {:?}",
                     path
                 );
-                self.name_map.get(path).is_some()
+                self.contains_named(path)
             }
             PathResolvable::ActorId(ref uuid) => self.uuid_map.contains_key(uuid),
             PathResolvable::System => self.deadletter.is_some(),
@@ -288,6 +554,8 @@ impl ActorLookup for ActorStore {
         use crate::actors::{BROADCAST_MARKER, SELECT_MARKER};
         if path.is_empty() {
             self.deadletter.as_ref().into()
+        } else if let Some(name) = reserved_name(path) {
+            self.reserved.get(name).into()
         } else {
             debug_assert!(
                 crate::actors::validate_lookup_path(path).is_ok(),
@@ -305,91 +573,14 @@ impl ActorLookup for ActorStore {
                 SELECT_MARKER => (&path[..last_index], Some(SELECT_MARKER)),
                 _ => (path, None),
             };
-            match self.name_map.get_node(lookup_path) {
-                Some(node) => {
-                    if let Some(entry) = node.value() {
-                        match entry {
-                            ActorTreeEntry::Ref(aref) => {
-                                if let Some(marker) = marker_opt {
-                                    LookupResult::Err(format!("Expected a routing policy (marker={}), but found an actor reference at path={:?}", marker, path))
-                                } else {
-                                    LookupResult::Ref(aref)
-                                }
-                            }
-                            ActorTreeEntry::Policy(policy) => {
-                                let children: Vec<&'a DynActorRef> = node
-                                    .values()
-                                    .flat_map(|v| {
-                                        if let ActorTreeEntry::Ref(aref) = v {
-                                            Some(aref)
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
-                                if let Some(marker) = marker_opt {
-                                    // requested routing
-                                    match marker {
-                                        BROADCAST_MARKER => {
-                                            if let Some(broadcast_policy) = policy.broadcast() {
-                                                let group =
-                                                    RoutingGroup::new(children, broadcast_policy);
-                                                LookupResult::Group(group)
-                                            } else {
-                                                LookupResult::Err(format!("Expected a broadcast policy (marker={}), but found a non-broadcast policy at path={:?}", marker, path))
-                                            }
-                                        }
-                                        SELECT_MARKER => {
-                                            if let Some(select_policy) = policy.select() {
-                                                let group =
-                                                    RoutingGroup::new(children, select_policy);
-                                                LookupResult::Group(group)
-                                            } else {
-                                                LookupResult::Err(format!("Expected a select policy (marker={}), but found a non-select policy at path={:?}", marker, path))
-                                            }
-                                        }
-                                        _ => unreachable!(
-                                            "Only put marker characters in to marker field!"
-                                        ),
-                                    }
-                                } else {
-                                    // transparent routing
-                                    let group = RoutingGroup::new(children, policy.deref());
-                                    LookupResult::Group(group)
-                                }
-                            }
-                        }
-                    } else if let Some(marker) = marker_opt {
-                        if cfg!(feature = "implicit_routes") {
-                            // implicit routing
-                            let policy: &(dyn RoutingPolicy<DynActorRef, NetMessage>
-                                  + Send
-                                  + Sync) = match marker {
-                                BROADCAST_MARKER => &DEFAULT_BROADCAST_POLICY,
-                                SELECT_MARKER => &DEFAULT_SELECT_POLICY,
-                                _ => unreachable!("Only put marker characters in to marker field!"),
-                            };
-                            let children: Vec<&'a DynActorRef> = node
-                                .values()
-                                .flat_map(|v| {
-                                    if let ActorTreeEntry::Ref(aref) = v {
-                                        Some(aref)
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            let group = RoutingGroup::new(children, policy);
-                            LookupResult::Group(group)
-                        } else {
-                            LookupResult::None
-                        }
-                    } else {
-                        LookupResult::None
-                    }
+            if marker_opt.is_none() {
+                match self.name_index.get_segments(path, PATH_SEP as u8) {
+                    Some(IndexEntry::Ref(aref)) => return LookupResult::Ref(aref),
+                    Some(IndexEntry::Policy) => (), // the group's members are in the trie
+                    None => return LookupResult::None,
                 }
-                None => LookupResult::None,
             }
+            self.resolve_node(self.name_map.get_node(lookup_path), marker_opt, &path)
         }
     }
 
@@ -417,17 +608,67 @@ impl ActorLookup for ActorStore {
     }
 
     fn remove_by_named_path(&mut self, path: &[String]) -> bool {
+        if let Some(name) = reserved_name(path) {
+            return self.reserved.remove(name).is_some();
+        }
         let existed = self.name_map.get(path).is_some();
-        self.name_map.remove(path);
+        self.remove_named(path);
         existed
     }
 
+    fn remove_actor_by_named_path(&mut self, path: &[String]) -> bool {
+        if let Some(name) = reserved_name(path) {
+            return self.reserved.remove(name).is_some();
+        }
+        if let Some(ActorTreeEntry::Ref(_)) = self.name_map.get(path) {
+            self.remove_named(path);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_routing_policy(&mut self, path: &[String]) -> bool {
+        if let Some(ActorTreeEntry::Policy(_)) = self.name_map.get(path) {
+            self.remove_named(path);
+            true
+        } else {
+            false
+        }
+    }
+
     fn cleanup(&mut self) -> usize {
         self.remove_deallocated_entries()
     }
 }
 
 impl ActorStore {
+    fn insert_named(&mut self, path: Vec<String>, entry: ActorTreeEntry) -> Option<ActorTreeEntry> {
+        if let (Some(name), ActorTreeEntry::Ref(actor)) = (reserved_name(&path), &entry) {
+            return self
+                .reserved
+                .insert(name.to_string(), actor.clone())
+                .map(ActorTreeEntry::Ref);
+        }
+        let key = path.join("/");
+        self.name_index
+            .insert(key.as_bytes(), IndexEntry::from(&entry));
+        self.name_map.insert_owned(path, entry)
+    }
+
+    fn contains_named(&self, path: &[String]) -> bool {
+        match reserved_name(path) {
+            Some(name) => self.reserved.contains_key(name),
+            None => self.name_map.get(path).is_some(),
+        }
+    }
+
+    fn remove_named(&mut self, path: &[String]) {
+        let key = path.join("/");
+        self.name_index.remove(key.as_bytes());
+        self.name_map.remove(path);
+    }
+
     fn remove_from_uuid_map(&mut self, actor: &DynActorRef) -> usize {
         let matches: Vec<_> = self
             .uuid_map
@@ -443,73 +684,45 @@ impl ActorStore {
     }
 
     fn remove_from_name_map(&mut self, actor: &DynActorRef) -> usize {
-        // let matches: Vec<_> = self
-        //     .name_map
-        //     .iter()
-        //     .filter(|&(_, entry)| {
-        //         if let ActorTreeEntry::Ref(other_actor) = entry {
-        //             actor == other_actor
-        //         } else {
-        //             false
-        //         }
-        //     })
-        //     .map(|(key, _)| {
-        //         // Clone the entire Vec<&String> path to be able to remove entries below
-        //         let mut cl = Vec::new();
-        //         for k in key {
-        //             cl.push(k.clone());
-        //         }
-        //         cl
-        //     })
-        //     .collect();
-        // let existed = matches.len();
-        // for m in matches {
-        //     self.name_map.remove(&m);
-        // }
-        // existed
-        self.name_map.retain(|entry| {
-            if let ActorTreeEntry::Ref(other_actor) = entry {
-                actor != other_actor
+        let reserved_before = self.reserved.len();
+        self.reserved.retain(|_, other_actor| actor != other_actor);
+        let reserved_removed = reserved_before - self.reserved.len();
+        reserved_removed + self.retain_named(|other_actor| actor != other_actor)
+    }
+
+    /// Removes all named actor references that do not meet `condition`, leaving policies untouched
+    ///
+    /// Runs once over the radix index, and then removes only the affected paths from the Trie,
+    /// instead of walking both structures in full.
+    fn retain_named<F>(&mut self, condition: F) -> usize
+    where
+        F: Fn(&DynActorRef) -> bool,
+    {
+        let removed = self.name_index.retain(|entry| match entry {
+            IndexEntry::Ref(actor) => condition(actor),
+            IndexEntry::Policy => true,
+        });
+        for key in removed.iter() {
+            // keys were joined from the segments of a path, so they are valid UTF-8
+            let key = std::str::from_utf8(key).expect("index keys are joined paths");
+            let path: Vec<String> = if key.is_empty() {
+                Vec::new()
             } else {
-                true
-            }
-        })
+                key.split(PATH_SEP).map(str::to_string).collect()
+            };
+            self.name_map.remove(&path);
+        }
+        removed.len()
     }
 
     /// Walks through the `name_map`and `uuid_map`, removing [DynActorRef]s which have been
     /// deallocated by the runtime lifecycle management.
     fn remove_deallocated_entries(&mut self) -> usize {
-        let mut existed = 0;
-        // let matches: Vec<_> = self
-        //     .name_map
-        //     .iter()
-        //     .filter(|&(_, entry)| {
-        //         if let ActorTreeEntry::Ref(actor) = entry {
-        //             !actor.can_upgrade_component()
-        //         } else {
-        //             false
-        //         }
-        //     })
-        //     .map(|(key, _)| {
-        //         // Clone the entire Vec<&String> path to be able to remove entries below
-        //         let mut cl = Vec::new();
-        //         for k in key {
-        //             cl.push(k.clone());
-        //         }
-        //         cl
-        //     })
-        //     .collect();
-        // existed += matches.len();
-        // for m in matches {
-        //     self.name_map.remove(&m);
-        // }
-        existed += self.name_map.retain(|entry| {
-            if let ActorTreeEntry::Ref(actor) = entry {
-                actor.can_upgrade_component()
-            } else {
-                true
-            }
-        });
+        let mut existed = self.reserved.len();
+        self.reserved
+            .retain(|_, actor| actor.can_upgrade_component());
+        existed -= self.reserved.len();
+        existed += self.retain_named(|actor| actor.can_upgrade_component());
 
         let matches: Vec<_> = self
             .uuid_map
@@ -524,3 +737,162 @@ impl ActorStore {
         existed
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{doctest_helpers::TestComponent1, prelude::*, routing::groups::BroadcastRouting};
+
+    fn segments(path: &str) -> Vec<String> {
+        crate::actors::parse_path(path)
+    }
+
+    fn assert_ref(result: LookupResult, expected: &DynActorRef) {
+        match result {
+            LookupResult::Ref(actor) => assert_eq!(actor, expected),
+            other => panic!("Expected {:?}, but got {:?}", expected, other),
+        }
+    }
+
+    fn assert_group_size(result: LookupResult, expected: usize) {
+        match result {
+            LookupResult::Group(group) => assert_eq!(group.members().len(), expected),
+            other => panic!("Expected a group, but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn named_lookups_agree_on_segments_and_bytes() {
+        let system = KompactConfig::default().build().expect("system");
+        let c1 = system.create(TestComponent1::new);
+        let c2 = system.create(TestComponent1::new);
+        let a1 = c1.actor_ref().dyn_ref();
+        let a2 = c2.actor_ref().dyn_ref();
+
+        let mut store = ActorStore::new();
+        store
+            .insert(PathResolvable::Alias("test/me".into()), a1.clone())
+            .expect("insert");
+        store
+            .insert(PathResolvable::Alias("test/you".into()), a2.clone())
+            .expect("insert");
+        store
+            .set_routing_policy(&segments("test"), BroadcastRouting.into())
+            .expect("policy");
+
+        for (path, expected) in [("test/me", &a1), ("test/you", &a2)] {
+            assert_ref(store.get_by_named_path(&segments(path)), expected);
+            assert_ref(store.get_by_path_bytes(path.as_bytes()), expected);
+        }
+        for path in ["test", "test/*"] {
+            assert_group_size(store.get_by_named_path(&segments(path)), 2);
+            assert_group_size(store.get_by_path_bytes(path.as_bytes()), 2);
+        }
+        for path in ["test/?", "test/me/*"] {
+            let expected_err = |result: LookupResult| matches!(result, LookupResult::Err(_));
+            assert!(expected_err(store.get_by_named_path(&segments(path))));
+            assert!(expected_err(store.get_by_path_bytes(path.as_bytes())));
+        }
+        for path in ["tes", "test/m", "test/me/further", "other"] {
+            assert!(store.get_by_named_path(&segments(path)).is_empty());
+            assert!(store.get_by_path_bytes(path.as_bytes()).is_empty());
+        }
+
+        // a snapshot is unaffected by later removals
+        let snapshot = store.clone();
+        assert!(store.remove_actor_by_named_path(&segments("test/me")));
+        assert_eq!(1, store.remove(a2.clone()));
+        assert!(store.remove_routing_policy(&segments("test")));
+        for path in ["test", "test/me", "test/you"] {
+            assert!(store.get_by_named_path(&segments(path)).is_empty());
+            assert!(store.get_by_path_bytes(path.as_bytes()).is_empty());
+        }
+        assert_ref(snapshot.get_by_path_bytes(b"test/me"), &a1);
+        assert_group_size(snapshot.get_by_path_bytes(b"test/*"), 2);
+
+        drop(snapshot);
+        drop(store);
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn removing_an_actor_updates_both_indexes() {
+        let system = KompactConfig::default().build().expect("system");
+        let c1 = system.create(TestComponent1::new);
+        let c2 = system.create(TestComponent1::new);
+        let a1 = c1.actor_ref().dyn_ref();
+        let a2 = c2.actor_ref().dyn_ref();
+
+        let mut store = ActorStore::new();
+        for (path, actor) in [("test/me", &a1), ("other/me", &a1), ("test/you", &a2)] {
+            store
+                .insert(PathResolvable::Alias(path.into()), actor.clone())
+                .expect("insert");
+        }
+        store
+            .set_routing_policy(&segments("test"), BroadcastRouting.into())
+            .expect("policy");
+
+        assert_eq!(2, store.remove(a1.clone()));
+        assert_eq!(0, store.remove(a1));
+        for path in ["test/me", "other/me", "other"] {
+            assert!(store.get_by_named_path(&segments(path)).is_empty());
+            assert!(store.get_by_path_bytes(path.as_bytes()).is_empty());
+        }
+        assert_ref(store.get_by_path_bytes(b"test/you"), &a2);
+        assert_group_size(store.get_by_named_path(&segments("test")), 1);
+        assert_group_size(store.get_by_path_bytes(b"test"), 1);
+
+        drop(store);
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn reserved_names_are_kept_out_of_routing() {
+        let system = KompactConfig::default().build().expect("system");
+        let c1 = system.create(TestComponent1::new);
+        let c2 = system.create(TestComponent1::new);
+
+        let mut store = ActorStore::new();
+        store
+            .insert(
+                PathResolvable::Alias("$dispatcher".into()),
+                c1.actor_ref().dyn_ref(),
+            )
+            .expect("insert");
+        store
+            .insert(
+                PathResolvable::Alias("test".into()),
+                c2.actor_ref().dyn_ref(),
+            )
+            .expect("insert");
+
+        assert_eq!(
+            store.list(&[]),
+            vec![(segments("test"), PathEntry::Actor(c2.id()))]
+        );
+        // Reserved names can still be listed explicitly
+        assert_eq!(store.list(&segments("$dispatcher")).len(), 1);
+        // and resolved, but are never routed to
+        assert_ref(
+            store.get_by_named_path(&segments("$dispatcher")),
+            &c1.actor_ref().dyn_ref(),
+        );
+        assert_ref(
+            store.get_by_path_bytes(b"$dispatcher"),
+            &c1.actor_ref().dyn_ref(),
+        );
+        assert!(store.contains(&PathResolvable::Alias("$dispatcher".into())));
+        if cfg!(feature = "implicit_routes") {
+            for path in ["*", "?"] {
+                assert_group_size(store.get_by_named_path(&segments(path)), 1);
+                assert_group_size(store.get_by_path_bytes(path.as_bytes()), 1);
+            }
+        }
+        assert!(store.remove_by_named_path(&segments("$dispatcher")));
+        assert!(store.get_by_path_bytes(b"$dispatcher").is_empty());
+
+        drop(store);
+        system.shutdown().expect("shutdown");
+    }
+}
@@ -1,17 +1,35 @@
-use rustc_hash::FxHashMap;
-use std::{borrow::Borrow, collections::hash_map, hash::Hash};
-
+use im::hashmap;
+use rustc_hash::FxHasher;
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasherDefault, Hash},
+};
+
+type Children<V> = hashmap::HashMap<String, PathTrie<V>, BuildHasherDefault<FxHasher>>;
+
+/// A persistent trie over path segments
+///
+/// Children are kept in a hash array mapped trie with structural sharing,
+/// so cloning a `PathTrie` is O(1) and an update only copies the nodes
+/// on the path to the changed key.
 #[derive(Debug, Clone)]
 pub struct PathTrie<V> {
     value: Option<V>,
-    children: FxHashMap<String, PathTrie<V>>,
+    children: Children<V>,
+}
+
+/// What became of a node after removing values from its subtree
+enum Retained<V> {
+    Unchanged,
+    Changed(PathTrie<V>),
+    Emptied,
 }
 
-impl<V> PathTrie<V> {
+impl<V: Clone> PathTrie<V> {
     pub fn new() -> Self {
         PathTrie {
             value: None,
-            children: FxHashMap::default(),
+            children: Children::default(),
         }
     }
 
@@ -19,7 +37,7 @@ impl<V> PathTrie<V> {
     pub fn with_value(value: V) -> Self {
         PathTrie {
             value: Some(value),
-            children: FxHashMap::default(),
+            children: Children::default(),
         }
     }
 
@@ -88,10 +106,20 @@ impl<V> PathTrie<V> {
     /// String does not implement Borrow<&str>
     #[allow(dead_code)] // used for testing
     pub fn get_node_static(&self, key: &[&str]) -> Option<&Self> {
+        self.get_node_by_segments(key.iter().copied())
+    }
+
+    /// Get the node at the path made up of `segments`
+    ///
+    /// Allows looking up paths that were never split into a collection, e.g. straight from the wire.
+    pub fn get_node_by_segments<'k, I>(&self, segments: I) -> Option<&Self>
+    where
+        I: IntoIterator<Item = &'k str>,
+    {
         let mut current_node = self;
 
-        for fragment in key {
-            match current_node.children.get(*fragment) {
+        for fragment in segments {
+            match current_node.children.get(fragment) {
                 Some(node) => current_node = node,
                 None => return None,
             }
@@ -166,43 +194,64 @@ impl<V> PathTrie<V> {
     /// Makes sure to clean up the tree so no empty nodes are left behind.
     ///
     /// Returns the total number of values removed.
+    #[allow(dead_code)] // the actor store retains through its radix index instead
     pub fn retain<F>(&mut self, condition: F) -> usize
     where
         F: Fn(&V) -> bool,
     {
         let mut count = 0;
-        self.retain_recursive(&condition, &mut count); // don't remove root
+        match self.retained(&condition, &mut count) {
+            Retained::Unchanged => (),
+            Retained::Changed(node) => *self = node,
+            Retained::Emptied => *self = PathTrie::new(), // don't remove root
+        }
         count
     }
 
-    fn retain_recursive<F>(&mut self, condition: &F, count: &mut usize) -> bool
+    /// Checks every value below this node exactly once, bottom-up
+    ///
+    /// Only the nodes on paths to removed values are copied,
+    /// so all other subtrees stay shared with earlier versions of the trie.
+    fn retained<F>(&self, condition: &F, count: &mut usize) -> Retained<V>
     where
         F: Fn(&V) -> bool,
     {
-        // if let Some(fragment) = key.first() {
-        //     let delete_child = match self.children.get_mut(fragment.borrow()) {
-        //         Some(child) => child.remove_recursive(&key[1..], result),
-        //         None => false,
-        //     };
-        //     if delete_child {
-        //         self.children.remove(fragment.borrow());
-        //     }
-        // } else {
-        //     std::mem::swap(result, &mut self.value);
-        // }
-        if let Some(ref v) = self.value {
-            if !(*condition)(v) {
-                self.value = None;
-                *count += 1;
+        let remove_value = self.value.as_ref().map_or(false, |v| !(*condition)(v));
+        if remove_value {
+            *count += 1;
+        }
+        let changed_children: Vec<(String, Retained<V>)> = self
+            .children
+            .iter()
+            .filter_map(|(fragment, child)| match child.retained(condition, count) {
+                Retained::Unchanged => None,
+                changed => Some((fragment.clone(), changed)),
+            })
+            .collect();
+        if !remove_value && changed_children.is_empty() {
+            return Retained::Unchanged;
+        }
+        let mut node = self.clone();
+        if remove_value {
+            node.value = None;
+        }
+        for (fragment, child) in changed_children {
+            match child {
+                Retained::Changed(child) => {
+                    node.children.insert(fragment, child);
+                }
+                Retained::Emptied => {
+                    node.children.remove(&fragment);
+                }
+                Retained::Unchanged => unreachable!("Unchanged children are filtered out"),
             }
         }
-        self.children.retain(|_, child| {
-            let delete = child.retain_recursive(condition, count);
-            !delete
-        });
-
         // If the node is childless and valueless, mark it for deletion.
-        self.is_empty()
+        if node.is_empty() {
+            Retained::Emptied
+        } else {
+            Retained::Changed(node)
+        }
     }
 
     /// Checks if this node is empty
@@ -217,6 +266,29 @@ impl<V> PathTrie<V> {
         self.children.is_empty()
     }
 
+    /// Returns all values at or below this node, together with their keys relative to this node
+    pub fn entries(&self) -> Vec<(Vec<&str>, &V)> {
+        let mut entries = Vec::new();
+        let mut key = Vec::new();
+        self.collect_entries(&mut key, &mut entries);
+        entries
+    }
+
+    fn collect_entries<'a>(
+        &'a self,
+        key: &mut Vec<&'a str>,
+        entries: &mut Vec<(Vec<&'a str>, &'a V)>,
+    ) {
+        if let Some(ref value) = self.value {
+            entries.push((key.clone(), value));
+        }
+        for (fragment, child) in self.children.iter() {
+            key.push(fragment);
+            child.collect_entries(key, entries);
+            key.pop();
+        }
+    }
+
     pub fn values(&self) -> Values<V> {
         Values {
             root: self,
@@ -234,7 +306,7 @@ pub struct Values<'a, V: 'a> {
 }
 /// Information stored on the iteration stack whilst exploring
 struct StackItem<'a, V: 'a> {
-    child_iter: hash_map::Iter<'a, String, PathTrie<V>>,
+    child_iter: hashmap::Iter<'a, String, PathTrie<V>>,
 }
 
 impl<'a, V> Iterator for Values<'a, V> {
@@ -329,6 +401,32 @@ mod tests {
         assert_eq!(Some(&3), trie.get_static(&["test", "you"]));
     }
 
+    #[test]
+    fn test_retain_checks_each_value_once() {
+        let mut trie: PathTrie<usize> = PathTrie::new();
+        for (i, key) in [&["a"][..], &["a", "b"], &["a", "b", "c"], &["d", "e"]]
+            .iter()
+            .enumerate()
+        {
+            trie.insert(key, i);
+        }
+        let snapshot = trie.clone();
+        let checks = std::cell::Cell::new(0);
+        let removed = trie.retain(|v| {
+            checks.set(checks.get() + 1);
+            *v != 2 && *v != 3
+        });
+        assert_eq!(2, removed);
+        assert_eq!(4, checks.get());
+        assert_eq!(Some(&1), trie.get_static(&["a", "b"]));
+        assert!(trie.get_node_static(&["a", "b", "c"]).is_none());
+        // emptied subtrees are pruned
+        assert!(trie.get_node_static(&["d"]).is_none());
+        // earlier versions are unaffected
+        assert_eq!(Some(&2), snapshot.get_static(&["a", "b", "c"]));
+        assert_eq!(Some(&3), snapshot.get_static(&["d", "e"]));
+    }
+
     #[test]
     #[allow(clippy::needless_collect)]
     fn test_iter() {
@@ -346,4 +444,54 @@ mod tests {
             assert!(values.contains(&i));
         }
     }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut trie: PathTrie<usize> = PathTrie::new();
+        assert!(trie.insert(&["test", "me"], 1).is_none());
+        assert!(trie.insert(&["test", "you"], 2).is_none());
+
+        let snapshot = trie.clone();
+        assert_eq!(Some(1), trie.insert(&["test", "me"], 3));
+        assert!(trie.insert(&["other"], 4).is_none());
+        assert_eq!(
+            Some(2),
+            trie.remove(&["test".to_string(), "you".to_string()])
+        );
+        assert_eq!(1, trie.retain(|v| *v != 4));
+
+        assert_eq!(Some(&1), snapshot.get_static(&["test", "me"]));
+        assert_eq!(Some(&2), snapshot.get_static(&["test", "you"]));
+        assert_eq!(None, snapshot.get_static(&["other"]));
+        assert_eq!(Some(&3), trie.get_static(&["test", "me"]));
+        assert_eq!(None, trie.get_static(&["test", "you"]));
+        assert_eq!(None, trie.get_static(&["other"]));
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut trie: PathTrie<usize> = PathTrie::new();
+
+        assert!(trie.insert(&["test"], 1).is_none());
+        assert!(trie.insert(&["test", "me", "further"], 4).is_none());
+        assert!(trie.insert(&["other"], 5).is_none());
+
+        let mut entries: Vec<(Vec<&str>, usize)> =
+            trie.entries().into_iter().map(|(k, v)| (k, *v)).collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                (vec!["other"], 5),
+                (vec!["test"], 1),
+                (vec!["test", "me", "further"], 4)
+            ]
+        );
+
+        let node = trie.get_node_static(&["test"]).expect("node");
+        let mut entries: Vec<(Vec<&str>, usize)> =
+            node.entries().into_iter().map(|(k, v)| (k, *v)).collect();
+        entries.sort();
+        assert_eq!(entries, vec![(vec![], 1), (vec!["me", "further"], 4)]);
+    }
 }
@@ -0,0 +1,449 @@
+use std::sync::Arc;
+
+/// A persistent radix tree keyed by byte slices
+///
+/// Edges are path-compressed, and nodes are shared between clones,
+/// so cloning a `ByteSliceMap` is O(1) and an update only copies the nodes
+/// on the path to the changed key.
+///
+/// Lookups work directly on borrowed bytes (e.g. a named path as it arrives from the wire),
+/// or on a sequence of path segments, which are matched as if they were joined by a separator,
+/// but without allocating the joined key.
+#[derive(Debug, Clone)]
+pub struct ByteSliceMap<V> {
+    root: Arc<Node<V>>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    /// The bytes on the edge leading into this node
+    prefix: Box<[u8]>,
+    value: Option<V>,
+    /// Sorted by the first byte of their prefix, which is unique among siblings
+    children: Vec<Arc<Node<V>>>,
+}
+
+impl<V> Node<V> {
+    fn empty() -> Self {
+        Node {
+            prefix: Box::new([]),
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn leaf(prefix: &[u8], value: V) -> Self {
+        Node {
+            prefix: prefix.into(),
+            value: Some(value),
+            children: Vec::new(),
+        }
+    }
+
+    fn find_child(&self, first: u8) -> Result<usize, usize> {
+        self.children
+            .binary_search_by(|child| child.prefix[0].cmp(&first))
+    }
+
+    fn child(&self, first: u8) -> Option<&Node<V>> {
+        self.find_child(first).ok().map(|i| &*self.children[i])
+    }
+
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+}
+
+/// The outcome of [retained](Node::retained) for a subtree
+enum Retained<V> {
+    Unchanged,
+    Changed(Node<V>),
+    Emptied,
+}
+
+impl<V: Clone> Node<V> {
+    /// Merges a valueless node with its only child, to keep the tree path-compressed
+    fn compact(&mut self) {
+        if self.value.is_none() && self.children.len() == 1 {
+            let child = self.children.pop().expect("one child");
+            let child = Arc::try_unwrap(child).unwrap_or_else(|shared| (*shared).clone());
+            let mut prefix = Vec::with_capacity(self.prefix.len() + child.prefix.len());
+            prefix.extend_from_slice(&self.prefix);
+            prefix.extend_from_slice(&child.prefix);
+            self.prefix = prefix.into_boxed_slice();
+            self.value = child.value;
+            self.children = child.children;
+        }
+    }
+
+    fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        if key.is_empty() {
+            return self.value.replace(value);
+        }
+        match self.find_child(key[0]) {
+            Ok(index) => {
+                let child = Arc::make_mut(&mut self.children[index]);
+                let common = common_prefix_len(&child.prefix, key);
+                if common < child.prefix.len() {
+                    // split the edge at the first differing byte
+                    let lower = Node {
+                        prefix: child.prefix[common..].into(),
+                        value: child.value.take(),
+                        children: std::mem::take(&mut child.children),
+                    };
+                    child.prefix = key[..common].into();
+                    child.children.push(Arc::new(lower));
+                }
+                child.insert(&key[common..], value)
+            }
+            Err(index) => {
+                self.children
+                    .insert(index, Arc::new(Node::leaf(key, value)));
+                None
+            }
+        }
+    }
+
+    /// Expects `key` to be present below this node
+    fn remove(&mut self, key: &[u8]) -> Option<V> {
+        if key.is_empty() {
+            return self.value.take();
+        }
+        let index = self.find_child(key[0]).ok()?;
+        let child = Arc::make_mut(&mut self.children[index]);
+        let prefix_len = child.prefix.len();
+        let removed = child.remove(&key[prefix_len..]);
+        if child.is_empty() {
+            self.children.remove(index);
+        } else {
+            child.compact();
+        }
+        removed
+    }
+
+    /// Checks every value below this node exactly once, bottom-up
+    ///
+    /// `key` holds the bytes leading up to this node, excluding its own prefix,
+    /// and the keys of all removed values are added to `removed`.
+    /// Only the nodes on paths to removed values are copied,
+    /// so all other subtrees stay shared with earlier versions of the tree.
+    fn retained<F>(
+        &self,
+        condition: &F,
+        key: &mut Vec<u8>,
+        removed: &mut Vec<Vec<u8>>,
+    ) -> Retained<V>
+    where
+        F: Fn(&V) -> bool,
+    {
+        key.extend_from_slice(&self.prefix);
+        let remove_value = self.value.as_ref().map_or(false, |v| !condition(v));
+        if remove_value {
+            removed.push(key.clone());
+        }
+        let changed_children: Vec<(usize, Retained<V>)> = self
+            .children
+            .iter()
+            .enumerate()
+            .filter_map(
+                |(index, child)| match child.retained(condition, key, removed) {
+                    Retained::Unchanged => None,
+                    changed => Some((index, changed)),
+                },
+            )
+            .collect();
+        key.truncate(key.len() - self.prefix.len());
+        if !remove_value && changed_children.is_empty() {
+            return Retained::Unchanged;
+        }
+        let mut node = self.clone();
+        if remove_value {
+            node.value = None;
+        }
+        // back to front, so removing children doesn't shift the remaining indices
+        for (index, child) in changed_children.into_iter().rev() {
+            match child {
+                Retained::Changed(mut child) => {
+                    child.compact();
+                    node.children[index] = Arc::new(child);
+                }
+                Retained::Emptied => {
+                    node.children.remove(index);
+                }
+                Retained::Unchanged => unreachable!("Unchanged children are filtered out"),
+            }
+        }
+        if node.is_empty() {
+            Retained::Emptied
+        } else {
+            Retained::Changed(node)
+        }
+    }
+}
+
+impl<V> ByteSliceMap<V> {
+    /// Creates a new, empty map
+    pub fn new() -> Self {
+        ByteSliceMap {
+            root: Arc::new(Node::empty()),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of entries in the map
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no entries
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the value at `key`
+    pub fn get(&self, key: &[u8]) -> Option<&V> {
+        let mut node: &Node<V> = &self.root;
+        let mut key = key;
+        while let Some(first) = key.first() {
+            let child = node.child(*first)?;
+            key = key.strip_prefix(&*child.prefix)?;
+            node = child;
+        }
+        node.value.as_ref()
+    }
+
+    /// Returns the value at the key made of `segments` joined by `separator`
+    pub fn get_segments<S>(&self, segments: &[S], separator: u8) -> Option<&V>
+    where
+        S: AsRef<str>,
+    {
+        let mut key = segments.iter().enumerate().flat_map(|(i, segment)| {
+            let separator = if i > 0 { Some(separator) } else { None };
+            separator.into_iter().chain(segment.as_ref().bytes())
+        });
+        let mut node: &Node<V> = &self.root;
+        while let Some(first) = key.next() {
+            let child = node.child(first)?;
+            for expected in child.prefix[1..].iter() {
+                if key.next()? != *expected {
+                    return None;
+                }
+            }
+            node = child;
+        }
+        node.value.as_ref()
+    }
+}
+
+impl<V: Clone> ByteSliceMap<V> {
+    /// Inserts `value` at `key`, returning the previous value, if any
+    pub fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let previous = Arc::make_mut(&mut self.root).insert(key, value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Removes and returns the value at `key`, if any
+    pub fn remove(&mut self, key: &[u8]) -> Option<V> {
+        // Avoid copying any nodes, if there is nothing to remove
+        self.get(key)?;
+        let removed = Arc::make_mut(&mut self.root).remove(key);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Removes all values that do not meet `condition`
+    ///
+    /// Returns the keys of all removed values.
+    pub fn retain<F>(&mut self, condition: F) -> Vec<Vec<u8>>
+    where
+        F: Fn(&V) -> bool,
+    {
+        let mut removed = Vec::new();
+        match self
+            .root
+            .retained(&condition, &mut Vec::new(), &mut removed)
+        {
+            Retained::Unchanged => (),
+            // the root is never compacted, as it has no edge to merge with
+            Retained::Changed(root) => self.root = Arc::new(root),
+            Retained::Emptied => self.root = Arc::new(Node::empty()),
+        }
+        self.len -= removed.len();
+        removed
+    }
+}
+
+impl<V> Default for ByteSliceMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn common_prefix_len(left: &[u8], right: &[u8]) -> usize {
+    left.iter()
+        .zip(right.iter())
+        .position(|(l, r)| l != r)
+        .unwrap_or_else(|| left.len().min(right.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SIZE: usize = 1000;
+
+    fn int_to_key(i: usize) -> [u8; 8] {
+        (i * 100).to_le_bytes()
+    }
+
+    #[test]
+    fn gaps() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        let k1 = [44, 1, 0, 0, 0, 0, 0, 0];
+        assert!(map.insert(&k1, 1).is_none());
+        assert_eq!(Some(&1), map.get(&k1));
+
+        let k2 = [44, 26, 0, 0, 0, 0, 0, 0];
+        assert!(map.insert(&k2, 2).is_none());
+        assert_eq!(Some(&2), map.get(&k2));
+        assert_eq!(Some(&1), map.get(&k1));
+        assert_eq!(None, map.get(&[44]));
+        assert_eq!(None, map.get(&[44, 1, 0]));
+    }
+
+    #[test]
+    fn insert_and_read() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        for i in 0..TEST_SIZE {
+            assert!(map.insert(&int_to_key(i), i).is_none());
+        }
+        assert_eq!(TEST_SIZE, map.len());
+        for i in 0..TEST_SIZE {
+            assert_eq!(Some(&i), map.get(&int_to_key(i)));
+        }
+        assert_eq!(Some(0), map.insert(&int_to_key(0), 42));
+        assert_eq!(Some(&42), map.get(&int_to_key(0)));
+        assert_eq!(TEST_SIZE, map.len());
+    }
+
+    #[test]
+    fn prefixes_and_segments() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        assert!(map.insert(b"test", 1).is_none());
+        assert!(map.insert(b"test/me", 2).is_none());
+        assert!(map.insert(b"test/you", 3).is_none());
+        assert!(map.insert(b"te", 4).is_none());
+        assert!(map.insert(b"", 0).is_none());
+
+        assert_eq!(Some(&0), map.get(b""));
+        assert_eq!(Some(&4), map.get(b"te"));
+        assert_eq!(Some(&1), map.get(b"test"));
+        assert_eq!(Some(&2), map.get(b"test/me"));
+        assert_eq!(Some(&3), map.get(b"test/you"));
+        assert_eq!(None, map.get(b"test/"));
+        assert_eq!(None, map.get(b"tes"));
+        assert_eq!(None, map.get(b"test/me/further"));
+
+        assert_eq!(Some(&0), map.get_segments::<&str>(&[], b'/'));
+        assert_eq!(Some(&1), map.get_segments(&["test"], b'/'));
+        assert_eq!(Some(&2), map.get_segments(&["test", "me"], b'/'));
+        assert_eq!(
+            Some(&3),
+            map.get_segments(&["test".to_string(), "you".to_string()], b'/')
+        );
+        assert_eq!(None, map.get_segments(&["test", ""], b'/'));
+        assert_eq!(None, map.get_segments(&["te", "st"], b'/'));
+        assert_eq!(None, map.get_segments(&["test", "me", "further"], b'/'));
+    }
+
+    #[test]
+    fn remove_and_retain() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        for i in 0..TEST_SIZE {
+            assert!(map.insert(&int_to_key(i), i).is_none());
+        }
+        for i in (0..TEST_SIZE).step_by(2) {
+            assert_eq!(Some(i), map.remove(&int_to_key(i)));
+            assert_eq!(None, map.remove(&int_to_key(i)));
+        }
+        assert_eq!(TEST_SIZE / 2, map.len());
+        let removed = map.retain(|v| v % 4 != 1);
+        assert_eq!(TEST_SIZE / 4, removed.len());
+        for i in (1..TEST_SIZE).step_by(4) {
+            assert!(removed.contains(&int_to_key(i).to_vec()), "key={}", i);
+        }
+        assert!(map.retain(|v| v % 4 != 1).is_empty());
+        for i in 0..TEST_SIZE {
+            let expected = if i % 4 == 3 { Some(&i) } else { None };
+            assert_eq!(expected, map.get(&int_to_key(i)), "key={}", i);
+        }
+        assert_eq!(TEST_SIZE / 4, map.len());
+
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        assert!(map.insert(b"test", 1).is_none());
+        assert!(map.insert(b"test/me", 2).is_none());
+        assert_eq!(Some(1), map.remove(b"test"));
+        assert_eq!(Some(&2), map.get(b"test/me"));
+        assert_eq!(Some(2), map.remove(b"test/me"));
+        assert!(map.is_empty());
+        assert!(map.root.is_empty());
+    }
+
+    #[test]
+    fn retain_only_copies_changed_subtrees() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        assert!(map.insert(b"test/me", 1).is_none());
+        assert!(map.insert(b"test/you", 2).is_none());
+        assert!(map.insert(b"other", 3).is_none());
+        assert!(map.insert(b"other/one", 4).is_none());
+
+        let snapshot = map.clone();
+        assert!(map.retain(|_| true).is_empty());
+        assert!(Arc::ptr_eq(&snapshot.root, &map.root));
+
+        assert_eq!(vec![b"test/me".to_vec()], map.retain(|v| *v != 1));
+        let unchanged = |m: &ByteSliceMap<usize>| m.root.child(b'o').map(|c| c as *const _);
+        assert_eq!(unchanged(&snapshot), unchanged(&map));
+        // the remaining value is merged into its parent edge
+        let test = map.root.child(b't').expect("test subtree");
+        assert_eq!(&*test.prefix, b"test/you");
+        assert_eq!(Some(&2), map.get(b"test/you"));
+        assert_eq!(Some(&1), snapshot.get(b"test/me"));
+        assert_eq!(3, map.len());
+
+        let mut removed = map.retain(|v| *v == 2);
+        removed.sort();
+        assert_eq!(vec![b"other".to_vec(), b"other/one".to_vec()], removed);
+        assert_eq!(1, map.len());
+        assert!(map.root.child(b'o').is_none());
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let mut map: ByteSliceMap<usize> = ByteSliceMap::new();
+        assert!(map.insert(b"test/me", 1).is_none());
+        assert!(map.insert(b"test/you", 2).is_none());
+
+        let snapshot = map.clone();
+        assert_eq!(Some(1), map.insert(b"test/me", 3));
+        assert!(map.insert(b"test", 4).is_none());
+        assert_eq!(Some(2), map.remove(b"test/you"));
+
+        assert_eq!(Some(&1), snapshot.get(b"test/me"));
+        assert_eq!(Some(&2), snapshot.get(b"test/you"));
+        assert_eq!(None, snapshot.get(b"test"));
+        assert_eq!(2, snapshot.len());
+        assert_eq!(Some(&3), map.get(b"test/me"));
+        assert_eq!(None, map.get(b"test/you"));
+        assert_eq!(Some(&4), map.get(b"test"));
+        assert_eq!(2, map.len());
+    }
+}
@@ -9,23 +9,36 @@ use std::{pin::Pin, sync::Arc};
 use crate::{
     actors::{NamedPath, Transport::Tcp},
     messaging::{
+        ActorIdentity,
         ActorRegistration,
+        Deregistration,
         DispatchData,
         DispatchEnvelope,
         EventEnvelope,
+        IdentifyMessage,
+        MembershipEvent,
+        MembershipMessage,
         MsgEnvelope,
         NetMessage,
+        PathEntry,
         PathResolvable,
         PolicyRegistration,
+        RegisteredPath,
         RegistrationEnvelope,
         RegistrationError,
         RegistrationEvent,
         RegistrationPromise,
+        Terminated,
+        WatchEvent,
+        WatchMessage,
     },
     net::{
         buffers::*,
         events::NetworkEvent,
+        rate_limit::{RateLimit, ThrottledPeer, TrafficDirection},
+        ConnectionLimitPolicy,
         ConnectionState,
+        NetworkBackend,
         NetworkBridgeErr,
         Protocol,
         SocketAddr,
@@ -41,7 +54,18 @@ use futures::{
 use lookup::{ActorLookup, ActorStore, InsertResult, LookupResult};
 use queue_manager::QueueManager;
 use rustc_hash::FxHashMap;
-use std::{collections::VecDeque, net::IpAddr, time::Duration};
+use std::{cmp::Ordering, collections::VecDeque, net::IpAddr, time::Duration};
+use uuid::Uuid;
+
+/// The alias under which each [NetworkDispatcher](NetworkDispatcher) registers itself
+///
+/// Remote dispatchers use it to exchange death-watch messages.
+pub(crate) const DISPATCHER_ALIAS: &str = "$dispatcher";
+
+/// Returns the path of the dispatcher on the given `system`
+fn dispatcher_path(system: &SystemPath) -> ActorPath {
+    NamedPath::with_system(system.clone(), vec![DISPATCHER_ALIAS.to_string()]).into()
+}
 
 pub mod lookup;
 pub mod queue_manager;
@@ -50,6 +74,7 @@ pub mod queue_manager;
 const RETRY_CONNECTIONS_INTERVAL: u64 = 5000;
 const BOOT_TIMEOUT: u64 = 5000;
 const MAX_RETRY_ATTEMPTS: u8 = 10;
+const IDENTIFY_TIMEOUT: u64 = 10_000;
 
 type NetHashMap<K, V> = FxHashMap<K, V>;
 
@@ -77,6 +102,13 @@ pub struct NetworkConfig {
     max_connection_retry_attempts: u8,
     connection_retry_interval: u64,
     boot_timeout: u64,
+    backend: NetworkBackend,
+    inbound_rate_limit: Option<RateLimit>,
+    outbound_rate_limit: Option<RateLimit>,
+    idle_timeout: Option<u64>,
+    max_connections: Option<usize>,
+    connection_limit_policy: ConnectionLimitPolicy,
+    identify_timeout: u64,
 }
 
 impl NetworkConfig {
@@ -92,6 +124,13 @@ impl NetworkConfig {
             max_connection_retry_attempts: MAX_RETRY_ATTEMPTS,
             connection_retry_interval: RETRY_CONNECTIONS_INTERVAL,
             boot_timeout: BOOT_TIMEOUT,
+            backend: NetworkBackend::default(),
+            inbound_rate_limit: None,
+            outbound_rate_limit: None,
+            idle_timeout: None,
+            max_connections: None,
+            connection_limit_policy: ConnectionLimitPolicy::default(),
+            identify_timeout: IDENTIFY_TIMEOUT,
         }
     }
 
@@ -121,6 +160,13 @@ impl NetworkConfig {
             max_connection_retry_attempts: MAX_RETRY_ATTEMPTS,
             connection_retry_interval: RETRY_CONNECTIONS_INTERVAL,
             boot_timeout: BOOT_TIMEOUT,
+            backend: NetworkBackend::default(),
+            inbound_rate_limit: None,
+            outbound_rate_limit: None,
+            idle_timeout: None,
+            max_connections: None,
+            connection_limit_policy: ConnectionLimitPolicy::default(),
+            identify_timeout: IDENTIFY_TIMEOUT,
         }
     }
 
@@ -203,6 +249,101 @@ impl NetworkConfig {
     pub fn get_boot_timeout(&self) -> u64 {
         self.boot_timeout
     }
+
+    /// Selects the I/O backend the `NetworkThread` is driven by.
+    ///
+    /// Default value is [NetworkBackend::Mio].
+    /// If the selected backend can not be set up on the host, the `NetworkThread` falls back to `mio`.
+    pub fn set_backend(&mut self, backend: NetworkBackend) {
+        self.backend = backend;
+    }
+
+    /// Returns the I/O backend the `NetworkThread` is driven by.
+    pub fn get_backend(&self) -> NetworkBackend {
+        self.backend
+    }
+
+    /// Limits the rate at which messages are received from remote systems.
+    ///
+    /// Default value is `None`, i.e. inbound traffic is not limited.
+    pub fn set_inbound_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.inbound_rate_limit = limit;
+    }
+
+    /// Returns the limit on the rate at which messages are received from remote systems.
+    pub fn get_inbound_rate_limit(&self) -> Option<&RateLimit> {
+        self.inbound_rate_limit.as_ref()
+    }
+
+    /// Limits the rate at which messages are sent to remote systems.
+    ///
+    /// Default value is `None`, i.e. outbound traffic is not limited.
+    pub fn set_outbound_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.outbound_rate_limit = limit;
+    }
+
+    /// Returns the limit on the rate at which messages are sent to remote systems.
+    pub fn get_outbound_rate_limit(&self) -> Option<&RateLimit> {
+        self.outbound_rate_limit.as_ref()
+    }
+
+    /// Configures how long (in ms) a connection may go without sending or receiving messages
+    /// before it is gracefully closed.
+    ///
+    /// Closed connections are reported as [ConnectionClosed](NetworkStatus::ConnectionClosed)
+    /// and re-established when the next message is sent to the remote system.
+    ///
+    /// Default value is `None`, i.e. idle connections are kept open.
+    pub fn set_idle_timeout(&mut self, milliseconds: Option<u64>) {
+        self.idle_timeout = milliseconds;
+    }
+
+    /// How long (in ms) a connection may be idle before it is gracefully closed.
+    pub fn get_idle_timeout(&self) -> Option<u64> {
+        self.idle_timeout
+    }
+
+    /// Limits how many TCP connections the `NetworkThread` keeps open at the same time.
+    ///
+    /// What happens to connections beyond the limit is determined by the
+    /// [ConnectionLimitPolicy](NetworkConfig::set_connection_limit_policy).
+    ///
+    /// Default value is `None`, i.e. the number of connections is not limited.
+    pub fn set_max_connections(&mut self, max: Option<usize>) {
+        self.max_connections = max;
+    }
+
+    /// How many TCP connections the `NetworkThread` keeps open at the same time.
+    pub fn get_max_connections(&self) -> Option<usize> {
+        self.max_connections
+    }
+
+    /// Selects what happens to new connections once the maximum number of connections is reached.
+    ///
+    /// Default value is [ConnectionLimitPolicy::Reject].
+    pub fn set_connection_limit_policy(&mut self, policy: ConnectionLimitPolicy) {
+        self.connection_limit_policy = policy;
+    }
+
+    /// Returns what happens to new connections once the maximum number of connections is reached.
+    pub fn get_connection_limit_policy(&self) -> ConnectionLimitPolicy {
+        self.connection_limit_policy
+    }
+
+    /// Configures how long (in ms) to wait for a remote system to answer an
+    /// [identify](KompactSystem::identify) or [list](KompactSystem::list_paths) query.
+    ///
+    /// The future of an unanswered query fails once the timeout expires.
+    ///
+    /// Default value is 10000 ms.
+    pub fn set_identify_timeout(&mut self, milliseconds: u64) {
+        self.identify_timeout = milliseconds;
+    }
+
+    /// How long (in ms) to wait for a remote system to answer an identify or list query.
+    pub fn get_identify_timeout(&self) -> u64 {
+        self.identify_timeout
+    }
 }
 
 /// Socket defaults to `127.0.0.1:0` (i.e. a random local port) and protocol is [TCP](Transport::Tcp)
@@ -217,6 +358,13 @@ impl Default for NetworkConfig {
             max_connection_retry_attempts: MAX_RETRY_ATTEMPTS,
             connection_retry_interval: RETRY_CONNECTIONS_INTERVAL,
             boot_timeout: BOOT_TIMEOUT,
+            backend: NetworkBackend::default(),
+            inbound_rate_limit: None,
+            outbound_rate_limit: None,
+            idle_timeout: None,
+            max_connections: None,
+            connection_limit_policy: ConnectionLimitPolicy::default(),
+            identify_timeout: IDENTIFY_TIMEOUT,
         }
     }
 }
@@ -250,6 +398,12 @@ pub enum NetworkStatus {
     UnblockedSystem(SystemPath),
     /// Indicates that an IpAddr has been allowed after previously being blocked
     UnblockedIp(IpAddr),
+    /// Indicates that traffic with a remote system or host exceeds the configured
+    /// [RateLimit] in the given direction and is being delayed or dropped
+    PeerThrottled(ThrottledPeer, TrafficDirection),
+    /// Indicates that traffic with a previously throttled remote system or host
+    /// is no longer being held back
+    PeerUnthrottled(ThrottledPeer, TrafficDirection),
 }
 
 /// Sent by Actors and Components to request information about the Network
@@ -307,6 +461,38 @@ pub struct NetworkDispatcher {
     garbage_buffers: VecDeque<BufferChunk>,
     /// The dispatcher emits NetworkStatusUpdates to the `NetworkStatusPort`.
     network_status_port: ProvidedPort<NetworkStatusPort>,
+    /// Watchers of local actors, indexed by the watched path
+    ///
+    /// Checked when watched components terminate and when actors are deregistered.
+    watchers: FxHashMap<ActorPath, Vec<ActorPath>>,
+    /// `(target, watcher)` pairs of local watchers of remote actors, indexed by the remote address
+    remote_watches: FxHashMap<SocketAddr, Vec<(ActorPath, ActorPath)>>,
+    /// Identify requests awaiting a response, with the address of the remote system they were sent to
+    pending_identifies: FxHashMap<Uuid, (SocketAddr, PendingQuery)>,
+    /// Watchers of the members under local prefixes, indexed by the prefix's segments
+    member_watches: FxHashMap<Vec<String>, MemberWatch>,
+    /// Local watchers of the members under remote prefixes, indexed by the remote address
+    remote_member_watches: FxHashMap<SocketAddr, Vec<RemoteMemberWatch>>,
+}
+
+/// The watchers of the members under a local prefix, together with the members they were told about
+#[derive(Default)]
+struct MemberWatch {
+    watchers: Vec<ActorPath>,
+    members: Vec<RegisteredPath>,
+}
+
+/// A local watcher of the members under a remote prefix, together with the members it was told about
+struct RemoteMemberWatch {
+    watcher: ActorPath,
+    prefix: NamedPath,
+    members: Vec<RegisteredPath>,
+}
+
+/// A query to a remote dispatcher's [ActorStore](lookup::ActorStore) awaiting its response
+enum PendingQuery {
+    Identify(KPromise<ActorIdentity>),
+    List(KPromise<Vec<RegisteredPath>>),
 }
 
 impl NetworkDispatcher {
@@ -353,6 +539,11 @@ impl NetworkDispatcher {
             garbage_buffers: VecDeque::new(),
             retry_map: Default::default(),
             network_status_port: ProvidedPort::uninitialised(),
+            watchers: Default::default(),
+            remote_watches: Default::default(),
+            pending_identifies: Default::default(),
+            member_watches: Default::default(),
+            remote_member_watches: Default::default(),
         }
     }
 
@@ -394,16 +585,23 @@ impl NetworkDispatcher {
         );
 
         let deadletter: DynActorRef = self.ctx.system().deadletter_ref().dyn_ref();
+        let own_ref: DynActorRef = self.actor_ref().dyn_ref();
         self.lookup.rcu(|current| {
             let mut next = ActorStore::clone(current);
             next.insert(PathResolvable::System, deadletter.clone())
                 .expect("Deadletter shouldn't error");
+            next.insert(
+                PathResolvable::Alias(DISPATCHER_ALIAS.to_string()),
+                own_ref.clone(),
+            )
+            .expect("Dispatcher alias shouldn't error");
             next
         });
 
         bridge.set_dispatcher(dispatcher);
         self.schedule_retries();
         self.net_bridge = Some(bridge);
+        let _ = self.system_path(); // fill the cache for is_local
     }
 
     fn stop(&mut self) -> () {
@@ -442,6 +640,8 @@ impl NetworkDispatcher {
                 self.reaper.strategy_mut().decr();
             }
         }
+        self.check_watched_actors();
+        self.check_member_watches();
         let next_wakeup = self.reaper.strategy().curr();
         debug!(
             self.ctx().log(),
@@ -490,6 +690,10 @@ impl NetworkDispatcher {
                 );
                 self.queue_manager.drop_queue(&addr);
                 self.connections.remove(&addr);
+                self.remote_system_unreachable(addr);
+                // Dropping the promises fails the pending futures
+                self.pending_identifies
+                    .retain(|_, (remote, _)| *remote != addr);
                 self.network_status_port
                     .trigger(NetworkStatus::ConnectionDropped(SystemPath::with_socket(
                         Transport::Tcp,
@@ -525,6 +729,14 @@ impl NetworkDispatcher {
                     // These are messages which we routed to a network-thread before they lost the connection.
                     self.queue_manager.enqueue_priority_data(data, addr);
                 }
+                NetworkEvent::ConnectionRejected(addr) => {
+                    debug!(
+                        self.ctx().log(),
+                        "Connection to {} rejected by the connection limit", addr
+                    );
+                    // Retried until the retry attempts are used up and the queue is dropped
+                    self.retry_map.entry(addr).or_insert(0);
+                }
                 NetworkEvent::BlockedSocket(socket_addr, trigger_status_port) => {
                     let sys_path = SystemPath::new(Tcp, socket_addr.ip(), socket_addr.port());
                     self.connections
@@ -550,6 +762,14 @@ impl NetworkDispatcher {
                     self.network_status_port
                         .trigger(NetworkStatus::UnblockedIp(ip_addr));
                 }
+                NetworkEvent::Throttled(peer, direction) => {
+                    self.network_status_port
+                        .trigger(NetworkStatus::PeerThrottled(peer, direction));
+                }
+                NetworkEvent::Unthrottled(peer, direction) => {
+                    self.network_status_port
+                        .trigger(NetworkStatus::PeerUnthrottled(peer, direction));
+                }
             },
         }
     }
@@ -572,6 +792,7 @@ impl NetworkDispatcher {
                         session,
                     ));
                 let _ = self.retry_map.remove(&addr);
+                self.renew_remote_watches(addr);
                 if self.queue_manager.has_data(&addr) {
                     // Drain as much as possible
                     while let Some(frame) = self.queue_manager.pop_data(&addr) {
@@ -715,6 +936,7 @@ impl NetworkDispatcher {
             ConnectionState::Closed(_) => {
                 self.queue_manager.enqueue_data(data, addr);
                 if let Some(bridge) = &self.net_bridge {
+                    self.retry_map.insert(addr, 0); // Make sure we will re-request connection later
                     bridge.connect(Tcp, addr)?;
                 }
                 Some(ConnectionState::Initializing)
@@ -781,6 +1003,706 @@ impl NetworkDispatcher {
         }
     }
 
+    fn is_local(&self, path: &ActorPath) -> bool {
+        Self::is_local_to(self.system_path.as_ref(), path)
+    }
+
+    fn is_local_to(own: Option<&SystemPath>, path: &ActorPath) -> bool {
+        own.map_or(false, |own| own == path.system())
+            || path.system().protocol() == Transport::Local
+    }
+
+    /// Returns `true` if `path` lives on the remote system at `addr`
+    fn is_remote_at(path: &ActorPath, addr: SocketAddr) -> bool {
+        path.system().protocol() != Transport::Local && path.system().socket_address() == addr
+    }
+
+    fn on_watch(&mut self, event: WatchEvent) {
+        match event {
+            WatchEvent::Watch { watcher, target } => {
+                if self.is_local(&target) {
+                    self.add_watcher(watcher, target);
+                } else {
+                    let addr = target.system().socket_address();
+                    let watches = self.remote_watches.entry(addr).or_default();
+                    let entry = (target, watcher);
+                    if !watches.contains(&entry) {
+                        watches.push(entry.clone());
+                    }
+                    let (target, watcher) = entry;
+                    self.send_watch_message(WatchMessage::Watch { watcher, target });
+                }
+            }
+            WatchEvent::Unwatch { watcher, target } => {
+                if self.is_local(&target) {
+                    self.remove_watcher(&watcher, &target);
+                } else {
+                    let addr = target.system().socket_address();
+                    if let Some(watches) = self.remote_watches.get_mut(&addr) {
+                        watches.retain(|(t, w)| !(t == &target && w == &watcher));
+                        if watches.is_empty() {
+                            self.remote_watches.remove(&addr);
+                        }
+                    }
+                    self.send_watch_message(WatchMessage::Unwatch { watcher, target });
+                }
+            }
+            WatchEvent::WatchMembers { watcher, prefix } => {
+                if self.is_local(&prefix.clone().into()) {
+                    self.add_member_watcher(watcher, prefix);
+                } else {
+                    let addr = prefix.system().socket_address();
+                    let watches = self.remote_member_watches.entry(addr).or_default();
+                    if !watches
+                        .iter()
+                        .any(|w| w.watcher == watcher && w.prefix == prefix)
+                    {
+                        watches.push(RemoteMemberWatch {
+                            watcher: watcher.clone(),
+                            prefix: prefix.clone(),
+                            members: Vec::new(),
+                        });
+                    }
+                    self.send_membership_message(MembershipMessage::Watch { watcher, prefix });
+                }
+            }
+            WatchEvent::UnwatchMembers { watcher, prefix } => {
+                if self.is_local(&prefix.clone().into()) {
+                    self.remove_member_watcher(&watcher, &prefix);
+                } else {
+                    let addr = prefix.system().socket_address();
+                    if let Some(watches) = self.remote_member_watches.get_mut(&addr) {
+                        watches.retain(|w| !(w.watcher == watcher && w.prefix == prefix));
+                        if watches.is_empty() {
+                            self.remote_member_watches.remove(&addr);
+                        }
+                    }
+                    self.send_membership_message(MembershipMessage::Unwatch { watcher, prefix });
+                }
+            }
+            WatchEvent::Terminated(id) => {
+                debug!(self.ctx.log(), "Watched component {} terminated", id);
+                self.check_watched_actors();
+            }
+        }
+    }
+
+    /// Registers `watcher` for the local `target`, notifying it immediately if `target` is already gone
+    fn add_watcher(&mut self, watcher: ActorPath, target: ActorPath) {
+        let registered = match self.lookup.load().get_by_actor_path(&target) {
+            LookupResult::Ref(actor) => {
+                // Marked before checking, so a concurrent termination can't go unnoticed
+                actor.mark_watched();
+                !actor.is_terminated()
+            }
+            LookupResult::Group(_) => true,
+            LookupResult::None | LookupResult::Err(_) => false,
+        };
+        if !registered {
+            debug!(
+                self.ctx.log(),
+                "Watched actor {} is not registered, notifying {}", target, watcher
+            );
+            self.notify_terminated(watcher, target);
+            return;
+        }
+        let watchers = self.watchers.entry(target).or_default();
+        if !watchers.contains(&watcher) {
+            watchers.push(watcher);
+        }
+    }
+
+    fn remove_watcher(&mut self, watcher: &ActorPath, target: &ActorPath) {
+        if let Some(watchers) = self.watchers.get_mut(target) {
+            watchers.retain(|w| w != watcher);
+            if watchers.is_empty() {
+                self.watchers.remove(target);
+            }
+        }
+    }
+
+    fn is_registered(lookup: &ActorStore, path: &ActorPath) -> bool {
+        match lookup.get_by_actor_path(path) {
+            LookupResult::Ref(actor) => !actor.is_terminated(),
+            LookupResult::Group(_) => true,
+            LookupResult::None | LookupResult::Err(_) => false,
+        }
+    }
+
+    /// Notifies the watchers of all local actors that were killed or deregistered
+    ///
+    /// Local watchers that are gone themselves are dropped without being notified.
+    fn check_watched_actors(&mut self) {
+        if self.watchers.is_empty() {
+            return;
+        }
+        let own = self.system_path.clone();
+        let lookup = self.lookup.load();
+        self.watchers.retain(|_, watchers| {
+            watchers.retain(|watcher| {
+                !Self::is_local_to(own.as_ref(), watcher) || Self::is_registered(&lookup, watcher)
+            });
+            !watchers.is_empty()
+        });
+        let gone: Vec<ActorPath> = self
+            .watchers
+            .keys()
+            .filter(|target| !Self::is_registered(&lookup, target))
+            .cloned()
+            .collect();
+        drop(lookup);
+        for target in gone {
+            if let Some(watchers) = self.watchers.remove(&target) {
+                debug!(
+                    self.ctx.log(),
+                    "Watched actor {} is gone, notifying {} watcher(s)",
+                    target,
+                    watchers.len()
+                );
+                for watcher in watchers {
+                    self.notify_terminated(watcher, target.clone());
+                }
+            }
+        }
+    }
+
+    /// Notifies all local watchers of actors on the system at `addr`, which is deemed unreachable
+    ///
+    /// Membership watches are kept, as the members are added again once the system is reachable.
+    fn remote_system_unreachable(&mut self, addr: SocketAddr) {
+        if let Some(watches) = self.remote_watches.remove(&addr) {
+            for (target, watcher) in watches {
+                self.notify_terminated(watcher, target);
+            }
+        }
+        self.drop_remote_watchers(addr);
+        let mut removed: Vec<(ActorPath, NamedPath, MembershipEvent)> = Vec::new();
+        if let Some(watches) = self.remote_member_watches.get_mut(&addr) {
+            for watch in watches.iter_mut() {
+                for member in watch.members.drain(..) {
+                    let event = MembershipEvent::MemberRemoved(member);
+                    removed.push((watch.watcher.clone(), watch.prefix.clone(), event));
+                }
+            }
+        }
+        for (watcher, prefix, event) in removed {
+            self.notify_membership(watcher, prefix, event);
+        }
+    }
+
+    /// Drops all (membership) watchers on the system at `addr`, which won't be notified anymore
+    ///
+    /// The remote dispatcher renews its watches, once the systems are connected again.
+    fn drop_remote_watchers(&mut self, addr: SocketAddr) {
+        self.watchers.retain(|_, watchers| {
+            watchers.retain(|watcher| !Self::is_remote_at(watcher, addr));
+            !watchers.is_empty()
+        });
+        self.member_watches.retain(|_, watch| {
+            watch
+                .watchers
+                .retain(|watcher| !Self::is_remote_at(watcher, addr));
+            !watch.watchers.is_empty()
+        });
+    }
+
+    /// Re-sends all watch requests to the system at `addr`, which may have lost them
+    fn renew_remote_watches(&mut self, addr: SocketAddr) {
+        if let Some(watches) = self.remote_watches.get(&addr).cloned() {
+            for (target, watcher) in watches {
+                self.send_watch_message(WatchMessage::Watch { watcher, target });
+            }
+        }
+        let member_watches: Vec<(ActorPath, NamedPath)> = self
+            .remote_member_watches
+            .get(&addr)
+            .map(|watches| {
+                watches
+                    .iter()
+                    .map(|w| (w.watcher.clone(), w.prefix.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for (watcher, prefix) in member_watches {
+            self.send_membership_message(MembershipMessage::Watch { watcher, prefix });
+        }
+    }
+
+    /// Lists the actors registered at or below the local `prefix`, sorted by path
+    fn list_members(
+        lookup: &ActorStore,
+        system: &SystemPath,
+        prefix: &[String],
+    ) -> Vec<RegisteredPath> {
+        let mut members: Vec<RegisteredPath> = lookup
+            .list(prefix)
+            .into_iter()
+            .filter(|(_, entry)| matches!(entry, PathEntry::Actor(_)))
+            .map(|(path, entry)| RegisteredPath {
+                path: NamedPath::with_system(system.clone(), path),
+                entry,
+            })
+            .collect();
+        members.sort_by(|a, b| a.path.path_ref().cmp(b.path.path_ref()));
+        members
+    }
+
+    /// Diffs the `old` against the `current` members, both sorted by path
+    ///
+    /// Returns the events for all removed members, followed by those for all added members.
+    fn diff_members(old: &[RegisteredPath], current: &[RegisteredPath]) -> Vec<MembershipEvent> {
+        let mut removed: Vec<MembershipEvent> = Vec::new();
+        let mut added: Vec<MembershipEvent> = Vec::new();
+        let mut old = old.iter().peekable();
+        let mut current = current.iter().peekable();
+        loop {
+            match (old.peek(), current.peek()) {
+                (Some(o), Some(c)) => match o.path.path_ref().cmp(c.path.path_ref()) {
+                    Ordering::Less => {
+                        removed.push(MembershipEvent::MemberRemoved((*o).clone()));
+                        old.next();
+                    }
+                    Ordering::Greater => {
+                        added.push(MembershipEvent::MemberAdded((*c).clone()));
+                        current.next();
+                    }
+                    Ordering::Equal => {
+                        // the path was re-registered with a different entry in between
+                        if o != c {
+                            removed.push(MembershipEvent::MemberRemoved((*o).clone()));
+                            added.push(MembershipEvent::MemberAdded((*c).clone()));
+                        }
+                        old.next();
+                        current.next();
+                    }
+                },
+                (Some(o), None) => {
+                    removed.push(MembershipEvent::MemberRemoved((*o).clone()));
+                    old.next();
+                }
+                (None, Some(c)) => {
+                    added.push(MembershipEvent::MemberAdded((*c).clone()));
+                    current.next();
+                }
+                (None, None) => break,
+            }
+        }
+        removed.append(&mut added);
+        removed
+    }
+
+    /// Registers `watcher` for the members under the local `prefix`, telling it about all current members
+    fn add_member_watcher(&mut self, watcher: ActorPath, prefix: NamedPath) {
+        let system = self.system_path();
+        let lookup = self.lookup.load();
+        let watch = self
+            .member_watches
+            .entry(prefix.clone_path())
+            .or_insert_with(|| MemberWatch {
+                watchers: Vec::new(),
+                members: Self::list_members(&lookup, &system, prefix.path_ref()),
+            });
+        drop(lookup);
+        if !watch.watchers.contains(&watcher) {
+            watch.watchers.push(watcher.clone());
+        }
+        // (re-)watching always starts with the current members
+        let members = watch.members.clone();
+        debug!(
+            self.ctx.log(),
+            "{} is watching the {} members under {:?}",
+            watcher,
+            members.len(),
+            prefix
+        );
+        for member in members {
+            let event = MembershipEvent::MemberAdded(member);
+            self.notify_membership(watcher.clone(), prefix.clone(), event);
+        }
+        if !self.reaper.is_scheduled() {
+            self.schedule_reaper();
+        }
+    }
+
+    fn remove_member_watcher(&mut self, watcher: &ActorPath, prefix: &NamedPath) {
+        if let Some(watch) = self.member_watches.get_mut(prefix.path_ref()) {
+            watch.watchers.retain(|w| w != watcher);
+            if watch.watchers.is_empty() {
+                self.member_watches.remove(prefix.path_ref());
+            }
+        }
+    }
+
+    /// Notifies the membership watchers of the local prefixes of `path` that `member` is registered there
+    ///
+    /// A `member` of `None` means that no actor is registered at `path` anymore.
+    /// Only the watched prefixes of `path` are checked, while listing the members
+    /// of all watched prefixes is left to [check_member_watches](NetworkDispatcher::check_member_watches).
+    fn update_member_watches(&mut self, path: &[String], member: Option<PathEntry>) {
+        if self.member_watches.is_empty() {
+            return;
+        }
+        let system = self.system_path();
+        let current = member.map(|entry| RegisteredPath {
+            path: NamedPath::with_system(system.clone(), path.to_vec()),
+            entry,
+        });
+        let mut changes: Vec<(ActorPath, NamedPath, MembershipEvent)> = Vec::new();
+        for (prefix, watch) in self.member_watches.iter_mut() {
+            if !lookup::is_listed_under(prefix, path) {
+                continue;
+            }
+            let mut events: Vec<MembershipEvent> = Vec::new();
+            match watch
+                .members
+                .binary_search_by(|m| m.path.path_ref().cmp(path))
+            {
+                Ok(index) if current.as_ref() == Some(&watch.members[index]) => (),
+                Ok(index) => {
+                    let previous = match current.clone() {
+                        Some(current) => std::mem::replace(&mut watch.members[index], current),
+                        None => watch.members.remove(index),
+                    };
+                    events.push(MembershipEvent::MemberRemoved(previous));
+                    events.extend(current.clone().map(MembershipEvent::MemberAdded));
+                }
+                Err(index) => {
+                    if let Some(current) = current.clone() {
+                        watch.members.insert(index, current.clone());
+                        events.push(MembershipEvent::MemberAdded(current));
+                    }
+                }
+            }
+            if events.is_empty() {
+                continue;
+            }
+            let prefix = NamedPath::with_system(system.clone(), prefix.clone());
+            for watcher in watch.watchers.iter() {
+                for event in events.iter() {
+                    changes.push((watcher.clone(), prefix.clone(), event.clone()));
+                }
+            }
+        }
+        for (watcher, prefix, event) in changes {
+            self.notify_membership(watcher, prefix, event);
+        }
+    }
+
+    /// Notifies the membership watchers of all local prefixes whose members changed
+    ///
+    /// Local watchers that are gone themselves are dropped without being notified.
+    /// As this lists the members of every watched prefix, it is only run by the reaper.
+    fn check_member_watches(&mut self) {
+        if self.member_watches.is_empty() {
+            return;
+        }
+        let system = self.system_path();
+        let lookup = self.lookup.load();
+        self.member_watches.retain(|_, watch| {
+            watch.watchers.retain(|watcher| {
+                !Self::is_local_to(Some(&system), watcher) || Self::is_registered(&lookup, watcher)
+            });
+            !watch.watchers.is_empty()
+        });
+        let mut changes: Vec<(ActorPath, NamedPath, MembershipEvent)> = Vec::new();
+        for (prefix, watch) in self.member_watches.iter_mut() {
+            let current = Self::list_members(&lookup, &system, prefix);
+            if current == watch.members {
+                continue;
+            }
+            let events = Self::diff_members(&watch.members, &current);
+            let prefix = NamedPath::with_system(system.clone(), prefix.clone());
+            for watcher in watch.watchers.iter() {
+                for event in events.iter() {
+                    changes.push((watcher.clone(), prefix.clone(), event.clone()));
+                }
+            }
+            watch.members = current;
+        }
+        drop(lookup);
+        for (watcher, prefix, event) in changes {
+            self.notify_membership(watcher, prefix, event);
+        }
+    }
+
+    fn notify_membership(&mut self, watcher: ActorPath, prefix: NamedPath, event: MembershipEvent) {
+        if self.is_local(&watcher) {
+            let msg = DispatchData::Lazy(Box::new(event), prefix.into(), watcher.clone());
+            if let Err(e) = self.route(watcher, msg) {
+                error!(self.ctx.log(), "Failed to route MembershipEvent: {:?}", e);
+            }
+        } else {
+            self.send_membership_message(MembershipMessage::Changed {
+                watcher,
+                prefix,
+                event,
+            });
+        }
+    }
+
+    /// Sends `msg` to the dispatcher of the remote side of the membership watch
+    fn send_membership_message(&mut self, msg: MembershipMessage) {
+        let remote = match &msg {
+            MembershipMessage::Watch { prefix, .. } | MembershipMessage::Unwatch { prefix, .. } => {
+                prefix.system()
+            }
+            MembershipMessage::Changed { watcher, .. } => watcher.system(),
+        };
+        let dst = dispatcher_path(remote);
+        let src = dispatcher_path(self.system_path_ref());
+        if let Err(e) = self.route(dst.clone(), DispatchData::Lazy(Box::new(msg), src, dst)) {
+            error!(
+                self.ctx.log(),
+                "Failed to route membership message: {:?}", e
+            );
+        }
+    }
+
+    fn on_membership_message(&mut self, msg: MembershipMessage) {
+        match msg {
+            MembershipMessage::Watch { watcher, prefix } => {
+                self.add_member_watcher(watcher, prefix)
+            }
+            MembershipMessage::Unwatch { watcher, prefix } => {
+                self.remove_member_watcher(&watcher, &prefix)
+            }
+            MembershipMessage::Changed {
+                watcher,
+                prefix,
+                event,
+            } => {
+                let addr = prefix.system().socket_address();
+                let watch = self
+                    .remote_member_watches
+                    .get_mut(&addr)
+                    .and_then(|watches| {
+                        watches
+                            .iter_mut()
+                            .find(|w| w.watcher == watcher && w.prefix == prefix)
+                    });
+                // drop duplicates, e.g. from renewed watches
+                let changed = match (watch, &event) {
+                    (Some(watch), MembershipEvent::MemberAdded(member)) => {
+                        if watch.members.contains(member) {
+                            false
+                        } else {
+                            watch.members.push(member.clone());
+                            true
+                        }
+                    }
+                    (Some(watch), MembershipEvent::MemberRemoved(member)) => {
+                        let before = watch.members.len();
+                        watch.members.retain(|m| m != member);
+                        watch.members.len() != before
+                    }
+                    (None, _) => {
+                        debug!(
+                            self.ctx.log(),
+                            "Dropping membership change under {:?}, which {} doesn't watch anymore",
+                            prefix,
+                            watcher
+                        );
+                        false
+                    }
+                };
+                if changed {
+                    self.notify_membership(watcher, prefix, event);
+                }
+            }
+        }
+    }
+
+    fn notify_terminated(&mut self, watcher: ActorPath, target: ActorPath) {
+        if self.is_local(&watcher) {
+            let msg = DispatchData::Lazy(
+                Box::new(Terminated(target.clone())),
+                target,
+                watcher.clone(),
+            );
+            if let Err(e) = self.route(watcher, msg) {
+                error!(self.ctx.log(), "Failed to route Terminated: {:?}", e);
+            }
+        } else {
+            self.send_watch_message(WatchMessage::Terminated { watcher, target });
+        }
+    }
+
+    /// Sends `msg` to the dispatcher of the remote side of the watch
+    fn send_watch_message(&mut self, msg: WatchMessage) {
+        let remote = match &msg {
+            WatchMessage::Watch { target, .. } | WatchMessage::Unwatch { target, .. } => {
+                target.system()
+            }
+            WatchMessage::Terminated { watcher, .. } => watcher.system(),
+        };
+        let dst = dispatcher_path(remote);
+        let src = dispatcher_path(self.system_path_ref());
+        if let Err(e) = self.route(dst.clone(), DispatchData::Lazy(Box::new(msg), src, dst)) {
+            error!(self.ctx.log(), "Failed to route watch message: {:?}", e);
+        }
+    }
+
+    fn on_identify(&mut self, path: ActorPath, promise: KPromise<ActorIdentity>) {
+        if self.is_local(&path) {
+            let identity = self.identify_locally(&path);
+            promise
+                .fulfil(identity)
+                .unwrap_or_else(|e| error!(self.ctx.log(), "Could not notify listeners: {:?}", e));
+        } else {
+            let id = Uuid::new_v4();
+            let remote = path.system().clone();
+            let msg = IdentifyMessage::Request { id, path };
+            self.send_query(&remote, id, msg, PendingQuery::Identify(promise));
+        }
+    }
+
+    fn on_list_paths(&mut self, prefix: NamedPath, promise: KPromise<Vec<RegisteredPath>>) {
+        let local = self.is_local(&prefix.clone().into());
+        if local {
+            let entries = self.list_locally(&prefix);
+            promise
+                .fulfil(entries)
+                .unwrap_or_else(|e| error!(self.ctx.log(), "Could not notify listeners: {:?}", e));
+        } else {
+            let id = Uuid::new_v4();
+            let remote = prefix.system().clone();
+            let msg = IdentifyMessage::ListRequest { id, prefix };
+            self.send_query(&remote, id, msg, PendingQuery::List(promise));
+        }
+    }
+
+    fn send_query(
+        &mut self,
+        remote: &SystemPath,
+        id: Uuid,
+        msg: IdentifyMessage,
+        query: PendingQuery,
+    ) {
+        self.pending_identifies
+            .insert(id, (remote.socket_address(), query));
+        let dst = dispatcher_path(remote);
+        let src = dispatcher_path(self.system_path_ref());
+        if let Err(e) = self.route(dst.clone(), DispatchData::Lazy(Box::new(msg), src, dst)) {
+            error!(self.ctx.log(), "Failed to route identify request: {:?}", e);
+            self.pending_identifies.remove(&id);
+            return;
+        }
+        self.schedule_once(
+            Duration::from_millis(self.cfg.identify_timeout),
+            move |target, _id| {
+                target.expire_query(id);
+                Handled::Ok
+            },
+        );
+    }
+
+    /// Gives up on the query with `id`, if it is still unanswered
+    fn expire_query(&mut self, id: Uuid) {
+        if let Some((remote, _)) = self.pending_identifies.remove(&id) {
+            // Dropping the promise fails the pending future
+            debug!(
+                self.ctx.log(),
+                "Query {} to {} was not answered in time", id, remote
+            );
+        }
+    }
+
+    fn send_query_response(&mut self, msg: IdentifyMessage, dst: ActorPath) {
+        let src = dispatcher_path(self.system_path_ref());
+        if let Err(e) = self.route(dst.clone(), DispatchData::Lazy(Box::new(msg), src, dst)) {
+            error!(self.ctx.log(), "Failed to route identify response: {:?}", e);
+        }
+    }
+
+    fn on_identify_message(&mut self, msg: IdentifyMessage, sender: ActorPath) {
+        match msg {
+            IdentifyMessage::Request { id, path } => {
+                let identity = self.identify_locally(&path);
+                self.send_query_response(IdentifyMessage::Response { id, identity }, sender);
+            }
+            IdentifyMessage::ListRequest { id, prefix } => {
+                let entries = self.list_locally(&prefix);
+                self.send_query_response(IdentifyMessage::ListResponse { id, entries }, sender);
+            }
+            IdentifyMessage::Response { id, identity } => match self.pending_identifies.remove(&id)
+            {
+                Some((_, PendingQuery::Identify(promise))) => {
+                    promise.fulfil(identity).unwrap_or_else(|e| {
+                        error!(self.ctx.log(), "Could not notify listeners: {:?}", e)
+                    })
+                }
+                _ => debug!(
+                    self.ctx.log(),
+                    "Dropping identify response {} without pending request", id
+                ),
+            },
+            IdentifyMessage::ListResponse { id, entries } => {
+                match self.pending_identifies.remove(&id) {
+                    Some((_, PendingQuery::List(promise))) => {
+                        promise.fulfil(entries).unwrap_or_else(|e| {
+                            error!(self.ctx.log(), "Could not notify listeners: {:?}", e)
+                        })
+                    }
+                    _ => debug!(
+                        self.ctx.log(),
+                        "Dropping list response {} without pending request", id
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Lists the entries under `prefix` in the local [ActorStore](lookup::ActorStore)
+    fn list_locally(&mut self, prefix: &NamedPath) -> Vec<RegisteredPath> {
+        let system = self.system_path();
+        let lookup = self.lookup.load();
+        lookup
+            .list(prefix.path_ref())
+            .into_iter()
+            .map(|(path, entry)| RegisteredPath {
+                path: NamedPath::with_system(system.clone(), path),
+                entry,
+            })
+            .collect()
+    }
+
+    /// Resolves `path` against the local [ActorStore](lookup::ActorStore)
+    fn identify_locally(&mut self, path: &ActorPath) -> ActorIdentity {
+        let system = self.system_path();
+        let unique = |actor: &DynActorRef| -> Option<ActorPath> {
+            actor
+                .component_id()
+                .map(|id| system.clone().into_unique(id).into())
+        };
+        let lookup = self.lookup.load();
+        match lookup.get_by_actor_path(path) {
+            LookupResult::Ref(actor) => unique(actor)
+                .map(ActorIdentity::Actor)
+                .unwrap_or(ActorIdentity::NotFound),
+            LookupResult::Group(group) => {
+                ActorIdentity::Group(group.members().iter().filter_map(|a| unique(a)).collect())
+            }
+            LookupResult::None | LookupResult::Err(_) => ActorIdentity::NotFound,
+        }
+    }
+
+    fn on_watch_message(&mut self, msg: WatchMessage) {
+        match msg {
+            WatchMessage::Watch { watcher, target } => self.add_watcher(watcher, target),
+            WatchMessage::Unwatch { watcher, target } => self.remove_watcher(&watcher, &target),
+            WatchMessage::Terminated { watcher, target } => {
+                let addr = target.system().socket_address();
+                if let Some(watches) = self.remote_watches.get_mut(&addr) {
+                    watches.retain(|(t, w)| !(t == &target && w == &watcher));
+                    if watches.is_empty() {
+                        self.remote_watches.remove(&addr);
+                    }
+                }
+                self.notify_terminated(watcher, target);
+            }
+        }
+    }
+
     fn deadletter_path(&mut self) -> ActorPath {
         ActorPath::Named(NamedPath::with_system(self.system_path(), Vec::new()))
     }
@@ -816,6 +1738,10 @@ impl NetworkDispatcher {
                         if !res.is_empty() {
                             info!(self.ctx.log(), "Replaced entry for path={:?}", path);
                         }
+                        if let InsertResult::Ref(previous) = res {
+                            previous.forget_registration(&ap);
+                        }
+                        actor.record_registration(&ap);
                     }
                     result.map(|_| ap)
                         .map_err(RegistrationError::InvalidPath)
@@ -824,6 +1750,10 @@ impl NetworkDispatcher {
         if res.is_ok() && !self.reaper.is_scheduled() {
             self.schedule_reaper();
         }
+        if let Ok(ActorPath::Named(np)) = &res {
+            let member = actor.component_id().map(PathEntry::Actor);
+            self.update_member_watches(np.path_ref(), member);
+        }
         debug!(self.log(), "Completed actor registration with {:?}", res);
         match promise {
             RegistrationPromise::Fulfil(promise) => {
@@ -871,10 +1801,17 @@ impl NetworkDispatcher {
                         if !res.is_empty() {
                             info!(self.ctx.log(), "Replaced entry for path={:?}", path);
                         }
+                        if let InsertResult::Ref(previous) = res {
+                            previous.forget_registration(&ap);
+                        }
                     }
                     result.map(|_| ap).map_err(RegistrationError::InvalidPath)
                 }
             });
+        if let Ok(ActorPath::Named(np)) = &res {
+            // a policy may replace a member
+            self.update_member_watches(np.path_ref(), None);
+        }
         debug!(self.log(), "Completed policy registration with {:?}", res);
         match promise {
             RegistrationPromise::Fulfil(promise) => {
@@ -886,6 +1823,72 @@ impl NetworkDispatcher {
         }
     }
 
+    fn deregister(&mut self, deregistration: Deregistration, promise: RegistrationPromise) {
+        let res = match deregistration {
+            Deregistration::Actor(path) => self
+                .resolve_path(&path)
+                .map_err(RegistrationError::InvalidPath)
+                .and_then(|ap| {
+                    let previous = match self.lookup.load().get_by_actor_path(&ap) {
+                        LookupResult::Ref(actor) => Some(actor.clone()),
+                        _ => None,
+                    };
+                    let mut removed = false;
+                    self.lookup.rcu(|current| {
+                        let mut next = ActorStore::clone(current);
+                        removed = match &ap {
+                            ActorPath::Unique(up) => next.remove_by_uuid(&up.id()),
+                            ActorPath::Named(np) => next.remove_actor_by_named_path(np.path_ref()),
+                        };
+                        next
+                    });
+                    if removed {
+                        if let Some(previous) = previous {
+                            previous.forget_registration(&ap);
+                        }
+                        Ok(ap)
+                    } else {
+                        Err(RegistrationError::NotFound)
+                    }
+                }),
+            Deregistration::Policy(path) => {
+                let path_res = PathResolvable::Segments(path);
+                self.resolve_path(&path_res)
+                    .map_err(RegistrationError::InvalidPath)
+                    .and_then(|ap| {
+                        let_irrefutable!(path, PathResolvable::Segments(path) = path_res);
+                        let mut removed = false;
+                        self.lookup.rcu(|current| {
+                            let mut next = ActorStore::clone(current);
+                            removed = next.remove_routing_policy(&path);
+                            next
+                        });
+                        if removed {
+                            Ok(ap)
+                        } else {
+                            Err(RegistrationError::NotFound)
+                        }
+                    })
+            }
+        };
+        debug!(self.log(), "Completed deregistration with {:?}", res);
+        if res.is_ok() {
+            // Watchers of the removed path shouldn't have to wait for the reaper
+            self.check_watched_actors();
+        }
+        if let Ok(ActorPath::Named(np)) = &res {
+            self.update_member_watches(np.path_ref(), None);
+        }
+        match promise {
+            RegistrationPromise::Fulfil(promise) => {
+                promise.fulfil(res).unwrap_or_else(|e| {
+                    error!(self.ctx.log(), "Could not notify listeners: {:?}", e)
+                });
+            }
+            RegistrationPromise::None => (), // ignore
+        }
+    }
+
     fn close_channel(&mut self, addr: SocketAddr) -> () {
         if let Some(state) = self.connections.get_mut(&addr) {
             match state {
@@ -919,6 +1922,7 @@ impl NetworkDispatcher {
         } else {
             warn!(self.ctx.log(), "Closing channel to unknown system {}", addr);
         }
+        self.drop_remote_watchers(addr);
     }
 }
 
@@ -948,16 +1952,39 @@ impl Actor for NetworkDispatcher {
                 match event {
                     RegistrationEvent::Actor(rea) => self.register_actor(rea, update, promise),
                     RegistrationEvent::Policy(rep) => self.register_policy(rep, update, promise),
+                    RegistrationEvent::Deregistration(der) => self.deregister(der, promise),
                 }
             }
             DispatchEnvelope::Event(ev) => self.on_event(ev),
             DispatchEnvelope::LockedChunk(trash) => self.garbage_buffers.push_back(trash),
+            DispatchEnvelope::Watch(event) => self.on_watch(event),
+            DispatchEnvelope::Identify { path, promise } => self.on_identify(path, promise),
+            DispatchEnvelope::ListPaths { prefix, promise } => self.on_list_paths(prefix, promise),
         }
         Handled::Ok
     }
 
     fn receive_network(&mut self, msg: NetMessage) -> Handled {
-        warn!(self.ctx.log(), "Received network message: {:?}", msg,);
+        let sender = msg.sender.clone();
+        match_deser! {
+            (msg.data) {
+                msg(watch): WatchMessage => {
+                    self.on_watch_message(watch);
+                },
+                msg(identify): IdentifyMessage => {
+                    self.on_identify_message(identify, sender);
+                },
+                msg(membership): MembershipMessage => {
+                    self.on_membership_message(membership);
+                },
+                err(e) => {
+                    warn!(self.ctx.log(), "Could not deserialise network message: {:?}", e);
+                },
+                default(_) => {
+                    warn!(self.ctx.log(), "Received unexpected network message from {}", sender);
+                },
+            }
+        }
         Handled::Ok
     }
 }
@@ -984,6 +2011,10 @@ impl Dispatcher for NetworkDispatcher {
     fn network_status_port(&mut self) -> &mut ProvidedPort<NetworkStatusPort> {
         &mut self.network_status_port
     }
+
+    fn network_backend(&mut self) -> Option<NetworkBackend> {
+        self.net_bridge.as_ref().map(net::Bridge::backend)
+    }
 }
 
 impl ComponentLifecycle for NetworkDispatcher {
@@ -1239,6 +2270,135 @@ mod tests {
             .expect("2nd KompactSystem failed to shut down!");
     }
 
+    #[test]
+    fn registrations_are_recorded_on_components() {
+        let timeout = Duration::from_millis(1000);
+        let mut cfg = KompactConfig::default();
+        cfg.system_components(DeadletterBox::new, NetworkConfig::default().build());
+        let system = cfg.build().expect("KompactSystem");
+        let (c1, registration) = system.create_and_register(PongerAct::new_lazy);
+        registration.wait_expect(timeout, "unique registration");
+        system
+            .register_by_alias(&c1, "a/b")
+            .wait_expect(timeout, "alias registration");
+        let registrations = c1.core().registrations();
+        assert!(registrations.unique);
+        assert_eq!(
+            registrations.named,
+            vec![vec!["a".to_string(), "b".to_string()]]
+        );
+
+        // the alias moves to c2
+        let c2 = system.create(PongerAct::new_lazy);
+        system
+            .update_alias_registration(&c2, "a/b")
+            .wait_expect(timeout, "alias update");
+        assert!(c1.core().registrations().named.is_empty());
+        assert_eq!(c2.core().registrations().named.len(), 1);
+        assert!(!c2.core().registrations().unique);
+
+        system
+            .deregister_alias("a/b")
+            .wait_expect(timeout, "alias deregistration");
+        assert!(c2.core().registrations().named.is_empty());
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+    }
+
+    #[test]
+    fn member_diff() {
+        let system = SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), 8080);
+        let member = |name: &str, id: Uuid| RegisteredPath {
+            path: NamedPath::with_system(system.clone(), vec!["group".into(), name.into()]),
+            entry: PathEntry::Actor(id),
+        };
+        let (a, b, c, d) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let old = vec![member("a", a), member("b", b), member("c", c)];
+        let current = vec![member("b", b), member("c", d), member("d", d)];
+        let events = NetworkDispatcher::diff_members(&old, &current);
+        assert_eq!(
+            events,
+            vec![
+                MembershipEvent::MemberRemoved(member("a", a)),
+                MembershipEvent::MemberRemoved(member("c", c)),
+                MembershipEvent::MemberAdded(member("c", d)),
+                MembershipEvent::MemberAdded(member("d", d)),
+            ]
+        );
+        assert!(NetworkDispatcher::diff_members(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn member_watches_are_updated_incrementally() {
+        let mut cfg = KompactConfig::default();
+        cfg.system_components(DeadletterBox::new, NetworkConfig::default().build());
+        let system = cfg.build().expect("KompactSystem");
+        let sc: &dyn SystemComponents = system.get_system_components();
+        let cc = sc
+            .downcast::<CustomComponents<DeadletterBox, NetworkDispatcher>>()
+            .expect("network dispatcher");
+        cc.dispatcher.on_definition(|nd| {
+            let system_path = nd.system_path();
+            let member = |path: &[&str], id: Uuid| RegisteredPath {
+                path: NamedPath::with_system(
+                    system_path.clone(),
+                    path.iter().map(|s| s.to_string()).collect(),
+                ),
+                entry: PathEntry::Actor(id),
+            };
+            let members = |nd: &NetworkDispatcher, prefix: &[&str]| {
+                let prefix: Vec<String> = prefix.iter().map(|s| s.to_string()).collect();
+                nd.member_watches[&prefix].members.clone()
+            };
+            // never listed, so only a full re-list would drop it
+            let stale = member(&["other", "gone"], Uuid::new_v4());
+            nd.member_watches.insert(Vec::new(), MemberWatch::default());
+            nd.member_watches
+                .insert(vec!["group".into()], MemberWatch::default());
+            nd.member_watches.insert(
+                vec!["other".into()],
+                MemberWatch {
+                    watchers: Vec::new(),
+                    members: vec![stale.clone()],
+                },
+            );
+
+            let path: Vec<String> = vec!["group".into(), "a".into()];
+            let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+            nd.update_member_watches(&path, Some(PathEntry::Actor(first)));
+            assert_eq!(
+                members(nd, &["group"]),
+                vec![member(&["group", "a"], first)]
+            );
+            assert_eq!(members(nd, &[]), vec![member(&["group", "a"], first)]);
+            assert_eq!(members(nd, &["other"]), vec![stale.clone()]);
+
+            nd.update_member_watches(&path, Some(PathEntry::Actor(second)));
+            assert_eq!(
+                members(nd, &["group"]),
+                vec![member(&["group", "a"], second)]
+            );
+
+            // reserved names are not members of the root
+            nd.update_member_watches(&["$reserved".to_string()], Some(PathEntry::Actor(first)));
+            assert_eq!(members(nd, &[]), vec![member(&["group", "a"], second)]);
+
+            nd.update_member_watches(&path, None);
+            assert!(members(nd, &["group"]).is_empty());
+            assert!(members(nd, &[]).is_empty());
+            assert_eq!(members(nd, &["other"]), vec![stale]);
+        });
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+    }
+
     #[test]
     fn test_system_path_timing() {
         let mut cfg = KompactConfig::default();
@@ -0,0 +1,653 @@
+//! Finite state machines on top of components
+//!
+//! Many components implement some protocol, where the way messages and events are
+//! handled depends on the protocol state the component is in, and where some states
+//! must be left again after a while. Instead of matching on a state field in every handler
+//! and keeping track of timers manually, such components can implement [FiniteStateMachine].
+//!
+//! The states of the machine are declared as a fieldless enum, which derives [FsmState].
+//! For each state, [behaviour](FiniteStateMachine::behaviour) returns a [StateBehaviour],
+//! which holds the handlers for local and network messages and port events in that state,
+//! as well as optional entry and exit actions and a state timeout.
+//! The machine moves between states with [goto](FiniteStateMachine::goto), which runs the exit
+//! and entry actions, cancels the timeout of the old state, schedules the timeout of the new one,
+//! and logs the transition.
+//!
+//! Deriving [FsmActor](crate::prelude::FsmActor) routes all messages of the component to the
+//! handlers of its current state. Port events are routed by calling
+//! [fsm_handle_indication](FiniteStateMachine::fsm_handle_indication) or
+//! [fsm_handle_request](FiniteStateMachine::fsm_handle_request) from the component's
+//! [Require](crate::prelude::Require) and [Provide](crate::prelude::Provide) implementations.
+//!
+//! # Example
+//!
+//! A door that can be locked when closed, and closes itself a while after being opened.
+//!
+//! ```
+//! use kompact::prelude::*;
+//! use std::time::Duration;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq, FsmState)]
+//! enum DoorState {
+//!     Closed,
+//!     Open,
+//!     Locked,
+//! }
+//!
+//! #[derive(Debug)]
+//! enum DoorCommand {
+//!     Open,
+//!     Close,
+//!     Lock,
+//!     Unlock,
+//! }
+//!
+//! #[derive(ComponentDefinition, FsmActor)]
+//! struct Door {
+//!     ctx: ComponentContext<Self>,
+//!     fsm: FsmContext<Self>,
+//! }
+//! impl Door {
+//!     fn new() -> Self {
+//!         Door {
+//!             ctx: ComponentContext::uninitialised(),
+//!             fsm: FsmContext::new(DoorState::Closed),
+//!         }
+//!     }
+//!
+//!     fn closed(&mut self, cmd: DoorCommand) -> Handled {
+//!         match cmd {
+//!             DoorCommand::Open => self.goto(DoorState::Open),
+//!             DoorCommand::Lock => self.goto(DoorState::Locked),
+//!             _ => (),
+//!         }
+//!         Handled::Ok
+//!     }
+//!
+//!     fn open(&mut self, cmd: DoorCommand) -> Handled {
+//!         if let DoorCommand::Close = cmd {
+//!             self.goto(DoorState::Closed);
+//!         }
+//!         Handled::Ok
+//!     }
+//!
+//!     fn locked(&mut self, cmd: DoorCommand) -> Handled {
+//!         if let DoorCommand::Unlock = cmd {
+//!             self.goto(DoorState::Closed);
+//!         }
+//!         Handled::Ok
+//!     }
+//!
+//!     fn close_automatically(&mut self) -> Handled {
+//!         self.goto(DoorState::Closed);
+//!         Handled::Ok
+//!     }
+//! }
+//! impl ComponentLifecycle for Door {
+//!     fn on_start(&mut self) -> Handled {
+//!         self.start_fsm();
+//!         Handled::Ok
+//!     }
+//! }
+//! impl FiniteStateMachine for Door {
+//!     type State = DoorState;
+//!     type Message = DoorCommand;
+//!
+//!     fn fsm(&self) -> &FsmContext<Self> {
+//!         &self.fsm
+//!     }
+//!
+//!     fn fsm_mut(&mut self) -> &mut FsmContext<Self> {
+//!         &mut self.fsm
+//!     }
+//!
+//!     fn behaviour(state: DoorState) -> StateBehaviour<Self> {
+//!         match state {
+//!             DoorState::Closed => StateBehaviour::new().on_message(Self::closed),
+//!             DoorState::Open => StateBehaviour::new()
+//!                 .on_message(Self::open)
+//!                 .timeout(Duration::from_secs(5), Self::close_automatically),
+//!             DoorState::Locked => StateBehaviour::new().on_message(Self::locked),
+//!         }
+//!     }
+//! }
+//!
+//! let system = KompactConfig::default().build().expect("system");
+//! let door = system.create(Door::new);
+//! system.start_notify(&door).wait_timeout(Duration::from_millis(1000)).expect("started");
+//! door.actor_ref().tell(DoorCommand::Lock);
+//! # std::thread::sleep(Duration::from_millis(100));
+//! # assert_eq!(door.on_definition(|d| d.state()), DoorState::Locked);
+//! # system.shutdown().expect("shutdown");
+//! ```
+
+use crate::{
+    actors::MessageBounds,
+    component::{ComponentDefinition, Handled},
+    messaging::NetMessage,
+    ports::Port,
+    timer::timer_manager::{ScheduledTimer, Timer},
+};
+#[allow(unused_imports)]
+use slog::{crit, debug, error, info, trace, warn};
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    time::Duration,
+};
+
+/// The declared states of a [FiniteStateMachine]
+///
+/// This is usually derived via `#[derive(FsmState)]` on a fieldless enum.
+pub trait FsmState: Copy + Eq + fmt::Debug + Send + 'static {
+    /// All declared states
+    const STATES: &'static [Self];
+
+    /// The name of the state, as used in transition logs
+    fn name(&self) -> &'static str;
+}
+
+type PortHandlers = Vec<(TypeId, Box<dyn Any + Send>)>;
+type StateTimeout<F> = (Duration, fn(&mut F) -> Handled);
+
+/// The handlers of a [FiniteStateMachine] for a single state
+///
+/// Messages and events without a handler in the current state are logged and dropped.
+pub struct StateBehaviour<F: FiniteStateMachine> {
+    on_enter: Option<fn(&mut F)>,
+    on_exit: Option<fn(&mut F)>,
+    on_message: Option<fn(&mut F, <F as FiniteStateMachine>::Message) -> Handled>,
+    on_network: Option<fn(&mut F, NetMessage) -> Handled>,
+    indication_handlers: PortHandlers,
+    request_handlers: PortHandlers,
+    timeout: Option<StateTimeout<F>>,
+}
+
+impl<F: FiniteStateMachine> StateBehaviour<F> {
+    /// A state without any handlers
+    pub fn new() -> Self {
+        StateBehaviour {
+            on_enter: None,
+            on_exit: None,
+            on_message: None,
+            on_network: None,
+            indication_handlers: Vec::new(),
+            request_handlers: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Run `action` whenever the state is entered
+    pub fn on_enter(mut self, action: fn(&mut F)) -> Self {
+        self.on_enter = Some(action);
+        self
+    }
+
+    /// Run `action` whenever the state is left
+    pub fn on_exit(mut self, action: fn(&mut F)) -> Self {
+        self.on_exit = Some(action);
+        self
+    }
+
+    /// Handle local messages with `handler` in this state
+    pub fn on_message(
+        mut self,
+        handler: fn(&mut F, <F as FiniteStateMachine>::Message) -> Handled,
+    ) -> Self {
+        self.on_message = Some(handler);
+        self
+    }
+
+    /// Handle network messages with `handler` in this state
+    pub fn on_network(mut self, handler: fn(&mut F, NetMessage) -> Handled) -> Self {
+        self.on_network = Some(handler);
+        self
+    }
+
+    /// Handle indications on the required port `P` with `handler` in this state
+    ///
+    /// See [fsm_handle_indication](FiniteStateMachine::fsm_handle_indication).
+    pub fn on_indication<P: Port + 'static>(
+        mut self,
+        handler: fn(&mut F, P::Indication) -> Handled,
+    ) -> Self {
+        Self::insert_handler::<P, _>(&mut self.indication_handlers, handler);
+        self
+    }
+
+    /// Handle requests on the provided port `P` with `handler` in this state
+    ///
+    /// See [fsm_handle_request](FiniteStateMachine::fsm_handle_request).
+    pub fn on_request<P: Port + 'static>(
+        mut self,
+        handler: fn(&mut F, P::Request) -> Handled,
+    ) -> Self {
+        Self::insert_handler::<P, _>(&mut self.request_handlers, handler);
+        self
+    }
+
+    /// Run `action` if the machine is still in this state after `duration`
+    ///
+    /// The timer is started whenever the state is entered,
+    /// and cancelled when the state is left before it expires.
+    /// Usually, `action` moves the machine to a different state.
+    pub fn timeout(mut self, duration: Duration, action: fn(&mut F) -> Handled) -> Self {
+        self.timeout = Some((duration, action));
+        self
+    }
+
+    fn insert_handler<P: Port + 'static, H: Any + Send>(handlers: &mut PortHandlers, handler: H) {
+        let port = TypeId::of::<P>();
+        handlers.retain(|(id, _)| *id != port);
+        handlers.push((port, Box::new(handler)));
+    }
+
+    fn find_handler<P: Port + 'static, H: Any + Copy>(handlers: &PortHandlers) -> Option<H> {
+        let port = TypeId::of::<P>();
+        handlers
+            .iter()
+            .find(|(id, _)| *id == port)
+            .and_then(|(_, handler)| handler.downcast_ref::<H>().copied())
+    }
+}
+
+impl<F: FiniteStateMachine> Default for StateBehaviour<F> {
+    fn default() -> Self {
+        StateBehaviour::new()
+    }
+}
+
+/// The runtime state of a [FiniteStateMachine]
+///
+/// Keep this as a field of the component, and provide access to it via
+/// [fsm](FiniteStateMachine::fsm) and [fsm_mut](FiniteStateMachine::fsm_mut).
+pub struct FsmContext<F: FiniteStateMachine> {
+    state: F::State,
+    behaviour: Option<StateBehaviour<F>>,
+    state_timer: Option<ScheduledTimer>,
+}
+
+impl<F: FiniteStateMachine> FsmContext<F> {
+    /// Create a new context for a machine in the `initial` state
+    ///
+    /// The `initial` state is only entered in [start_fsm](FiniteStateMachine::start_fsm).
+    pub fn new(initial: F::State) -> Self {
+        FsmContext {
+            state: initial,
+            behaviour: None,
+            state_timer: None,
+        }
+    }
+
+    /// The current state of the machine
+    pub fn state(&self) -> F::State {
+        self.state
+    }
+}
+
+impl<F: FiniteStateMachine> fmt::Debug for FsmContext<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FsmContext")
+            .field("state", &self.state)
+            .field("state_timer", &self.state_timer)
+            .finish()
+    }
+}
+
+/// A component that is structured as a finite state machine
+///
+/// See the [module level documentation](crate::fsm) for an overview and an example.
+pub trait FiniteStateMachine: ComponentDefinition + Sized + 'static {
+    /// The declared states of the machine
+    type State: FsmState;
+    /// The type of local messages the machine accepts
+    ///
+    /// This becomes the `Actor::Message` type of the component when deriving
+    /// [FsmActor](crate::prelude::FsmActor).
+    type Message: MessageBounds;
+
+    /// Returns a reference to the machine's context
+    fn fsm(&self) -> &FsmContext<Self>;
+
+    /// Returns a mutable reference to the machine's context
+    fn fsm_mut(&mut self) -> &mut FsmContext<Self>;
+
+    /// Returns the handlers for `state`
+    ///
+    /// This is invoked every time `state` is entered.
+    fn behaviour(state: Self::State) -> StateBehaviour<Self>;
+
+    /// The current state of the machine
+    fn state(&self) -> Self::State {
+        self.fsm().state
+    }
+
+    /// Enter the current state, which is the initial state on the first invocation
+    ///
+    /// This runs the state's entry action and starts its timeout,
+    /// and should be invoked from [on_start](crate::prelude::ComponentLifecycle::on_start).
+    fn start_fsm(&mut self) -> () {
+        debug!(self.log(), "Starting FSM in state {}", self.state().name());
+        cancel_state_timer(self);
+        enter_state(self);
+    }
+
+    /// Move the machine to the `next` state
+    ///
+    /// This runs the exit action of the current state, cancels its timeout,
+    /// and then enters `next`, running its entry action and starting its timeout.
+    /// Going to the current state leaves and re-enters it.
+    fn goto(&mut self, next: Self::State) -> () {
+        let current = self.state();
+        cancel_state_timer(self);
+        if let Some(on_exit) = self.fsm().behaviour.as_ref().and_then(|b| b.on_exit) {
+            on_exit(self);
+        }
+        debug!(
+            self.log(),
+            "FSM transition: {} -> {}",
+            current.name(),
+            next.name()
+        );
+        let fsm = self.fsm_mut();
+        fsm.state = next;
+        fsm.behaviour = None;
+        enter_state(self);
+    }
+
+    /// Handle a local message with the handler of the current state
+    fn fsm_receive_local(&mut self, msg: <Self as FiniteStateMachine>::Message) -> Handled {
+        match current_behaviour(self).on_message {
+            Some(handler) => handler(self, msg),
+            None => {
+                warn!(
+                    self.log(),
+                    "Unhandled message in FSM state {}: {:?}",
+                    self.state().name(),
+                    msg
+                );
+                Handled::Ok
+            }
+        }
+    }
+
+    /// Handle a network message with the handler of the current state
+    fn fsm_receive_network(&mut self, msg: NetMessage) -> Handled {
+        match current_behaviour(self).on_network {
+            Some(handler) => handler(self, msg),
+            None => {
+                warn!(
+                    self.log(),
+                    "Unhandled network message in FSM state {}: {:?}",
+                    self.state().name(),
+                    msg
+                );
+                Handled::Ok
+            }
+        }
+    }
+
+    /// Handle an indication on the required port `P` with the handler of the current state
+    ///
+    /// Call this from the component's `Require<P>` implementation.
+    fn fsm_handle_indication<P: Port + 'static>(&mut self, event: P::Indication) -> Handled {
+        let handler = StateBehaviour::<Self>::find_handler::<
+            P,
+            fn(&mut Self, P::Indication) -> Handled,
+        >(&current_behaviour(self).indication_handlers);
+        match handler {
+            Some(handler) => handler(self, event),
+            None => {
+                warn!(
+                    self.log(),
+                    "Unhandled indication in FSM state {}: {:?}",
+                    self.state().name(),
+                    event
+                );
+                Handled::Ok
+            }
+        }
+    }
+
+    /// Handle a request on the provided port `P` with the handler of the current state
+    ///
+    /// Call this from the component's `Provide<P>` implementation.
+    fn fsm_handle_request<P: Port + 'static>(&mut self, event: P::Request) -> Handled {
+        let handler = StateBehaviour::<Self>::find_handler::<P, fn(&mut Self, P::Request) -> Handled>(
+            &current_behaviour(self).request_handlers,
+        );
+        match handler {
+            Some(handler) => handler(self, event),
+            None => {
+                warn!(
+                    self.log(),
+                    "Unhandled request in FSM state {}: {:?}",
+                    self.state().name(),
+                    event
+                );
+                Handled::Ok
+            }
+        }
+    }
+}
+
+fn current_behaviour<F: FiniteStateMachine>(fsm: &mut F) -> &StateBehaviour<F> {
+    let state = fsm.state();
+    fsm.fsm_mut()
+        .behaviour
+        .get_or_insert_with(|| F::behaviour(state))
+}
+
+fn cancel_state_timer<F: FiniteStateMachine>(fsm: &mut F) {
+    if let Some(timer) = fsm.fsm_mut().state_timer.take() {
+        Timer::cancel_timer(fsm, timer);
+    }
+}
+
+fn enter_state<F: FiniteStateMachine>(fsm: &mut F) {
+    let behaviour = current_behaviour(fsm);
+    let on_enter = behaviour.on_enter;
+    if let Some((duration, action)) = behaviour.timeout {
+        let timer = fsm.schedule_once(duration, move |fsm, id| on_state_timeout(fsm, id, action));
+        fsm.fsm_mut().state_timer = Some(timer);
+    }
+    if let Some(on_enter) = on_enter {
+        on_enter(fsm);
+    }
+}
+
+fn on_state_timeout<F: FiniteStateMachine>(
+    fsm: &mut F,
+    id: ScheduledTimer,
+    action: fn(&mut F) -> Handled,
+) -> Handled {
+    // cancellation is asynchronous, so the timeout of a state that was left may still fire
+    if fsm.fsm().state_timer.as_ref() == Some(&id) {
+        fsm.fsm_mut().state_timer = None;
+        debug!(fsm.log(), "FSM state {} timed out", fsm.state().name());
+        action(fsm)
+    } else {
+        Handled::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::{sync::Arc, thread, time::Instant};
+
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+    const RUNNING_TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, FsmState)]
+    enum Phase {
+        Idle,
+        Running,
+        Done,
+    }
+
+    #[derive(Clone, Debug)]
+    enum Cmd {
+        Run,
+        Finish,
+    }
+
+    struct ControlPort;
+    impl Port for ControlPort {
+        type Indication = Never;
+        type Request = Cmd;
+    }
+
+    #[derive(ComponentDefinition, FsmActor)]
+    struct Job {
+        ctx: ComponentContext<Self>,
+        control: ProvidedPort<ControlPort>,
+        fsm: FsmContext<Self>,
+        entered: Vec<Phase>,
+        exited: Vec<Phase>,
+    }
+    impl Job {
+        fn new() -> Self {
+            Job {
+                ctx: ComponentContext::uninitialised(),
+                control: ProvidedPort::uninitialised(),
+                fsm: FsmContext::new(Phase::Idle),
+                entered: Vec::new(),
+                exited: Vec::new(),
+            }
+        }
+
+        fn record_entry(&mut self) {
+            let state = self.state();
+            self.entered.push(state);
+        }
+
+        fn record_exit(&mut self) {
+            let state = self.state();
+            self.exited.push(state);
+        }
+
+        fn idle(&mut self, cmd: Cmd) -> Handled {
+            if let Cmd::Run = cmd {
+                self.goto(Phase::Running);
+            }
+            Handled::Ok
+        }
+
+        fn running(&mut self, cmd: Cmd) -> Handled {
+            if let Cmd::Finish = cmd {
+                self.goto(Phase::Done);
+            }
+            Handled::Ok
+        }
+
+        fn timed_out(&mut self) -> Handled {
+            self.goto(Phase::Idle);
+            Handled::Ok
+        }
+    }
+    impl ComponentLifecycle for Job {
+        fn on_start(&mut self) -> Handled {
+            self.start_fsm();
+            Handled::Ok
+        }
+    }
+    impl Provide<ControlPort> for Job {
+        fn handle(&mut self, cmd: Cmd) -> Handled {
+            self.fsm_handle_request::<ControlPort>(cmd)
+        }
+    }
+    impl FiniteStateMachine for Job {
+        type Message = Cmd;
+        type State = Phase;
+
+        fn fsm(&self) -> &FsmContext<Self> {
+            &self.fsm
+        }
+
+        fn fsm_mut(&mut self) -> &mut FsmContext<Self> {
+            &mut self.fsm
+        }
+
+        fn behaviour(state: Phase) -> StateBehaviour<Self> {
+            let behaviour = StateBehaviour::new().on_enter(Self::record_entry);
+            match state {
+                Phase::Idle => behaviour.on_message(Self::idle),
+                Phase::Running => behaviour
+                    .on_exit(Self::record_exit)
+                    .on_request::<ControlPort>(Self::running)
+                    .timeout(RUNNING_TIMEOUT, Self::timed_out),
+                Phase::Done => behaviour,
+            }
+        }
+    }
+
+    fn wait_for_state(job: &Arc<Component<Job>>, state: Phase) {
+        let start = Instant::now();
+        while job.on_definition(|c| c.state()) != state {
+            assert!(
+                start.elapsed() < TIMEOUT,
+                "Job never reached state {:?}",
+                state
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn start_job(system: &KompactSystem) -> Arc<Component<Job>> {
+        let job = system.create(Job::new);
+        system
+            .start_notify(&job)
+            .wait_timeout(TIMEOUT)
+            .expect("job never started");
+        job
+    }
+
+    #[test]
+    fn fsm_states_are_declared() {
+        assert_eq!(Phase::STATES, &[Phase::Idle, Phase::Running, Phase::Done]);
+        assert_eq!(Phase::Running.name(), "Running");
+    }
+
+    #[test]
+    fn fsm_dispatches_by_state() {
+        let system = KompactConfig::default().build().expect("system");
+        let job = start_job(&system);
+        // not handled while idle
+        job.actor_ref().tell(Cmd::Finish);
+        job.actor_ref().tell(Cmd::Run);
+        wait_for_state(&job, Phase::Running);
+        // messages are only handled via the port while running
+        job.actor_ref().tell(Cmd::Finish);
+        assert!(job
+            .on_definition(|c| c.fsm_handle_request::<ControlPort>(Cmd::Finish))
+            .is_ok());
+        assert_eq!(job.on_definition(|c| c.state()), Phase::Done);
+        // the running timeout was cancelled when the state was left
+        thread::sleep(RUNNING_TIMEOUT * 3);
+        job.on_definition(|c| {
+            assert_eq!(c.state(), Phase::Done);
+            assert_eq!(c.entered, vec![Phase::Idle, Phase::Running, Phase::Done]);
+            assert_eq!(c.exited, vec![Phase::Running]);
+        });
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn fsm_state_times_out() {
+        let system = KompactConfig::default().build().expect("system");
+        let job = start_job(&system);
+        job.actor_ref().tell(Cmd::Run);
+        wait_for_state(&job, Phase::Running);
+        wait_for_state(&job, Phase::Idle);
+        job.on_definition(|c| {
+            assert_eq!(c.entered, vec![Phase::Idle, Phase::Running, Phase::Idle]);
+            assert_eq!(c.exited, vec![Phase::Running]);
+        });
+        // the machine keeps working after a timeout
+        job.actor_ref().tell(Cmd::Run);
+        wait_for_state(&job, Phase::Running);
+        system.shutdown().expect("shutdown");
+    }
+}
@@ -96,6 +96,8 @@ pub use executors;
 use kompact_actor_derive::*;
 use kompact_component_derive::*;
 #[allow(unused_imports)]
+use kompact_fsm_derive::*;
+#[allow(unused_imports)]
 use slog::{crit, debug, error, info, o, trace, warn, Drain, Fuse, Logger};
 use slog_async::Async;
 use std::convert::{From, Into};
@@ -110,10 +112,14 @@ mod dedicated_scheduler;
 /// Default implementations for system components
 pub mod default_components;
 mod dispatch;
+/// Finite state machines on top of components
+pub mod fsm;
 /// Facilities and utilities for dealing with network messages
 pub mod messaging;
 /// Default networking implementation
 pub mod net;
+/// Event-sourced persistence for components
+pub mod persistence;
 mod ports;
 /// Facilities for routing messages
 pub mod routing;
@@ -175,6 +181,7 @@ pub mod prelude {
 
     pub use kompact_actor_derive::*;
     pub use kompact_component_derive::*;
+    pub use kompact_fsm_derive::*;
 
     #[allow(deprecated)]
     pub use crate::{
@@ -201,16 +208,20 @@ pub mod prelude {
             DispatchingPath,
             DynActorRef,
             DynActorRefFactory,
+            MailboxConfig,
             MessageBounds,
             NamedPath,
             NetworkActor,
+            OverflowStrategy,
             PathParseError,
+            Priority,
             Receiver,
             Recipient,
             Request,
             SystemField,
             SystemPath,
             Transport,
+            TryTellError,
             UniquePath,
             WithRecipient,
             WithSender,
@@ -233,15 +244,30 @@ pub mod prelude {
             ProvideRef,
             Require,
             RequireRef,
+            Snapshot,
+            StashFull,
         },
+        fsm::{FiniteStateMachine, FsmContext, FsmState, StateBehaviour},
         net::{
             buffers::{BufferConfig, ChunkLease, ChunkRef},
+            rate_limit::{
+                RateLimit,
+                RateLimitScope,
+                ThrottlePolicy,
+                ThrottledPeer,
+                TrafficDirection,
+            },
+            ConnectionLimitPolicy,
+            NetworkBackend,
             SessionId,
         },
         ports::{
             Channel,
             DisconnectError,
+            Intercepted,
+            InterceptorChain,
             Port,
+            PortInterceptor,
             ProvidedPort,
             ProvidedRef,
             ProviderChannel,
@@ -252,7 +278,14 @@ pub mod prelude {
             TwoWayChannel,
         },
         runtime::{KompactConfig, KompactSystem, SystemHandle},
-        supervision::{FaultContext, RecoveryHandler},
+        supervision::{
+            FaultContext,
+            FaultStatistics,
+            RecoveryHandler,
+            RestartLimitAction,
+            RestartPolicy,
+            SupervisionStrategy,
+        },
         Never,
     };
 
@@ -266,14 +299,20 @@ pub mod prelude {
             NetworkStatusRequest,
         },
         messaging::{
+            ActorIdentity,
             DispatchEnvelope,
+            MembershipEvent,
             MsgEnvelope,
             NetMessage,
+            PathEntry,
             PathResolvable,
+            RegisteredPath,
             RegistrationError,
             RegistrationResult,
             Serialised,
+            Terminated,
             UnpackError,
+            WatchEvent,
         },
         timer::timer_manager::{CanCancelTimers, ScheduledTimer, Timer, TimerRefFactory},
     };
@@ -282,6 +321,7 @@ pub mod prelude {
         serialisation::*,
         utils::{
             biconnect_components,
+            biconnect_components_with_interceptors,
             biconnect_ports,
             block_on,
             block_until,
@@ -1211,6 +1251,381 @@ mod tests {
             .expect("Kompact didn't shut down properly");
     }
 
+    type SupervisionEvents = std::sync::mpsc::Sender<(&'static str, Arc<Component<Supervised>>)>;
+
+    #[derive(ComponentDefinition)]
+    struct Supervised {
+        ctx: ComponentContext<Self>,
+        name: &'static str,
+        // recovered replacements are reported here
+        events: SupervisionEvents,
+    }
+
+    impl Supervised {
+        fn new(name: &'static str, events: SupervisionEvents) -> Supervised {
+            Supervised {
+                ctx: ComponentContext::uninitialised(),
+                name,
+                events,
+            }
+        }
+    }
+
+    impl ComponentLifecycle for Supervised {
+        fn on_start(&mut self) -> Handled {
+            let name = self.name;
+            let events = self.events.clone();
+            self.ctx.set_recovery_function(move |fault| {
+                fault.recover_with(move |_ctx, system, _logger| {
+                    let events2 = events.clone();
+                    let c = system.create(move || Supervised::new(name, events2));
+                    system.start(&c);
+                    events.send((name, c)).expect("sent replacement");
+                })
+            });
+            Handled::Ok
+        }
+    }
+
+    impl Actor for Supervised {
+        type Message = ();
+
+        fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+            info!(self.ctx.log(), "Crashing {}", self.name);
+            panic!("Test panic please ignore");
+        }
+
+        fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+            unimplemented!();
+        }
+    }
+
+    fn wait_terminated(c: &Arc<Component<Supervised>>, timeout: Duration) -> () {
+        let deadline = time::Instant::now() + timeout;
+        while !(c.is_destroyed() || c.is_faulty()) {
+            assert!(
+                time::Instant::now() < deadline,
+                "{} did not terminate",
+                c.on_definition(|cd| cd.name)
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_supervision_kill_cascades() -> () {
+        let system = KompactConfig::default().build().expect("KompactSystem");
+        let one_sec = Duration::from_millis(1000);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let root = system.create(|| Supervised::new("root", sender.clone()));
+        let child = system.create_child(&root, || Supervised::new("child", sender.clone()));
+        let grandchild =
+            system.create_child(&child, || Supervised::new("grandchild", sender.clone()));
+        let sibling = system.create(|| Supervised::new("sibling", sender.clone()));
+        assert_eq!(child.core().parent_id(), Some(root.id()));
+        assert_eq!(grandchild.core().parent_id(), Some(child.id()));
+        assert_eq!(sibling.core().parent_id(), None);
+        for c in [&root, &child, &grandchild, &sibling] {
+            system
+                .start_notify(c)
+                .wait_timeout(one_sec)
+                .expect("Component never started!");
+        }
+
+        system
+            .kill_notify(root.clone())
+            .wait_timeout(one_sec)
+            .expect("root never died");
+        wait_terminated(&child, one_sec);
+        wait_terminated(&grandchild, one_sec);
+        assert!(child.is_destroyed());
+        assert!(grandchild.is_destroyed());
+        assert!(sibling.is_active());
+
+        // children of dead parents are killed as soon as they start
+        let late = system.create_child(&root, || Supervised::new("late", sender.clone()));
+        system.start(&late);
+        wait_terminated(&late, one_sec);
+
+        // killing is not a fault
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+    }
+
+    /// Returns the names of the recovered components and of the original children still alive
+    fn crash_child_with_strategy(
+        strategy: SupervisionStrategy,
+    ) -> (Vec<&'static str>, Vec<&'static str>) {
+        let system = KompactConfig::default().build().expect("KompactSystem");
+        let one_sec = Duration::from_millis(1000);
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let root = system.create(|| Supervised::new("root", sender.clone()));
+        root.set_supervision_strategy(strategy);
+        system
+            .start_notify(&root)
+            .wait_timeout(one_sec)
+            .expect("root never started");
+        let children: Vec<Arc<Component<Supervised>>> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let events = sender.clone();
+                let child = root
+                    .on_definition(|r| r.ctx.create_child(move || Supervised::new(name, events)));
+                system
+                    .start_notify(&child)
+                    .wait_timeout(one_sec)
+                    .expect("child never started");
+                child
+            })
+            .collect();
+        // the supervisor learns about the hierarchy when a child has started
+        thread::sleep(Duration::from_millis(100));
+
+        children[1].actor_ref().tell(());
+
+        let mut recovered = Vec::new();
+        while let Ok((name, replacement)) = receiver.recv_timeout(Duration::from_millis(500)) {
+            if name == "root" {
+                assert_eq!(replacement.core().parent_id(), None);
+            } else {
+                assert_eq!(replacement.core().parent_id(), Some(root.id()));
+            }
+            recovered.push(name);
+        }
+        recovered.sort_unstable();
+        let alive: Vec<&'static str> = children
+            .iter()
+            .filter(|child| child.is_active())
+            .map(|child| child.on_definition(|cd| cd.name))
+            .collect();
+        // an escalated fault is counted for both the child and the parent
+        let expected_faults = if strategy == SupervisionStrategy::Escalate {
+            2
+        } else {
+            1
+        };
+        assert_eq!(system.fault_statistics().faults, expected_faults);
+
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+        (recovered, alive)
+    }
+
+    // replace ignore with panic cfg gate when https://github.com/rust-lang/rust/pull/74754 is merged
+    #[test]
+    #[ignore]
+    fn test_supervision_strategies() -> () {
+        assert_eq!(
+            crash_child_with_strategy(SupervisionStrategy::OneForOne),
+            (vec!["b"], vec!["a", "c"])
+        );
+        assert_eq!(
+            crash_child_with_strategy(SupervisionStrategy::OneForAll),
+            (vec!["a", "b", "c"], vec![])
+        );
+        assert_eq!(
+            crash_child_with_strategy(SupervisionStrategy::RestForOne),
+            (vec!["b", "c"], vec!["a"])
+        );
+        assert_eq!(
+            crash_child_with_strategy(SupervisionStrategy::Escalate),
+            (vec!["root"], vec![])
+        );
+    }
+
+    static CRASH_LOOP_FAULT_COUNTS: Lazy<Mutex<Vec<usize>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+    #[derive(ComponentDefinition, Actor)]
+    struct CrashLooper {
+        ctx: ComponentContext<Self>,
+    }
+
+    impl Default for CrashLooper {
+        fn default() -> Self {
+            CrashLooper {
+                ctx: ComponentContext::uninitialised(),
+            }
+        }
+    }
+
+    impl ComponentLifecycle for CrashLooper {
+        fn on_start(&mut self) -> Handled {
+            self.ctx.set_recovery_function(|fault| {
+                fault.recover_with(|ctx, system, _logger| {
+                    CRASH_LOOP_FAULT_COUNTS
+                        .lock()
+                        .unwrap()
+                        .push(ctx.fault_count);
+                    let c = system.create(CrashLooper::default);
+                    system.start(&c);
+                })
+            });
+            info!(self.ctx.log(), "Crashing CrashLooper");
+            panic!("Test panic please ignore");
+        }
+    }
+
+    // replace ignore with panic cfg gate when https://github.com/rust-lang/rust/pull/74754 is merged
+    #[test]
+    #[ignore]
+    fn test_restart_policy_limits_crash_loops() -> () {
+        let system = KompactConfig::default().build().expect("KompactSystem");
+        let backoff = Duration::from_millis(20);
+
+        let c = system.create(CrashLooper::default);
+        // the policy is inherited by the replacements
+        c.set_restart_policy(
+            RestartPolicy::new(3, Duration::from_secs(60)).with_backoff(backoff, 4 * backoff),
+        );
+        let started = time::Instant::now();
+        system.start(&c);
+        let deadline = started + Duration::from_secs(5);
+        while system.fault_statistics().limits_exceeded == 0 {
+            assert!(time::Instant::now() < deadline, "Restarts were not limited");
+            thread::sleep(Duration::from_millis(10));
+        }
+        // 20ms + 40ms + 80ms of backoff
+        assert!(started.elapsed() >= 7 * backoff);
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(
+            system.fault_statistics(),
+            FaultStatistics {
+                faults: 4,
+                recoveries: 3,
+                limits_exceeded: 1,
+            }
+        );
+        assert_eq!(*CRASH_LOOP_FAULT_COUNTS.lock().unwrap(), vec![1, 2, 3]);
+
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+    }
+
+    #[derive(Debug)]
+    enum SnapshotMsg {
+        Add(u64),
+        Store,
+        Get(KPromise<u64>),
+        Crash,
+    }
+
+    static SNAPSHOT_CURRENT_REF: Lazy<Mutex<Option<ActorRef<SnapshotMsg>>>> =
+        Lazy::new(|| Mutex::new(None));
+
+    #[derive(ComponentDefinition)]
+    struct SnapshotCounter {
+        ctx: ComponentContext<Self>,
+        count: u64,
+    }
+
+    impl ComponentLifecycle for SnapshotCounter {
+        fn on_start(&mut self) -> Handled {
+            *SNAPSHOT_CURRENT_REF.lock().unwrap() = Some(self.actor_ref());
+            self.ctx
+                .set_recovery_function(|fault| fault.restore_snapshot::<SnapshotCounter>());
+            Handled::Ok
+        }
+    }
+
+    impl Snapshot for SnapshotCounter {
+        type State = u64;
+
+        fn snapshot(&self) -> Self::State {
+            self.count
+        }
+
+        fn restore(state: Option<Self::State>) -> Self {
+            SnapshotCounter {
+                ctx: ComponentContext::uninitialised(),
+                count: state.unwrap_or(0),
+            }
+        }
+    }
+
+    impl Actor for SnapshotCounter {
+        type Message = SnapshotMsg;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            match msg {
+                SnapshotMsg::Add(n) => self.count += n,
+                SnapshotMsg::Store => self.store_snapshot().expect("snapshot"),
+                SnapshotMsg::Get(promise) => promise.fulfil(self.count).expect("fulfilled"),
+                SnapshotMsg::Crash => panic!("Test panic please ignore"),
+            }
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, msg: NetMessage) -> Handled {
+            let n: u64 = msg.try_deserialise::<u64, u64>().expect("u64");
+            self.count += n;
+            Handled::Ok
+        }
+    }
+
+    // replace ignore with panic cfg gate when https://github.com/rust-lang/rust/pull/74754 is merged
+    #[test]
+    #[ignore]
+    fn test_component_recovery_from_snapshot() -> () {
+        let mut cfg = KompactConfig::default();
+        cfg.system_components(DeadletterBox::new, NetworkConfig::default().build());
+        let system = cfg.build().expect("KompactSystem");
+        let one_sec = Duration::from_millis(1000);
+        let get = |aref: &ActorRef<SnapshotMsg>| {
+            aref.ask_with(SnapshotMsg::Get)
+                .wait_timeout(one_sec)
+                .expect("count")
+        };
+
+        let (c, registration) = system.create_and_register(|| SnapshotCounter::restore(None));
+        registration.wait_expect(one_sec, "registration");
+        let path = system
+            .register_by_alias(&c, "snapshot-counter")
+            .wait_expect(one_sec, "alias registration");
+        system
+            .start_notify(&c)
+            .wait_timeout(one_sec)
+            .expect("counter never started");
+        let original = c.actor_ref();
+        assert!(!c.core().has_snapshot());
+
+        original.tell(SnapshotMsg::Add(5));
+        original.tell(SnapshotMsg::Store);
+        // lost on restart
+        original.tell(SnapshotMsg::Add(3));
+        assert_eq!(get(&original), 8);
+        assert!(c.core().has_snapshot());
+
+        original.tell(SnapshotMsg::Crash);
+        let deadline = time::Instant::now() + one_sec;
+        let replacement = loop {
+            let current = SNAPSHOT_CURRENT_REF.lock().unwrap().clone().expect("ref");
+            if current != original {
+                break current;
+            }
+            assert!(time::Instant::now() < deadline, "Counter was not restored");
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert!(c.is_faulty());
+        assert_eq!(get(&replacement), 5);
+
+        // the alias now points to the replacement
+        path.tell(10u64, &system);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(get(&replacement), 15);
+
+        system
+            .shutdown()
+            .expect("Kompact didn't shut down properly");
+    }
+
     #[derive(ComponentDefinition, Actor)]
     struct Stopper {
         ctx: ComponentContext<Self>,
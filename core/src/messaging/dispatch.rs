@@ -1,5 +1,5 @@
 use super::*;
-use crate::serialisation::ser_helpers::deserialise_bytes;
+use crate::{actors::NamedPath, serialisation::ser_helpers::deserialise_bytes};
 
 /// An abstraction over lazy or eagerly serialised data sent to the dispatcher
 #[derive(Debug)]
@@ -82,4 +82,20 @@ pub enum DispatchEnvelope {
     Event(EventEnvelope),
     /// Killed components send their BufferChunks to the Dispatcher for safe de-allocation
     LockedChunk(BufferChunk),
+    /// A request to start or stop watching an actor
+    Watch(WatchEvent),
+    /// A request to identify what is registered at an actor path
+    Identify {
+        /// The path to identify
+        path: ActorPath,
+        /// The promise to fulfil with the identity
+        promise: utils::KPromise<ActorIdentity>,
+    },
+    /// A request to list all paths registered under a named prefix
+    ListPaths {
+        /// The prefix to list
+        prefix: NamedPath,
+        /// The promise to fulfil with the registered paths
+        promise: utils::KPromise<Vec<RegisteredPath>>,
+    },
 }
@@ -32,7 +32,7 @@ pub enum PathType {
 }
 
 impl BitField for AddressType {
-    const POS: usize = 2;
+    const POS: usize = 5;
     const WIDTH: usize = 2;
 }
 
@@ -77,6 +77,7 @@ impl TryFrom<u8> for AddressType {
         match x {
             x if x == AddressType::IPv4 as u8 => Ok(AddressType::IPv4),
             x if x == AddressType::IPv6 as u8 => Ok(AddressType::IPv6),
+            x if x == AddressType::Domain as u8 => Ok(AddressType::Domain),
             _ => Err(SerError::InvalidType("Unsupported AddressType".into())),
         }
     }
@@ -245,6 +246,22 @@ impl TryFrom<u8> for SystemPathHeader {
 /// |                   Address (4/16/ * bytes)                  ...| Port (2 bytes) |
 /// +---------------------------------------------------------------+----------------+
 /// ```
+///
+/// Domain addresses are length-prefixed (1 byte) followed by the variable-length name.
+///
+/// In the header byte, the path type is stored in bit 7, the address type in bits 5-6,
+/// and the protocol in bits 0-4.
+///
+/// # Compatibility
+///
+/// Before 0.11, the address type was stored in bits 2-3, overlapping the protocol.
+/// IPv4 headers are unaffected, as their address type is all zeros,
+/// but IPv6 headers are encoded differently now.
+/// The old IPv6 encoding could not be decoded correctly by either version,
+/// as it corrupted the protocol field.
+///
+/// A named path with an empty name is decoded as the root path (i.e. no segments)
+/// since 0.11, instead of a path with a single empty segment.
 impl Serialisable for SystemPath {
     fn ser_id(&self) -> SerId {
         serialisation_ids::SYSTEM_PATH
@@ -287,6 +304,11 @@ fn system_path_put_into_buf(path: &SystemPath, buf: &mut dyn BufMut) -> () {
 #[inline(always)]
 fn system_path_from_buf(buf: &mut dyn Buf) -> Result<(SystemPathHeader, SystemPath), SerError> {
     // Deserialize system path
+    if buf.remaining() < 1 {
+        return Err(SerError::InvalidData(
+            "Could not get 1 byte for the system path header".into(),
+        ));
+    }
     let fields: u8 = buf.get_u8();
     let header = SystemPathHeader::try_from(fields)?;
     let address: IpAddr = match header.address_type {
@@ -313,9 +335,16 @@ fn system_path_from_buf(buf: &mut dyn Buf) -> Result<(SystemPathHeader, SystemPa
             }
         }
         AddressType::Domain => {
-            unimplemented!();
+            return Err(SerError::InvalidData(
+                "Domain addresses are not supported by system paths".into(),
+            ));
         }
     };
+    if buf.remaining() < 2 {
+        return Err(SerError::InvalidData(
+            "Could not get 2 bytes for the port".into(),
+        ));
+    }
     let port = buf.get_u16();
     let system_path = SystemPath::new(header.protocol, address, port);
     Ok((header, system_path))
@@ -329,6 +358,55 @@ impl Deserialiser<SystemPath> for SystemPath {
     }
 }
 
+/// Splits a serialised actor path off the front of `buf`, without allocating
+///
+/// Returns the path's name bytes (`None` for unique paths) and its total length,
+/// or `None` if `buf` does not start with a complete path.
+fn split_path(buf: &[u8]) -> Option<(Option<&[u8]>, usize)> {
+    let (&fields, rest) = buf.split_first()?;
+    let header = SystemPathHeader::try_from(fields).ok()?;
+    let address_len = match header.address_type {
+        AddressType::IPv4 => 4,
+        AddressType::IPv6 => 16,
+        AddressType::Domain => 1 + *rest.first()? as usize,
+    };
+    let offset = 1 + address_len + 2; // header, address and port
+    match header.path_type {
+        PathType::Unique => buf.get(offset..offset + 16).map(|_| (None, offset + 16)),
+        PathType::Named => {
+            let len_bytes = buf.get(offset..offset + 2)?;
+            let name_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+            let name = buf.get(offset + 2..offset + 2 + name_len)?;
+            Some((Some(name), offset + 2 + name_len))
+        }
+    }
+}
+
+/// The receiver of a message, located in a frame without deserialising it
+#[derive(Debug, PartialEq)]
+pub(crate) struct PeekedReceiver<'a> {
+    /// The receiver's name bytes, or `None` for a unique path
+    pub(crate) name: Option<&'a [u8]>,
+    /// The receiver's serialised path
+    pub(crate) path: &'a [u8],
+}
+
+/// Locates the receiver of a message
+///
+/// Peeks at a frame in the format produced by [serialise_msg](crate::serialisation::ser_helpers::serialise_msg),
+/// so a named receiver can be looked up before (and without) deserialising it,
+/// and its path only needs to be deserialised once.
+/// Returns `None` if `frame` does not contain both paths completely.
+pub(crate) fn peek_receiver(frame: &[u8]) -> Option<PeekedReceiver<'_>> {
+    let (_, sender_len) = split_path(frame)?;
+    let rest = &frame[sender_len..];
+    let (name, receiver_len) = split_path(rest)?;
+    Some(PeekedReceiver {
+        name,
+        path: &rest[..receiver_len],
+    })
+}
+
 impl Serialisable for ActorPath {
     fn ser_id(&self) -> SerId {
         serialisation_ids::ACTOR_PATH
@@ -423,10 +501,9 @@ impl Deserialiser<ActorPath> for ActorPath {
                         String::from_utf8_unchecked(name_bytes)
                     };
                     let parts: Vec<&str> = name.split('/').collect();
-                    if parts.is_empty() {
-                        return Err(SerError::InvalidData(
-                            "Could not determine name for Named path type".into(),
-                        ));
+                    if name.is_empty() {
+                        // the root path, e.g. the deadletter path
+                        ActorPath::Named(NamedPath::with_system(system_path, Vec::new()))
                     } else {
                         let path = parts.into_iter().map(|s| s.to_string()).collect();
                         ActorPath::Named(NamedPath::with_system(system_path, path))
@@ -484,6 +561,37 @@ mod serialisation_tests {
         assert_eq!(system_path, deserialised);
     }
 
+    /// Pins the header layout on the wire, independently of a serialisation round-trip
+    #[test]
+    #[allow(clippy::unusual_byte_groupings)] // grouped by header field
+    fn system_path_header_bits() {
+        let ipv4: IpAddr = "127.0.0.1".parse().unwrap();
+        let ipv6: IpAddr = "::1".parse().unwrap();
+        let header_byte = |path: ActorPath| {
+            let mut buf = BytesMut::new();
+            Serialisable::serialise(&path, &mut buf).expect("serialise");
+            buf[0]
+        };
+        let named = |transport, addr| {
+            ActorPath::Named(NamedPath::new(transport, addr, 1234, vec!["a".into()]))
+        };
+        let unique = |transport, addr| {
+            ActorPath::Unique(UniquePath::new(transport, addr, 1234, Uuid::nil()))
+        };
+
+        // path type in bit 7, address type in bits 5-6, transport in bits 0-4
+        assert_eq!(header_byte(unique(Transport::Local, ipv4)), 0b0_00_00000);
+        assert_eq!(header_byte(named(Transport::Tcp, ipv4)), 0b1_00_00001);
+        assert_eq!(header_byte(unique(Transport::Udp, ipv4)), 0b0_00_00010);
+        assert_eq!(header_byte(named(Transport::Tcp, ipv6)), 0b1_01_00001);
+        assert_eq!(header_byte(unique(Transport::Udp, ipv6)), 0b0_01_00010);
+
+        let header = SystemPathHeader::try_from(0b1_10_00001).expect("header");
+        assert_eq!(header.path_type, PathType::Named);
+        assert_eq!(header.protocol, Transport::Tcp);
+        assert_eq!(header.address_type, AddressType::Domain);
+    }
+
     #[test]
     fn actor_path_serequiv() {
         let expected_transport: Transport = Transport::Tcp;
@@ -552,5 +660,79 @@ mod serialisation_tests {
                 }
             }
         }
+
+        // peeking at the receiver of a message
+        {
+            let mut buf = BytesMut::new();
+            Serialisable::serialise(&unique_path, &mut buf).expect("serialise");
+            Serialisable::serialise(&named_path, &mut buf).expect("serialise");
+            let frame = buf.to_vec();
+            let peeked = super::peek_receiver(&frame).expect("Both paths are complete");
+            assert_eq!(Some("test/me/please".as_bytes()), peeked.name);
+            let mut receiver_bytes = peeked.path;
+            let receiver = ActorPath::deserialise(&mut receiver_bytes).expect("deserialise");
+            assert_eq!(named_path, receiver);
+            assert!(receiver_bytes.is_empty());
+            assert_eq!(None, super::peek_receiver(&frame[..frame.len() - 1]));
+
+            let mut buf = BytesMut::new();
+            Serialisable::serialise(&named_path, &mut buf).expect("serialise");
+            Serialisable::serialise(&unique_path, &mut buf).expect("serialise");
+            let peeked = super::peek_receiver(&buf).expect("Both paths are complete");
+            assert_eq!(None, peeked.name);
+            assert_eq!(&buf[buf.len() - peeked.path.len()..], peeked.path);
+        }
+
+        // the address type does not overlap the transport in the header
+        {
+            let ipv6_path = ActorPath::Named(NamedPath::new(
+                Transport::Tcp,
+                "::1".parse().unwrap(),
+                port,
+                name.clone(),
+            ));
+            let mut buf = BytesMut::new();
+            Serialisable::serialise(&ipv6_path, &mut buf).expect("serialise");
+            let deser_path = ActorPath::deserialise(&mut buf).expect("deserialise");
+            assert_eq!(ipv6_path, deser_path);
+        }
+
+        // system paths with a domain address can be skipped, but not deserialised
+        {
+            use bitfields::BitFieldExt;
+            let mut header = [0u8];
+            header.store(PathType::Named).expect("store");
+            header.store(Transport::Tcp).expect("store");
+            header.store(AddressType::Domain).expect("store");
+            let mut buf = BytesMut::new();
+            buf.put_slice(&header);
+            buf.put_u8(9);
+            buf.put_slice(b"localhost");
+            buf.put_u16(port);
+            buf.put_u16(2);
+            buf.put_slice(b"me");
+            Serialisable::serialise(&named_path, &mut buf).expect("serialise");
+            let peeked = super::peek_receiver(&buf).expect("Both paths are complete");
+            assert_eq!(Some("test/me/please".as_bytes()), peeked.name);
+            assert!(ActorPath::deserialise(&mut buf).is_err());
+        }
+
+        // the root path
+        {
+            let root_path = ActorPath::Named(NamedPath::new(
+                expected_transport,
+                expected_addr,
+                port,
+                Vec::new(),
+            ));
+            let size = Serialisable::size_hint(&root_path).expect("Paths should have size hints");
+            let mut buf = BytesMut::with_capacity(size);
+            Serialisable::serialise(&root_path, &mut buf)
+                .expect("Root ActorPath Serialisation should succeed");
+            let mut buf = buf.copy_to_bytes(buf.remaining());
+            let deser_path = ActorPath::deserialise(&mut buf)
+                .expect("Root ActorPath Deserialisation should succeed");
+            assert_eq!(deser_path, root_path);
+        }
     }
 }
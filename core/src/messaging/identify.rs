@@ -0,0 +1,385 @@
+use super::*;
+use crate::{actors::NamedPath, serialisation::serialisation_ids};
+use bytes::BufMut;
+
+/// What is registered at an [identified](crate::prelude::KompactSystem::identify) actor path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActorIdentity {
+    /// Neither an actor nor a routing group is registered at the path
+    NotFound,
+    /// A single actor, given by its unique path
+    Actor(ActorPath),
+    /// A routing group, given by the unique paths of its current members
+    ///
+    /// This is the result for broadcast and select paths,
+    /// as well as for paths with an explicit routing policy.
+    Group(Vec<ActorPath>),
+}
+
+impl ActorIdentity {
+    /// Returns `true` if an actor or a routing group is registered at the path
+    pub fn is_found(&self) -> bool {
+        !matches!(self, ActorIdentity::NotFound)
+    }
+
+    /// Returns the unique paths of all actors behind the path
+    pub fn unique_paths(&self) -> &[ActorPath] {
+        match self {
+            ActorIdentity::NotFound => &[],
+            ActorIdentity::Actor(path) => std::slice::from_ref(path),
+            ActorIdentity::Group(members) => members,
+        }
+    }
+
+    /// Returns the number of actors behind the path
+    pub fn member_count(&self) -> usize {
+        self.unique_paths().len()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let paths: Option<usize> = self.unique_paths().iter().map(|p| p.size_hint()).sum();
+        Some(1 + 4 + paths?)
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        let kind = match self {
+            ActorIdentity::NotFound => NOT_FOUND_ID,
+            ActorIdentity::Actor(_) => ACTOR_ID,
+            ActorIdentity::Group(_) => GROUP_ID,
+        };
+        buf.put_u8(kind);
+        let paths = self.unique_paths();
+        buf.put_u32(paths.len() as u32);
+        for path in paths {
+            path.serialise(buf)?;
+        }
+        Ok(())
+    }
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<ActorIdentity, SerError> {
+        if buf.remaining() < 5 {
+            return Err(SerError::InvalidData(
+                "Could not get 5 bytes for the actor identity header".into(),
+            ));
+        }
+        let kind = buf.get_u8();
+        let count = buf.get_u32() as usize;
+        let mut paths = Vec::with_capacity(capacity_for(count, buf.remaining(), MIN_PATH_SIZE));
+        for _ in 0..count {
+            paths.push(ActorPath::deserialise(buf)?);
+        }
+        match (kind, paths.len()) {
+            (NOT_FOUND_ID, 0) => Ok(ActorIdentity::NotFound),
+            (ACTOR_ID, 1) => Ok(ActorIdentity::Actor(paths.pop().unwrap())),
+            (GROUP_ID, _) => Ok(ActorIdentity::Group(paths)),
+            (kind, count) => Err(SerError::InvalidData(format!(
+                "Invalid actor identity of kind {} with {} paths",
+                kind, count
+            ))),
+        }
+    }
+}
+
+/// What is registered at a [RegisteredPath](RegisteredPath)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathEntry {
+    /// An actor, given by the unique id of its component
+    Actor(Uuid),
+    /// A routing policy
+    Policy,
+}
+
+/// A named path together with what is registered at it
+///
+/// Returned when [listing](crate::prelude::KompactSystem::list_paths) the paths under a prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredPath {
+    /// The registered path
+    pub path: NamedPath,
+    /// What is registered at `path`
+    pub entry: PathEntry,
+}
+
+impl RegisteredPath {
+    pub(crate) fn size_hint(&self) -> Option<usize> {
+        let path: ActorPath = self.path.clone().into();
+        Some(path.size_hint()? + 1 + 16)
+    }
+
+    pub(crate) fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        let path: ActorPath = self.path.clone().into();
+        path.serialise(buf)?;
+        match self.entry {
+            PathEntry::Actor(id) => {
+                buf.put_u8(ACTOR_ID);
+                buf.put_slice(id.as_bytes());
+            }
+            PathEntry::Policy => buf.put_u8(POLICY_ID),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn deserialise(buf: &mut dyn Buf) -> Result<RegisteredPath, SerError> {
+        let path = match ActorPath::deserialise(buf)? {
+            ActorPath::Named(path) => path,
+            ActorPath::Unique(path) => {
+                return Err(SerError::InvalidData(format!(
+                    "Expected a named path, but got {:?}",
+                    path
+                )))
+            }
+        };
+        if buf.remaining() < 1 {
+            return Err(SerError::InvalidData(
+                "Could not get 1 byte for the path entry kind".into(),
+            ));
+        }
+        let entry = match buf.get_u8() {
+            ACTOR_ID => PathEntry::Actor(deserialise_uuid(buf)?),
+            POLICY_ID => PathEntry::Policy,
+            kind => {
+                return Err(SerError::InvalidData(format!(
+                    "Found unknown path entry kind {}",
+                    kind
+                )))
+            }
+        };
+        Ok(RegisteredPath { path, entry })
+    }
+}
+
+fn deserialise_uuid(buf: &mut dyn Buf) -> Result<Uuid, SerError> {
+    if buf.remaining() < 16 {
+        return Err(SerError::InvalidData(
+            "Could not get 16 bytes for a uuid".into(),
+        ));
+    }
+    let mut id_bytes = [0u8; 16];
+    buf.copy_to_slice(&mut id_bytes);
+    Ok(Uuid::from_bytes(id_bytes))
+}
+
+/// Caps the capacity reserved for `count` entries of at least `min_size` bytes each,
+/// so a corrupt count can't make us allocate more than the `remaining` bytes can hold
+fn capacity_for(count: usize, remaining: usize, min_size: usize) -> usize {
+    count.min(remaining / min_size)
+}
+
+/// The size of the smallest serialised actor path: header, IPv4 address, port, and an empty name
+const MIN_PATH_SIZE: usize = 1 + 4 + 2 + 2;
+
+const NOT_FOUND_ID: u8 = 0;
+const ACTOR_ID: u8 = 1;
+const GROUP_ID: u8 = 2;
+const POLICY_ID: u8 = 3;
+
+const REQUEST_ID: u8 = 1;
+const RESPONSE_ID: u8 = 2;
+const LIST_REQUEST_ID: u8 = 3;
+const LIST_RESPONSE_ID: u8 = 4;
+
+/// The protocol for querying the actor paths registered at a remote dispatcher
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IdentifyMessage {
+    /// Ask for the identity of `path`
+    Request { id: Uuid, path: ActorPath },
+    /// The answer to the request with the same `id`
+    Response { id: Uuid, identity: ActorIdentity },
+    /// Ask for all paths registered under `prefix`
+    ListRequest { id: Uuid, prefix: NamedPath },
+    /// The answer to the list request with the same `id`
+    ListResponse {
+        id: Uuid,
+        entries: Vec<RegisteredPath>,
+    },
+}
+
+impl Serialisable for IdentifyMessage {
+    fn ser_id(&self) -> SerId {
+        serialisation_ids::IDENTIFY
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let body = match self {
+            IdentifyMessage::Request { path, .. } => path.size_hint()?,
+            IdentifyMessage::Response { identity, .. } => identity.size_hint()?,
+            IdentifyMessage::ListRequest { prefix, .. } => {
+                ActorPath::from(prefix.clone()).size_hint()?
+            }
+            IdentifyMessage::ListResponse { entries, .. } => {
+                let entries: Option<usize> = entries.iter().map(|e| e.size_hint()).sum();
+                4 + entries?
+            }
+        };
+        Some(1 + 16 + body)
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        match self {
+            IdentifyMessage::Request { id, path } => {
+                buf.put_u8(REQUEST_ID);
+                buf.put_slice(id.as_bytes());
+                path.serialise(buf)
+            }
+            IdentifyMessage::Response { id, identity } => {
+                buf.put_u8(RESPONSE_ID);
+                buf.put_slice(id.as_bytes());
+                identity.serialise(buf)
+            }
+            IdentifyMessage::ListRequest { id, prefix } => {
+                buf.put_u8(LIST_REQUEST_ID);
+                buf.put_slice(id.as_bytes());
+                ActorPath::from(prefix.clone()).serialise(buf)
+            }
+            IdentifyMessage::ListResponse { id, entries } => {
+                buf.put_u8(LIST_RESPONSE_ID);
+                buf.put_slice(id.as_bytes());
+                buf.put_u32(entries.len() as u32);
+                for entry in entries {
+                    entry.serialise(buf)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn local(self: Box<Self>) -> Result<Box<dyn Any + Send>, Box<dyn Serialisable>> {
+        Ok(self)
+    }
+}
+
+impl Deserialiser<IdentifyMessage> for IdentifyMessage {
+    const SER_ID: SerId = serialisation_ids::IDENTIFY;
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<IdentifyMessage, SerError> {
+        if buf.remaining() < 1 {
+            return Err(SerError::InvalidData(
+                "Could not get 1 byte for the identify message type".into(),
+            ));
+        }
+        let kind = buf.get_u8();
+        let id = deserialise_uuid(buf)?;
+        match kind {
+            REQUEST_ID => {
+                let path = ActorPath::deserialise(buf)?;
+                Ok(IdentifyMessage::Request { id, path })
+            }
+            RESPONSE_ID => {
+                let identity = ActorIdentity::deserialise(buf)?;
+                Ok(IdentifyMessage::Response { id, identity })
+            }
+            LIST_REQUEST_ID => match ActorPath::deserialise(buf)? {
+                ActorPath::Named(prefix) => Ok(IdentifyMessage::ListRequest { id, prefix }),
+                ActorPath::Unique(path) => Err(SerError::InvalidData(format!(
+                    "Expected a named prefix, but got {:?}",
+                    path
+                ))),
+            },
+            LIST_RESPONSE_ID => {
+                if buf.remaining() < 4 {
+                    return Err(SerError::InvalidData(
+                        "Could not get 4 bytes for the number of entries".into(),
+                    ));
+                }
+                let count = buf.get_u32() as usize;
+                let capacity = capacity_for(count, buf.remaining(), MIN_PATH_SIZE + 1);
+                let mut entries = Vec::with_capacity(capacity);
+                for _ in 0..count {
+                    entries.push(RegisteredPath::deserialise(buf)?);
+                }
+                Ok(IdentifyMessage::ListResponse { id, entries })
+            }
+            kind => Err(SerError::InvalidType(format!(
+                "Found unknown identify message type {}",
+                kind
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::{NamedPath, SystemPath, Transport, UniquePath};
+    use bytes::BytesMut;
+
+    fn roundtrip(msg: &IdentifyMessage) -> IdentifyMessage {
+        let mut buf = BytesMut::with_capacity(msg.size_hint().unwrap_or(64));
+        msg.serialise(&mut buf).expect("serialise");
+        IdentifyMessage::deserialise(&mut buf.freeze()).expect("deserialise")
+    }
+
+    #[test]
+    fn identify_messages_roundtrip() {
+        let system = SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), 8080);
+        let unique = |system: &SystemPath| -> ActorPath {
+            UniquePath::with_system(system.clone(), Uuid::new_v4()).into()
+        };
+        let prefix = NamedPath::with_system(system.clone(), vec!["a".into()]);
+        let named: ActorPath = prefix.clone().into();
+        let messages = vec![
+            IdentifyMessage::Request {
+                id: Uuid::new_v4(),
+                path: named,
+            },
+            IdentifyMessage::Response {
+                id: Uuid::new_v4(),
+                identity: ActorIdentity::NotFound,
+            },
+            IdentifyMessage::Response {
+                id: Uuid::new_v4(),
+                identity: ActorIdentity::Actor(unique(&system)),
+            },
+            IdentifyMessage::Response {
+                id: Uuid::new_v4(),
+                identity: ActorIdentity::Group(vec![unique(&system), unique(&system)]),
+            },
+            IdentifyMessage::ListRequest {
+                id: Uuid::new_v4(),
+                prefix: prefix.clone(),
+            },
+            IdentifyMessage::ListResponse {
+                id: Uuid::new_v4(),
+                entries: vec![
+                    RegisteredPath {
+                        path: prefix.clone(),
+                        entry: PathEntry::Policy,
+                    },
+                    RegisteredPath {
+                        path: NamedPath::with_system(system.clone(), vec!["a".into(), "b".into()]),
+                        entry: PathEntry::Actor(Uuid::new_v4()),
+                    },
+                ],
+            },
+        ];
+        for msg in messages {
+            assert_eq!(roundtrip(&msg), msg);
+        }
+    }
+
+    #[test]
+    fn corrupt_path_count_is_rejected() {
+        let mut buf = BytesMut::new();
+        buf.put_u8(RESPONSE_ID);
+        buf.put_slice(Uuid::new_v4().as_bytes());
+        buf.put_u8(GROUP_ID);
+        buf.put_u32(u32::MAX);
+        assert_eq!(capacity_for(u32::MAX as usize, 0, MIN_PATH_SIZE), 0);
+        assert!(IdentifyMessage::deserialise(&mut buf.freeze()).is_err());
+
+        let mut buf = BytesMut::new();
+        buf.put_u8(LIST_RESPONSE_ID);
+        buf.put_slice(Uuid::new_v4().as_bytes());
+        buf.put_u32(u32::MAX);
+        assert!(IdentifyMessage::deserialise(&mut buf.freeze()).is_err());
+    }
+
+    #[test]
+    fn min_path_size_matches_smallest_path() {
+        let system = SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), 8080);
+        let path: ActorPath = NamedPath::with_system(system, Vec::new()).into();
+        let mut buf = BytesMut::new();
+        path.serialise(&mut buf).expect("serialise");
+        assert_eq!(buf.len(), MIN_PATH_SIZE);
+    }
+}
@@ -27,6 +27,10 @@ mod registration;
 pub use registration::*;
 mod serialised;
 pub use serialised::*;
+mod watch;
+pub use watch::*;
+mod identify;
+pub use identify::*;
 pub(crate) mod dispatch;
 pub use dispatch::*;
 mod deser_macro;
@@ -10,6 +10,8 @@ pub enum RegistrationError {
     Unsupported,
     /// The supplied path was not invalid
     InvalidPath(PathParseError),
+    /// There is no matching entry to deregister at the supplied path
+    NotFound,
 }
 
 /// Convenience alias for the result of a path registration attempt
@@ -49,13 +51,32 @@ pub struct PolicyRegistration {
     pub path: Vec<String>,
 }
 
-/// One of the two registration event types
+/// A deregistration event
+///
+/// This is used for removing an [ActorRef](crate::prelude::ActorRef)
+/// or a [RoutingPolicy](crate::routing::groups::RoutingPolicy) from an
+/// [ActorPath](crate::prelude::ActorPath) on a dispatcher.
+#[derive(Debug)]
+pub enum Deregistration {
+    /// Remove the actor registered at the path
+    ///
+    /// Routing policies at the same path are left untouched.
+    Actor(PathResolvable),
+    /// Remove the routing policy set at the path
+    ///
+    /// Actors registered under the path are left untouched.
+    Policy(Vec<String>),
+}
+
+/// One of the three registration event types
 #[derive(Debug)]
 pub enum RegistrationEvent {
     /// An actor registration event
     Actor(ActorRegistration),
     /// A routing policy registration event
     Policy(PolicyRegistration),
+    /// A deregistration event
+    Deregistration(Deregistration),
 }
 impl From<ActorRegistration> for RegistrationEvent {
     fn from(r: ActorRegistration) -> Self {
@@ -67,12 +88,18 @@ impl From<PolicyRegistration> for RegistrationEvent {
         RegistrationEvent::Policy(r)
     }
 }
+impl From<Deregistration> for RegistrationEvent {
+    fn from(d: Deregistration) -> Self {
+        RegistrationEvent::Deregistration(d)
+    }
+}
 
 /// Envelope representing some registration event
 ///
 /// Supported registration events are:
 /// 1. Binding an actor reference to an actor path.
 /// 2. Binding a routing policy to an named path.
+/// 3. Removing an actor reference or a routing policy from a path.
 #[derive(Debug)]
 pub struct RegistrationEnvelope {
     /// The actual registration event
@@ -147,4 +174,18 @@ impl RegistrationEnvelope {
             promise: RegistrationPromise::Fulfil(promise),
         }
     }
+
+    /// Create a deregistration envelope using a promise for feedback
+    ///
+    /// The promise returns the path the entry was removed from.
+    pub fn deregistration_with_promise(
+        deregistration: Deregistration,
+        promise: utils::KPromise<RegistrationResult>,
+    ) -> Self {
+        RegistrationEnvelope {
+            event: deregistration.into(),
+            update: false,
+            promise: RegistrationPromise::Fulfil(promise),
+        }
+    }
 }
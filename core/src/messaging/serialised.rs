@@ -1,4 +1,5 @@
 use super::*;
+use std::io::IoSlice;
 
 /// Wrapper for serialised data with a serialisation id
 ///
@@ -87,7 +88,30 @@ impl SerialisedFrame {
         }
     }
 
-    /// Used by UDP sending which requires the frame to be a contiguous byte-sequence.
+    /// Fills `dst` with slices of the remaining data in this frame, one per chained segment
+    ///
+    /// Returns the number of slices written, which may be fewer than the number of segments
+    /// if `dst` is too short. Used for vectored (scatter-gather) writes on the network thread.
+    pub fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        match self {
+            SerialisedFrame::ChunkLease(chunk) => chunk.chunks_vectored(dst),
+            SerialisedFrame::ChunkRef(chunk) => chunk.chunks_vectored(dst),
+            SerialisedFrame::Bytes(bytes) => bytes.chunks_vectored(dst),
+        }
+    }
+
+    /// Advances the read position of the frame by `cnt` bytes
+    ///
+    /// Used to discard the part of a frame that has already been written to the network.
+    pub fn advance(&mut self, cnt: usize) {
+        match self {
+            SerialisedFrame::ChunkLease(chunk) => chunk.advance(cnt),
+            SerialisedFrame::ChunkRef(chunk) => chunk.advance(cnt),
+            SerialisedFrame::Bytes(bytes) => bytes.advance(cnt),
+        }
+    }
+
+    /// Converts the frame into a contiguous byte-sequence, by copying chained segments.
     /// Does nothing if it's already contiguous.
     pub fn make_contiguous(&mut self) {
         let len = self.len();
@@ -0,0 +1,480 @@
+use super::*;
+use crate::{actors::NamedPath, serialisation::serialisation_ids};
+use bytes::BufMut;
+
+/// A request to start or stop watching an actor, sent to the local dispatcher
+///
+/// Usually created via [watch](crate::prelude::ComponentContext::watch) and
+/// [unwatch](crate::prelude::ComponentContext::unwatch).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// `watcher` wants to be notified with [Terminated](Terminated) once `target` is gone
+    Watch {
+        /// The actor to notify
+        watcher: ActorPath,
+        /// The actor being watched
+        target: ActorPath,
+    },
+    /// `watcher` is no longer interested in `target`
+    Unwatch {
+        /// The actor that was being notified
+        watcher: ActorPath,
+        /// The actor that was being watched
+        target: ActorPath,
+    },
+    /// `watcher` wants to be notified with [MembershipEvent](MembershipEvent)
+    /// whenever an actor is registered or removed under `prefix`
+    WatchMembers {
+        /// The actor to notify
+        watcher: ActorPath,
+        /// The prefix of the group being watched
+        prefix: NamedPath,
+    },
+    /// `watcher` is no longer interested in the members under `prefix`
+    UnwatchMembers {
+        /// The actor that was being notified
+        watcher: ActorPath,
+        /// The prefix of the group that was being watched
+        prefix: NamedPath,
+    },
+    /// The watched local component with the given id was killed or has faulted
+    ///
+    /// Sent by the component itself, so its watchers are notified right away.
+    Terminated(Uuid),
+}
+
+/// Notifies a watcher that the watched actor is gone
+///
+/// Delivered as a network message to the actor that [watched](crate::prelude::ComponentContext::watch)
+/// the contained path, once the actor was killed or deregistered, or its system became unreachable.
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+///
+/// #[derive(ComponentDefinition)]
+/// struct Watcher {
+///     ctx: ComponentContext<Self>,
+/// }
+/// ignore_lifecycle!(Watcher);
+///
+/// impl Actor for Watcher {
+///     type Message = ActorPath;
+///
+///     fn receive_local(&mut self, target: Self::Message) -> Handled {
+///         self.ctx.watch(target);
+///         Handled::Ok
+///     }
+///
+///     fn receive_network(&mut self, msg: NetMessage) -> Handled {
+///         if let Ok(Terminated(path)) = msg.try_deserialise::<Terminated, Terminated>() {
+///             info!(self.log(), "{} is gone", path);
+///         }
+///         Handled::Ok
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Terminated(pub ActorPath);
+
+impl Serialisable for Terminated {
+    fn ser_id(&self) -> SerId {
+        serialisation_ids::TERMINATED
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        self.0.serialise(buf)
+    }
+
+    fn local(self: Box<Self>) -> Result<Box<dyn Any + Send>, Box<dyn Serialisable>> {
+        Ok(self)
+    }
+}
+
+impl Deserialiser<Terminated> for Terminated {
+    const SER_ID: SerId = serialisation_ids::TERMINATED;
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<Terminated, SerError> {
+        ActorPath::deserialise(buf).map(Terminated)
+    }
+}
+
+/// Notifies a watcher of a change in the members of a group
+///
+/// Delivered as a network message to the actor that [watched the members](crate::prelude::ComponentContext::watch_members)
+/// under a prefix. Directly after watching, the watcher receives a
+/// [MemberAdded](MembershipEvent::MemberAdded) event for every current member.
+///
+/// Members are the actors registered at or below the prefix.
+/// A member is removed when it is deregistered or killed,
+/// or when its system becomes unreachable.
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+///
+/// #[derive(ComponentDefinition)]
+/// struct MemberCache {
+///     ctx: ComponentContext<Self>,
+///     members: Vec<NamedPath>,
+/// }
+/// ignore_lifecycle!(MemberCache);
+///
+/// impl Actor for MemberCache {
+///     type Message = NamedPath;
+///
+///     fn receive_local(&mut self, prefix: Self::Message) -> Handled {
+///         self.ctx.watch_members(prefix);
+///         Handled::Ok
+///     }
+///
+///     fn receive_network(&mut self, msg: NetMessage) -> Handled {
+///         match msg.try_deserialise::<MembershipEvent, MembershipEvent>() {
+///             Ok(MembershipEvent::MemberAdded(member)) => self.members.push(member.path),
+///             Ok(MembershipEvent::MemberRemoved(member)) => {
+///                 self.members.retain(|path| path != &member.path)
+///             }
+///             Err(e) => error!(self.log(), "Unexpected message: {:?}", e),
+///         }
+///         Handled::Ok
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MembershipEvent {
+    /// An actor was registered under the watched prefix
+    MemberAdded(RegisteredPath),
+    /// An actor under the watched prefix was deregistered, killed, or became unreachable
+    MemberRemoved(RegisteredPath),
+}
+
+const MEMBER_ADDED_ID: u8 = 1;
+const MEMBER_REMOVED_ID: u8 = 2;
+
+impl MembershipEvent {
+    /// The member that was added or removed
+    pub fn member(&self) -> &RegisteredPath {
+        match self {
+            MembershipEvent::MemberAdded(member) | MembershipEvent::MemberRemoved(member) => member,
+        }
+    }
+
+    fn parts(&self) -> (u8, &RegisteredPath) {
+        match self {
+            MembershipEvent::MemberAdded(member) => (MEMBER_ADDED_ID, member),
+            MembershipEvent::MemberRemoved(member) => (MEMBER_REMOVED_ID, member),
+        }
+    }
+}
+
+impl Serialisable for MembershipEvent {
+    fn ser_id(&self) -> SerId {
+        serialisation_ids::MEMBERSHIP
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(1 + self.member().size_hint()?)
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        let (id, member) = self.parts();
+        buf.put_u8(id);
+        member.serialise(buf)
+    }
+
+    fn local(self: Box<Self>) -> Result<Box<dyn Any + Send>, Box<dyn Serialisable>> {
+        Ok(self)
+    }
+}
+
+impl Deserialiser<MembershipEvent> for MembershipEvent {
+    const SER_ID: SerId = serialisation_ids::MEMBERSHIP;
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<MembershipEvent, SerError> {
+        if buf.remaining() < 1 {
+            return Err(SerError::InvalidData(
+                "Could not get 1 byte for the membership event type".into(),
+            ));
+        }
+        let id = buf.get_u8();
+        let member = RegisteredPath::deserialise(buf)?;
+        match id {
+            MEMBER_ADDED_ID => Ok(MembershipEvent::MemberAdded(member)),
+            MEMBER_REMOVED_ID => Ok(MembershipEvent::MemberRemoved(member)),
+            id => Err(SerError::InvalidType(format!(
+                "Found unknown membership event type {}",
+                id
+            ))),
+        }
+    }
+}
+
+const WATCH_ID: u8 = 1;
+const UNWATCH_ID: u8 = 2;
+const TERMINATED_ID: u8 = 3;
+
+/// The protocol between the dispatchers of the watching and the watched actor's systems
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WatchMessage {
+    /// Start notifying `watcher` about `target`
+    Watch {
+        watcher: ActorPath,
+        target: ActorPath,
+    },
+    /// Stop notifying `watcher` about `target`
+    Unwatch {
+        watcher: ActorPath,
+        target: ActorPath,
+    },
+    /// `target`, watched by `watcher`, is gone
+    Terminated {
+        watcher: ActorPath,
+        target: ActorPath,
+    },
+}
+
+impl WatchMessage {
+    fn parts(&self) -> (u8, &ActorPath, &ActorPath) {
+        match self {
+            WatchMessage::Watch { watcher, target } => (WATCH_ID, watcher, target),
+            WatchMessage::Unwatch { watcher, target } => (UNWATCH_ID, watcher, target),
+            WatchMessage::Terminated { watcher, target } => (TERMINATED_ID, watcher, target),
+        }
+    }
+}
+
+impl Serialisable for WatchMessage {
+    fn ser_id(&self) -> SerId {
+        serialisation_ids::WATCH
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let (_, watcher, target) = self.parts();
+        Some(1 + watcher.size_hint()? + target.size_hint()?)
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        let (id, watcher, target) = self.parts();
+        buf.put_u8(id);
+        watcher.serialise(buf)?;
+        target.serialise(buf)
+    }
+
+    fn local(self: Box<Self>) -> Result<Box<dyn Any + Send>, Box<dyn Serialisable>> {
+        Ok(self)
+    }
+}
+
+impl Deserialiser<WatchMessage> for WatchMessage {
+    const SER_ID: SerId = serialisation_ids::WATCH;
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<WatchMessage, SerError> {
+        if buf.remaining() < 1 {
+            return Err(SerError::InvalidData(
+                "Could not get 1 byte for the watch message type".into(),
+            ));
+        }
+        let id = buf.get_u8();
+        let watcher = ActorPath::deserialise(buf)?;
+        let target = ActorPath::deserialise(buf)?;
+        match id {
+            WATCH_ID => Ok(WatchMessage::Watch { watcher, target }),
+            UNWATCH_ID => Ok(WatchMessage::Unwatch { watcher, target }),
+            TERMINATED_ID => Ok(WatchMessage::Terminated { watcher, target }),
+            id => Err(SerError::InvalidType(format!(
+                "Found unknown watch message type {}",
+                id
+            ))),
+        }
+    }
+}
+
+const WATCH_MEMBERS_ID: u8 = 1;
+const UNWATCH_MEMBERS_ID: u8 = 2;
+const MEMBERSHIP_CHANGED_ID: u8 = 3;
+
+/// The protocol between the dispatchers of a membership watcher and the watched group's system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MembershipMessage {
+    /// Start notifying `watcher` about the members under `prefix`
+    Watch {
+        watcher: ActorPath,
+        prefix: NamedPath,
+    },
+    /// Stop notifying `watcher` about the members under `prefix`
+    Unwatch {
+        watcher: ActorPath,
+        prefix: NamedPath,
+    },
+    /// The members under `prefix`, watched by `watcher`, changed
+    Changed {
+        watcher: ActorPath,
+        prefix: NamedPath,
+        event: MembershipEvent,
+    },
+}
+
+impl MembershipMessage {
+    fn parts(&self) -> (u8, &ActorPath, &NamedPath) {
+        match self {
+            MembershipMessage::Watch { watcher, prefix } => (WATCH_MEMBERS_ID, watcher, prefix),
+            MembershipMessage::Unwatch { watcher, prefix } => (UNWATCH_MEMBERS_ID, watcher, prefix),
+            MembershipMessage::Changed {
+                watcher, prefix, ..
+            } => (MEMBERSHIP_CHANGED_ID, watcher, prefix),
+        }
+    }
+}
+
+impl Serialisable for MembershipMessage {
+    fn ser_id(&self) -> SerId {
+        serialisation_ids::MEMBERSHIP_WATCH
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        let (_, watcher, prefix) = self.parts();
+        let prefix: ActorPath = prefix.clone().into();
+        let event_size = match self {
+            MembershipMessage::Changed { event, .. } => event.size_hint()?,
+            _ => 0,
+        };
+        Some(1 + watcher.size_hint()? + prefix.size_hint()? + event_size)
+    }
+
+    fn serialise(&self, buf: &mut dyn BufMut) -> Result<(), SerError> {
+        let (id, watcher, prefix) = self.parts();
+        buf.put_u8(id);
+        watcher.serialise(buf)?;
+        ActorPath::Named(prefix.clone()).serialise(buf)?;
+        if let MembershipMessage::Changed { event, .. } = self {
+            event.serialise(buf)?;
+        }
+        Ok(())
+    }
+
+    fn local(self: Box<Self>) -> Result<Box<dyn Any + Send>, Box<dyn Serialisable>> {
+        Ok(self)
+    }
+}
+
+impl Deserialiser<MembershipMessage> for MembershipMessage {
+    const SER_ID: SerId = serialisation_ids::MEMBERSHIP_WATCH;
+
+    fn deserialise(buf: &mut dyn Buf) -> Result<MembershipMessage, SerError> {
+        if buf.remaining() < 1 {
+            return Err(SerError::InvalidData(
+                "Could not get 1 byte for the membership message type".into(),
+            ));
+        }
+        let id = buf.get_u8();
+        let watcher = ActorPath::deserialise(buf)?;
+        let prefix = match ActorPath::deserialise(buf)? {
+            ActorPath::Named(prefix) => prefix,
+            ActorPath::Unique(path) => {
+                return Err(SerError::InvalidData(format!(
+                    "Expected a named prefix, but got {:?}",
+                    path
+                )))
+            }
+        };
+        match id {
+            WATCH_MEMBERS_ID => Ok(MembershipMessage::Watch { watcher, prefix }),
+            UNWATCH_MEMBERS_ID => Ok(MembershipMessage::Unwatch { watcher, prefix }),
+            MEMBERSHIP_CHANGED_ID => {
+                let event = MembershipEvent::deserialise(buf)?;
+                Ok(MembershipMessage::Changed {
+                    watcher,
+                    prefix,
+                    event,
+                })
+            }
+            id => Err(SerError::InvalidType(format!(
+                "Found unknown membership message type {}",
+                id
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::{NamedPath, SystemPath, Transport, UniquePath};
+    use bytes::BytesMut;
+
+    fn roundtrip<T, D>(value: &T) -> T
+    where
+        T: Serialisable + 'static,
+        D: Deserialiser<T>,
+    {
+        let mut buf = BytesMut::with_capacity(value.size_hint().unwrap_or(64));
+        value.serialise(&mut buf).expect("serialise");
+        D::deserialise(&mut buf.freeze()).expect("deserialise")
+    }
+
+    #[test]
+    fn watch_messages_roundtrip() {
+        let system = SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), 8080);
+        let watcher: ActorPath = UniquePath::with_system(system.clone(), Uuid::new_v4()).into();
+        let target: ActorPath = NamedPath::with_system(system, vec!["a".into(), "b".into()]).into();
+        let messages = vec![
+            WatchMessage::Watch {
+                watcher: watcher.clone(),
+                target: target.clone(),
+            },
+            WatchMessage::Unwatch {
+                watcher: watcher.clone(),
+                target: target.clone(),
+            },
+            WatchMessage::Terminated {
+                watcher,
+                target: target.clone(),
+            },
+        ];
+        for msg in messages {
+            assert_eq!(roundtrip::<WatchMessage, WatchMessage>(&msg), msg);
+        }
+        let terminated = Terminated(target);
+        assert_eq!(roundtrip::<Terminated, Terminated>(&terminated), terminated);
+    }
+
+    #[test]
+    fn membership_messages_roundtrip() {
+        let system = SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), 8080);
+        let watcher: ActorPath = UniquePath::with_system(system.clone(), Uuid::new_v4()).into();
+        let prefix = NamedPath::with_system(system.clone(), vec!["group".into()]);
+        let member = RegisteredPath {
+            path: NamedPath::with_system(system, vec!["group".into(), "a".into()]),
+            entry: PathEntry::Actor(Uuid::new_v4()),
+        };
+        let events = vec![
+            MembershipEvent::MemberAdded(member.clone()),
+            MembershipEvent::MemberRemoved(member),
+        ];
+        for event in events {
+            assert_eq!(roundtrip::<MembershipEvent, MembershipEvent>(&event), event);
+            let msg = MembershipMessage::Changed {
+                watcher: watcher.clone(),
+                prefix: prefix.clone(),
+                event,
+            };
+            assert_eq!(roundtrip::<MembershipMessage, MembershipMessage>(&msg), msg);
+        }
+        let messages = vec![
+            MembershipMessage::Watch {
+                watcher: watcher.clone(),
+                prefix: prefix.clone(),
+            },
+            MembershipMessage::Unwatch { watcher, prefix },
+        ];
+        for msg in messages {
+            assert_eq!(roundtrip::<MembershipMessage, MembershipMessage>(&msg), msg);
+        }
+    }
+}
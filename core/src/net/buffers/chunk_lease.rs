@@ -1,6 +1,6 @@
 use super::*;
 use bytes::{buf::UninitSlice, Bytes};
-use std::{cmp::Ordering, ptr::NonNull};
+use std::{cmp::Ordering, io::IoSlice, ptr::NonNull};
 
 /// A ChunkLease is a smart-pointer to a byte-slice, implementing [Buf](bytes::Buf) and
 /// [BufMut](bytes::BufMut) interfaces. They are created with one or many distinct slices of
@@ -224,6 +224,13 @@ impl Buf for ChunkLease {
     fn advance(&mut self, cnt: usize) {
         self.read_pointer += cnt;
     }
+
+    // Fills `dst` with one slice per remaining link in the chain
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        fill_vectored(self.read_pointer, self.chain_len, dst, |pos| {
+            self.get_chunk_at(pos)
+        })
+    }
 }
 
 // BufMut currently only used for injecting a FrameHead at the front.
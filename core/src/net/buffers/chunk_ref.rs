@@ -1,5 +1,6 @@
+use super::fill_vectored;
 use crate::prelude::Buf;
-use std::sync::Arc;
+use std::{io::IoSlice, sync::Arc};
 
 /// A `ChunkRef` is created from a `ChunkLease`, or from a `ChunkRef` and a `ChunkLease`.
 /// It is immutable and may be cloned and shared and chained with many other `ChunkRefs`.
@@ -80,6 +81,13 @@ impl Buf for ChunkRef {
     fn advance(&mut self, cnt: usize) {
         self.read_pointer += cnt;
     }
+
+    // Fills `dst` with one slice per remaining link in the chain
+    fn chunks_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        fill_vectored(self.read_pointer, self.chain_len, dst, |pos| {
+            self.get_chunk_at(pos)
+        })
+    }
 }
 
 unsafe impl Send for ChunkRef {}
@@ -247,4 +255,38 @@ mod tests {
         // Should be released as they are now out of scope
         assert_eq!(encode_buffer.buffer_pool.count_locked_chunks(), 0);
     }
+
+    #[test]
+    fn chunk_ref_chained_vectored() {
+        let byte_vec = generate_bytes(256, 2);
+        let mut encode_buffer = get_testing_encode_buffer();
+        let mut buffer_encoder = encode_buffer
+            .get_buffer_encoder()
+            .expect("Should not run out of buffers in test case");
+        buffer_encoder.put(byte_vec[0].clone());
+        let head = buffer_encoder.get_chunk_lease().unwrap().into_chunk_ref();
+        buffer_encoder.put(byte_vec[1].clone());
+        let mut chunk_ref = buffer_encoder
+            .get_chunk_lease()
+            .unwrap()
+            .into_chunk_ref_with_head(head);
+        drop(buffer_encoder);
+        // Skip into the middle of the first segment to check that the read pointer is respected
+        chunk_ref.advance(10);
+
+        let mut slices = [IoSlice::new(&[]); 8];
+        let filled = chunk_ref.chunks_vectored(&mut slices);
+        assert!(filled > 1, "Chained ChunkRef should produce several slices");
+        let gathered: Vec<u8> = slices[..filled]
+            .iter()
+            .flat_map(|slice| slice.iter().copied())
+            .collect();
+        let comp_vec = generate_bytes(512, 1);
+        assert_eq!(&gathered[..], &comp_vec[0][10..]);
+
+        // A too short destination only gets as many slices as fit
+        let mut short = [IoSlice::new(&[]); 1];
+        assert_eq!(chunk_ref.chunks_vectored(&mut short), 1);
+        assert_eq!(&short[0][..], chunk_ref.chunk());
+    }
 }
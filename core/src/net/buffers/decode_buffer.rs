@@ -72,6 +72,12 @@ impl DecodeBuffer {
         }
     }
 
+    /// Returns the raw parts of the underlying [BufferChunk](BufferChunk::raw_parts)
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    pub(crate) fn raw_parts(&mut self) -> (*mut u8, usize, std::sync::Weak<u8>) {
+        self.buffer.raw_parts()
+    }
+
     /// True if there is sufficient amount of writeable bytes
     pub(crate) fn is_writeable(&mut self) -> bool {
         self.writeable_len() > 8
@@ -6,6 +6,7 @@ use hocon::{Hocon, HoconLoader};
 use std::{
     convert::TryFrom,
     fmt::{Debug, Formatter},
+    io::IoSlice,
     sync::Arc,
 };
 
@@ -300,6 +301,16 @@ impl BufferChunk {
         }
     }
 
+    /// Returns the address and length of the underlying [Chunk](Chunk),
+    /// together with a handle that stays alive for as long as the BufferChunk does.
+    ///
+    /// Used to register the chunk's memory with the operating system.
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    pub(crate) fn raw_parts(&mut self) -> (*mut u8, usize, std::sync::Weak<u8>) {
+        let ptr = unsafe { (&mut *self.chunk).as_mut_ptr() };
+        (ptr, self.len(), Arc::downgrade(&self.ref_count))
+    }
+
     /// Locks the BufferChunk, it will not be writable nor will any leases be created before it has been unlocked.
     pub fn lock(&mut self) -> () {
         self.locked = true;
@@ -337,6 +348,29 @@ impl ChunkAllocator for DefaultAllocator {
     }
 }
 
+/// Fills `dst` with one slice per chunk of a chain, reading from `pos` until `end`
+///
+/// `chunk_at` must return the remainder of the chunk that contains the given position.
+/// Returns the number of slices written to `dst`.
+pub(super) fn fill_vectored<'a, F>(
+    mut pos: usize,
+    end: usize,
+    dst: &mut [IoSlice<'a>],
+    chunk_at: F,
+) -> usize
+where
+    F: Fn(usize) -> &'a [u8],
+{
+    let mut filled = 0;
+    while filled < dst.len() && pos < end {
+        let slice = chunk_at(pos);
+        dst[filled] = IoSlice::new(slice);
+        pos += slice.len();
+        filled += 1;
+    }
+    filled
+}
+
 /// Errors that can be thrown during serialisation or deserialisation
 #[derive(Debug)]
 pub enum BufferError {
@@ -20,7 +20,10 @@ pub mod buffers;
 pub mod frames;
 pub(crate) mod network_channel;
 pub(crate) mod network_thread;
+pub mod rate_limit;
 pub(crate) mod udp_state;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub(crate) mod uring;
 
 /// The state of a connection
 #[derive(Clone, Debug)]
@@ -81,13 +84,72 @@ impl SessionId {
     }
 }
 
+/// The I/O backend driving the `NetworkThread`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkBackend {
+    /// Readiness-based I/O through `mio`, available on all platforms
+    Mio,
+    /// Completion-based I/O through Linux `io_uring`
+    ///
+    /// Reads go directly into the network thread's pooled buffers,
+    /// which are registered with the kernel as they are first used.
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    IoUring,
+}
+
+impl Default for NetworkBackend {
+    fn default() -> Self {
+        NetworkBackend::Mio
+    }
+}
+
+/// What the `NetworkThread` does with new connections once the maximum number of connections is reached
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionLimitPolicy {
+    /// New connections are refused
+    ///
+    /// Incoming connections are shut down as soon as they are accepted,
+    /// while outgoing connections are retried like failed connection attempts.
+    Reject,
+    /// The connection that has been idle for the longest time is gracefully closed to make room
+    EvictIdlest,
+}
+
+impl Default for ConnectionLimitPolicy {
+    fn default() -> Self {
+        ConnectionLimitPolicy::Reject
+    }
+}
+
+/// Wakes up the `NetworkThread` when new [DispatchEvents](events::DispatchEvent) are queued
+#[derive(Debug)]
+pub(crate) enum NetworkWaker {
+    Mio(Waker),
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    Uring(uring::UringWaker),
+}
+
+impl NetworkWaker {
+    pub(crate) fn wake(&self) -> io::Result<()> {
+        match self {
+            NetworkWaker::Mio(waker) => waker.wake(),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            NetworkWaker::Uring(waker) => waker.wake(),
+        }
+    }
+}
+
 /// Events on the network level
 pub mod events {
 
     use super::ConnectionState;
     use crate::{
         messaging::DispatchData,
-        net::{frames::*, SocketAddr},
+        net::{
+            frames::*,
+            rate_limit::{ThrottledPeer, TrafficDirection},
+            SocketAddr,
+        },
     };
     use std::net::IpAddr;
 
@@ -100,6 +162,9 @@ pub mod events {
         Data(Frame),
         /// The NetworkThread lost connection to the remote host and rejects the frame
         RejectedData(SocketAddr, DispatchData),
+        /// The NetworkThread did not connect to the `SocketAddr`,
+        /// as the maximum number of connections is reached
+        ConnectionRejected(SocketAddr),
         /// The NetworkThread has blocked `SocketAddr` and dropped its corresponding channel.
         /// Boolean flag determines if an Indication on NetworkStatusPort should be triggered.
         BlockedSocket(SocketAddr, bool),
@@ -110,6 +175,10 @@ pub mod events {
         UnblockedSocket(SocketAddr, bool),
         /// The NetworkThread has unblocked `IpAddr`
         UnblockedIp(IpAddr),
+        /// The NetworkThread has started enforcing a rate limit on the traffic of the peer
+        Throttled(ThrottledPeer, TrafficDirection),
+        /// The NetworkThread no longer holds back traffic of the peer
+        Unthrottled(ThrottledPeer, TrafficDirection),
     }
 
     /// BridgeEvents emitted to the network `Bridge`
@@ -200,13 +269,15 @@ pub struct Bridge {
     // network_thread: Box<NetworkThread>,
     // ^ Can we avoid storing this by moving it into itself?
     network_input_queue: Sender<events::DispatchEvent>,
-    waker: Waker,
+    waker: NetworkWaker,
     /// Tokio Runtime
     // tokio_runtime: Option<Runtime>,
     /// Reference back to the Kompact dispatcher
     dispatcher: Option<DispatcherRef>,
     /// Socket the network actually bound on
     bound_address: Option<SocketAddr>,
+    /// Backend the network thread actually runs on
+    backend: NetworkBackend,
     shutdown_future: KFuture<()>,
 }
 
@@ -237,6 +308,7 @@ impl Bridge {
         ) {
             Ok(mut network_thread_builder) => {
                 let bound_address = network_thread_builder.address;
+                let backend = network_thread_builder.backend();
                 let waker = network_thread_builder
                     .take_waker()
                     .expect("NetworkThread poll error");
@@ -256,6 +328,7 @@ impl Bridge {
                     waker,
                     dispatcher: Some(dispatcher_ref),
                     bound_address: Some(bound_address),
+                    backend,
                     shutdown_future: shutdown_f,
                 };
 
@@ -297,6 +370,13 @@ impl Bridge {
         &self.bound_address
     }
 
+    /// Returns the backend the network thread runs on
+    ///
+    /// This is [NetworkBackend::Mio] if the configured backend could not be set up.
+    pub fn backend(&self) -> NetworkBackend {
+        self.backend
+    }
+
     /// Forwards `serialized` to the NetworkThread and makes sure that it will wake up.
     pub(crate) fn route(
         &self,
@@ -836,6 +916,118 @@ pub mod net_test_helpers {
         }
     }
 
+    /// An actor which watches `target` and completes a future once it is terminated
+    #[derive(ComponentDefinition)]
+    pub struct WatcherAct {
+        ctx: ComponentContext<Self>,
+        target: ActorPath,
+        promise: Option<KPromise<ActorPath>>,
+    }
+
+    impl WatcherAct {
+        /// Creates a new `WatcherAct` watching `target`
+        pub fn new(target: ActorPath) -> Self {
+            WatcherAct {
+                ctx: ComponentContext::uninitialised(),
+                target,
+                promise: None,
+            }
+        }
+
+        /// Returns a future which will complete with the path in the received `Terminated`
+        pub fn terminated_future(&mut self) -> KFuture<ActorPath> {
+            let (promise, future) = promise();
+            self.promise = Some(promise);
+            future
+        }
+    }
+
+    impl ComponentLifecycle for WatcherAct {
+        fn on_start(&mut self) -> Handled {
+            self.ctx.watch(self.target.clone());
+            Handled::Ok
+        }
+    }
+
+    impl Actor for WatcherAct {
+        type Message = Never;
+
+        fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+            unimplemented!();
+        }
+
+        fn receive_network(&mut self, msg: NetMessage) -> Handled {
+            match msg.try_deserialise::<Terminated, Terminated>() {
+                Ok(Terminated(path)) => {
+                    debug!(self.ctx.log(), "Watched actor {} terminated", path);
+                    if let Some(promise) = self.promise.take() {
+                        promise.fulfil(path).expect("fulfil terminated promise");
+                    }
+                }
+                Err(e) => error!(self.ctx.log(), "Error deserialising Terminated: {:?}", e),
+            }
+            Handled::Ok
+        }
+    }
+
+    /// An actor which watches the members under `prefix` and keeps track of them
+    #[derive(ComponentDefinition)]
+    pub struct MembershipWatcherAct {
+        ctx: ComponentContext<Self>,
+        prefix: NamedPath,
+        /// The paths of the current members, in order of addition
+        pub members: Vec<NamedPath>,
+        /// The number of [MembershipEvent] received
+        pub events: usize,
+    }
+
+    impl MembershipWatcherAct {
+        /// Creates a new `MembershipWatcherAct` watching the members under `prefix`
+        pub fn new(prefix: NamedPath) -> Self {
+            MembershipWatcherAct {
+                ctx: ComponentContext::uninitialised(),
+                prefix,
+                members: Vec::new(),
+                events: 0,
+            }
+        }
+    }
+
+    impl ComponentLifecycle for MembershipWatcherAct {
+        fn on_start(&mut self) -> Handled {
+            self.ctx.watch_members(self.prefix.clone());
+            Handled::Ok
+        }
+    }
+
+    impl Actor for MembershipWatcherAct {
+        type Message = Never;
+
+        fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+            unimplemented!();
+        }
+
+        fn receive_network(&mut self, msg: NetMessage) -> Handled {
+            match msg.try_deserialise::<MembershipEvent, MembershipEvent>() {
+                Ok(event) => {
+                    debug!(self.ctx.log(), "Got {:?}", event);
+                    self.events += 1;
+                    match event {
+                        MembershipEvent::MemberAdded(member) => self.members.push(member.path),
+                        MembershipEvent::MemberRemoved(member) => {
+                            self.members.retain(|path| path != &member.path)
+                        }
+                    }
+                }
+                Err(e) => error!(
+                    self.ctx.log(),
+                    "Error deserialising MembershipEvent: {:?}", e
+                ),
+            }
+            Handled::Ok
+        }
+    }
+
     #[derive(Clone)]
     struct BigPingMsg {
         i: u64,
@@ -1304,6 +1496,8 @@ pub mod net_test_helpers {
         pub blocked_systems: Vec<SystemPath>,
         /// The blocked ip addresses
         pub blocked_ip: Vec<IpAddr>,
+        /// The peers currently being throttled, and in which direction
+        pub throttled_peers: Vec<(ThrottledPeer, TrafficDirection)>,
         /// Counts the number of max_channels_reached messages received
         pub max_channels_reached: u32,
         /// Counts the number of network_out_of_buffers messages received
@@ -1325,6 +1519,7 @@ pub mod net_test_helpers {
                 connected_systems: Vec::new(),
                 disconnected_systems: Vec::new(),
                 blocked_systems: Vec::new(),
+                throttled_peers: Vec::new(),
                 blocked_ip: Vec::new(),
                 max_channels_reached: 0,
                 network_out_of_buffers: 0,
@@ -1414,6 +1609,12 @@ pub mod net_test_helpers {
                     self.blocked_systems.retain(|s| s != &sys_path)
                 }
                 NetworkStatus::UnblockedIp(ip_addr) => self.blocked_ip.retain(|ip| ip != &ip_addr),
+                NetworkStatus::PeerThrottled(peer, direction) => {
+                    self.throttled_peers.push((peer, direction))
+                }
+                NetworkStatus::PeerUnthrottled(peer, direction) => self
+                    .throttled_peers
+                    .retain(|throttled| throttled != &(peer.clone(), direction)),
             }
             Handled::Ok
         }
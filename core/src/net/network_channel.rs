@@ -1,3 +1,5 @@
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+use super::uring::{Completion, CompletionState, OpKind, UringDriver};
 use super::*;
 use crate::{
     messaging::SerialisedFrame,
@@ -6,17 +8,20 @@ use crate::{
         frames::{Frame, FramingError, Hello, Start, FRAME_HEAD_LEN},
     },
 };
-use bytes::{Buf, BytesMut};
+use bytes::BytesMut;
 use mio::{net::TcpStream, Token};
 use network_thread::*;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+use std::os::unix::io::AsRawFd;
 use std::{
     cell::RefCell,
     cmp::Ordering,
     collections::VecDeque,
     fmt::Formatter,
     io,
-    io::{Error, ErrorKind, Read, Write},
+    io::{Error, ErrorKind, IoSlice, Read, Write},
     net::{Shutdown::Both, SocketAddr},
+    time::Instant,
 };
 
 /// Received connection: Initialising -> Say Hello, Receive Start -> Connected, Send Ack
@@ -65,6 +70,11 @@ pub(crate) struct TcpChannel {
     pub messages: u32,
     own_addr: SocketAddr,
     nodelay: bool,
+    /// When a message was last sent or received on the channel
+    last_activity: Instant,
+    /// Set if the channel's I/O is performed by an `io_uring` instead of on readiness
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    completion: Option<CompletionState>,
 }
 
 impl TcpChannel {
@@ -88,6 +98,9 @@ impl TcpChannel {
             messages: 0,
             own_addr,
             nodelay: network_config.get_tcp_nodelay(),
+            last_activity: Instant::now(),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            completion: None,
         }
     }
 
@@ -105,10 +118,25 @@ impl TcpChannel {
         matches!(self.state, ChannelState::Connected(_, _))
     }
 
+    /// Returns `true` if the channel is being gracefully closed
+    pub fn closing(&self) -> bool {
+        matches!(
+            self.state,
+            ChannelState::CloseRequested(_, _)
+                | ChannelState::CloseReceived(_, _)
+                | ChannelState::Closed(_, _)
+        )
+    }
+
     pub fn session_id(&self) -> Option<SessionId> {
         self.state.session_id()
     }
 
+    /// Returns when a message was last sent or received on the channel
+    pub fn last_activity(&self) -> Instant {
+        self.last_activity
+    }
+
     /// Internal helper function for special frames
     fn send_frame(&mut self, mut frame: Frame) -> () {
         let len = frame.encoded_len() + FRAME_HEAD_LEN as usize;
@@ -191,6 +219,14 @@ impl TcpChannel {
     }
 
     pub fn take_outbound(&mut self) -> Vec<SerialisedFrame> {
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        {
+            // Frames referenced by a write in flight must stay in the queue until it completes
+            if let Some(state) = &self.completion {
+                let in_flight = state.frames_in_flight().min(self.outbound_queue.len());
+                return self.outbound_queue.drain(in_flight..).collect();
+            }
+        }
         let mut ret = Vec::new();
         while let Some(frame) = self.outbound_queue.pop_front() {
             ret.push(frame);
@@ -237,6 +273,17 @@ impl TcpChannel {
 
     /// This tries to read from the Tcp buffer into the DecodeBuffer, nothing else.
     fn receive(&mut self) -> io::Result<()> {
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        {
+            // The ring has already read into the buffer, only report the outcome
+            if let Some(state) = &mut self.completion {
+                state.take_recv_result()?;
+                if !state.recv_in_flight() && !self.input_buffer.is_writeable() {
+                    return Err(io::Error::new(ErrorKind::InvalidInput, "No Buffer Space"));
+                }
+                return Ok(());
+            }
+        }
         let mut interrupts = 0;
         loop {
             let mut buf = self
@@ -327,6 +374,7 @@ impl TcpChannel {
         match self.input_buffer.get_frame() {
             Ok(frame) => {
                 self.messages += 1;
+                self.last_activity = Instant::now();
                 Ok(frame)
             }
             Err(e) => Err(e),
@@ -336,55 +384,48 @@ impl TcpChannel {
     /// Enqueues the frame for sending on the channel.
     /// Enquing to a non-connected channel is disallowed.
     pub fn enqueue_serialised(&mut self, serialized: SerialisedFrame) -> () {
+        self.last_activity = Instant::now();
         self.outbound_queue.push_back(serialized);
     }
 
-    /// Tries to drain the outbound buffer into
+    /// Tries to drain the outbound buffer into the socket
+    ///
+    /// Segments of several queued frames are gathered into a single vectored write,
+    /// so chained and preserialised payloads are written without copying them first.
     pub fn try_drain(&mut self) -> io::Result<usize> {
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        {
+            // Writes are submitted to the ring by the network thread, only report failed ones
+            if let Some(state) = &mut self.completion {
+                return state.take_write_result();
+            }
+        }
         let mut sent_bytes: usize = 0;
         let mut interrupts = 0;
-        while let Some(mut serialized_frame) = self.outbound_queue.pop_front() {
-            match self.write_serialized(&serialized_frame) {
+        self.discard_sent(0);
+        while !self.outbound_queue.is_empty() {
+            match self.write_vectored() {
+                Ok(0) => {
+                    // Nothing was accepted by the socket, try again on the next writable event
+                    return Ok(sent_bytes);
+                }
                 Ok(n) => {
                     sent_bytes += n;
-                    match &mut serialized_frame {
-                        // Split the data and continue sending the rest later if we sent less than the full frame
-                        SerialisedFrame::Bytes(bytes) => {
-                            if n < bytes.len() {
-                                let _ = bytes.split_to(n); // Discard the already sent split off part.
-                                self.outbound_queue.push_front(serialized_frame);
-                            }
-                        }
-                        SerialisedFrame::ChunkLease(chunk) => {
-                            if n < chunk.remaining() {
-                                chunk.advance(n);
-                                self.outbound_queue.push_front(serialized_frame);
-                            }
-                        }
-                        SerialisedFrame::ChunkRef(chunk) => {
-                            if n < chunk.remaining() {
-                                chunk.advance(n);
-                                self.outbound_queue.push_front(serialized_frame);
-                            }
-                        }
-                    }
-                    // Continue looping for the next message
+                    // Drop fully sent frames and advance a partially sent one, then continue
+                    self.discard_sent(n);
                 }
                 // Would block "errors" are the OS's way of saying that the
                 // connection is not actually ready to perform this I/O operation.
                 Err(ref err) if would_block(err) => {
-                    self.outbound_queue.push_front(serialized_frame);
                     return Ok(sent_bytes);
                 }
                 Err(err) if interrupted(&err) => {
-                    self.outbound_queue.push_front(serialized_frame);
                     interrupts += 1;
                     if interrupts >= MAX_INTERRUPTS {
                         return Err(err);
                     }
                 }
                 Err(err) => {
-                    self.outbound_queue.push_front(serialized_frame);
                     return Err(err);
                 }
             }
@@ -392,18 +433,111 @@ impl TcpChannel {
         Ok(sent_bytes)
     }
 
+    /// Removes `sent` bytes from the front of the outbound queue.
+    ///
+    /// Frames that were written completely are dropped, releasing their buffers,
+    /// while a partially written frame stays at the front with its read position advanced.
+    fn discard_sent(&mut self, mut sent: usize) -> () {
+        while let Some(frame) = self.outbound_queue.front_mut() {
+            let len = frame.len();
+            if sent >= len {
+                sent -= len;
+                self.outbound_queue.pop_front();
+            } else {
+                frame.advance(sent);
+                return;
+            }
+        }
+        debug_assert_eq!(sent, 0, "Wrote more bytes than were queued");
+    }
+
     /// No direct writing allowed, Must use other interface.
-    fn write_serialized(&mut self, serialized: &SerialisedFrame) -> io::Result<usize> {
-        match serialized {
-            SerialisedFrame::ChunkLease(chunk) => self.stream.write(chunk.chunk()),
-            SerialisedFrame::Bytes(bytes) => self.stream.write(bytes.chunk()),
-            SerialisedFrame::ChunkRef(chunkref) => self.stream.write(chunkref.chunk()),
+    ///
+    /// Gathers up to [MAX_IO_SLICES] segments from the front of the outbound queue
+    /// and writes them with a single `writev` call.
+    fn write_vectored(&mut self) -> io::Result<usize> {
+        let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+        let mut filled = 0;
+        for frame in self.outbound_queue.iter() {
+            if filled == MAX_IO_SLICES {
+                break;
+            }
+            filled += frame.chunks_vectored(&mut slices[filled..]);
         }
+        self.stream.write_vectored(&slices[..filled])
     }
 
     pub(crate) fn kill(&mut self) -> () {
         let _ = self.stream.shutdown(Both);
     }
+
+    /// Returns `true` if there are no frames waiting to be sent
+    pub(crate) fn outbound_is_empty(&self) -> bool {
+        self.outbound_queue.is_empty()
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+impl TcpChannel {
+    /// Switches the channel to completion-based I/O through an `io_uring`
+    ///
+    /// The socket stays non-blocking, so operations that find it not ready fail with `EAGAIN`,
+    /// after which its readiness is awaited through the ring before they are retried.
+    pub(crate) fn enable_completion(&mut self) -> io::Result<()> {
+        self.completion = Some(CompletionState::default());
+        Ok(())
+    }
+
+    pub(crate) fn completion_state(&mut self) -> Option<&mut CompletionState> {
+        self.completion.as_mut()
+    }
+
+    /// Submits a read and a write to the ring, unless one of them is already in flight
+    pub(crate) fn submit_io(&mut self, driver: &mut UringDriver) -> io::Result<()> {
+        let token = self.token;
+        let fd = self.stream.as_raw_fd();
+        let state = match &mut self.completion {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        if state.awaiting_readable() {
+            driver.poll(token, fd, OpKind::PollIn)?;
+        } else if state.can_recv() {
+            let chunk = self.input_buffer.raw_parts();
+            if let Some(buffer) = self.input_buffer.get_writeable() {
+                driver.recv(token, fd, state, buffer, chunk)?;
+            }
+        }
+        if state.awaiting_writable() {
+            if !self.outbound_queue.is_empty() {
+                driver.poll(token, fd, OpKind::PollOut)?;
+            }
+        } else if state.can_write() {
+            driver.write(token, fd, state, self.outbound_queue.iter())?;
+        }
+        Ok(())
+    }
+
+    /// Applies a completed operation of the ring to the channel
+    pub(crate) fn complete_io(&mut self, completion: &Completion) -> () {
+        let state = match &mut self.completion {
+            Some(state) => state,
+            None => return,
+        };
+        match completion.kind {
+            OpKind::Recv => {
+                let read = state.complete_recv(completion);
+                self.input_buffer.advance_writeable(read);
+            }
+            OpKind::Write => {
+                let written = state.complete_write(completion);
+                self.discard_sent(written);
+            }
+            OpKind::PollIn => state.complete_poll(),
+            OpKind::PollOut => state.complete_poll_out(),
+            _ => (),
+        }
+    }
 }
 
 impl std::fmt::Debug for TcpChannel {
@@ -470,3 +604,103 @@ impl Ord for SocketWrapper {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::buffers::{BufferConfig, ChunkLease, EncodeBuffer};
+    use bytes::{Buf, BufMut, Bytes};
+    use std::{net::TcpListener, time::Duration};
+
+    fn testing_encode_buffer() -> EncodeBuffer {
+        let mut cfg = BufferConfig::default();
+        cfg.chunk_size(128);
+        cfg.initial_chunk_count(2);
+        EncodeBuffer::with_config(&cfg, &None)
+    }
+
+    fn chained_lease(encode_buffer: &mut EncodeBuffer, data: &[u8]) -> ChunkLease {
+        let mut encoder = encode_buffer
+            .get_buffer_encoder()
+            .expect("Should not run out of buffers in test case");
+        encoder.put_slice(data);
+        encoder.get_chunk_lease().expect("lease")
+    }
+
+    fn connected_channel() -> (TcpChannel, std::net::TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let client = std::net::TcpStream::connect(addr).expect("connect");
+        let (server, _) = listener.accept().expect("accept");
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .expect("timeout");
+        client.set_nonblocking(true).expect("nonblocking");
+        let own_addr = client.local_addr().expect("addr");
+        let channel = TcpChannel::new(
+            TcpStream::from_std(client),
+            Token(0),
+            addr,
+            BufferChunk::new(128),
+            ChannelState::Initialising,
+            own_addr,
+            &NetworkConfig::default(),
+        );
+        (channel, server)
+    }
+
+    fn read_exactly(stream: &mut std::net::TcpStream, len: usize) -> Vec<u8> {
+        let mut received = vec![0u8; len];
+        stream.read_exact(&mut received).expect("read");
+        received
+    }
+
+    fn payload(len: usize, offset: usize) -> Vec<u8> {
+        (0..len).map(|i| ((i + offset) % 251) as u8).collect()
+    }
+
+    #[test]
+    fn partial_write_resumes_within_chained_frame() {
+        let mut encode_buffer = testing_encode_buffer();
+        let (mut channel, mut server) = connected_channel();
+        let first = payload(300, 0);
+        let second = payload(50, 7);
+        let lease = chained_lease(&mut encode_buffer, &first);
+        assert!(lease.chunk().len() < first.len(), "frame must be chained");
+        channel.enqueue_serialised(SerialisedFrame::ChunkLease(lease));
+        channel.enqueue_serialised(SerialisedFrame::Bytes(Bytes::from(second.clone())));
+
+        // pretend a previous write stopped past the first segment of the chained frame
+        let sent = front_segment_len(&channel) + 5;
+        channel.discard_sent(sent);
+        let expected: Vec<u8> = first[sent..].iter().chain(second.iter()).copied().collect();
+        assert_eq!(expected.len(), channel.try_drain().expect("drain"));
+        assert!(channel.outbound_is_empty());
+        assert_eq!(expected, read_exactly(&mut server, expected.len()));
+    }
+
+    #[test]
+    fn partial_write_resumes_across_frames() {
+        let mut encode_buffer = testing_encode_buffer();
+        let (mut channel, mut server) = connected_channel();
+        let first = payload(200, 0);
+        let second = payload(300, 3);
+        let first_lease = chained_lease(&mut encode_buffer, &first);
+        let second_lease = chained_lease(&mut encode_buffer, &second);
+        channel.enqueue_serialised(SerialisedFrame::ChunkRef(first_lease.into_chunk_ref()));
+        channel.enqueue_serialised(SerialisedFrame::ChunkLease(second_lease));
+
+        // the first frame was written completely and the second one into its second segment
+        let sent = first.len() + 130;
+        channel.discard_sent(sent);
+        assert_eq!(1, channel.outbound_queue.len());
+        let expected = second[130..].to_vec();
+        assert_eq!(expected.len(), channel.try_drain().expect("drain"));
+        assert_eq!(expected, read_exactly(&mut server, expected.len()));
+    }
+
+    // The length of the first segment of the frame at the front of the queue
+    fn front_segment_len(channel: &TcpChannel) -> usize {
+        channel.outbound_queue.front().expect("frame").bytes().len()
+    }
+}
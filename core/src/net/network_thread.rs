@@ -1,20 +1,35 @@
 use super::*;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+use crate::net::uring::{Completion, OpKind, UringDriver};
 use crate::{
     dispatch::{
         lookup::{ActorLookup, LookupResult},
         NetworkConfig,
     },
-    messaging::{DispatchEnvelope, EventEnvelope, NetMessage, SerialisedFrame},
+    messaging::{
+        framing::peek_receiver,
+        DispatchEnvelope,
+        EventEnvelope,
+        NetMessage,
+        SerialisedFrame,
+    },
     net::{
         buffers::{BufferChunk, BufferPool, EncodeBuffer},
         network_channel::{ChannelState, TcpChannel},
+        rate_limit::{Admission, Throttle, TrafficDirection},
         udp_state::UdpState,
+        ConnectionLimitPolicy,
         ConnectionState,
         ConnectionState::Connected,
+        Protocol,
     },
     prelude::SessionId,
-    serialisation::ser_helpers::deserialise_chunk_lease,
+    serialisation::{
+        ser_helpers::{deserialise_chunk_lease, deserialise_chunk_lease_to},
+        Deserialiser,
+    },
 };
+use bytes::Buf;
 use crossbeam_channel::Receiver as Recv;
 use mio::{
     event::Event,
@@ -24,6 +39,8 @@ use mio::{
     Token,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+use std::os::unix::io::AsRawFd;
 use std::{
     cell::{RefCell, RefMut},
     collections::VecDeque,
@@ -32,7 +49,7 @@ use std::{
     ops::DerefMut,
     rc::Rc,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
     usize,
 };
 
@@ -45,14 +62,63 @@ const START_TOKEN: Token = Token(3);
 const MAX_POLL_EVENTS: usize = 1024;
 /// How many times to retry on interrupt before we give up
 pub const MAX_INTERRUPTS: i32 = 9;
+/// The maximum number of buffer segments passed to a single vectored write
+pub const MAX_IO_SLICES: usize = 64;
 // We do retries when we fail to bind a socket listener during boot-up:
 const MAX_BIND_RETRIES: usize = 5;
 const BIND_RETRY_INTERVAL: u64 = 1000;
 
+/// The mechanism the NetworkThread waits for I/O with
+enum Driver {
+    Mio(Poll),
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    Uring(Box<UringDriver>),
+}
+
+impl Driver {
+    /// Sets up the driver for the configured [NetworkBackend], falling back to `mio` if that fails
+    #[cfg_attr(
+        not(all(target_os = "linux", feature = "io_uring")),
+        allow(unused_variables)
+    )]
+    fn new(network_config: &NetworkConfig, log: &KompactLogger) -> (Driver, NetworkWaker) {
+        match network_config.get_backend() {
+            NetworkBackend::Mio => (),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            NetworkBackend::IoUring => {
+                let max_buffers = network_config.get_buffer_config().max_chunk_count;
+                match UringDriver::new(max_buffers, log.clone()) {
+                    Ok((driver, waker)) => {
+                        return (Driver::Uring(Box::new(driver)), NetworkWaker::Uring(waker));
+                    }
+                    Err(e) => {
+                        warn!(
+                            log,
+                            "Failed to set up io_uring, falling back to mio. Error was: {}", e
+                        );
+                    }
+                }
+            }
+        }
+        let poll = Poll::new().expect("failed to create Poll instance in NetworkThread");
+        let waker =
+            Waker::new(poll.registry(), DISPATCHER).expect("failed to create Waker for DISPATCHER");
+        (Driver::Mio(poll), NetworkWaker::Mio(waker))
+    }
+
+    fn backend(&self) -> NetworkBackend {
+        match self {
+            Driver::Mio(_) => NetworkBackend::Mio,
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(_) => NetworkBackend::IoUring,
+        }
+    }
+}
+
 /// Builder struct, can be sent to a thread safely to launch a NetworkThread
 pub struct NetworkThreadBuilder {
-    poll: Poll,
-    waker: Option<Waker>,
+    driver: Driver,
+    waker: Option<NetworkWaker>,
     log: KompactLogger,
     pub address: SocketAddr,
     lookup: Arc<ArcSwap<ActorStore>>,
@@ -73,13 +139,11 @@ impl NetworkThreadBuilder {
         dispatcher_ref: DispatcherRef,
         network_config: NetworkConfig,
     ) -> Result<NetworkThreadBuilder, NetworkBridgeErr> {
-        let poll = Poll::new().expect("failed to create Poll instance in NetworkThread");
-        let waker =
-            Waker::new(poll.registry(), DISPATCHER).expect("failed to create Waker for DISPATCHER");
+        let (driver, waker) = Driver::new(&network_config, &log);
         let tcp_listener = bind_with_retries(&address, MAX_BIND_RETRIES, &log)?;
         let actual_address = tcp_listener.local_addr()?;
         Ok(NetworkThreadBuilder {
-            poll,
+            driver,
             tcp_listener,
             waker: Some(waker),
             log,
@@ -92,10 +156,15 @@ impl NetworkThreadBuilder {
         })
     }
 
-    pub fn take_waker(&mut self) -> Option<Waker> {
+    pub(crate) fn take_waker(&mut self) -> Option<NetworkWaker> {
         self.waker.take()
     }
 
+    /// Returns the backend the NetworkThread will run on, after any fallback to `mio`
+    pub(crate) fn backend(&self) -> NetworkBackend {
+        self.driver.backend()
+    }
+
     pub fn build(mut self) -> NetworkThread {
         let actual_addr = self
             .tcp_listener
@@ -105,18 +174,23 @@ impl NetworkThreadBuilder {
         let mut udp_socket = UdpSocket::bind(actual_addr).expect("could not bind UDP on TCP port");
 
         // Register Listeners
-        self.poll
-            .registry()
-            .register(&mut self.tcp_listener, TCP_SERVER, Interest::READABLE)
-            .expect("failed to register TCP SERVER");
-        self.poll
-            .registry()
-            .register(
-                &mut udp_socket,
-                UDP_SOCKET,
-                Interest::READABLE | Interest::WRITABLE,
-            )
-            .expect("failed to register UDP SOCKET");
+        match self.driver {
+            Driver::Mio(ref poll) => {
+                poll.registry()
+                    .register(&mut self.tcp_listener, TCP_SERVER, Interest::READABLE)
+                    .expect("failed to register TCP SERVER");
+                poll.registry()
+                    .register(
+                        &mut udp_socket,
+                        UDP_SOCKET,
+                        Interest::READABLE | Interest::WRITABLE,
+                    )
+                    .expect("failed to register UDP SOCKET");
+            }
+            // Listeners are polled by the ring once the thread runs
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(_) => (),
+        }
 
         let mut buffer_pool = BufferPool::with_config(
             self.network_config.get_buffer_config(),
@@ -130,6 +204,18 @@ impl NetworkThreadBuilder {
             .get_buffer()
             .expect("Could not get buffer for setting up UDP");
         let udp_state = UdpState::new(udp_socket, udp_buffer, logger.clone(), &self.network_config);
+        let inbound_throttle = self
+            .network_config
+            .get_inbound_rate_limit()
+            .map(|limit| Throttle::new(limit.clone(), TrafficDirection::Inbound));
+        let outbound_throttle = self
+            .network_config
+            .get_outbound_rate_limit()
+            .map(|limit| Throttle::new(limit.clone(), TrafficDirection::Outbound));
+        let next_idle_check = self
+            .network_config
+            .get_idle_timeout()
+            .map(|timeout| Instant::now() + Duration::from_millis(timeout));
 
         NetworkThread {
             log: logger,
@@ -137,9 +223,11 @@ impl NetworkThreadBuilder {
             lookup: self.lookup,
             tcp_listener: self.tcp_listener,
             udp_state: Some(udp_state),
-            poll: self.poll,
+            driver: self.driver,
             address_map: FxHashMap::default(),
             token_map: FxHashMap::default(),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            zombies: FxHashMap::default(),
             token: START_TOKEN,
             input_queue: self.input_queue,
             buffer_pool: RefCell::new(buffer_pool),
@@ -150,6 +238,9 @@ impl NetworkThreadBuilder {
             retry_queue: VecDeque::new(),
             out_of_buffers: false,
             encode_buffer,
+            inbound_throttle,
+            outbound_throttle,
+            next_idle_check,
             block_list: AdressSet::default(), // TODO: extend NetworkConfig to build NetworkThread with a blocklist
         }
     }
@@ -161,9 +252,12 @@ pub struct NetworkThread {
     lookup: Arc<ArcSwap<ActorStore>>,
     tcp_listener: TcpListener,
     udp_state: Option<UdpState>,
-    poll: Poll,
+    driver: Driver,
     address_map: FxHashMap<SocketAddr, Rc<RefCell<TcpChannel>>>,
     token_map: FxHashMap<Token, Rc<RefCell<TcpChannel>>>,
+    /// Deregistered channels that must be kept alive until the ring releases their buffers
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    zombies: FxHashMap<Token, Rc<RefCell<TcpChannel>>>,
     token: Token,
     input_queue: Recv<DispatchEvent>,
     dispatcher_ref: DispatcherRef,
@@ -174,6 +268,12 @@ pub struct NetworkThread {
     retry_queue: VecDeque<EventWithRetries>,
     out_of_buffers: bool,
     encode_buffer: EncodeBuffer,
+    /// Holds back received messages exceeding the configured inbound rate limit
+    inbound_throttle: Option<Throttle<NetMessage>>,
+    /// Holds back serialised frames exceeding the configured outbound rate limit
+    outbound_throttle: Option<Throttle<SerialisedFrame>>,
+    /// When the next connected channel may exceed the idle timeout
+    next_idle_check: Option<Instant>,
     block_list: AdressSet,
 }
 
@@ -181,19 +281,28 @@ impl NetworkThread {
     pub fn run(mut self) -> () {
         trace!(self.log, "NetworkThread starting");
         let mut events = Events::with_capacity(MAX_POLL_EVENTS);
+        let mut ready = Vec::with_capacity(MAX_POLL_EVENTS);
+        self.submit_io();
         loop {
-            self.poll
-                .poll(&mut events, self.get_poll_timeout())
-                .expect("Error when calling Poll");
-
-            for event in events
-                .iter()
-                .map(|e| (EventWithRetries::from(e)))
-                .chain(self.retry_queue.split_off(0))
-            {
+            let timeout = self.get_poll_timeout();
+            match self.driver {
+                Driver::Mio(ref mut poll) => {
+                    poll.poll(&mut events, timeout)
+                        .expect("Error when calling Poll");
+                    ready.extend(events.iter().map(EventWithRetries::from));
+                }
+                #[cfg(all(target_os = "linux", feature = "io_uring"))]
+                Driver::Uring(_) => self.wait_for_completions(timeout, &mut ready),
+            }
+            self.release_throttled();
+            self.close_idle_channels();
+            ready.extend(self.retry_queue.drain(..));
+
+            for event in ready.drain(..) {
                 self.handle_event(event);
 
                 if self.stopped {
+                    self.release_driver();
                     if let Err(e) = self.shutdown_promise.complete() {
                         error!(self.log, "Error, shutting down sender: {:?}", e);
                     };
@@ -201,11 +310,147 @@ impl NetworkThread {
                     return;
                 };
             }
+            self.submit_io();
+        }
+    }
+
+    /// Submits reads and writes of all channels, and polls of the listeners, to the ring
+    ///
+    /// Must be called after each round of event handling, as that may free up buffers or enqueue frames.
+    fn submit_io(&mut self) -> () {
+        match self.driver {
+            Driver::Mio(_) => (),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(ref mut driver) => {
+                let listener_fd = self.tcp_listener.as_raw_fd();
+                if let Err(e) = driver.poll(TCP_SERVER, listener_fd, OpKind::PollIn) {
+                    error!(self.log, "Failed to poll TCP SERVER: {}", e);
+                }
+                if let Some(udp_state) = &self.udp_state {
+                    let udp_fd = udp_state.socket.as_raw_fd();
+                    if let Err(e) = driver.poll(UDP_SOCKET, udp_fd, OpKind::PollIn) {
+                        error!(self.log, "Failed to poll UDP SOCKET: {}", e);
+                    }
+                    if udp_state.pending_messages() > 0 {
+                        if let Err(e) = driver.poll(UDP_SOCKET, udp_fd, OpKind::PollOut) {
+                            error!(self.log, "Failed to poll UDP SOCKET: {}", e);
+                        }
+                    }
+                }
+                for channel_rc in self.token_map.values() {
+                    let mut channel = channel_rc.borrow_mut();
+                    if let Err(e) = channel.submit_io(driver) {
+                        error!(
+                            self.log,
+                            "Failed to submit I/O for {}\n{:?}",
+                            channel.address(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Waits for operations of the ring to complete and turns them into events
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    fn wait_for_completions(
+        &mut self,
+        timeout: Option<Duration>,
+        ready: &mut Vec<EventWithRetries>,
+    ) -> () {
+        let mut completions = Vec::new();
+        if let Driver::Uring(ref mut driver) = self.driver {
+            driver
+                .wait(timeout, &mut completions)
+                .expect("Error when waiting for io_uring completions");
+        }
+        for completion in completions {
+            match (completion.token, completion.kind) {
+                (_, OpKind::Wake) => ready.push(EventWithRetries::readable_with_token(&DISPATCHER)),
+                (TCP_SERVER, OpKind::PollIn) | (UDP_SOCKET, OpKind::PollIn) => {
+                    ready.push(EventWithRetries::readable_with_token(&completion.token))
+                }
+                (UDP_SOCKET, OpKind::PollOut) => {
+                    ready.push(EventWithRetries::writeable_with_token(&UDP_SOCKET))
+                }
+                _ => self.complete_channel_io(&completion, ready),
+            }
+        }
+    }
+
+    /// Applies a completed read or write to its channel
+    ///
+    /// Completions of deregistered channels release their buffers,
+    /// while those of live channels are handled like readiness events.
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    fn complete_channel_io(
+        &mut self,
+        completion: &Completion,
+        ready: &mut Vec<EventWithRetries>,
+    ) -> () {
+        let token = completion.token;
+        if let Some(channel_rc) = self.get_channel_by_token(&token) {
+            channel_rc.borrow_mut().complete_io(completion);
+            match completion.kind {
+                OpKind::Recv => ready.push(EventWithRetries::readable_with_token(&token)),
+                OpKind::Write | OpKind::PollOut => {
+                    ready.push(EventWithRetries::writeable_with_token(&token))
+                }
+                _ => (),
+            }
+        } else if let Some(channel_rc) = self.zombies.get(&token).cloned() {
+            let mut channel = channel_rc.borrow_mut();
+            channel.complete_io(completion);
+            let (release_buffer, in_flight) = match channel.completion_state() {
+                Some(state) if state.release_buffer && !state.recv_in_flight() => {
+                    state.release_buffer = false;
+                    (true, state.in_flight())
+                }
+                Some(state) => (false, state.in_flight()),
+                None => (false, false),
+            };
+            if release_buffer {
+                let mut buffer = BufferChunk::new(0);
+                channel.swap_buffer(&mut buffer);
+                self.return_buffer(buffer);
+            }
+            if !in_flight {
+                drop(channel);
+                self.zombies.remove(&token);
+            }
+        }
+    }
+
+    /// Makes sure the ring holds no more buffers of the channels before they are dropped
+    fn release_driver(&mut self) -> () {
+        match self.driver {
+            Driver::Mio(_) => (),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(ref mut driver) => {
+                // Give Bye frames of channels that are shutting down a chance to be sent
+                for channel_rc in self.token_map.values() {
+                    let _ = channel_rc.borrow_mut().submit_io(driver);
+                }
+                let grace =
+                    Duration::from_millis(self.network_config.get_connection_retry_interval());
+                driver.flush_writes(grace);
+                // Shutting the sockets down completes reads and polls that can't be cancelled
+                for channel_rc in self.token_map.values().chain(self.zombies.values()) {
+                    channel_rc.borrow_mut().kill();
+                }
+                if let Err(e) = driver.cancel_all() {
+                    error!(
+                        self.log,
+                        "Failed to cancel outstanding io_uring operations: {}", e
+                    );
+                }
+            }
         }
     }
 
     fn get_poll_timeout(&self) -> Option<Duration> {
-        if self.out_of_buffers {
+        let timeout = if self.out_of_buffers {
             Some(Duration::from_millis(
                 self.network_config.get_connection_retry_interval(),
             ))
@@ -213,6 +458,171 @@ impl NetworkThread {
             None
         } else {
             Some(Duration::from_secs(0))
+        };
+        let next_deadline = self
+            .inbound_throttle
+            .iter()
+            .filter_map(Throttle::next_release)
+            .chain(
+                self.outbound_throttle
+                    .iter()
+                    .filter_map(Throttle::next_release),
+            )
+            .chain(self.next_idle_check)
+            .min();
+        match next_deadline {
+            Some(at) => {
+                let until_deadline = at.saturating_duration_since(Instant::now());
+                Some(timeout.map_or(until_deadline, |t| t.min(until_deadline)))
+            }
+            None => timeout,
+        }
+    }
+
+    /// Gracefully closes all connected channels that have been idle for longer than the idle timeout
+    fn close_idle_channels(&mut self) -> () {
+        let (timeout, check_at) =
+            match (self.network_config.get_idle_timeout(), self.next_idle_check) {
+                (Some(timeout), Some(check_at)) => (Duration::from_millis(timeout), check_at),
+                _ => return,
+            };
+        let now = Instant::now();
+        if check_at > now {
+            return;
+        }
+        let mut next_check = now + timeout;
+        for channel_rc in self.token_map.values() {
+            let mut channel = channel_rc.borrow_mut();
+            if !channel.connected() {
+                continue;
+            }
+            let expires_at = channel.last_activity() + timeout;
+            if expires_at <= now {
+                debug!(
+                    self.log,
+                    "Closing connection to {} after being idle for {:?}",
+                    channel.address(),
+                    now.saturating_duration_since(channel.last_activity())
+                );
+                let _ = channel.initiate_graceful_shutdown();
+            } else if expires_at < next_check {
+                next_check = expires_at;
+            }
+        }
+        self.next_idle_check = Some(next_check);
+    }
+
+    /// Returns `true` if another connection may be opened without exceeding the configured maximum
+    ///
+    /// With [ConnectionLimitPolicy::EvictIdlest] the connection idle for the longest time is
+    /// gracefully closed to make room, if there is one.
+    fn make_room_for_connection(&mut self) -> bool {
+        let max_connections = match self.network_config.get_max_connections() {
+            Some(max) => max,
+            None => return true,
+        };
+        let open_connections = self
+            .token_map
+            .values()
+            .filter(|channel_rc| !channel_rc.borrow().closing())
+            .count();
+        if open_connections < max_connections {
+            return true;
+        }
+        match self.network_config.get_connection_limit_policy() {
+            ConnectionLimitPolicy::Reject => false,
+            ConnectionLimitPolicy::EvictIdlest => {
+                let idlest = self
+                    .token_map
+                    .values()
+                    .filter(|channel_rc| channel_rc.borrow().connected())
+                    .min_by_key(|channel_rc| channel_rc.borrow().last_activity());
+                if let Some(channel_rc) = idlest {
+                    let mut channel = channel_rc.borrow_mut();
+                    debug!(
+                        self.log,
+                        "Evicting connection to {} to stay within {} connections",
+                        channel.address(),
+                        max_connections
+                    );
+                    let _ = channel.initiate_graceful_shutdown();
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Passes on all messages held back by the rate limits that may be exchanged by now
+    ///
+    /// TCP channels whose inbound messages are no longer delayed are scheduled to be read again.
+    fn release_throttled(&mut self) -> () {
+        let now = Instant::now();
+        if let Some(mut throttle) = self.inbound_throttle.take() {
+            let mut released_from = FxHashSet::default();
+            throttle.release(now, |transport, addr, envelope| {
+                self.deliver_net_message(envelope);
+                if transport == Transport::Tcp {
+                    released_from.insert(addr);
+                }
+            });
+            for addr in released_from {
+                if throttle.is_delayed(Transport::Tcp, addr) {
+                    continue;
+                }
+                if let Some(channel_rc) = self.get_channel_by_address(&addr) {
+                    let token = channel_rc.borrow().token;
+                    self.retry_queue
+                        .push_back(EventWithRetries::readable_with_token(&token));
+                }
+            }
+            self.notify_throttle_changes(&mut throttle);
+            self.inbound_throttle = Some(throttle);
+        }
+        if let Some(mut throttle) = self.outbound_throttle.take() {
+            let mut released = Vec::new();
+            throttle.release(now, |transport, addr, frame| {
+                released.push((transport, addr, frame))
+            });
+            for (transport, addr, frame) in released {
+                match Protocol::from(transport) {
+                    Protocol::Tcp => self.enqueue_tcp_frame(addr, frame),
+                    Protocol::Udp => self.enqueue_udp_frame(addr, frame),
+                }
+            }
+            self.notify_throttle_changes(&mut throttle);
+            self.outbound_throttle = Some(throttle);
+        }
+    }
+
+    fn notify_throttle_changes<T>(&self, throttle: &mut Throttle<T>) -> () {
+        let direction = throttle.direction();
+        for (peer, throttled) in throttle.take_changes() {
+            if throttled {
+                debug!(self.log, "Throttling {:?} traffic of {:?}", direction, peer);
+                self.notify_network_event(NetworkEvent::Throttled(peer, direction));
+            } else {
+                debug!(
+                    self.log,
+                    "No longer throttling {:?} traffic of {:?}", direction, peer
+                );
+                self.notify_network_event(NetworkEvent::Unthrottled(peer, direction));
+            }
+        }
+    }
+
+    /// Offers a message of `size` bytes to `throttle`, if a rate limit is configured
+    fn admit<T>(
+        throttle: &mut Option<Throttle<T>>,
+        transport: Transport,
+        addr: SocketAddr,
+        size: usize,
+        item: T,
+    ) -> Admission<T> {
+        match throttle {
+            Some(throttle) => throttle.offer(transport, addr, size, item, Instant::now()),
+            None => Admission::Pass(item),
         }
     }
 
@@ -340,12 +750,17 @@ impl NetworkThread {
         if let Some(channel_rc) = self.get_channel_by_token(&event.token) {
             let mut channel = channel_rc.borrow_mut();
             loop {
+                if self.inbound_is_delayed(&channel.address()) {
+                    // Reading resumes once the delayed messages have been released
+                    return;
+                }
                 match channel.read_frame(&self.buffer_pool) {
                     Ok(None) => {
                         return;
                     }
                     Ok(Some(Frame::Data(data))) => {
                         self.handle_data_frame(
+                            channel.address(),
                             data,
                             channel
                                 .session_id()
@@ -418,8 +833,8 @@ impl NetworkThread {
                 warn!(self.log, "Error during UDP reading: {}", e);
             }
         }
-        while let Some(net_message) = udp_state.incoming_messages.pop_front() {
-            self.deliver_net_message(net_message);
+        while let Some((source, size, net_message)) = udp_state.incoming_messages.pop_front() {
+            self.receive_net_message(Transport::Udp, source, size, net_message, None);
         }
     }
 
@@ -430,7 +845,7 @@ impl NetworkThread {
                 Err(ref err) if broken_pipe(err) => {
                     self.lost_connection(channel);
                 }
-                Ok(_) => {
+                Ok(_) if channel.outbound_is_empty() => {
                     if let ChannelState::CloseReceived(addr, id) = channel.state {
                         channel.state = ChannelState::Closed(addr, id);
                         debug!(self.log, "Connection to {} shutdown gracefully", &addr);
@@ -442,6 +857,7 @@ impl NetworkThread {
                         self.reject_outbound_for_channel(&mut channel);
                     }
                 }
+                Ok(_) => {}
                 Err(e) => {
                     warn!(
                         self.log,
@@ -465,12 +881,19 @@ impl NetworkThread {
 
     fn send_tcp_message(&mut self, address: SocketAddr, data: DispatchData) {
         if let Some(channel_rc) = self.get_channel_by_address(&address) {
-            let mut channel = channel_rc.borrow_mut();
+            let channel = channel_rc.borrow();
             if channel.connected() {
                 match self.serialise_dispatch_data(data) {
                     Ok(frame) => {
-                        channel.enqueue_serialised(frame);
-                        self.enqueue_writeable_event(&channel.token);
+                        drop(channel);
+                        let size = frame.len();
+                        if let Some(frame) =
+                            self.admit_outbound(Transport::Tcp, address, size, frame)
+                        {
+                            let mut channel = channel_rc.borrow_mut();
+                            channel.enqueue_serialised(frame);
+                            self.enqueue_writeable_event(&channel.token);
+                        }
                     }
                     Err(e) if out_of_buffers(&e) => {
                         self.out_of_buffers = true;
@@ -506,13 +929,9 @@ impl NetworkThread {
         if let Some(mut udp_state) = self.udp_state.take() {
             match self.serialise_dispatch_data(data) {
                 Ok(frame) => {
-                    udp_state.enqueue_serialised(address, frame);
-                    match udp_state.try_write() {
-                        Ok(_) => {}
-                        Err(e) => {
-                            warn!(self.log, "Error during UDP sending: {}", e);
-                            debug!(self.log, "UDP error debug info: {:?}", e);
-                        }
+                    let size = frame.len();
+                    if let Some(frame) = self.admit_outbound(Transport::Udp, address, size, frame) {
+                        self.write_udp_frame(&mut udp_state, address, frame);
                     }
                 }
                 Err(e) if out_of_buffers(&e) => {
@@ -538,16 +957,155 @@ impl NetworkThread {
         }
     }
 
-    fn handle_data_frame(&self, data: Data, session: SessionId) -> () {
+    /// Returns the frame if it may be sent right away under the outbound rate limit
+    fn admit_outbound(
+        &mut self,
+        transport: Transport,
+        address: SocketAddr,
+        size: usize,
+        frame: SerialisedFrame,
+    ) -> Option<SerialisedFrame> {
+        let throttle = &mut self.outbound_throttle;
+        let admitted = match Self::admit(throttle, transport, address, size, frame) {
+            Admission::Pass(frame) => Some(frame),
+            Admission::Delayed => None,
+            Admission::Dropped => {
+                trace!(
+                    self.log,
+                    "Dropping message to {} exceeding the outbound rate limit",
+                    address
+                );
+                None
+            }
+        };
+        if let Some(mut throttle) = self.outbound_throttle.take() {
+            self.notify_throttle_changes(&mut throttle);
+            self.outbound_throttle = Some(throttle);
+        }
+        admitted
+    }
+
+    /// Enqueues a frame released by the outbound rate limit, if its channel is still connected
+    fn enqueue_tcp_frame(&mut self, address: SocketAddr, frame: SerialisedFrame) -> () {
+        match self.get_channel_by_address(&address) {
+            Some(channel_rc) if channel_rc.borrow().connected() => {
+                let mut channel = channel_rc.borrow_mut();
+                channel.enqueue_serialised(frame);
+                self.enqueue_writeable_event(&channel.token);
+            }
+            _ => {
+                trace!(
+                    self.log,
+                    "Delayed message to {} can no longer be sent, rejecting the message",
+                    address
+                );
+                self.reject_dispatch_data(address, DispatchData::Serialised(frame));
+            }
+        }
+    }
+
+    /// Sends a frame released by the outbound rate limit, if the socket is still open
+    fn enqueue_udp_frame(&mut self, address: SocketAddr, frame: SerialisedFrame) -> () {
+        if let Some(mut udp_state) = self.udp_state.take() {
+            self.write_udp_frame(&mut udp_state, address, frame);
+            self.udp_state = Some(udp_state);
+        } else {
+            self.reject_dispatch_data(address, DispatchData::Serialised(frame));
+        }
+    }
+
+    fn write_udp_frame(
+        &mut self,
+        udp_state: &mut UdpState,
+        address: SocketAddr,
+        frame: SerialisedFrame,
+    ) -> () {
+        udp_state.enqueue_serialised(address, frame);
+        match udp_state.try_write() {
+            Ok(_) => {}
+            Err(e) => {
+                warn!(self.log, "Error during UDP sending: {}", e);
+                debug!(self.log, "UDP error debug info: {:?}", e);
+            }
+        }
+    }
+
+    fn handle_data_frame(&mut self, source: SocketAddr, data: Data, session: SessionId) -> () {
         let buf = data.payload();
-        let mut envelope = deserialise_chunk_lease(buf).expect("s11n errors");
+        let size = buf.remaining();
+        let lease_lookup = self.lookup.load();
+        // Resolve named receivers on the wire bytes, and deserialise the receiver only once
+        let peeked = peek_receiver(buf.chunk()).map(|receiver| {
+            let target = receiver
+                .name
+                .map(|name| lease_lookup.get_by_path_bytes(name));
+            let receiver_len = receiver.path.len();
+            let mut receiver_bytes = receiver.path;
+            let receiver = ActorPath::deserialise(&mut receiver_bytes).expect("s11n errors");
+            (receiver, receiver_len, target)
+        });
+        let (mut envelope, target) = match peeked {
+            Some((receiver, receiver_len, target)) => (
+                deserialise_chunk_lease_to(buf, receiver, receiver_len).expect("s11n errors"),
+                target,
+            ),
+            None => (deserialise_chunk_lease(buf).expect("s11n errors"), None),
+        };
         envelope.set_session(session);
-        self.deliver_net_message(envelope);
+        self.receive_net_message(Transport::Tcp, source, size, envelope, target);
+    }
+
+    /// Delivers a received message of `size` bytes, unless it exceeds the inbound rate limit
+    ///
+    /// Messages are delivered to `target`, if their receiver was already looked up,
+    /// unless they are delayed by the rate limit and must be looked up again on release.
+    fn receive_net_message(
+        &mut self,
+        transport: Transport,
+        source: SocketAddr,
+        size: usize,
+        envelope: NetMessage,
+        target: Option<LookupResult>,
+    ) {
+        match Self::admit(
+            &mut self.inbound_throttle,
+            transport,
+            source,
+            size,
+            envelope,
+        ) {
+            Admission::Pass(envelope) => match target {
+                Some(target) => self.deliver_to(target, envelope),
+                None => self.deliver_net_message(envelope),
+            },
+            Admission::Delayed => (),
+            Admission::Dropped => {
+                trace!(
+                    self.log,
+                    "Dropping message from {} exceeding the inbound rate limit",
+                    source
+                );
+            }
+        }
+        if let Some(mut throttle) = self.inbound_throttle.take() {
+            self.notify_throttle_changes(&mut throttle);
+            self.inbound_throttle = Some(throttle);
+        }
+    }
+
+    fn inbound_is_delayed(&self, addr: &SocketAddr) -> bool {
+        self.inbound_throttle
+            .as_ref()
+            .map_or(false, |throttle| throttle.is_delayed(Transport::Tcp, *addr))
     }
 
     fn deliver_net_message(&self, envelope: NetMessage) -> () {
         let lease_lookup = self.lookup.load();
-        match lease_lookup.get_by_actor_path(&envelope.receiver) {
+        self.deliver_to(lease_lookup.get_by_actor_path(&envelope.receiver), envelope);
+    }
+
+    fn deliver_to(&self, target: LookupResult, envelope: NetMessage) -> () {
+        match target {
             LookupResult::Ref(actor) => {
                 actor.enqueue(envelope);
             }
@@ -661,13 +1219,40 @@ impl NetworkThread {
         self.deregister_channel(channel);
         self.address_map.remove(&channel.address());
         channel.shutdown();
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        {
+            if let Some(state) = channel.completion_state() {
+                if state.recv_in_flight() {
+                    // The buffer is returned once the ring is done reading into it
+                    state.release_buffer = true;
+                    return;
+                }
+            }
+        }
         let mut buffer = BufferChunk::new(0);
         channel.swap_buffer(&mut buffer);
         self.return_buffer(buffer);
     }
 
     fn deregister_channel(&mut self, channel: &mut TcpChannel) {
-        let _ = self.poll.registry().deregister(channel.stream_mut());
+        match self.driver {
+            Driver::Mio(ref poll) => {
+                let _ = poll.registry().deregister(channel.stream_mut());
+            }
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(ref mut driver) => {
+                let token = channel.token;
+                if let (Some(state), Some(channel_rc)) =
+                    (channel.completion_state(), self.token_map.get(&token))
+                {
+                    if state.in_flight() {
+                        let _ = driver.cancel(token, OpKind::Recv);
+                        self.zombies.insert(token, channel_rc.clone());
+                    }
+                }
+                let _ = driver.cancel(token, OpKind::PollIn);
+            }
+        }
         self.token_map.remove(&channel.token);
     }
 
@@ -694,6 +1279,15 @@ impl NetworkThread {
                 }
             }
         }
+        if !self.make_room_for_connection() {
+            debug!(
+                self.log,
+                "Not connecting to {} as the maximum number of connections is reached", &address
+            );
+            // The dispatcher re-requests the connection or gives up on it
+            self.notify_network_event(NetworkEvent::ConnectionRejected(address));
+            return;
+        }
         if let Some(buffer) = self.get_buffer() {
             trace!(self.log, "Requesting connection to {}", &address);
             match TcpStream::connect(address) {
@@ -730,6 +1324,13 @@ impl NetworkThread {
         while let Ok((stream, address)) = self.tcp_listener.accept() {
             if self.block_list.contains_ip_addr(&address.ip()) {
                 stream.shutdown(Shutdown::Both)?;
+            } else if !self.make_room_for_connection() {
+                warn!(
+                    self.log,
+                    "Rejecting connection from {} as the maximum number of connections is reached",
+                    &address
+                );
+                stream.shutdown(Shutdown::Both)?;
             } else if let Some(buffer) = self.get_buffer() {
                 trace!(self.log, "Accepting connection from {}", &address);
                 self.store_stream(stream, address, ChannelState::Initialising, buffer);
@@ -756,17 +1357,22 @@ impl NetworkThread {
             self.addr,
             &self.network_config,
         );
-        channel.initialise(&self.addr);
-        if let Err(e) = self.poll.registry().register(
-            channel.stream_mut(),
-            self.token,
-            Interest::READABLE | Interest::WRITABLE,
-        ) {
+        let registered = match self.driver {
+            Driver::Mio(ref poll) => poll.registry().register(
+                channel.stream_mut(),
+                self.token,
+                Interest::READABLE | Interest::WRITABLE,
+            ),
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(_) => channel.enable_completion(),
+        };
+        if let Err(e) = registered {
             error!(
                 self.log,
                 "Failed to register polling for {}\n{:?}", address, e
             );
         }
+        channel.initialise(&self.addr);
         let rc = Rc::new(RefCell::new(channel));
         self.address_map.insert(address, rc.clone());
         self.token_map.insert(self.token, rc);
@@ -808,12 +1414,20 @@ impl NetworkThread {
             );
             let _ = channel.initiate_graceful_shutdown();
         }
-        self.poll
-            .registry()
-            .deregister(&mut self.tcp_listener)
-            .expect("Deregistering listener while stopping network should work");
-        if let Some(mut udp_state) = self.udp_state.take() {
-            self.poll.registry().deregister(&mut udp_state.socket).ok();
+        match self.driver {
+            Driver::Mio(ref poll) => {
+                poll.registry()
+                    .deregister(&mut self.tcp_listener)
+                    .expect("Deregistering listener while stopping network should work");
+                if let Some(udp_state) = self.udp_state.as_mut() {
+                    poll.registry().deregister(&mut udp_state.socket).ok();
+                }
+            }
+            // Polls of the ring are cancelled in `release_driver`
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(_) => (),
+        }
+        if let Some(udp_state) = self.udp_state.take() {
             let count = udp_state.pending_messages();
             drop(udp_state);
             debug!(
@@ -963,6 +1577,15 @@ impl EventWithRetries {
         }
     }
 
+    fn readable_with_token(token: &Token) -> EventWithRetries {
+        EventWithRetries {
+            token: *token,
+            readable: true,
+            writeable: false,
+            retries: 0,
+        }
+    }
+
     fn writeable_with_token(token: &Token) -> EventWithRetries {
         EventWithRetries {
             token: *token,
@@ -1031,12 +1654,20 @@ mod tests {
     // Cleaner test-cases for manually running the thread
     fn poll_and_handle(thread: &mut NetworkThread) -> () {
         let mut events = Events::with_capacity(10);
-        thread
-            .poll
-            .poll(&mut events, Some(Duration::from_millis(100)));
-        for event in events.iter() {
-            thread.handle_event(EventWithRetries::from(event));
+        let mut ready = Vec::new();
+        let timeout = Some(Duration::from_millis(100));
+        match thread.driver {
+            Driver::Mio(ref mut poll) => {
+                let _ = poll.poll(&mut events, timeout);
+                ready.extend(events.iter().map(EventWithRetries::from));
+            }
+            #[cfg(all(target_os = "linux", feature = "io_uring"))]
+            Driver::Uring(_) => thread.wait_for_completions(timeout, &mut ready),
+        }
+        for event in ready {
+            thread.handle_event(event);
         }
+        thread.submit_io();
     }
 
     #[allow(unused_must_use)]
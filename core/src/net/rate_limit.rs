@@ -0,0 +1,630 @@
+//! Token-bucket rate limits for the traffic exchanged with remote systems
+//!
+//! Limits are configured separately for inbound and outbound traffic on the
+//! [NetworkConfig](crate::prelude::NetworkConfig) and enforced by the `NetworkThread`.
+
+use crate::{actors::SystemPath, prelude::Transport};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    collections::VecDeque,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+/// Default number of messages that may be delayed per remote system before further messages are dropped
+const DEFAULT_MAX_DELAYED: usize = 1024;
+/// Default number of bytes that may be delayed across all remote systems before further messages are dropped
+const DEFAULT_MAX_DELAYED_BYTES: usize = 16 * 1024 * 1024;
+/// How often buckets that are full and idle are evicted
+const EVICTION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Determines which traffic shares a token bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// Each remote system, identified by its [SystemPath], has its own bucket
+    System,
+    /// All remote systems on the same host, identified by their [IpAddr], share a bucket
+    Ip,
+}
+
+/// What happens to messages that exceed a [RateLimit]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThrottlePolicy {
+    /// Messages are held back until the bucket has refilled
+    ///
+    /// Inbound TCP channels of a delayed system are not read from in the meantime,
+    /// pushing back on the sender.
+    Delay,
+    /// Messages are dropped
+    Drop,
+}
+
+/// The direction of the traffic a [RateLimit] applies to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrafficDirection {
+    /// Messages received from remote systems
+    Inbound,
+    /// Messages sent to remote systems
+    Outbound,
+}
+
+/// The remote system or host a [RateLimit] is being enforced on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThrottledPeer {
+    /// A single remote system, for limits with [RateLimitScope::System]
+    System(SystemPath),
+    /// All remote systems on a host, for limits with [RateLimitScope::Ip]
+    Ip(IpAddr),
+}
+
+/// A token-bucket rate limit, in messages per second and bytes per second
+///
+/// A rate of `0` leaves that dimension unlimited.
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+///
+/// let mut limit = RateLimit::new(RateLimitScope::System, 1000, 10 * 1024 * 1024);
+/// limit.policy(ThrottlePolicy::Drop);
+/// let mut conf = NetworkConfig::default();
+/// conf.set_inbound_rate_limit(Some(limit));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub(crate) scope: RateLimitScope,
+    pub(crate) messages_per_second: u64,
+    pub(crate) bytes_per_second: u64,
+    pub(crate) message_burst: u64,
+    pub(crate) byte_burst: u64,
+    pub(crate) policy: ThrottlePolicy,
+    pub(crate) max_delayed: usize,
+    pub(crate) max_delayed_bytes: usize,
+}
+
+impl RateLimit {
+    /// Creates a new limit of `messages_per_second` and `bytes_per_second` for each bucket in `scope`
+    ///
+    /// The burst size defaults to one second's worth of traffic and the policy to [ThrottlePolicy::Delay].
+    pub fn new(scope: RateLimitScope, messages_per_second: u64, bytes_per_second: u64) -> Self {
+        RateLimit {
+            scope,
+            messages_per_second,
+            bytes_per_second,
+            message_burst: messages_per_second,
+            byte_burst: bytes_per_second,
+            policy: ThrottlePolicy::Delay,
+            max_delayed: DEFAULT_MAX_DELAYED,
+            max_delayed_bytes: DEFAULT_MAX_DELAYED_BYTES,
+        }
+    }
+
+    /// Sets the capacity of the buckets, i.e. how much traffic may pass at once after a quiet period.
+    ///
+    /// A single message bigger than the byte capacity is let through whenever the bucket is full.
+    pub fn burst(&mut self, messages: u64, bytes: u64) -> () {
+        self.message_burst = messages;
+        self.byte_burst = bytes;
+    }
+
+    /// Sets what happens to messages exceeding the limit
+    pub fn policy(&mut self, policy: ThrottlePolicy) -> () {
+        self.policy = policy;
+    }
+
+    /// Sets how many messages may be delayed per remote system before further ones are dropped
+    ///
+    /// Only applies with [ThrottlePolicy::Delay]. Default value is `1024`.
+    pub fn max_delayed(&mut self, count: usize) -> () {
+        self.max_delayed = count;
+    }
+
+    /// Sets how many bytes may be delayed in total, across all remote systems,
+    /// before further messages are dropped
+    ///
+    /// Delayed messages hold on to network buffers, so this bounds how much of the
+    /// buffer pool slow peers can occupy.
+    /// Only applies with [ThrottlePolicy::Delay]. Default value is `16 MiB`.
+    pub fn max_delayed_bytes(&mut self, bytes: usize) -> () {
+        self.max_delayed_bytes = bytes;
+    }
+
+    fn key(&self, peer: PeerAddr) -> PeerKey {
+        match self.scope {
+            RateLimitScope::System => PeerKey::System(peer),
+            RateLimitScope::Ip => PeerKey::Ip(peer.1.ip()),
+        }
+    }
+}
+
+/// The transport and address messages are exchanged with
+type PeerAddr = (Transport, SocketAddr);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum PeerKey {
+    System(PeerAddr),
+    Ip(IpAddr),
+}
+
+impl PeerKey {
+    fn peer(self) -> ThrottledPeer {
+        match self {
+            PeerKey::System((transport, addr)) => {
+                ThrottledPeer::System(SystemPath::with_socket(transport, addr))
+            }
+            PeerKey::Ip(ip) => ThrottledPeer::Ip(ip),
+        }
+    }
+}
+
+struct TokenBucket {
+    messages: f64,
+    bytes: f64,
+    last_refill: Instant,
+    /// The number of peers sharing this bucket that have delayed messages
+    delayed: usize,
+    /// When a bucket whose messages were dropped instead of delayed can take messages again
+    recovers_at: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn full(limit: &RateLimit, now: Instant) -> Self {
+        TokenBucket {
+            messages: limit.message_burst as f64,
+            bytes: limit.byte_burst as f64,
+            last_refill: now,
+            delayed: 0,
+            recovers_at: None,
+        }
+    }
+
+    /// Returns `true` if the bucket has refilled completely by `now`,
+    /// so it can be dropped and recreated [full](TokenBucket::full) on demand
+    fn is_full(&mut self, limit: &RateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        self.messages >= limit.message_burst as f64 && self.bytes >= limit.byte_burst as f64
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) -> () {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.last_refill = now;
+        self.messages = (self.messages + elapsed * limit.messages_per_second as f64)
+            .min(limit.message_burst as f64);
+        self.bytes =
+            (self.bytes + elapsed * limit.bytes_per_second as f64).min(limit.byte_burst as f64);
+    }
+
+    /// Takes tokens for a message of `size` bytes, or returns how long to wait until they are available
+    fn take(&mut self, limit: &RateLimit, size: usize, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        let mut wait: f64 = 0.0;
+        if limit.messages_per_second > 0 && self.messages < 1.0 {
+            wait = wait.max((1.0 - self.messages) / limit.messages_per_second as f64);
+        }
+        if limit.bytes_per_second > 0 {
+            // Oversized messages may overdraw a full bucket, or they would never pass
+            let needed = (size as f64).min(limit.byte_burst as f64);
+            if self.bytes < needed {
+                wait = wait.max((needed - self.bytes) / limit.bytes_per_second as f64);
+            }
+        }
+        if wait > 0.0 {
+            return Err(Duration::from_secs_f64(wait));
+        }
+        if limit.messages_per_second > 0 {
+            self.messages -= 1.0;
+        }
+        if limit.bytes_per_second > 0 {
+            self.bytes -= size as f64;
+        }
+        Ok(())
+    }
+}
+
+struct DelayedQueue<T> {
+    items: VecDeque<(usize, T)>,
+    retry_at: Instant,
+}
+
+/// The outcome of [offering](Throttle::offer) a message to a [Throttle]
+pub(crate) enum Admission<T> {
+    /// The message may pass now
+    Pass(T),
+    /// The message was held back and will be [released](Throttle::release) later
+    Delayed,
+    /// The message exceeded the limit and was dropped
+    Dropped,
+}
+
+/// Enforces a [RateLimit] on one direction of traffic, holding back delayed messages
+pub(crate) struct Throttle<T> {
+    limit: RateLimit,
+    direction: TrafficDirection,
+    /// Buckets are only kept while they are in use, i.e. not [full](TokenBucket::is_full)
+    buckets: FxHashMap<PeerKey, TokenBucket>,
+    /// The keys of all buckets that are currently throttled
+    throttled: FxHashSet<PeerKey>,
+    delayed: FxHashMap<PeerAddr, DelayedQueue<T>>,
+    /// The total size of all delayed messages
+    delayed_bytes: usize,
+    next_eviction: Option<Instant>,
+    changes: Vec<(ThrottledPeer, bool)>,
+}
+
+impl<T> Throttle<T> {
+    pub(crate) fn new(limit: RateLimit, direction: TrafficDirection) -> Self {
+        Throttle {
+            limit,
+            direction,
+            buckets: FxHashMap::default(),
+            throttled: FxHashSet::default(),
+            delayed: FxHashMap::default(),
+            delayed_bytes: 0,
+            next_eviction: None,
+            changes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn direction(&self) -> TrafficDirection {
+        self.direction
+    }
+
+    /// Offers a message of `size` bytes exchanged with `addr` over `transport`
+    pub(crate) fn offer(
+        &mut self,
+        transport: Transport,
+        addr: SocketAddr,
+        size: usize,
+        item: T,
+        now: Instant,
+    ) -> Admission<T> {
+        let peer = (transport, addr);
+        let key = self.limit.key(peer);
+        let has_room = self.delayed_bytes.saturating_add(size) <= self.limit.max_delayed_bytes;
+        if let Some(queue) = self.delayed.get_mut(&peer) {
+            // Keep messages in order behind those already delayed
+            if queue.items.len() < self.limit.max_delayed && has_room {
+                queue.items.push_back((size, item));
+                self.delayed_bytes += size;
+                return Admission::Delayed;
+            } else {
+                return Admission::Dropped;
+            }
+        }
+        let limit = &self.limit;
+        let bucket = self
+            .buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::full(limit, now));
+        match bucket.take(limit, size, now) {
+            Ok(()) => {
+                if bucket.delayed == 0 && self.throttled.remove(&key) {
+                    bucket.recovers_at = None;
+                    self.changes.push((key.peer(), false));
+                }
+                Admission::Pass(item)
+            }
+            Err(wait) => {
+                if self.throttled.insert(key) {
+                    self.changes.push((key.peer(), true));
+                }
+                let delay =
+                    limit.policy == ThrottlePolicy::Delay && limit.max_delayed > 0 && has_room;
+                if delay {
+                    let mut items = VecDeque::new();
+                    items.push_back((size, item));
+                    self.delayed.insert(
+                        peer,
+                        DelayedQueue {
+                            items,
+                            retry_at: now + wait,
+                        },
+                    );
+                    self.delayed_bytes += size;
+                    bucket.delayed += 1;
+                    Admission::Delayed
+                } else {
+                    // Without a delayed queue, the release is driven by the bucket itself
+                    let recovers_at = now + wait;
+                    bucket.recovers_at = Some(
+                        bucket
+                            .recovers_at
+                            .map_or(recovers_at, |earlier| earlier.max(recovers_at)),
+                    );
+                    Admission::Dropped
+                }
+            }
+        }
+    }
+
+    /// Passes all delayed messages that fit into their buckets by now to `release`
+    ///
+    /// Also ends the throttling of peers whose messages were dropped, once their buckets have recovered,
+    /// and evicts buckets that are full and idle every [EVICTION_INTERVAL].
+    pub(crate) fn release<F>(&mut self, now: Instant, mut release: F) -> ()
+    where
+        F: FnMut(Transport, SocketAddr, T),
+    {
+        let limit = &self.limit;
+        let buckets = &mut self.buckets;
+        let mut emptied = Vec::new();
+        let mut released_bytes = 0;
+        for (peer, queue) in self.delayed.iter_mut() {
+            if queue.retry_at > now {
+                continue;
+            }
+            let key = limit.key(*peer);
+            let bucket = buckets
+                .entry(key)
+                .or_insert_with(|| TokenBucket::full(limit, now));
+            while let Some((size, _)) = queue.items.front() {
+                match bucket.take(limit, *size, now) {
+                    Ok(()) => {
+                        let (size, item) = queue.items.pop_front().expect("Checked above");
+                        released_bytes += size;
+                        release(peer.0, peer.1, item);
+                    }
+                    Err(wait) => {
+                        queue.retry_at = now + wait;
+                        break;
+                    }
+                }
+            }
+            if queue.items.is_empty() {
+                emptied.push(*peer);
+                bucket.delayed = bucket.delayed.saturating_sub(1);
+            }
+        }
+        self.delayed_bytes -= released_bytes;
+        for peer in emptied {
+            self.delayed.remove(&peer);
+        }
+        let changes = &mut self.changes;
+        self.throttled.retain(|key| {
+            let bucket = match buckets.get_mut(key) {
+                Some(bucket) => bucket,
+                None => return false,
+            };
+            if bucket.delayed > 0 {
+                return true;
+            }
+            // Buckets of emptied queues are released right away, others once they have recovered
+            if bucket.recovers_at.map_or(true, |at| at <= now) {
+                bucket.recovers_at = None;
+                changes.push((key.peer(), false));
+                false
+            } else {
+                true
+            }
+        });
+        if self.next_eviction.map_or(true, |at| at <= now) {
+            let throttled = &self.throttled;
+            buckets.retain(|key, bucket| throttled.contains(key) || !bucket.is_full(limit, now));
+            self.next_eviction = Some(now + EVICTION_INTERVAL);
+        }
+    }
+
+    /// Returns `true` if messages exchanged with `addr` over `transport` are currently held back
+    pub(crate) fn is_delayed(&self, transport: Transport, addr: SocketAddr) -> bool {
+        self.delayed.contains_key(&(transport, addr))
+    }
+
+    /// Returns when the next delayed message may be released or a throttled bucket recovers
+    pub(crate) fn next_release(&self) -> Option<Instant> {
+        let delayed = self.delayed.values().map(|queue| queue.retry_at);
+        let recovering = self
+            .throttled
+            .iter()
+            .filter_map(|key| self.buckets.get(key))
+            .filter_map(|bucket| bucket.recovers_at);
+        delayed.chain(recovering).min()
+    }
+
+    /// Returns the peers that became throttled (`true`) or were released again (`false`)
+    pub(crate) fn take_changes(&mut self) -> Vec<(ThrottledPeer, bool)> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::new("127.0.0.1".parse().unwrap(), port)
+    }
+
+    fn passed<T>(admission: Admission<T>) -> bool {
+        matches!(admission, Admission::Pass(_))
+    }
+
+    #[test]
+    fn message_rate_delays_and_releases() {
+        let mut limit = RateLimit::new(RateLimitScope::System, 10, 0);
+        limit.burst(2, 0);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Outbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 1, 1, now)));
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 1, 2, now)));
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 1, 3, now),
+            Admission::Delayed
+        ));
+        // Later messages queue up behind the delayed one
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 1, 4, now),
+            Admission::Delayed
+        ));
+        // Other systems have their own bucket
+        assert!(passed(throttle.offer(Transport::Tcp, addr(2), 1, 5, now)));
+        assert!(throttle.is_delayed(Transport::Tcp, addr(1)));
+        assert_eq!(
+            throttle.take_changes(),
+            vec![(
+                ThrottledPeer::System(SystemPath::with_socket(Transport::Tcp, addr(1))),
+                true
+            )]
+        );
+
+        let release_at = throttle.next_release().expect("Should be delayed");
+        assert_eq!(release_at, now + Duration::from_millis(100));
+        let mut released = Vec::new();
+        throttle.release(release_at, |_, _, item| released.push(item));
+        assert_eq!(released, vec![3]);
+        throttle.release(release_at + Duration::from_millis(100), |_, _, item| {
+            released.push(item)
+        });
+        assert_eq!(released, vec![3, 4]);
+        assert!(!throttle.is_delayed(Transport::Tcp, addr(1)));
+        assert_eq!(throttle.take_changes().len(), 1);
+    }
+
+    #[test]
+    fn ip_scope_shares_buckets_and_drops() {
+        let mut limit = RateLimit::new(RateLimitScope::Ip, 0, 100);
+        limit.policy(ThrottlePolicy::Drop);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Inbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 60, (), now)));
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(2), 60, (), now),
+            Admission::Dropped
+        ));
+        assert!(!throttle.is_delayed(Transport::Tcp, addr(2)));
+        let ip = addr(1).ip();
+        assert_eq!(throttle.take_changes(), vec![(ThrottledPeer::Ip(ip), true)]);
+        // Oversized messages pass whenever the bucket is full
+        let later = now + Duration::from_secs(2);
+        assert!(passed(throttle.offer(
+            Transport::Tcp,
+            addr(2),
+            500,
+            (),
+            later
+        )));
+        assert_eq!(
+            throttle.take_changes(),
+            vec![(ThrottledPeer::Ip(ip), false)]
+        );
+    }
+
+    #[test]
+    fn transports_are_throttled_separately() {
+        let mut limit = RateLimit::new(RateLimitScope::System, 1, 0);
+        limit.burst(1, 0);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Inbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Udp, addr(1), 1, 1, now)));
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 1, 2, now)));
+        assert!(matches!(
+            throttle.offer(Transport::Udp, addr(1), 1, 3, now),
+            Admission::Delayed
+        ));
+        assert!(throttle.is_delayed(Transport::Udp, addr(1)));
+        assert!(!throttle.is_delayed(Transport::Tcp, addr(1)));
+        assert_eq!(
+            throttle.take_changes(),
+            vec![(
+                ThrottledPeer::System(SystemPath::with_socket(Transport::Udp, addr(1))),
+                true
+            )]
+        );
+        let mut released = Vec::new();
+        throttle.release(now + Duration::from_secs(1), |transport, _, item| {
+            released.push((transport, item))
+        });
+        assert_eq!(released, vec![(Transport::Udp, 3)]);
+    }
+
+    #[test]
+    fn delayed_bytes_are_bounded_across_systems() {
+        let mut limit = RateLimit::new(RateLimitScope::System, 0, 100);
+        limit.max_delayed_bytes(150);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Outbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 100, 1, now)));
+        assert!(passed(throttle.offer(Transport::Tcp, addr(2), 100, 2, now)));
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 100, 3, now),
+            Admission::Delayed
+        ));
+        // The budget is shared, so the second system's message no longer fits
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(2), 100, 4, now),
+            Admission::Dropped
+        ));
+        assert!(!throttle.is_delayed(Transport::Tcp, addr(2)));
+        let mut released = Vec::new();
+        throttle.release(now + Duration::from_secs(1), |_, _, item| {
+            released.push(item)
+        });
+        assert_eq!(released, vec![3]);
+        // Releasing frees the budget again
+        let later = now + Duration::from_secs(1);
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 100, 5, later),
+            Admission::Delayed
+        ));
+    }
+
+    #[test]
+    fn dropping_throttle_ends_once_bucket_recovers() {
+        let mut limit = RateLimit::new(RateLimitScope::System, 10, 0);
+        limit.burst(1, 0);
+        limit.policy(ThrottlePolicy::Drop);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Inbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 1, (), now)));
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 1, (), now),
+            Admission::Dropped
+        ));
+        assert_eq!(throttle.take_changes().len(), 1);
+        let recovers_at = throttle.next_release().expect("Should be throttled");
+        assert_eq!(recovers_at, now + Duration::from_millis(100));
+        throttle.release(now, |_, _, _| unreachable!("Nothing was delayed"));
+        assert!(throttle.take_changes().is_empty());
+        // Released without waiting for the next message from the peer
+        throttle.release(recovers_at, |_, _, _| unreachable!("Nothing was delayed"));
+        assert_eq!(
+            throttle.take_changes(),
+            vec![(
+                ThrottledPeer::System(SystemPath::with_socket(Transport::Tcp, addr(1))),
+                false
+            )]
+        );
+        assert_eq!(throttle.next_release(), None);
+    }
+
+    #[test]
+    fn idle_buckets_are_evicted() {
+        let mut limit = RateLimit::new(RateLimitScope::System, 10, 0);
+        limit.burst(1, 0);
+        let mut throttle = Throttle::new(limit, TrafficDirection::Outbound);
+        let now = Instant::now();
+        assert!(passed(throttle.offer(Transport::Tcp, addr(1), 1, 1, now)));
+        assert!(matches!(
+            throttle.offer(Transport::Tcp, addr(1), 1, 2, now),
+            Admission::Delayed
+        ));
+        assert!(passed(throttle.offer(Transport::Tcp, addr(2), 1, 3, now)));
+        // The bucket of the delayed system is kept, the other one has not refilled yet
+        throttle.release(now, |_, _, _| unreachable!("Nothing to release yet"));
+        assert_eq!(throttle.buckets.len(), 2);
+        let mut released = Vec::new();
+        let later = now + EVICTION_INTERVAL;
+        throttle.release(later, |_, _, item| released.push(item));
+        assert_eq!(released, vec![2]);
+        // The bucket of addr(1) was just drawn from, so only the other one is gone
+        assert_eq!(throttle.buckets.len(), 1);
+        throttle.release(later + EVICTION_INTERVAL, |_, _, _| {
+            unreachable!("Nothing was delayed")
+        });
+        assert!(throttle.buckets.is_empty());
+        assert!(throttle.throttled.is_empty());
+        assert_eq!(throttle.next_release(), None);
+    }
+}
@@ -3,9 +3,18 @@ use crate::{
     messaging::{NetMessage, SerialisedFrame},
     net::buffers::{BufferChunk, BufferPool, DecodeBuffer},
 };
+use bytes::Buf;
 use mio::net::UdpSocket;
 use network_thread::*;
-use std::{cell::RefCell, cmp::min, collections::VecDeque, io, io::Error, net::SocketAddr};
+use socket2::{SockAddr, SockRef};
+use std::{
+    cell::RefCell,
+    cmp::min,
+    collections::VecDeque,
+    io,
+    io::{Error, IoSlice},
+    net::SocketAddr,
+};
 
 // Note that this is a theoretical IPv4 limit.
 // This may be violated with IPv6 jumbograms.
@@ -17,7 +26,7 @@ pub(super) struct UdpState {
     pub(super) socket: UdpSocket,
     outbound_queue: VecDeque<(SocketAddr, SerialisedFrame)>,
     input_buffer: DecodeBuffer,
-    pub(super) incoming_messages: VecDeque<NetMessage>,
+    pub(super) incoming_messages: VecDeque<(SocketAddr, usize, NetMessage)>,
     max_packet_size: usize,
 }
 
@@ -45,12 +54,15 @@ impl UdpState {
         self.outbound_queue.len()
     }
 
+    /// Sends as many queued datagrams as the socket will accept
+    ///
+    /// Each datagram is sent with a single `sendmsg` call, gathering the segments of
+    /// chained frames instead of copying them into a contiguous buffer first.
     pub(super) fn try_write(&mut self) -> io::Result<usize> {
         let mut sent_bytes: usize = 0;
         let mut interrupts = 0;
         while let Some((addr, mut frame)) = self.outbound_queue.pop_front() {
-            frame.make_contiguous();
-            match self.socket.send_to(frame.bytes(), addr) {
+            match send_frame_to(&self.socket, &mut frame, addr) {
                 Ok(n) => {
                     // This really shouldn't happen, and can lead to inconsistent network messages
                     assert_eq!(n, frame.len(), "A UDP frame was written incompletely!");
@@ -136,8 +148,9 @@ impl UdpState {
             Ok(Frame::Data(frame)) => {
                 use serialisation::ser_helpers::deserialise_chunk_lease;
                 let buf = frame.payload();
+                let size = buf.remaining();
                 match deserialise_chunk_lease(buf) {
-                    Ok(envelope) => self.incoming_messages.push_back(envelope),
+                    Ok(envelope) => self.incoming_messages.push_back((source, size, envelope)),
                     Err(e) => {
                         warn!(
                             self.logger,
@@ -165,3 +178,77 @@ impl UdpState {
         self.outbound_queue.push_back((addr, frame));
     }
 }
+
+/// Sends `frame` as a single datagram to `addr` using a vectored `sendmsg`
+///
+/// Only if the frame has more segments than [MAX_IO_SLICES] is it made contiguous first.
+fn send_frame_to(
+    socket: &UdpSocket,
+    frame: &mut SerialisedFrame,
+    addr: SocketAddr,
+) -> io::Result<usize> {
+    let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+    let filled = frame.chunks_vectored(&mut slices);
+    let gathered: usize = slices[..filled].iter().map(|slice| slice.len()).sum();
+    if gathered < frame.len() {
+        frame.make_contiguous();
+        return socket.send_to(frame.bytes(), addr);
+    }
+    SockRef::from(socket).send_to_vectored(&slices[..filled], &SockAddr::from(addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::buffers::{BufferConfig, EncodeBuffer};
+    use bytes::BufMut;
+    use std::time::Duration;
+
+    fn chained_frame(len: usize) -> (EncodeBuffer, SerialisedFrame, Vec<u8>) {
+        let mut cfg = BufferConfig::default();
+        cfg.chunk_size(128);
+        cfg.initial_chunk_count(2);
+        let mut encode_buffer = EncodeBuffer::with_config(&cfg, &None);
+        let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        let lease = {
+            let mut encoder = encode_buffer
+                .get_buffer_encoder()
+                .expect("Should not run out of buffers in test case");
+            encoder.put_slice(&data);
+            encoder.get_chunk_lease().expect("lease")
+        };
+        (encode_buffer, SerialisedFrame::ChunkLease(lease), data)
+    }
+
+    fn send_and_receive(frame: &mut SerialisedFrame) -> Vec<u8> {
+        let receiver = std::net::UdpSocket::bind("127.0.0.1:0").expect("bind");
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .expect("timeout");
+        let sender = UdpSocket::bind("127.0.0.1:0".parse().unwrap()).expect("bind");
+        let sent =
+            send_frame_to(&sender, frame, receiver.local_addr().expect("addr")).expect("send");
+        let mut buf = vec![0u8; MAX_PACKET_SIZE];
+        let (received, _) = receiver.recv_from(&mut buf).expect("receive");
+        assert_eq!(sent, received);
+        buf.truncate(received);
+        buf
+    }
+
+    #[test]
+    fn chained_frame_is_sent_as_one_datagram() {
+        let (_buffer, mut frame, data) = chained_frame(1000);
+        assert!(frame.bytes().len() < data.len(), "frame must be chained");
+        assert_eq!(data, send_and_receive(&mut frame));
+    }
+
+    #[test]
+    fn frame_with_too_many_segments_is_made_contiguous() {
+        let (_buffer, mut frame, data) = chained_frame(128 * (MAX_IO_SLICES + 4));
+        let mut slices = [IoSlice::new(&[]); MAX_IO_SLICES];
+        frame.chunks_vectored(&mut slices);
+        let gathered: usize = slices.iter().map(|slice| slice.len()).sum();
+        assert!(gathered < data.len(), "frame must exceed the slice limit");
+        assert_eq!(data, send_and_receive(&mut frame));
+    }
+}
@@ -0,0 +1,835 @@
+use super::*;
+use crate::messaging::SerialisedFrame;
+use io_uring::{opcode, squeue, types, IoUring};
+use mio::Token;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{io::IoSlice, os::unix::io::RawFd, sync::Weak, time::Instant};
+
+/// The number of submission queue entries of the ring
+const RING_ENTRIES: u32 = 1024;
+/// The kernel limit on the number of buffers in a registered buffer table
+const MAX_REGISTERED_BUFFERS: usize = 1 << 14;
+/// How long to wait for in-flight operations to be cancelled during shutdown before warning about it
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The kind of operation a completion belongs to
+///
+/// Encoded together with the [Token] of the socket in the `user_data` of each entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum OpKind {
+    /// The socket became readable
+    PollIn = 1,
+    /// The socket became writable
+    PollOut = 2,
+    /// A read into the channel's [DecodeBuffer](super::buffers::DecodeBuffer) completed
+    Recv = 3,
+    /// A vectored write of the channel's outbound queue completed
+    Write = 4,
+    /// The [UringWaker] was woken
+    Wake = 5,
+    /// The timeout of a [wait](UringDriver::wait) expired
+    Timeout = 6,
+    /// A cancellation request completed
+    Cancel = 7,
+}
+
+/// How long to wait before reissuing cancellations of operations that are still in flight
+const CANCEL_RETRY: Duration = Duration::from_millis(100);
+
+impl OpKind {
+    fn user_data(self, token: Token) -> u64 {
+        ((token.0 as u64) << 8) | self as u64
+    }
+
+    fn from_user_data(user_data: u64) -> (Token, Option<OpKind>) {
+        let kind = match user_data & 0xff {
+            1 => Some(OpKind::PollIn),
+            2 => Some(OpKind::PollOut),
+            3 => Some(OpKind::Recv),
+            4 => Some(OpKind::Write),
+            5 => Some(OpKind::Wake),
+            6 => Some(OpKind::Timeout),
+            7 => Some(OpKind::Cancel),
+            _ => None,
+        };
+        (Token((user_data >> 8) as usize), kind)
+    }
+}
+
+/// A completed operation, as reaped from the completion queue
+#[derive(Debug)]
+pub(crate) struct Completion {
+    pub(crate) token: Token,
+    pub(crate) kind: OpKind,
+    /// The number of bytes transferred, or a negated `errno`
+    pub(crate) result: i32,
+}
+
+impl Completion {
+    /// Returns the result as an `io::Result`
+    pub(crate) fn io_result(&self) -> io::Result<usize> {
+        if self.result < 0 {
+            Err(io::Error::from_raw_os_error(-self.result))
+        } else {
+            Ok(self.result as usize)
+        }
+    }
+}
+
+/// Book-keeping for a [TcpChannel](super::network_channel::TcpChannel)
+/// whose reads and writes are performed by the ring
+#[derive(Default)]
+pub(crate) struct CompletionState {
+    recv_in_flight: bool,
+    write_in_flight: bool,
+    /// The socket is not ready to be read from, so its readiness must be awaited before the next read
+    await_readable: bool,
+    /// The socket is not ready to be written to, so its readiness must be awaited before the next write
+    await_writable: bool,
+    /// The remote end closed the connection or it failed, so no further reads are submitted
+    eof: bool,
+    recv_error: Option<io::Error>,
+    write_error: Option<io::Error>,
+    /// The segments of the write in flight, which must stay valid until it completes
+    iovecs: Vec<libc::iovec>,
+    /// The number of frames at the front of the outbound queue that the write in flight refers to
+    write_frames: usize,
+    /// The channel was dropped while a read was in flight,
+    /// so its buffer must only be returned to the pool once the read completes
+    pub(crate) release_buffer: bool,
+}
+
+impl CompletionState {
+    /// Returns the error of the last completed read, if any
+    pub(crate) fn take_recv_result(&mut self) -> io::Result<()> {
+        match self.recv_error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the error of the last completed write, if any
+    pub(crate) fn take_write_result(&mut self) -> io::Result<usize> {
+        match self.write_error.take() {
+            Some(e) => Err(e),
+            None => Ok(0),
+        }
+    }
+
+    /// Returns `true` while the ring holds any of the channel's buffers
+    pub(crate) fn in_flight(&self) -> bool {
+        self.recv_in_flight || self.write_in_flight
+    }
+
+    pub(crate) fn recv_in_flight(&self) -> bool {
+        self.recv_in_flight
+    }
+
+    /// The number of queued frames that must not be released, as the write in flight refers to them
+    pub(crate) fn frames_in_flight(&self) -> usize {
+        if self.write_in_flight {
+            self.write_frames
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn can_recv(&self) -> bool {
+        !self.recv_in_flight && !self.eof
+    }
+
+    pub(crate) fn can_write(&self) -> bool {
+        !self.write_in_flight && !self.await_writable
+    }
+
+    pub(crate) fn awaiting_readable(&self) -> bool {
+        self.await_readable && !self.eof
+    }
+
+    pub(crate) fn awaiting_writable(&self) -> bool {
+        self.await_writable
+    }
+
+    /// Applies the result of a readiness poll for reading
+    pub(crate) fn complete_poll(&mut self) {
+        self.await_readable = false;
+    }
+
+    /// Applies the result of a readiness poll for writing
+    pub(crate) fn complete_poll_out(&mut self) {
+        self.await_writable = false;
+    }
+
+    /// Applies the result of a read, returning the number of bytes read into the buffer
+    pub(crate) fn complete_recv(&mut self, completion: &Completion) -> usize {
+        self.recv_in_flight = false;
+        match completion.io_result() {
+            Ok(0) => {
+                self.eof = true;
+                0
+            }
+            Ok(n) => n,
+            Err(ref e) if would_block(e) || not_connected(e) => {
+                self.await_readable = true;
+                0
+            }
+            Err(ref e) if interrupted(e) || cancelled(e) => 0,
+            Err(e) => {
+                self.eof = true;
+                self.recv_error = Some(e);
+                0
+            }
+        }
+    }
+
+    /// Applies the result of a write, returning the number of bytes written
+    pub(crate) fn complete_write(&mut self, completion: &Completion) -> usize {
+        self.write_in_flight = false;
+        self.write_frames = 0;
+        self.iovecs.clear();
+        match completion.io_result() {
+            Ok(n) => n,
+            Err(ref e) if would_block(e) => {
+                self.await_writable = true;
+                0
+            }
+            Err(ref e) if interrupted(e) || cancelled(e) => 0,
+            Err(e) => {
+                self.write_error = Some(e);
+                0
+            }
+        }
+    }
+}
+
+fn not_connected(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::NotConnected
+}
+
+fn cancelled(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::ECANCELED)
+}
+
+/// Wakes a [NetworkThread](super::network_thread::NetworkThread) running on the `io_uring` backend
+///
+/// The network thread keeps a read of the underlying `eventfd` in flight,
+/// which completes whenever the waker is woken.
+#[derive(Clone, Debug)]
+pub(crate) struct UringWaker {
+    fd: Arc<EventFd>,
+}
+
+impl UringWaker {
+    pub(crate) fn wake(&self) -> io::Result<()> {
+        let value: u64 = 1;
+        let res = unsafe {
+            libc::write(
+                self.fd.0,
+                &value as *const u64 as *const libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        if res < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EventFd(RawFd);
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.0);
+        }
+    }
+}
+
+/// The table of [BufferChunks](super::buffers::BufferChunk) registered with the ring
+///
+/// Chunks are registered lazily the first time a read into them is submitted,
+/// which avoids pinning memory for chunks that are only ever used for encoding.
+struct RegisteredBuffers {
+    enabled: bool,
+    /// Maps the address of a chunk to its slot, or `None` if it could not be registered
+    slots: FxHashMap<usize, Option<RegisteredBuffer>>,
+    capacity: usize,
+    next_slot: usize,
+}
+
+/// A slot of the registered buffer table
+///
+/// The handle is used to detect when the chunk was freed, so that the address may be registered anew.
+struct RegisteredBuffer {
+    index: u16,
+    alive: Weak<u8>,
+}
+
+/// Submits and reaps `io_uring` operations on behalf of the network thread
+pub(crate) struct UringDriver {
+    ring: IoUring,
+    log: KompactLogger,
+    wake_fd: Arc<EventFd>,
+    wake_buf: Box<u64>,
+    timeout: Box<types::Timespec>,
+    /// Whether the kernel takes the timeout of a wait as an argument,
+    /// instead of it having to be submitted as a separate operation
+    timeout_arg: bool,
+    /// The generation of the timeout operation in flight, if any
+    ///
+    /// Only used if timeouts can not be passed as an argument.
+    timeout_armed: Option<usize>,
+    timeout_generation: usize,
+    /// Set once all operations are being cancelled, after which the waker is not re-armed
+    shutting_down: bool,
+    /// The `user_data` of every operation whose completion has not been reaped yet
+    outstanding: FxHashSet<u64>,
+    buffers: RegisteredBuffers,
+}
+
+impl UringDriver {
+    /// Sets up a new ring with a registered buffer table for at most `max_buffers` chunks
+    pub(crate) fn new(max_buffers: usize, log: KompactLogger) -> io::Result<(Self, UringWaker)> {
+        let ring = IoUring::new(RING_ENTRIES)?;
+        let raw_fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC) };
+        if raw_fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let wake_fd = Arc::new(EventFd(raw_fd));
+        let capacity = max_buffers.min(MAX_REGISTERED_BUFFERS);
+        let timeout_arg = ring.params().is_feature_ext_arg();
+        // Register an empty table up front, slots are filled in as chunks are first used
+        let enabled = match ring.submitter().register_buffers_sparse(capacity as u32) {
+            Ok(()) => true,
+            Err(e) => {
+                debug!(
+                    log,
+                    "Could not register buffers with io_uring, reads will not use fixed buffers: {}",
+                    e
+                );
+                false
+            }
+        };
+        let mut driver = UringDriver {
+            ring,
+            log,
+            wake_fd: wake_fd.clone(),
+            wake_buf: Box::new(0),
+            timeout: Box::new(types::Timespec::new()),
+            timeout_arg,
+            timeout_armed: None,
+            timeout_generation: 0,
+            shutting_down: false,
+            outstanding: FxHashSet::default(),
+            buffers: RegisteredBuffers {
+                enabled,
+                slots: FxHashMap::default(),
+                capacity,
+                next_slot: 0,
+            },
+        };
+        driver.arm_wake()?;
+        Ok((driver, UringWaker { fd: wake_fd }))
+    }
+
+    fn push(&mut self, entry: squeue::Entry, user_data: u64) -> io::Result<()> {
+        let entry = entry.user_data(user_data);
+        loop {
+            // Safety: All buffers referenced by entries are kept alive until their completion is reaped,
+            // either by the driver itself or by the channel owning them.
+            let pushed = unsafe { self.ring.submission().push(&entry).is_ok() };
+            if pushed {
+                break;
+            }
+            // The submission queue is full, hand the entries to the kernel and try again
+            self.ring.submit()?;
+        }
+        if user_data & 0xff != OpKind::Cancel as u64 {
+            self.outstanding.insert(user_data);
+        }
+        Ok(())
+    }
+
+    fn arm_wake(&mut self) -> io::Result<()> {
+        let entry = opcode::Read::new(
+            types::Fd(self.wake_fd.0),
+            &mut *self.wake_buf as *mut u64 as *mut u8,
+            std::mem::size_of::<u64>() as u32,
+        )
+        .build();
+        self.push(entry, OpKind::Wake.user_data(Token(0)))
+    }
+
+    /// Waits for readiness of `fd` in the direction given by `kind`, unless that is already pending.
+    pub(crate) fn poll(&mut self, token: Token, fd: RawFd, kind: OpKind) -> io::Result<()> {
+        let user_data = kind.user_data(token);
+        if self.outstanding.contains(&user_data) {
+            return Ok(());
+        }
+        let flags = match kind {
+            OpKind::PollOut => libc::POLLOUT,
+            _ => libc::POLLIN,
+        };
+        let entry = opcode::PollAdd::new(types::Fd(fd), flags as u32).build();
+        self.push(entry, user_data)
+    }
+
+    /// Submits a read into `buffer` on behalf of `state`
+    ///
+    /// `chunk` are the [raw parts](super::buffers::BufferChunk::raw_parts) of the chunk backing `buffer`,
+    /// which is used to read into a registered buffer where possible.
+    pub(crate) fn recv(
+        &mut self,
+        token: Token,
+        fd: RawFd,
+        state: &mut CompletionState,
+        buffer: &mut [u8],
+        chunk: (*mut u8, usize, Weak<u8>),
+    ) -> io::Result<()> {
+        let len = buffer.len().min(u32::MAX as usize) as u32;
+        let entry = match self.buffer_index(chunk) {
+            Some(index) => {
+                opcode::ReadFixed::new(types::Fd(fd), buffer.as_mut_ptr(), len, index).build()
+            }
+            None => opcode::Recv::new(types::Fd(fd), buffer.as_mut_ptr(), len).build(),
+        };
+        self.push(entry, OpKind::Recv.user_data(token))?;
+        state.recv_in_flight = true;
+        Ok(())
+    }
+
+    /// Submits a single vectored write of up to [MAX_IO_SLICES](super::network_thread::MAX_IO_SLICES)
+    /// segments of the frames in `frames`, returning `false` if there was nothing to write
+    pub(crate) fn write<'a, I>(
+        &mut self,
+        token: Token,
+        fd: RawFd,
+        state: &mut CompletionState,
+        frames: I,
+    ) -> io::Result<bool>
+    where
+        I: Iterator<Item = &'a SerialisedFrame>,
+    {
+        let mut slices = [IoSlice::new(&[]); network_thread::MAX_IO_SLICES];
+        let mut filled = 0;
+        let mut frame_count = 0;
+        for frame in frames {
+            if filled == slices.len() {
+                break;
+            }
+            filled += frame.chunks_vectored(&mut slices[filled..]);
+            frame_count += 1;
+        }
+        if filled == 0 {
+            return Ok(false);
+        }
+        state.iovecs.clear();
+        state
+            .iovecs
+            .extend(slices[..filled].iter().map(|slice| libc::iovec {
+                iov_base: slice.as_ptr() as *mut libc::c_void,
+                iov_len: slice.len(),
+            }));
+        let entry = opcode::Writev::new(
+            types::Fd(fd),
+            state.iovecs.as_ptr(),
+            state.iovecs.len() as u32,
+        )
+        .build();
+        self.push(entry, OpKind::Write.user_data(token))?;
+        state.write_in_flight = true;
+        state.write_frames = frame_count;
+        Ok(true)
+    }
+
+    /// Requests cancellation of the operation of `kind` for `token`, if it is in flight
+    pub(crate) fn cancel(&mut self, token: Token, kind: OpKind) -> io::Result<()> {
+        let user_data = kind.user_data(token);
+        if self.outstanding.contains(&user_data) {
+            let entry = opcode::AsyncCancel::new(user_data).build();
+            self.push(entry, OpKind::Cancel.user_data(token))?;
+        }
+        Ok(())
+    }
+
+    /// Submits all queued operations and waits for at least one completion or until `timeout` expires
+    ///
+    /// A timeout of zero does not wait at all and `None` waits indefinitely.
+    pub(crate) fn wait(
+        &mut self,
+        timeout: Option<Duration>,
+        completions: &mut Vec<Completion>,
+    ) -> io::Result<()> {
+        let res = match timeout {
+            Some(duration) if duration == Duration::from_secs(0) => self.ring.submit(),
+            Some(duration) => {
+                *self.timeout = types::Timespec::new()
+                    .sec(duration.as_secs())
+                    .nsec(duration.subsec_nanos());
+                if self.timeout_arg {
+                    let args = types::SubmitArgs::new().timespec(&self.timeout);
+                    self.ring.submitter().submit_with_args(1, &args)
+                } else {
+                    self.arm_timeout()?;
+                    self.ring.submit_and_wait(1)
+                }
+            }
+            None => self.ring.submit_and_wait(1),
+        };
+        match res {
+            Ok(_) => (),
+            // The timeout passed as an argument expired
+            Err(ref e) if e.raw_os_error() == Some(libc::ETIME) => (),
+            Err(ref e) if interrupted(e) => (),
+            // The completion queue is full, reap it before submitting more
+            Err(ref e) if e.raw_os_error() == Some(libc::EBUSY) => (),
+            Err(e) => return Err(e),
+        }
+        self.reap(completions);
+        Ok(())
+    }
+
+    /// Replaces the timeout operation in flight with one for the current [timeout](UringDriver::timeout)
+    ///
+    /// Each timeout is tagged with its own generation, so that the completion of a replaced one
+    /// is not mistaken for that of its successor.
+    fn arm_timeout(&mut self) -> io::Result<()> {
+        if let Some(generation) = self.timeout_armed.take() {
+            let entry =
+                opcode::TimeoutRemove::new(OpKind::Timeout.user_data(Token(generation))).build();
+            self.push(entry, OpKind::Cancel.user_data(Token(generation)))?;
+        }
+        self.timeout_generation = self.timeout_generation.wrapping_add(1) & (usize::MAX >> 8);
+        let generation = self.timeout_generation;
+        // The kernel copies the timespec when the entry is submitted, so it may be overwritten afterwards
+        let entry = opcode::Timeout::new(&*self.timeout).build();
+        self.push(entry, OpKind::Timeout.user_data(Token(generation)))?;
+        self.timeout_armed = Some(generation);
+        Ok(())
+    }
+
+    fn reap(&mut self, completions: &mut Vec<Completion>) {
+        let mut rearm_wake = false;
+        for entry in self.ring.completion() {
+            let user_data = entry.user_data();
+            let (token, kind) = OpKind::from_user_data(user_data);
+            let kind = match kind {
+                Some(kind) => kind,
+                None => continue,
+            };
+            self.outstanding.remove(&user_data);
+            match kind {
+                OpKind::Timeout => {
+                    if self.timeout_armed == Some(token.0) {
+                        self.timeout_armed = None;
+                    }
+                }
+                OpKind::Cancel => (),
+                OpKind::Wake => {
+                    rearm_wake = true;
+                    completions.push(Completion {
+                        token,
+                        kind,
+                        result: entry.result(),
+                    });
+                }
+                _ => completions.push(Completion {
+                    token,
+                    kind,
+                    result: entry.result(),
+                }),
+            }
+        }
+        if rearm_wake && !self.shutting_down {
+            if let Err(e) = self.arm_wake() {
+                error!(self.log, "Could not re-arm the io_uring waker: {}", e);
+            }
+        }
+    }
+
+    /// Waits for all writes in flight to finish for at most `grace`
+    pub(crate) fn flush_writes(&mut self, grace: Duration) -> () {
+        let mut completions = Vec::new();
+        let deadline = Instant::now() + grace;
+        while self.has_outstanding(|kind| kind == OpKind::Write) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            if self.wait(Some(deadline - now), &mut completions).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Cancels every operation in flight and waits until the kernel has completed all of them
+    ///
+    /// Afterwards the ring holds no more references to any buffers, so they may be freed.
+    /// Cancellations are reissued periodically, as operations may be started by the kernel
+    /// before their cancellation is processed. Operations on sockets that can not be cancelled
+    /// complete once the socket is shut down, which the caller must do if this takes too long.
+    pub(crate) fn cancel_all(&mut self) -> io::Result<()> {
+        // The wake read is cancelled as well, so don't re-arm it
+        self.shutting_down = true;
+        let mut completions = Vec::new();
+        let mut last_warning = Instant::now();
+        while self.has_outstanding(|kind| kind != OpKind::Timeout) {
+            let pending: Vec<u64> = self
+                .outstanding
+                .iter()
+                .copied()
+                .filter(|user_data| OpKind::from_user_data(*user_data).1 != Some(OpKind::Timeout))
+                .collect();
+            for user_data in pending {
+                let (token, _) = OpKind::from_user_data(user_data);
+                let entry = opcode::AsyncCancel::new(user_data).build();
+                self.push(entry, OpKind::Cancel.user_data(token))?;
+            }
+            self.wait(Some(CANCEL_RETRY), &mut completions)?;
+            completions.clear();
+            if last_warning.elapsed() >= CANCEL_TIMEOUT {
+                warn!(
+                    self.log,
+                    "Still waiting for {} io_uring operations to be cancelled",
+                    self.outstanding.len()
+                );
+                last_warning = Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    fn has_outstanding<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(OpKind) -> bool,
+    {
+        self.outstanding.iter().any(|user_data| {
+            matches!(OpKind::from_user_data(*user_data).1, Some(kind) if predicate(kind))
+        })
+    }
+
+    /// Returns the index of `chunk` in the registered buffer table, registering it if possible
+    fn buffer_index(&mut self, chunk: (*mut u8, usize, Weak<u8>)) -> Option<u16> {
+        let (ptr, len, alive) = chunk;
+        if !self.buffers.enabled || len == 0 {
+            return None;
+        }
+        let address = ptr as usize;
+        let reuse = match self.buffers.slots.get(&address) {
+            Some(Some(slot)) if slot.alive.ptr_eq(&alive) => return Some(slot.index),
+            // The chunk at this address was freed and another one took its place
+            Some(Some(slot)) => Some(slot.index as usize),
+            Some(None) => return None,
+            None => None,
+        };
+        let index = match reuse.or_else(|| self.free_slot()) {
+            Some(index) => index,
+            None => {
+                self.buffers.slots.insert(address, None);
+                return None;
+            }
+        };
+        let slot = match self.register_buffer(index, ptr, len) {
+            Ok(()) => Some(RegisteredBuffer {
+                index: index as u16,
+                alive,
+            }),
+            Err(e) => {
+                // Most likely RLIMIT_MEMLOCK, fall back to normal reads for this chunk
+                debug!(
+                    self.log,
+                    "Could not register buffer with io_uring: {}, reads into it will not use fixed buffers",
+                    e
+                );
+                None
+            }
+        };
+        let index = slot.as_ref().map(|slot| slot.index);
+        self.buffers.slots.insert(address, slot);
+        index
+    }
+
+    /// Returns an unused slot of the table, reclaiming the slots of freed chunks once it is full
+    fn free_slot(&mut self) -> Option<usize> {
+        if self.buffers.next_slot < self.buffers.capacity {
+            self.buffers.next_slot += 1;
+            return Some(self.buffers.next_slot - 1);
+        }
+        let freed = self
+            .buffers
+            .slots
+            .iter()
+            .find_map(|(address, slot)| match slot {
+                Some(slot) if slot.alive.strong_count() == 0 => Some((*address, slot.index)),
+                _ => None,
+            });
+        freed.map(|(address, index)| {
+            self.buffers.slots.remove(&address);
+            index as usize
+        })
+    }
+
+    fn register_buffer(&self, index: usize, ptr: *mut u8, len: usize) -> io::Result<()> {
+        let iovec = libc::iovec {
+            iov_base: ptr as *mut libc::c_void,
+            iov_len: len,
+        };
+        // Safety: Registering pins the pages of the chunk, so the kernel never accesses freed memory,
+        // and reads into the slot are only submitted while the chunk it was registered for is alive.
+        unsafe {
+            self.ring
+                .submitter()
+                .register_buffers_update(index as u32, &[iovec], None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_data_roundtrip() {
+        let kinds = [
+            OpKind::PollIn,
+            OpKind::PollOut,
+            OpKind::Recv,
+            OpKind::Write,
+            OpKind::Wake,
+            OpKind::Timeout,
+            OpKind::Cancel,
+        ];
+        for kind in kinds.iter() {
+            let token = Token(usize::MAX >> 8);
+            assert_eq!(
+                OpKind::from_user_data(kind.user_data(token)),
+                (token, Some(*kind))
+            );
+        }
+    }
+
+    #[test]
+    fn completion_state_recv_outcomes() {
+        let completion = |result| Completion {
+            token: Token(3),
+            kind: OpKind::Recv,
+            result,
+        };
+        let mut state = CompletionState {
+            recv_in_flight: true,
+            ..Default::default()
+        };
+        assert_eq!(state.complete_recv(&completion(42)), 42);
+        assert!(state.can_recv());
+        assert!(state.take_recv_result().is_ok());
+
+        state.recv_in_flight = true;
+        assert_eq!(state.complete_recv(&completion(-libc::EAGAIN)), 0);
+        assert!(state.awaiting_readable());
+        state.complete_poll();
+        assert!(state.can_recv());
+
+        state.recv_in_flight = true;
+        assert_eq!(state.complete_recv(&completion(-libc::ECONNRESET)), 0);
+        assert!(!state.can_recv());
+        let err = state
+            .take_recv_result()
+            .expect_err("Reset must be reported");
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    }
+
+    /// Sets up a driver, or returns `None` if the kernel does not support `io_uring`
+    fn test_driver(system: &KompactSystem) -> Option<(UringDriver, UringWaker)> {
+        match UringDriver::new(16, system.logger().clone()) {
+            Ok(driver) => Some(driver),
+            Err(e) => {
+                println!("Skipping test, as io_uring is not available: {}", e);
+                None
+            }
+        }
+    }
+
+    fn shorter_timeout_after_longer(timeout_arg: bool) {
+        let system = KompactConfig::default().build().expect("system");
+        let (mut driver, waker) = match test_driver(&system) {
+            Some(driver) => driver,
+            None => return,
+        };
+        if timeout_arg && !driver.timeout_arg {
+            println!("Skipping test, as the kernel does not support timeout arguments");
+            return;
+        }
+        driver.timeout_arg = timeout_arg;
+        let mut completions = Vec::new();
+
+        // Arm a long timeout, but return right away due to the wakeup
+        waker.wake().expect("wake");
+        driver
+            .wait(Some(Duration::from_secs(30)), &mut completions)
+            .expect("wait");
+        assert!(completions.iter().any(|c| c.kind == OpKind::Wake));
+        completions.clear();
+
+        // A shorter timeout must not wait for the longer one
+        let start = Instant::now();
+        let short = Duration::from_millis(50);
+        while start.elapsed() < short {
+            driver.wait(Some(short), &mut completions).expect("wait");
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(
+            completions.is_empty(),
+            "Unexpected completions: {:?}",
+            completions
+        );
+
+        driver.cancel_all().expect("cancel");
+        assert!(!driver.has_outstanding(|kind| kind != OpKind::Timeout));
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn shorter_timeout_after_longer_with_timeout_op() {
+        shorter_timeout_after_longer(false);
+    }
+
+    #[test]
+    fn shorter_timeout_after_longer_with_timeout_arg() {
+        shorter_timeout_after_longer(true);
+    }
+
+    #[test]
+    fn completion_state_write_outcomes() {
+        let completion = |result| Completion {
+            token: Token(3),
+            kind: OpKind::Write,
+            result,
+        };
+        let mut state = CompletionState {
+            write_in_flight: true,
+            write_frames: 2,
+            ..Default::default()
+        };
+        assert_eq!(state.complete_write(&completion(-libc::EAGAIN)), 0);
+        assert!(state.awaiting_writable());
+        assert!(!state.can_write());
+        assert_eq!(state.frames_in_flight(), 0);
+        state.complete_poll_out();
+        assert!(state.can_write());
+
+        state.write_in_flight = true;
+        assert_eq!(state.complete_write(&completion(-libc::ECANCELED)), 0);
+        assert!(state.can_write());
+        assert_eq!(state.take_write_result().expect("No error"), 0);
+
+        state.write_in_flight = true;
+        assert_eq!(state.complete_write(&completion(-libc::EPIPE)), 0);
+        let err = state
+            .take_write_result()
+            .expect_err("Broken pipe must be reported");
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
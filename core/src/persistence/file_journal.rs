@@ -0,0 +1,475 @@
+use super::journal::{Journal, JournalEntry, JournalError, JournalFuture};
+use crate::{
+    serialisation::{SerId, SerIdBuf, SerIdBufMut, SerIdSize},
+    utils::{promise, Fulfillable, KPromise},
+};
+use bytes::{Buf, BufMut};
+use crossbeam_channel::{unbounded as channel, Receiver, Sender};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+};
+
+const JOURNAL_EXTENSION: &str = "journal";
+const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// A [Journal] that keeps one append-only file per persistence id in a directory
+///
+/// Each entry is written as a length-prefixed frame holding its sequence number,
+/// its serialiser id, and the serialised data. Snapshots are kept in a separate file
+/// per persistence id, which is replaced atomically whenever a new snapshot is saved.
+///
+/// All file operations are performed in order on a dedicated thread, and appends only complete
+/// after the data has been synced to disk.
+/// If the system crashed during an append, the incomplete frame is discarded
+/// when the journal is opened again.
+pub struct FileJournal {
+    directory: PathBuf,
+    requests: Sender<Request>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl FileJournal {
+    /// Open a journal in `directory`, creating the directory if necessary
+    pub fn open(directory: impl AsRef<Path>) -> io::Result<FileJournal> {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)?;
+        let (requests, receiver) = channel();
+        let mut worker = Worker {
+            directory: directory.clone(),
+            logs: HashMap::new(),
+        };
+        let handle = thread::Builder::new()
+            .name("kompact-file-journal".to_string())
+            .spawn(move || worker.run(receiver))?;
+        Ok(FileJournal {
+            directory,
+            requests,
+            worker: Some(handle),
+        })
+    }
+
+    /// The directory this journal is stored in
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn request<T: Send>(
+        &self,
+        f: impl FnOnce(KPromise<Result<T, JournalError>>) -> Request,
+    ) -> JournalFuture<T> {
+        let (promise, future) = promise();
+        if let Err(e) = self.requests.send(f(promise)) {
+            e.into_inner().fail(JournalError::Closed);
+        }
+        future
+    }
+}
+
+impl Journal for FileJournal {
+    fn append(&self, persistence_id: &str, entry: JournalEntry) -> JournalFuture<()> {
+        let persistence_id = persistence_id.to_string();
+        self.request(|promise| Request::Append {
+            persistence_id,
+            entry,
+            promise,
+        })
+    }
+
+    fn replay(
+        &self,
+        persistence_id: &str,
+        from_sequence_nr: u64,
+    ) -> JournalFuture<Vec<JournalEntry>> {
+        let persistence_id = persistence_id.to_string();
+        self.request(|promise| Request::Replay {
+            persistence_id,
+            from_sequence_nr,
+            promise,
+        })
+    }
+
+    fn save_snapshot(&self, persistence_id: &str, snapshot: JournalEntry) -> JournalFuture<()> {
+        let persistence_id = persistence_id.to_string();
+        self.request(|promise| Request::SaveSnapshot {
+            persistence_id,
+            snapshot,
+            promise,
+        })
+    }
+
+    fn load_snapshot(&self, persistence_id: &str) -> JournalFuture<Option<JournalEntry>> {
+        let persistence_id = persistence_id.to_string();
+        self.request(|promise| Request::LoadSnapshot {
+            persistence_id,
+            promise,
+        })
+    }
+}
+
+impl Drop for FileJournal {
+    fn drop(&mut self) {
+        // requests that were sent before are still completed
+        let _ = self.requests.send(Request::Shutdown);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl fmt::Debug for FileJournal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileJournal")
+            .field("directory", &self.directory)
+            .finish()
+    }
+}
+
+type Reply<T> = KPromise<Result<T, JournalError>>;
+
+enum Request {
+    Append {
+        persistence_id: String,
+        entry: JournalEntry,
+        promise: Reply<()>,
+    },
+    Replay {
+        persistence_id: String,
+        from_sequence_nr: u64,
+        promise: Reply<Vec<JournalEntry>>,
+    },
+    SaveSnapshot {
+        persistence_id: String,
+        snapshot: JournalEntry,
+        promise: Reply<()>,
+    },
+    LoadSnapshot {
+        persistence_id: String,
+        promise: Reply<Option<JournalEntry>>,
+    },
+    Shutdown,
+}
+
+impl Request {
+    fn fail(self, error: JournalError) {
+        // nobody may be waiting for the reply anymore, which is fine
+        let _ = match self {
+            Request::Append { promise, .. } => promise.fulfil(Err(error)),
+            Request::Replay { promise, .. } => promise.fulfil(Err(error)),
+            Request::SaveSnapshot { promise, .. } => promise.fulfil(Err(error)),
+            Request::LoadSnapshot { promise, .. } => promise.fulfil(Err(error)),
+            Request::Shutdown => Ok(()),
+        };
+    }
+}
+
+/// The open journal file of a single persistence id
+struct Log {
+    file: File,
+    last_sequence_nr: u64,
+}
+
+struct Worker {
+    directory: PathBuf,
+    logs: HashMap<String, Log>,
+}
+
+impl Worker {
+    fn run(&mut self, requests: Receiver<Request>) {
+        // the loop also ends once all senders are gone
+        while let Ok(request) = requests.recv() {
+            // as above, nobody may be waiting for the replies anymore
+            let _ = match request {
+                Request::Append {
+                    persistence_id,
+                    entry,
+                    promise,
+                } => promise.fulfil(self.append(persistence_id, entry)),
+                Request::Replay {
+                    persistence_id,
+                    from_sequence_nr,
+                    promise,
+                } => promise.fulfil(self.replay(&persistence_id, from_sequence_nr)),
+                Request::SaveSnapshot {
+                    persistence_id,
+                    snapshot,
+                    promise,
+                } => promise.fulfil(self.save_snapshot(&persistence_id, snapshot)),
+                Request::LoadSnapshot {
+                    persistence_id,
+                    promise,
+                } => promise.fulfil(self.load_snapshot(&persistence_id)),
+                Request::Shutdown => break,
+            };
+        }
+    }
+
+    fn path(&self, persistence_id: &str, extension: &str) -> PathBuf {
+        self.directory.join(format!(
+            "{}.{}",
+            encode_file_name(persistence_id),
+            extension
+        ))
+    }
+
+    fn append(&mut self, persistence_id: String, entry: JournalEntry) -> Result<(), JournalError> {
+        let log = match self.logs.get_mut(&persistence_id) {
+            Some(log) => log,
+            None => {
+                let log = self.open_log(&persistence_id)?;
+                self.logs.entry(persistence_id.clone()).or_insert(log)
+            }
+        };
+        let expected = log.last_sequence_nr + 1;
+        if entry.sequence_nr != expected {
+            return Err(JournalError::SequenceMismatch {
+                expected,
+                actual: entry.sequence_nr,
+            });
+        }
+        let frame = encode_frame(&entry)?;
+        let start = log.file.stream_position()?;
+        let written = log
+            .file
+            .write_all(&frame)
+            .and_then(|_| log.file.sync_data());
+        if let Err(e) = written {
+            // Don't leave a torn frame behind, as later appends would be written after it.
+            // The log is reopened on the next append, which also repairs it, if truncating failed.
+            let _ = log.file.set_len(start);
+            self.logs.remove(&persistence_id);
+            return Err(e.into());
+        }
+        log.last_sequence_nr = entry.sequence_nr;
+        Ok(())
+    }
+
+    /// Open the journal file for appending, discarding any incomplete frame at its end
+    fn open_log(&self, persistence_id: &str) -> Result<Log, JournalError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.path(persistence_id, JOURNAL_EXTENSION))?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let (entries, valid_len) = decode_frames(&data)?;
+        if valid_len < data.len() {
+            file.set_len(valid_len as u64)?;
+            file.sync_data()?;
+        }
+        file.seek(SeekFrom::Start(valid_len as u64))?;
+        let last_sequence_nr = entries.last().map(|e| e.sequence_nr).unwrap_or(0);
+        Ok(Log {
+            file,
+            last_sequence_nr,
+        })
+    }
+
+    fn replay(
+        &self,
+        persistence_id: &str,
+        from_sequence_nr: u64,
+    ) -> Result<Vec<JournalEntry>, JournalError> {
+        let data = read_if_exists(&self.path(persistence_id, JOURNAL_EXTENSION))?;
+        let (mut entries, _) = decode_frames(&data)?;
+        entries.retain(|entry| entry.sequence_nr >= from_sequence_nr);
+        Ok(entries)
+    }
+
+    fn save_snapshot(
+        &self,
+        persistence_id: &str,
+        snapshot: JournalEntry,
+    ) -> Result<(), JournalError> {
+        let path = self.path(persistence_id, SNAPSHOT_EXTENSION);
+        let tmp_path = path.with_extension(format!("{}.tmp", SNAPSHOT_EXTENSION));
+        let frame = encode_frame(&snapshot)?;
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&frame)?;
+        file.sync_data()?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    fn load_snapshot(&self, persistence_id: &str) -> Result<Option<JournalEntry>, JournalError> {
+        let data = read_if_exists(&self.path(persistence_id, SNAPSHOT_EXTENSION))?;
+        let (mut entries, valid_len) = decode_frames(&data)?;
+        if valid_len < data.len() || entries.len() > 1 {
+            Err(JournalError::Corrupted(format!(
+                "Invalid snapshot file for {}",
+                persistence_id
+            )))
+        } else {
+            Ok(entries.pop())
+        }
+    }
+}
+
+fn read_if_exists(path: &Path) -> io::Result<Vec<u8>> {
+    match fs::read(path) {
+        Ok(data) => Ok(data),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Map a persistence id to a valid file name, escaping everything but `[a-zA-Z0-9_-]`
+fn encode_file_name(persistence_id: &str) -> String {
+    let mut name = String::with_capacity(persistence_id.len());
+    for byte in persistence_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    name
+}
+
+fn frame_header_len() -> usize {
+    // sequence number + serialiser id
+    8 + SerId::default().size()
+}
+
+fn encode_frame(entry: &JournalEntry) -> Result<Vec<u8>, JournalError> {
+    let frame_len: u32 = (frame_header_len() + entry.data.len())
+        .try_into()
+        .map_err(|_| {
+            JournalError::Corrupted(format!("Entry {} is too large", entry.sequence_nr))
+        })?;
+    let mut frame = Vec::with_capacity(4 + frame_len as usize);
+    frame.put_u32(frame_len);
+    frame.put_u64(entry.sequence_nr);
+    frame.put_ser_id(entry.ser_id);
+    frame.put_slice(&entry.data);
+    Ok(frame)
+}
+
+/// Decode all complete frames in `data`
+///
+/// Returns the entries and the number of bytes they occupy.
+/// A trailing incomplete frame is ignored, as it is the result of an interrupted append.
+fn decode_frames(data: &[u8]) -> Result<(Vec<JournalEntry>, usize), JournalError> {
+    let mut entries = Vec::new();
+    let mut buf = data;
+    while buf.remaining() >= 4 {
+        let frame_len = (&buf[..4]).get_u32() as usize;
+        if buf.remaining() < 4 + frame_len {
+            break;
+        }
+        if frame_len < frame_header_len() {
+            return Err(JournalError::Corrupted(format!(
+                "Frame at offset {} is too short",
+                data.len() - buf.remaining()
+            )));
+        }
+        buf.advance(4);
+        let sequence_nr = buf.get_u64();
+        let ser_id = buf.get_ser_id();
+        let data_len = frame_len - frame_header_len();
+        entries.push(JournalEntry {
+            sequence_nr,
+            ser_id,
+            data: buf[..data_len].to_vec(),
+        });
+        buf.advance(data_len);
+    }
+    Ok((entries, data.len() - buf.remaining()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_millis(5000);
+
+    fn entry(sequence_nr: u64, value: u64) -> JournalEntry {
+        JournalEntry::serialise(sequence_nr, &value).expect("entry")
+    }
+
+    fn wait<T: Send>(future: JournalFuture<T>) -> Result<T, JournalError> {
+        future.wait_timeout(TIMEOUT).expect("journal reply")
+    }
+
+    #[test]
+    fn file_journal_appends_and_replays() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let id = "counters/a";
+        {
+            let journal = FileJournal::open(dir.path()).expect("journal");
+            for i in 1..=5u64 {
+                wait(journal.append(id, entry(i, i * 10))).expect("append");
+            }
+            assert!(matches!(
+                wait(journal.append(id, entry(3, 0))),
+                Err(JournalError::SequenceMismatch {
+                    expected: 6,
+                    actual: 3
+                })
+            ));
+            let events = wait(journal.replay(id, 4)).expect("replay");
+            assert_eq!(events, vec![entry(4, 40), entry(5, 50)]);
+            assert!(wait(journal.replay("counters/b", 1))
+                .expect("replay")
+                .is_empty());
+
+            assert_eq!(wait(journal.load_snapshot(id)).expect("snapshot"), None);
+            wait(journal.save_snapshot(id, entry(2, 30))).expect("save");
+            wait(journal.save_snapshot(id, entry(4, 100))).expect("save");
+        }
+        // reopen the journal and continue where it left off
+        let journal = FileJournal::open(dir.path()).expect("journal");
+        assert_eq!(
+            wait(journal.load_snapshot(id)).expect("snapshot"),
+            Some(entry(4, 100))
+        );
+        wait(journal.append(id, entry(6, 60))).expect("append");
+        let events = wait(journal.replay(id, 1)).expect("replay");
+        let numbers: Vec<u64> = events.iter().map(|e| e.sequence_nr).collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn file_journal_discards_incomplete_frames() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let id = "torn";
+        let path = {
+            let journal = FileJournal::open(dir.path()).expect("journal");
+            wait(journal.append(id, entry(1, 1))).expect("append");
+            wait(journal.append(id, entry(2, 2))).expect("append");
+            journal
+                .directory()
+                .join(format!("{}.{}", id, JOURNAL_EXTENSION))
+        };
+        // simulate a crash in the middle of the third append
+        let frame = encode_frame(&entry(3, 3)).expect("frame");
+        let mut file = OpenOptions::new().append(true).open(&path).expect("file");
+        file.write_all(&frame[..frame.len() - 3]).expect("write");
+        drop(file);
+
+        let journal = FileJournal::open(dir.path()).expect("journal");
+        assert_eq!(
+            wait(journal.replay(id, 1)).expect("replay"),
+            vec![entry(1, 1), entry(2, 2)]
+        );
+        wait(journal.append(id, entry(3, 4))).expect("append");
+        assert_eq!(
+            wait(journal.replay(id, 3)).expect("replay"),
+            vec![entry(3, 4)]
+        );
+    }
+
+    #[test]
+    fn file_names_are_escaped() {
+        assert_eq!(encode_file_name("a-b_C9"), "a-b_C9");
+        assert_eq!(encode_file_name("a/b.c"), "a%2Fb%2Ec");
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    serialisation::{Deserialiser, SerError, SerId, Serialisable},
+    utils::KFuture,
+};
+use std::{error, fmt, io};
+
+/// A single serialised record in a [Journal]
+///
+/// Entries are used both for events, and for snapshots of a component's state.
+/// For snapshots, the `sequence_nr` is the sequence number of the last event
+/// that is reflected in the snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The sequence number of the entry, starting at 1 for the first event
+    pub sequence_nr: u64,
+    /// The id of the serialiser that produced `data`
+    pub ser_id: SerId,
+    /// The serialised event or snapshot
+    pub data: Vec<u8>,
+}
+
+impl JournalEntry {
+    /// Serialise `value` into a new entry with the given `sequence_nr`
+    pub fn serialise(sequence_nr: u64, value: &dyn Serialisable) -> Result<Self, SerError> {
+        let mut data = Vec::with_capacity(value.size_hint().unwrap_or(0));
+        value.serialise(&mut data)?;
+        Ok(JournalEntry {
+            sequence_nr,
+            ser_id: value.ser_id(),
+            data,
+        })
+    }
+
+    /// Deserialise the content of this entry with the deserialiser `D`
+    ///
+    /// Fails with [InvalidType](SerError::InvalidType), if the entry was produced
+    /// by a different serialiser.
+    pub fn deserialise<T, D>(&self) -> Result<T, SerError>
+    where
+        D: Deserialiser<T>,
+    {
+        if self.ser_id == D::SER_ID {
+            D::deserialise(&mut self.data.as_slice())
+        } else {
+            Err(SerError::InvalidType(format!(
+                "Journal entry {} has serialiser id {}, but {} was expected",
+                self.sequence_nr,
+                self.ser_id,
+                D::SER_ID
+            )))
+        }
+    }
+}
+
+/// Errors produced by a [Journal], or while persisting to one
+#[derive(Debug)]
+pub enum JournalError {
+    /// Reading from or writing to the underlying storage failed
+    Io(io::Error),
+    /// An event or snapshot could not be (de-)serialised
+    Serialisation(SerError),
+    /// An entry was appended out of order
+    ///
+    /// This usually means that two components are writing with the same persistence id.
+    SequenceMismatch {
+        /// The sequence number the journal expected next
+        expected: u64,
+        /// The sequence number of the rejected entry
+        actual: u64,
+    },
+    /// The stored data is not a valid journal
+    Corrupted(String),
+    /// The journal was shut down before the request completed
+    Closed,
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Io(e) => write!(f, "Journal I/O error: {}", e),
+            JournalError::Serialisation(e) => write!(f, "Journal serialisation error: {}", e),
+            JournalError::SequenceMismatch { expected, actual } => write!(
+                f,
+                "Journal expected sequence number {}, but got {}",
+                expected, actual
+            ),
+            JournalError::Corrupted(msg) => write!(f, "Journal is corrupted: {}", msg),
+            JournalError::Closed => write!(f, "Journal was closed"),
+        }
+    }
+}
+
+impl error::Error for JournalError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            JournalError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        JournalError::Io(e)
+    }
+}
+
+impl From<SerError> for JournalError {
+    fn from(e: SerError) -> Self {
+        JournalError::Serialisation(e)
+    }
+}
+
+/// The result of a journal request, which completes once the request has been processed
+pub type JournalFuture<T> = KFuture<Result<T, JournalError>>;
+
+/// A durable store for the events and snapshots of [event-sourced](super::EventSourced) components
+///
+/// Entries are grouped by *persistence id*, which identifies the logical component
+/// across restarts. A journal must preserve the order of appends per persistence id,
+/// and only complete an append once the entry is durable.
+///
+/// All requests are asynchronous. Implementations should do any blocking work
+/// off the calling thread, as it is usually a component's scheduler thread.
+pub trait Journal: Send + Sync + 'static {
+    /// Append `entry` to the events of `persistence_id`
+    ///
+    /// The `sequence_nr` of the entry must directly follow the last appended one.
+    fn append(&self, persistence_id: &str, entry: JournalEntry) -> JournalFuture<()>;
+
+    /// Read all events of `persistence_id` with a sequence number of at least `from_sequence_nr`
+    fn replay(
+        &self,
+        persistence_id: &str,
+        from_sequence_nr: u64,
+    ) -> JournalFuture<Vec<JournalEntry>>;
+
+    /// Store `snapshot` as the latest snapshot of `persistence_id`, replacing any previous one
+    fn save_snapshot(&self, persistence_id: &str, snapshot: JournalEntry) -> JournalFuture<()>;
+
+    /// Load the latest snapshot of `persistence_id`, if any
+    fn load_snapshot(&self, persistence_id: &str) -> JournalFuture<Option<JournalEntry>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_entry_roundtrip() {
+        let entry = JournalEntry::serialise(7, &42u64).expect("entry");
+        assert_eq!(entry.sequence_nr, 7);
+        assert_eq!(entry.deserialise::<u64, u64>().expect("deserialise"), 42u64);
+        assert!(matches!(
+            entry.deserialise::<String, String>(),
+            Err(SerError::InvalidType(_))
+        ));
+    }
+}
@@ -0,0 +1,555 @@
+//! Event-sourced persistence for components
+//!
+//! An [EventSourced] component does not store its state directly. Instead, it
+//! [persists](EventSourced::persist) every change as an event in a [Journal],
+//! and [applies](EventSourced::apply_event) the event to its state once it is durable.
+//! When the component is started again, possibly after a crash or in a new system,
+//! it [recovers](EventSourced::recover) its state by replaying all events in order.
+//!
+//! To keep replay short for long-lived components, the state can additionally be
+//! saved as a snapshot every couple of events (see [Persistence::with_snapshot_every]).
+//! Recovery then starts from the latest snapshot and only replays the events after it.
+//!
+//! Events and snapshots are stored in serialised form, using the same
+//! [Serialisable] and [Deserialiser] implementations as network messages.
+//! The default [FileJournal] keeps an append-only file per persistence id in a local directory.
+
+use crate::{
+    component::ComponentDefinition,
+    prelude::Handled,
+    serialisation::{Deserialiser, SerError, Serialisable},
+};
+#[allow(unused_imports)]
+use slog::{crit, debug, error, info, trace, warn};
+use std::{fmt, sync::Arc};
+
+mod file_journal;
+mod journal;
+
+pub use file_journal::FileJournal;
+pub use journal::{Journal, JournalEntry, JournalError, JournalFuture};
+
+/// The persistence state of an [EventSourced] component
+///
+/// Keep this as a field of the component, and provide access to it via
+/// [persistence](EventSourced::persistence) and [persistence_mut](EventSourced::persistence_mut).
+#[derive(Clone)]
+pub struct Persistence {
+    persistence_id: String,
+    journal: Arc<dyn Journal>,
+    sequence_nr: u64,
+    snapshot_every: Option<u64>,
+}
+
+impl Persistence {
+    /// Create the persistence state for the component identified by `persistence_id`
+    ///
+    /// The `persistence_id` must be stable across restarts, and must not be used by any
+    /// other component writing to the same `journal` at the same time.
+    pub fn new(persistence_id: impl Into<String>, journal: Arc<dyn Journal>) -> Self {
+        Persistence {
+            persistence_id: persistence_id.into(),
+            journal,
+            sequence_nr: 0,
+            snapshot_every: None,
+        }
+    }
+
+    /// Save a snapshot of the component's state after every `events` persisted events
+    ///
+    /// # Panics
+    ///
+    /// Panics if `events` is 0.
+    pub fn with_snapshot_every(mut self, events: u64) -> Self {
+        assert!(events > 0, "Snapshot interval must be at least one event");
+        self.snapshot_every = Some(events);
+        self
+    }
+
+    /// The id that identifies the component's events in the journal
+    pub fn persistence_id(&self) -> &str {
+        &self.persistence_id
+    }
+
+    /// The journal events are persisted to
+    pub fn journal(&self) -> &Arc<dyn Journal> {
+        &self.journal
+    }
+
+    /// The sequence number of the last event that was applied to the component's state
+    ///
+    /// This is 0, if no event has been applied, yet.
+    pub fn sequence_nr(&self) -> u64 {
+        self.sequence_nr
+    }
+
+    fn snapshot_due(&self) -> bool {
+        self.snapshot_every
+            .map(|events| self.sequence_nr % events == 0)
+            .unwrap_or(false)
+    }
+}
+
+impl fmt::Debug for Persistence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Persistence")
+            .field("persistence_id", &self.persistence_id)
+            .field("sequence_nr", &self.sequence_nr)
+            .field("snapshot_every", &self.snapshot_every)
+            .finish()
+    }
+}
+
+/// A component that persists its state as a sequence of events
+///
+/// Call [recover](EventSourced::recover) from `on_start` to rebuild the state from the journal,
+/// and [persist](EventSourced::persist) an event whenever the state should change.
+/// Both block the component until the journal has completed the request,
+/// so no message is handled against a state that isn't durable, yet.
+///
+/// # Example
+///
+/// ```
+/// use kompact::{prelude::*, persistence::*};
+/// use std::sync::Arc;
+///
+/// #[derive(ComponentDefinition)]
+/// struct Counter {
+///     ctx: ComponentContext<Self>,
+///     persistence: Persistence,
+///     count: u64,
+/// }
+/// impl Counter {
+///     fn new(journal: Arc<dyn Journal>) -> Self {
+///         Counter {
+///             ctx: ComponentContext::uninitialised(),
+///             persistence: Persistence::new("counter", journal),
+///             count: 0,
+///         }
+///     }
+/// }
+/// impl ComponentLifecycle for Counter {
+///     fn on_start(&mut self) -> Handled {
+///         self.recover()
+///     }
+/// }
+/// impl EventSourced for Counter {
+///     type Event = u64;
+///
+///     fn persistence(&self) -> &Persistence {
+///         &self.persistence
+///     }
+///
+///     fn persistence_mut(&mut self) -> &mut Persistence {
+///         &mut self.persistence
+///     }
+///
+///     fn apply_event(&mut self, event: Self::Event) {
+///         self.count += event;
+///     }
+/// }
+/// impl Actor for Counter {
+///     type Message = Ask<u64, u64>;
+///
+///     fn receive_local(&mut self, msg: Self::Message) -> Handled {
+///         let (promise, add) = msg.take();
+///         // only reply once the event is durable
+///         self.persist(add, move |counter, result| {
+///             result.expect("persisted");
+///             promise.fulfil(counter.count).expect("reply");
+///         })
+///     }
+/// #    fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+/// #        unimplemented!("We don't care about this.");
+/// #    }
+/// }
+///
+/// # let dir = tempfile::tempdir().expect("temp dir");
+/// let journal: Arc<dyn Journal> = Arc::new(FileJournal::open(dir.path()).expect("journal"));
+/// # let system = KompactConfig::default().build().expect("system");
+/// let counter = system.create(|| Counter::new(journal.clone()));
+/// system.start(&counter);
+/// let count = counter.actor_ref().ask(5u64).wait();
+/// assert_eq!(count, 5);
+/// # system.shutdown().expect("shutdown");
+/// ```
+pub trait EventSourced: ComponentDefinition + Sized + 'static {
+    /// The events that change the component's state
+    type Event: Serialisable + Deserialiser<Self::Event> + Send + 'static;
+
+    /// The persistence state of this component
+    fn persistence(&self) -> &Persistence;
+
+    /// Mutable access to the persistence state of this component
+    fn persistence_mut(&mut self) -> &mut Persistence;
+
+    /// Update the component's state with `event`
+    ///
+    /// This is called for every event during recovery, and for every newly persisted event.
+    /// It must not have any side effects apart from changing the state.
+    fn apply_event(&mut self, event: Self::Event);
+
+    /// Capture the current state as a snapshot, to shorten future recoveries
+    ///
+    /// The default implementation returns `None`, i.e. never takes snapshots.
+    fn snapshot_state(&self) -> Option<Box<dyn Serialisable>> {
+        None
+    }
+
+    /// Replace the component's state with the content of `snapshot`
+    ///
+    /// Use [JournalEntry::deserialise] to access the state captured by
+    /// [snapshot_state](EventSourced::snapshot_state).
+    /// If this fails, the snapshot is ignored and all events are replayed instead.
+    fn apply_snapshot(&mut self, snapshot: &JournalEntry) -> Result<(), SerError> {
+        Err(SerError::InvalidType(format!(
+            "Component does not take snapshots, but one was found at sequence number {}",
+            snapshot.sequence_nr
+        )))
+    }
+
+    /// Handle a failure to recover the component's state
+    ///
+    /// The default implementation panics, which faults the component
+    /// and leaves the decision on how to proceed to its supervisor.
+    fn on_recovery_failure(&mut self, error: JournalError) {
+        error!(
+            self.log(),
+            "Could not recover {}: {}",
+            self.persistence().persistence_id(),
+            error
+        );
+        panic!(
+            "Recovery of {} failed: {}",
+            self.persistence().persistence_id(),
+            error
+        );
+    }
+
+    /// Rebuild the component's state from the latest snapshot and all events after it
+    ///
+    /// This is usually called from `on_start`, and blocks the component until replay is complete.
+    fn recover(&mut self) -> Handled {
+        let persistence_id = self.persistence().persistence_id().to_string();
+        let journal = self.persistence().journal().clone();
+        Handled::block_on(self, move |mut async_self| async move {
+            let snapshot = flatten(journal.load_snapshot(&persistence_id).await);
+            let mut sequence_nr = 0;
+            match snapshot {
+                Ok(Some(snapshot)) => match async_self.apply_snapshot(&snapshot) {
+                    Ok(()) => sequence_nr = snapshot.sequence_nr,
+                    Err(e) => warn!(
+                        async_self.log(),
+                        "Ignoring snapshot of {}: {}", persistence_id, e
+                    ),
+                },
+                Ok(None) => (),
+                Err(e) => warn!(
+                    async_self.log(),
+                    "Could not load snapshot of {}: {}", persistence_id, e
+                ),
+            }
+            let events = flatten(journal.replay(&persistence_id, sequence_nr + 1).await);
+            let replayed = events.and_then(|events| {
+                let count = events.len();
+                for entry in events {
+                    let event = entry.deserialise::<Self::Event, Self::Event>()?;
+                    async_self.apply_event(event);
+                    sequence_nr = entry.sequence_nr;
+                }
+                Ok(count)
+            });
+            async_self.persistence_mut().sequence_nr = sequence_nr;
+            match replayed {
+                Ok(count) => debug!(
+                    async_self.log(),
+                    "Recovered {} at sequence number {} after replaying {} events",
+                    persistence_id,
+                    sequence_nr,
+                    count
+                ),
+                Err(e) => async_self.on_recovery_failure(e),
+            }
+        })
+    }
+
+    /// Persist `event`, apply it, and then call `and_then` with the event's sequence number
+    ///
+    /// The component is blocked until the journal has completed the write.
+    /// If the write fails, the event is not applied and `and_then` is called with the error instead.
+    ///
+    /// Replies to the sender of the message that caused the event should be sent from `and_then`,
+    /// so they are only sent once the event is durable.
+    fn persist<F>(&mut self, event: Self::Event, and_then: F) -> Handled
+    where
+        F: FnOnce(&mut Self, Result<u64, JournalError>) + Send + 'static,
+    {
+        let sequence_nr = self.persistence().sequence_nr() + 1;
+        let entry = match JournalEntry::serialise(sequence_nr, &event) {
+            Ok(entry) => entry,
+            Err(e) => {
+                and_then(self, Err(e.into()));
+                return Handled::Ok;
+            }
+        };
+        let persistence = self.persistence();
+        let written = persistence
+            .journal()
+            .append(persistence.persistence_id(), entry);
+        Handled::block_on(self, move |mut async_self| async move {
+            match flatten(written.await) {
+                Ok(()) => {
+                    async_self.persistence_mut().sequence_nr = sequence_nr;
+                    async_self.apply_event(event);
+                    if async_self.persistence().snapshot_due() {
+                        async_self.save_snapshot();
+                    }
+                    and_then(&mut *async_self, Ok(sequence_nr));
+                }
+                Err(e) => and_then(&mut *async_self, Err(e)),
+            }
+        })
+    }
+
+    /// Save a snapshot of the current state in the background
+    ///
+    /// Does nothing if [snapshot_state](EventSourced::snapshot_state) returns `None`.
+    /// Failures to save the snapshot are logged, but otherwise ignored,
+    /// as the events it was taken from are still in the journal.
+    fn save_snapshot(&self) {
+        let persistence = self.persistence();
+        let snapshot = match self.snapshot_state() {
+            Some(state) => JournalEntry::serialise(persistence.sequence_nr(), state.as_ref()),
+            None => return,
+        };
+        let logger = self.log().clone();
+        let persistence_id = persistence.persistence_id().to_string();
+        match snapshot {
+            Ok(snapshot) => {
+                let saved = persistence
+                    .journal()
+                    .save_snapshot(&persistence_id, snapshot);
+                // the result is only logged, so the handle isn't needed
+                let handle = self.spawn_off(async move {
+                    if let Err(e) = flatten(saved.await) {
+                        warn!(
+                            logger,
+                            "Could not save snapshot of {}: {}", persistence_id, e
+                        );
+                    }
+                });
+                drop(handle);
+            }
+            Err(e) => warn!(
+                logger,
+                "Could not serialise snapshot of {}: {}", persistence_id, e
+            ),
+        }
+    }
+}
+
+/// Treat a dropped reply like a closed journal
+fn flatten<T, E>(reply: Result<Result<T, JournalError>, E>) -> Result<T, JournalError> {
+    reply.unwrap_or(Err(JournalError::Closed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_millis(5000);
+
+    #[derive(Debug)]
+    enum LedgerMsg {
+        Add(Ask<u64, u64>),
+        Snapshot(Ask<(), ()>),
+        Status(Ask<(), (u64, u64, usize)>),
+    }
+
+    #[derive(ComponentDefinition)]
+    struct Ledger {
+        ctx: ComponentContext<Self>,
+        persistence: Persistence,
+        balance: u64,
+        applied: usize,
+    }
+    impl Ledger {
+        fn new(persistence: Persistence) -> Self {
+            Ledger {
+                ctx: ComponentContext::uninitialised(),
+                persistence,
+                balance: 0,
+                applied: 0,
+            }
+        }
+    }
+    impl ComponentLifecycle for Ledger {
+        fn on_start(&mut self) -> Handled {
+            self.recover()
+        }
+    }
+    impl EventSourced for Ledger {
+        type Event = u64;
+
+        fn persistence(&self) -> &Persistence {
+            &self.persistence
+        }
+
+        fn persistence_mut(&mut self) -> &mut Persistence {
+            &mut self.persistence
+        }
+
+        fn apply_event(&mut self, event: Self::Event) {
+            self.balance += event;
+            self.applied += 1;
+        }
+
+        fn snapshot_state(&self) -> Option<Box<dyn Serialisable>> {
+            Some(Box::new(self.balance))
+        }
+
+        fn apply_snapshot(&mut self, snapshot: &JournalEntry) -> Result<(), SerError> {
+            self.balance = snapshot.deserialise::<u64, u64>()?;
+            Ok(())
+        }
+    }
+    impl Actor for Ledger {
+        type Message = LedgerMsg;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            match msg {
+                LedgerMsg::Add(ask) => {
+                    let (promise, amount) = ask.take();
+                    self.persist(amount, move |ledger, result| {
+                        result.expect("persisted");
+                        promise.fulfil(ledger.balance).expect("reply");
+                    })
+                }
+                LedgerMsg::Snapshot(ask) => {
+                    self.save_snapshot();
+                    ask.reply(()).expect("reply");
+                    Handled::Ok
+                }
+                LedgerMsg::Status(ask) => {
+                    let status = (self.balance, self.persistence.sequence_nr(), self.applied);
+                    ask.reply(status).expect("reply");
+                    Handled::Ok
+                }
+            }
+        }
+
+        fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+            unimplemented!("Only used locally");
+        }
+    }
+
+    fn start_ledger(system: &KompactSystem, persistence: Persistence) -> Arc<Component<Ledger>> {
+        let ledger = system.create(move || Ledger::new(persistence));
+        system
+            .start_notify(&ledger)
+            .wait_timeout(TIMEOUT)
+            .expect("ledger never started");
+        ledger
+    }
+
+    fn add(ledger: &Arc<Component<Ledger>>, amount: u64) -> u64 {
+        ledger
+            .actor_ref()
+            .ask_with(|promise| LedgerMsg::Add(Ask::new(promise, amount)))
+            .wait_timeout(TIMEOUT)
+            .expect("balance")
+    }
+
+    fn status(ledger: &Arc<Component<Ledger>>) -> (u64, u64, usize) {
+        ledger
+            .actor_ref()
+            .ask_with(|promise| LedgerMsg::Status(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("status")
+    }
+
+    fn kill(system: &KompactSystem, ledger: Arc<Component<Ledger>>) {
+        system
+            .kill_notify(ledger)
+            .wait_timeout(TIMEOUT)
+            .expect("ledger never died");
+    }
+
+    #[test]
+    fn event_sourced_component_recovers_from_events() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let journal: Arc<dyn Journal> = Arc::new(FileJournal::open(dir.path()).expect("journal"));
+        let system = KompactConfig::default().build().expect("system");
+
+        let ledger = start_ledger(&system, Persistence::new("ledger", journal.clone()));
+        assert_eq!(add(&ledger, 5), 5);
+        assert_eq!(add(&ledger, 7), 12);
+        ledger
+            .actor_ref()
+            .ask_with(|promise| LedgerMsg::Snapshot(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("snapshot");
+        kill(&system, ledger);
+
+        // snapshots don't remove any events from the journal
+        let journal_entries = journal
+            .replay("ledger", 1)
+            .wait_timeout(TIMEOUT)
+            .expect("reply")
+            .expect("replay");
+        assert_eq!(journal_entries.len(), 2);
+
+        let ledger = start_ledger(&system, Persistence::new("ledger", journal.clone()));
+        // recovery started from the snapshot, so no events needed replaying
+        assert_eq!(status(&ledger), (12, 2, 0));
+        assert_eq!(add(&ledger, 1), 13);
+        assert_eq!(status(&ledger), (13, 3, 1));
+        kill(&system, ledger);
+
+        // another component's events are kept separately
+        let other = start_ledger(&system, Persistence::new("other", journal));
+        assert_eq!(status(&other), (0, 0, 0));
+
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn event_sourced_component_takes_periodic_snapshots() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let journal: Arc<dyn Journal> = Arc::new(FileJournal::open(dir.path()).expect("journal"));
+        let system = KompactConfig::default().build().expect("system");
+        let persistence = || Persistence::new("ledger", journal.clone()).with_snapshot_every(3);
+
+        let ledger = start_ledger(&system, persistence());
+        for _ in 0..7 {
+            add(&ledger, 2);
+        }
+        assert_eq!(status(&ledger), (14, 7, 7));
+        kill(&system, ledger);
+
+        // snapshots are saved in the background, so wait for the second one
+        let mut snapshot = None;
+        for _ in 0..50 {
+            snapshot = journal
+                .load_snapshot("ledger")
+                .wait_timeout(TIMEOUT)
+                .expect("reply")
+                .expect("snapshot");
+            if snapshot.as_ref().map(|s| s.sequence_nr) == Some(6) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let snapshot = snapshot.expect("snapshot");
+        assert_eq!(snapshot.sequence_nr, 6);
+        assert_eq!(snapshot.deserialise::<u64, u64>().expect("state"), 12);
+
+        let ledger = start_ledger(&system, persistence());
+        // only the event after the snapshot is replayed
+        assert_eq!(status(&ledger), (14, 7, 1));
+
+        system.shutdown().expect("shutdown");
+    }
+}
@@ -1,11 +1,20 @@
 use std::{
     error::Error,
     fmt::{self, Debug},
-    sync::{Arc, Weak},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+        Weak,
+    },
+    time::Duration,
 };
 use uuid::Uuid;
 
 use super::*;
+use crate::{
+    actors::{MailboxConfig, OverflowStrategy},
+    timer::{self, timer_manager::TimerRefFactory},
+};
 
 /// A Kompact port specifies the API of an abstraction
 ///
@@ -89,6 +98,112 @@ impl<P: Port + 'static> CommonPortData<P> {
     }
 }
 
+/// The queue of events on a port, waiting to be handled by the port's owner
+///
+/// Port queues are bounded like the [mailbox](MailboxConfig) of their owner,
+/// that is each port queue holds at most as many events as the mailbox holds messages,
+/// and handles any further events according to the mailbox's [OverflowStrategy].
+/// As port events are neither network messages nor sent with `try_tell`,
+/// both [Deadletters](OverflowStrategy::Deadletters) and [Fail](OverflowStrategy::Fail)
+/// drop them with a log message.
+struct PortQueue<E> {
+    events: ConcurrentQueue<E>,
+    config: MailboxConfig,
+    // slots taken in a bounded queue, including events that are about to be pushed
+    reserved: AtomicUsize,
+}
+
+impl<E: Debug> PortQueue<E> {
+    fn with_config(config: MailboxConfig) -> PortQueue<E> {
+        PortQueue {
+            events: ConcurrentQueue::new(),
+            config,
+            reserved: AtomicUsize::new(0),
+        }
+    }
+
+    fn pop(&self) -> Option<E> {
+        let res = self.events.pop().ok();
+        if res.is_some() && self.config.capacity().is_some() {
+            self.reserved.fetch_sub(1, Ordering::AcqRel);
+        }
+        res
+    }
+
+    /// Try to take a slot for a new event, which must be filled by pushing it
+    fn try_reserve(&self) -> bool {
+        match self.config.capacity() {
+            Some(capacity) => self
+                .reserved
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |taken| {
+                    if taken < capacity {
+                        Some(taken + 1)
+                    } else {
+                        None
+                    }
+                })
+                .is_ok(),
+            None => true,
+        }
+    }
+
+    /// Add `event` to the queue of `component` and schedule it, if necessary
+    ///
+    /// If the queue is full, `event` is handled according to the queue's [OverflowStrategy].
+    fn enqueue(&self, component: &Arc<dyn CoreContainer>, event: E) {
+        loop {
+            if self.try_reserve() {
+                // must count the work before pushing, to maintain counting guarantees
+                let sd = component.core().increment_work();
+                self.events.push(event);
+                if let SchedulingDecision::Schedule = sd {
+                    let system = component.core().system();
+                    system.schedule(component.clone());
+                }
+                return;
+            }
+            match self.config.overflow_strategy() {
+                OverflowStrategy::DropOldest => match self.events.pop() {
+                    Ok(oldest) => {
+                        // the new event takes the place of the oldest, so the work is unchanged
+                        self.events.push(event);
+                        Self::log_dropped(component.as_ref(), &oldest);
+                        return;
+                    }
+                    Err(_) => continue, // the queue was emptied concurrently, so there is room again
+                },
+                OverflowStrategy::DropNew
+                | OverflowStrategy::Deadletters
+                | OverflowStrategy::Fail => {
+                    Self::log_dropped(component.as_ref(), &event);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn log_dropped(component: &dyn CoreContainer, event: &E) {
+        debug!(
+            component.system().logger(),
+            "Dropping port event, as the port queue of component {} is full: {:?}",
+            component.id(),
+            event
+        );
+    }
+}
+
+/// Replace the queue of a port with one bounded like the mailbox of `parent`
+///
+/// The queue is only replaced while it hasn't been shared yet,
+/// so port references handed out before never lose their target.
+fn bind_port_queue<E: Debug>(queue: &mut Arc<PortQueue<E>>, parent: &dyn CoreContainer) {
+    if let Some(queue) = Arc::get_mut(queue) {
+        if queue.events.is_empty() {
+            *queue = PortQueue::with_config(parent.dyn_message_queue().config());
+        }
+    }
+}
+
 /// An instance of a port type `P` that is marked as provided
 ///
 /// Any component `C` that holds an instance of a provided port for `P`
@@ -129,7 +244,7 @@ impl<P: Port + 'static> CommonPortData<P> {
 pub struct ProvidedPort<P: Port + 'static> {
     common: CommonPortData<P>,
     parent: Option<Weak<dyn CoreContainer>>,
-    msg_queue: Arc<ConcurrentQueue<P::Request>>,
+    msg_queue: Arc<PortQueue<P::Request>>,
 }
 
 impl<P: Port + 'static> ProvidedPort<P> {
@@ -143,7 +258,7 @@ impl<P: Port + 'static> ProvidedPort<P> {
         ProvidedPort {
             common: CommonPortData::new(),
             parent: None,
-            msg_queue: Arc::new(ConcurrentQueue::new()),
+            msg_queue: Arc::new(PortQueue::with_config(MailboxConfig::unbounded())),
         }
     }
 
@@ -204,6 +319,8 @@ impl<P: Port + 'static> ProvidedPort<P> {
                 ProvidedRef {
                     msg_queue: Arc::downgrade(&self.msg_queue),
                     component: core_container,
+                    interceptors: InterceptorChain::new(),
+                    connection: Arc::new(()),
                 }
             }
             None => panic!("Port is not properly initialized!"),
@@ -213,7 +330,10 @@ impl<P: Port + 'static> ProvidedPort<P> {
     /// Mark `p` as the parent component of this port
     ///
     /// This method should only be used in custom [ComponentDefinition](ComponentDefinition) implementations!
+    ///
+    /// The port's queue is bounded like the mailbox of `p`.
     pub fn set_parent(&mut self, p: Arc<dyn CoreContainer>) -> () {
+        bind_port_queue(&mut self.msg_queue, p.as_ref());
         self.parent = Some(Arc::downgrade(&p));
     }
 
@@ -221,7 +341,7 @@ impl<P: Port + 'static> ProvidedPort<P> {
     ///
     /// This method should only be used in custom [ComponentDefinition](ComponentDefinition) implementations!
     pub fn dequeue(&self) -> Option<P::Request> {
-        self.msg_queue.pop().ok()
+        self.msg_queue.pop()
     }
 }
 
@@ -265,7 +385,7 @@ impl<P: Port + 'static> ProvidedPort<P> {
 pub struct RequiredPort<P: Port + 'static> {
     common: CommonPortData<P>,
     parent: Option<Weak<dyn CoreContainer>>,
-    msg_queue: Arc<ConcurrentQueue<P::Indication>>,
+    msg_queue: Arc<PortQueue<P::Indication>>,
 }
 
 impl<P: Port + 'static> RequiredPort<P> {
@@ -279,7 +399,7 @@ impl<P: Port + 'static> RequiredPort<P> {
         RequiredPort {
             common: CommonPortData::new(),
             parent: None,
-            msg_queue: Arc::new(ConcurrentQueue::new()),
+            msg_queue: Arc::new(PortQueue::with_config(MailboxConfig::unbounded())),
         }
     }
 
@@ -340,6 +460,8 @@ impl<P: Port + 'static> RequiredPort<P> {
                 RequiredRef {
                     msg_queue: Arc::downgrade(&self.msg_queue),
                     component: core_container,
+                    interceptors: InterceptorChain::new(),
+                    connection: Arc::new(()),
                 }
             }
             None => panic!("Port is not properly initialized!"),
@@ -349,7 +471,10 @@ impl<P: Port + 'static> RequiredPort<P> {
     /// Mark `p` as the parent component of this port
     ///
     /// This method should only be used in custom [ComponentDefinition](ComponentDefinition) implementations!
+    ///
+    /// The port's queue is bounded like the mailbox of `p`.
     pub fn set_parent(&mut self, p: Arc<dyn CoreContainer>) -> () {
+        bind_port_queue(&mut self.msg_queue, p.as_ref());
         self.parent = Some(Arc::downgrade(&p));
     }
 
@@ -357,7 +482,7 @@ impl<P: Port + 'static> RequiredPort<P> {
     ///
     /// This method should only be used in custom [ComponentDefinition](ComponentDefinition) implementations!
     pub fn dequeue(&self) -> Option<P::Indication> {
-        self.msg_queue.pop().ok()
+        self.msg_queue.pop()
     }
 }
 
@@ -366,7 +491,10 @@ impl<P: Port + 'static> RequiredPort<P> {
 /// Can be connected to a [RequiredPort](RequiredPort::connect).
 pub struct ProvidedRef<P: Port + 'static> {
     component: Weak<dyn CoreContainer>,
-    msg_queue: Weak<ConcurrentQueue<P::Request>>,
+    msg_queue: Weak<PortQueue<P::Request>>,
+    interceptors: InterceptorChain<P>,
+    /// Only held by this reference, so events it delayed are dropped once it is disconnected
+    connection: Arc<()>,
 }
 
 impl<P: Port + 'static> Clone for ProvidedRef<P> {
@@ -374,6 +502,9 @@ impl<P: Port + 'static> Clone for ProvidedRef<P> {
         ProvidedRef {
             component: self.component.clone(),
             msg_queue: self.msg_queue.clone(),
+            interceptors: self.interceptors.clone(),
+            // every clone can be connected separately
+            connection: Arc::new(()),
         }
     }
 }
@@ -386,16 +517,45 @@ impl<P: Port + 'static> PartialEq for ProvidedRef<P> {
 }
 
 impl<P: Port + 'static> ProvidedRef<P> {
+    /// Attach an interceptor chain to this reference
+    ///
+    /// Every event sent through the returned reference is passed through `interceptors`
+    /// before it is enqueued at the port's owner.
+    /// Replaces any chain that was previously attached.
+    ///
+    /// Interceptors only apply to connections made with the returned reference,
+    /// other connections to the same port are unaffected.
+    pub fn with_interceptors(mut self, interceptors: InterceptorChain<P>) -> Self {
+        self.interceptors = interceptors;
+        self
+    }
+
     /// Returns `true` if there are deallocated ports
     pub(crate) fn enqueue(&self, event: P::Request) -> bool {
+        match self.interceptors.intercept_request(event) {
+            Intercepted::Forward(e) => self.deliver(e),
+            Intercepted::Drop => !self.is_live(),
+            Intercepted::Delay(delay, e) => match self.component.upgrade() {
+                Some(c) => {
+                    let target = self.clone().with_interceptors(InterceptorChain::new());
+                    let connection = Arc::downgrade(&self.connection);
+                    let mut timer = c.core().system().timer_ref();
+                    timer::run_once(&mut timer, delay, move || {
+                        if connection.strong_count() > 0 {
+                            target.deliver(e);
+                        }
+                    });
+                    false
+                }
+                None => true,
+            },
+        }
+    }
+
+    fn deliver(&self, event: P::Request) -> bool {
         match (self.msg_queue.upgrade(), self.component.upgrade()) {
             (Some(q), Some(c)) => {
-                let sd = c.core().increment_work();
-                q.push(event);
-                if let SchedulingDecision::Schedule = sd {
-                    let system = c.core().system();
-                    system.schedule(c.clone());
-                }
+                q.enqueue(&c, event);
                 false
             }
             (_q, _c) => {
@@ -438,7 +598,10 @@ impl<P: Port + 'static> ProvidedRef<P> {
 /// Can be connected to a [ProvidedPort](ProvidedPort::connect).
 pub struct RequiredRef<P: Port + 'static> {
     component: Weak<dyn CoreContainer>,
-    msg_queue: Weak<ConcurrentQueue<P::Indication>>,
+    msg_queue: Weak<PortQueue<P::Indication>>,
+    interceptors: InterceptorChain<P>,
+    /// Only held by this reference, so events it delayed are dropped once it is disconnected
+    connection: Arc<()>,
 }
 
 impl<P: Port + 'static> Clone for RequiredRef<P> {
@@ -446,6 +609,9 @@ impl<P: Port + 'static> Clone for RequiredRef<P> {
         RequiredRef {
             component: self.component.clone(),
             msg_queue: self.msg_queue.clone(),
+            interceptors: self.interceptors.clone(),
+            // every clone can be connected separately
+            connection: Arc::new(()),
         }
     }
 }
@@ -458,16 +624,45 @@ impl<P: Port + 'static> PartialEq for RequiredRef<P> {
 }
 
 impl<P: Port + 'static> RequiredRef<P> {
+    /// Attach an interceptor chain to this reference
+    ///
+    /// Every event sent through the returned reference is passed through `interceptors`
+    /// before it is enqueued at the port's owner.
+    /// Replaces any chain that was previously attached.
+    ///
+    /// Interceptors only apply to connections made with the returned reference,
+    /// other connections to the same port are unaffected.
+    pub fn with_interceptors(mut self, interceptors: InterceptorChain<P>) -> Self {
+        self.interceptors = interceptors;
+        self
+    }
+
     /// Returns `true` if there are deallocated ports
     pub(crate) fn enqueue(&self, event: P::Indication) -> bool {
+        match self.interceptors.intercept_indication(event) {
+            Intercepted::Forward(e) => self.deliver(e),
+            Intercepted::Drop => !self.is_live(),
+            Intercepted::Delay(delay, e) => match self.component.upgrade() {
+                Some(c) => {
+                    let target = self.clone().with_interceptors(InterceptorChain::new());
+                    let connection = Arc::downgrade(&self.connection);
+                    let mut timer = c.core().system().timer_ref();
+                    timer::run_once(&mut timer, delay, move || {
+                        if connection.strong_count() > 0 {
+                            target.deliver(e);
+                        }
+                    });
+                    false
+                }
+                None => true,
+            },
+        }
+    }
+
+    fn deliver(&self, event: P::Indication) -> bool {
         match (self.msg_queue.upgrade(), self.component.upgrade()) {
             (Some(q), Some(c)) => {
-                let sd = c.core().increment_work();
-                q.push(event);
-                if let SchedulingDecision::Schedule = sd {
-                    let system = c.core().system();
-                    system.schedule(c.clone());
-                }
+                q.enqueue(&c, event);
                 false
             }
             (_q, _c) => {
@@ -505,6 +700,173 @@ impl<P: Port + 'static> RequiredRef<P> {
     }
 }
 
+/// The outcome of passing an event through a [PortInterceptor](PortInterceptor)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Intercepted<E> {
+    /// Pass the (possibly transformed) event on
+    Forward(E),
+    /// Discard the event, so it is never delivered
+    Drop,
+    /// Deliver the (possibly transformed) event only after the given duration
+    ///
+    /// Delayed events may overtake or be overtaken by
+    /// other events on the same connection.
+    /// They are dropped, if the connection is closed before they are due.
+    Delay(Duration, E),
+}
+
+/// Cross-cutting logic that is applied to events on a port connection
+///
+/// Interceptors see every event *before* it is enqueued at the receiving component,
+/// and may inspect, transform, drop, or delay it.
+/// This allows logging, metrics, access checks, or event rewriting
+/// without modifying the handlers of either component.
+///
+/// Both methods forward events unchanged by default,
+/// so implementations only need to override the direction they care about.
+///
+/// Interceptors are run on the thread of the component that triggers the event,
+/// and should thus be cheap and must not block.
+///
+/// # Example
+///
+/// ```
+/// use kompact::prelude::*;
+/// # use kompact::doctest_helpers::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// #[derive(Default)]
+/// struct CountRequests(AtomicUsize);
+/// impl PortInterceptor<TestPort> for CountRequests {
+///     fn intercept_request(&self, event: Never) -> Intercepted<Never> {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///         Intercepted::Forward(event)
+///     }
+/// }
+///
+/// let system = KompactConfig::default().build().expect("system");
+/// let c1 = system.create(TestComponent1::new);
+/// let c2 = system.create(TestComponent2::new);
+/// let chain = InterceptorChain::new().with(CountRequests::default());
+/// biconnect_components_with_interceptors::<TestPort, _, _>(&c1, &c2, chain)
+///     .expect("connection");
+/// # system.shutdown().expect("shutdown");
+/// ```
+pub trait PortInterceptor<P: Port + 'static>: Send + Sync + 'static {
+    /// Intercept a request on its way to the providing component
+    fn intercept_request(&self, event: P::Request) -> Intercepted<P::Request> {
+        Intercepted::Forward(event)
+    }
+
+    /// Intercept an indication on its way to the requiring component
+    fn intercept_indication(&self, event: P::Indication) -> Intercepted<P::Indication> {
+        Intercepted::Forward(event)
+    }
+}
+
+/// An ordered sequence of [interceptors](PortInterceptor) for port type `P`
+///
+/// Events pass through the interceptors in the order they were added,
+/// with each interceptor seeing the output of the previous one.
+/// If any interceptor drops the event, the remaining ones are not invoked.
+/// Delays requested by multiple interceptors add up.
+///
+/// Chains are attached to port references via
+/// [ProvidedRef::with_interceptors](ProvidedRef::with_interceptors) and
+/// [RequiredRef::with_interceptors](RequiredRef::with_interceptors), or when connecting components
+/// with [biconnect_components_with_interceptors](crate::prelude::biconnect_components_with_interceptors).
+pub struct InterceptorChain<P: Port + 'static> {
+    interceptors: Vec<Arc<dyn PortInterceptor<P>>>,
+}
+
+impl<P: Port + 'static> InterceptorChain<P> {
+    /// Create an empty chain, which forwards all events unchanged
+    pub fn new() -> Self {
+        InterceptorChain {
+            interceptors: Vec::new(),
+        }
+    }
+
+    /// Append `interceptor` to the end of this chain
+    pub fn with<I>(self, interceptor: I) -> Self
+    where
+        I: PortInterceptor<P>,
+    {
+        self.with_shared(Arc::new(interceptor))
+    }
+
+    /// Append an interceptor that may be shared with other chains to the end of this chain
+    pub fn with_shared(mut self, interceptor: Arc<dyn PortInterceptor<P>>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// The number of interceptors in this chain
+    pub fn len(&self) -> usize {
+        self.interceptors.len()
+    }
+
+    /// Returns `true` if this chain contains no interceptors
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    /// Pass a request through all interceptors in this chain
+    pub fn intercept_request(&self, event: P::Request) -> Intercepted<P::Request> {
+        self.apply(event, |i, e| i.intercept_request(e))
+    }
+
+    /// Pass an indication through all interceptors in this chain
+    pub fn intercept_indication(&self, event: P::Indication) -> Intercepted<P::Indication> {
+        self.apply(event, |i, e| i.intercept_indication(e))
+    }
+
+    fn apply<E, F>(&self, event: E, f: F) -> Intercepted<E>
+    where
+        F: Fn(&dyn PortInterceptor<P>, E) -> Intercepted<E>,
+    {
+        let mut delay = Duration::ZERO;
+        let mut current = event;
+        for interceptor in self.interceptors.iter() {
+            match f(interceptor.as_ref(), current) {
+                Intercepted::Forward(e) => current = e,
+                Intercepted::Delay(d, e) => {
+                    delay += d;
+                    current = e;
+                }
+                Intercepted::Drop => return Intercepted::Drop,
+            }
+        }
+        if delay.is_zero() {
+            Intercepted::Forward(current)
+        } else {
+            Intercepted::Delay(delay, current)
+        }
+    }
+}
+
+impl<P: Port + 'static> Default for InterceptorChain<P> {
+    fn default() -> Self {
+        InterceptorChain::new()
+    }
+}
+
+impl<P: Port + 'static> Clone for InterceptorChain<P> {
+    fn clone(&self) -> Self {
+        InterceptorChain {
+            interceptors: self.interceptors.clone(),
+        }
+    }
+}
+
+impl<P: Port + 'static> Debug for InterceptorChain<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
 /// Result of checking port ownershipt
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OwnershipResult {
@@ -778,3 +1140,75 @@ impl Channel for Box<(dyn Channel + Send + 'static)> {
         Channel::disconnect_by_ref(self.as_ref())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NumberPort;
+    impl Port for NumberPort {
+        type Indication = u64;
+        type Request = u64;
+    }
+
+    struct AddOne;
+    impl PortInterceptor<NumberPort> for AddOne {
+        fn intercept_request(&self, event: u64) -> Intercepted<u64> {
+            Intercepted::Forward(event + 1)
+        }
+    }
+
+    struct DropOdd;
+    impl PortInterceptor<NumberPort> for DropOdd {
+        fn intercept_request(&self, event: u64) -> Intercepted<u64> {
+            if event % 2 == 1 {
+                Intercepted::Drop
+            } else {
+                Intercepted::Forward(event)
+            }
+        }
+    }
+
+    struct Slow(Duration);
+    impl PortInterceptor<NumberPort> for Slow {
+        fn intercept_indication(&self, event: u64) -> Intercepted<u64> {
+            Intercepted::Delay(self.0, event)
+        }
+    }
+
+    #[test]
+    fn empty_chain_forwards_unchanged() {
+        let chain: InterceptorChain<NumberPort> = InterceptorChain::new();
+        assert!(chain.is_empty());
+        assert_eq!(Intercepted::Forward(5), chain.intercept_request(5));
+        assert_eq!(Intercepted::Forward(5), chain.intercept_indication(5));
+    }
+
+    #[test]
+    fn chain_applies_interceptors_in_order() {
+        let chain = InterceptorChain::new().with(AddOne).with(DropOdd);
+        assert_eq!(2, chain.len());
+        assert_eq!(Intercepted::Forward(2), chain.intercept_request(1));
+        assert_eq!(Intercepted::Drop, chain.intercept_request(2));
+        // indications are untouched by request-only interceptors
+        assert_eq!(Intercepted::Forward(3), chain.intercept_indication(3));
+
+        let reversed = InterceptorChain::new().with(DropOdd).with(AddOne);
+        assert_eq!(Intercepted::Drop, reversed.intercept_request(1));
+        assert_eq!(Intercepted::Forward(3), reversed.intercept_request(2));
+    }
+
+    #[test]
+    fn chain_accumulates_delays() {
+        let slow: Arc<dyn PortInterceptor<NumberPort>> = Arc::new(Slow(Duration::from_millis(10)));
+        let chain = InterceptorChain::new()
+            .with_shared(slow.clone())
+            .with(AddOne)
+            .with_shared(slow);
+        assert_eq!(
+            Intercepted::Delay(Duration::from_millis(20), 7),
+            chain.intercept_indication(7)
+        );
+        assert_eq!(Intercepted::Forward(8), chain.intercept_request(7));
+    }
+}
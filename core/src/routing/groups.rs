@@ -1,8 +1,9 @@
 //! Support for routing groups and policies
 
 use crate::{
-    actors::{ActorPath, DynActorRef},
+    actors::{ActorPath, ActorRef, DynActorRef, MessageBounds, Recipient},
     messaging::NetMessage,
+    serialisation::TryClone,
     utils::IterExtras,
     KompactLogger,
 };
@@ -12,7 +13,7 @@ use std::{
     fmt,
     hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
     ops::Deref,
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
 };
 
 /// The default policy associated with the broadcast marker
@@ -85,6 +86,11 @@ impl<'a> RoutingGroup<'a> {
         RoutingGroup { members, policy }
     }
 
+    /// Returns the current members of this group
+    pub fn members(&self) -> &[&'a DynActorRef] {
+        &self.members
+    }
+
     /// Route `msg` to our members as instructed by our policy
     pub fn route(&self, msg: NetMessage, logger: &KompactLogger) {
         let members: &[&DynActorRef] = &self.members;
@@ -112,6 +118,104 @@ pub trait RoutingPolicy<Ref, M>: fmt::Debug {
     fn select(&self) -> Option<&(dyn RoutingPolicy<Ref, M> + Send + Sync)>;
 }
 
+/// A member of a routing group with a stable identity
+///
+/// Required by policies that must recognise the same member across membership changes,
+/// such as [ConsistentHashRouting].
+pub trait RouteeIdentity {
+    /// Feed a stable identity of this member into `state`
+    fn hash_identity(&self, state: &mut dyn Hasher);
+}
+
+/// A member of a routing group that a [NetMessage] can be delivered to
+///
+/// The built-in policies are implemented for any `Routee`,
+/// so they can route over local actor references as well as over
+/// [remote members](crate::routing::remote::RemoteMember).
+pub trait Routee {
+    /// Deliver `msg` to this member
+    fn deliver(&self, msg: NetMessage);
+
+    /// The number of messages waiting to be processed by this member, if known
+    ///
+    /// Used by load-aware policies, such as [LeastLoadedRouting].
+    fn mailbox_len(&self) -> Option<usize> {
+        None
+    }
+}
+impl Routee for DynActorRef {
+    fn deliver(&self, msg: NetMessage) {
+        self.tell(msg)
+    }
+
+    fn mailbox_len(&self) -> Option<usize> {
+        DynActorRef::mailbox_len(self)
+    }
+}
+
+/// A member of a routing group that messages of any type `M` can be delivered to
+///
+/// This extends [Routee] to typed messages: every `Routee` is a `TypedRoutee<NetMessage>`,
+/// and [ActorRef] and [Recipient] are typed routees for their message type.
+/// The built-in policies are implemented for any `TypedRoutee`, so they can also route
+/// typed messages over local references (see [TypedRouter](crate::routing::typed::TypedRouter)).
+pub trait TypedRoutee<M> {
+    /// Deliver `msg` to this member
+    fn tell(&self, msg: M);
+
+    /// The number of messages waiting to be processed by this member, if known
+    ///
+    /// See [Routee::mailbox_len].
+    fn mailbox_len(&self) -> Option<usize> {
+        None
+    }
+}
+impl<R: Routee> TypedRoutee<NetMessage> for R {
+    fn tell(&self, msg: NetMessage) {
+        self.deliver(msg)
+    }
+
+    fn mailbox_len(&self) -> Option<usize> {
+        Routee::mailbox_len(self)
+    }
+}
+
+impl RouteeIdentity for DynActorRef {
+    fn hash_identity(&self, mut state: &mut dyn Hasher) {
+        self.component_id().hash(&mut state)
+    }
+}
+
+impl<M: MessageBounds> RouteeIdentity for ActorRef<M> {
+    fn hash_identity(&self, mut state: &mut dyn Hasher) {
+        self.component_id().hash(&mut state)
+    }
+}
+impl<M: MessageBounds> TypedRoutee<M> for ActorRef<M> {
+    fn tell(&self, msg: M) {
+        ActorRef::tell(self, msg)
+    }
+
+    fn mailbox_len(&self) -> Option<usize> {
+        ActorRef::mailbox_len(self)
+    }
+}
+
+impl<M: fmt::Debug> RouteeIdentity for Recipient<M> {
+    fn hash_identity(&self, mut state: &mut dyn Hasher) {
+        self.component_id().hash(&mut state)
+    }
+}
+impl<M: fmt::Debug> TypedRoutee<M> for Recipient<M> {
+    fn tell(&self, msg: M) {
+        Recipient::tell(self, msg)
+    }
+
+    fn mailbox_len(&self) -> Option<usize> {
+        Recipient::mailbox_len(self)
+    }
+}
+
 /// Round-robin dispatch policy
 ///
 /// This policy can handle changing membership,
@@ -139,14 +243,14 @@ impl Default for RoundRobinRouting {
         Self::new()
     }
 }
-impl RoutingPolicy<DynActorRef, NetMessage> for RoundRobinRouting {
-    fn route(&self, members: &[&DynActorRef], msg: NetMessage, logger: &KompactLogger) {
+impl<R: TypedRoutee<M>, M> RoutingPolicy<R, M> for RoundRobinRouting {
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
         let index = self.get_and_increment_index(members.len());
         trace!(logger, "Routing msg to member at index={}", index);
         members[index].tell(msg);
     }
 
-    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync> {
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
         // just use the last value for the new router...it doesn't matter if there is some overlap between the two versions
         let offset = self.offset.load(Ordering::Relaxed);
         let routing = RoundRobinRouting {
@@ -155,11 +259,11 @@ impl RoutingPolicy<DynActorRef, NetMessage> for RoundRobinRouting {
         Box::new(routing)
     }
 
-    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         None
     }
 
-    fn select(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         Some(self)
     }
 }
@@ -240,30 +344,320 @@ impl<H: BuildHasher + Default + Clone> Default for SenderHashBucketRouting<H> {
     }
 }
 
-impl<H: BuildHasher + Clone + Send + Sync + 'static> RoutingPolicy<DynActorRef, NetMessage>
-    for SenderHashBucketRouting<H>
+impl<R, M, T, H> RoutingPolicy<R, M> for FieldHashBucketRouting<M, T, H>
+where
+    R: TypedRoutee<M>,
+    M: 'static,
+    T: Hash + 'static,
+    H: BuildHasher + Clone + Send + Sync + 'static,
 {
-    fn route(&self, members: &[&DynActorRef], msg: NetMessage, logger: &KompactLogger) {
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
         let index = self.get_bucket(&msg, members.len());
-        trace!(
-            logger,
-            "Routing msg with sender={} to member at index={}",
-            msg.sender,
-            index
-        );
+        trace!(logger, "Routing msg to member at index={}", index);
         members[index].tell(msg);
     }
 
-    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync> {
-        let cloned: SenderHashBucketRouting<H> = self.clone();
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
+        let cloned: FieldHashBucketRouting<M, T, H> = self.clone();
+        Box::new(cloned)
+    }
+
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        None
+    }
+
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        Some(self)
+    }
+}
+
+/// A router using consistent hashing on some field of the message
+///
+/// Uses rendezvous (highest random weight) hashing: every member is scored
+/// with the combined hash of the message field and the member's
+/// [identity](RouteeIdentity::hash_identity), and the member with the highest score wins.
+/// Consequently, when a member joins or leaves a group of `n` members only about `1/n`
+/// of the field values are assigned to a different member,
+/// as opposed to [FieldHashBucketRouting], which reassigns almost all of them.
+///
+/// Routing a message is linear in the number of members.
+pub struct ConsistentHashRouting<M, T: Hash, H: BuildHasher + Clone> {
+    hasher_builder: H,
+    field_extractor: fn(&M) -> &T,
+}
+impl<M, T: Hash, H: BuildHasher + Clone> Clone for ConsistentHashRouting<M, T, H> {
+    fn clone(&self) -> Self {
+        ConsistentHashRouting {
+            hasher_builder: self.hasher_builder.clone(),
+            field_extractor: self.field_extractor,
+        }
+    }
+}
+impl<M, T: Hash, H: BuildHasher + Clone + Copy> Copy for ConsistentHashRouting<M, T, H> {}
+
+impl<M, T: Hash, H: BuildHasher + Clone> fmt::Debug for ConsistentHashRouting<M, T, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ConsistentHashRouting {{ hasher_builder: {}, field_extractor: {} }}",
+            std::any::type_name::<H>(),
+            std::any::type_name::<fn(&M) -> &T>()
+        )
+    }
+}
+impl<M, T: Hash, H: BuildHasher + Clone> ConsistentHashRouting<M, T, H> {
+    /// Create a new instance of this router using the provided hasher and field extractor function
+    pub fn new(hasher_builder: H, field_extractor: fn(&M) -> &T) -> Self {
+        ConsistentHashRouting {
+            hasher_builder,
+            field_extractor,
+        }
+    }
+
+    /// Extracts and hashes the `msg` field using this router's `Hasher`
+    pub fn hash_message(&self, msg: &M) -> u64 {
+        let field_ref = (self.field_extractor)(msg);
+        let mut hasher = self.hasher_builder.build_hasher();
+        field_ref.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Scores `member` for a message with the hash `message_hash`
+    pub fn score<R: RouteeIdentity>(&self, message_hash: u64, member: &R) -> u64 {
+        let mut hasher = self.hasher_builder.build_hasher();
+        hasher.write_u64(message_hash);
+        member.hash_identity(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Provides the index of the member in `members` that `msg` is assigned to
+    ///
+    /// Returns `None` if there are no `members`.
+    pub fn get_member<R: RouteeIdentity>(&self, msg: &M, members: &[&R]) -> Option<usize> {
+        let message_hash = self.hash_message(msg);
+        members
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, member)| self.score(message_hash, **member))
+            .map(|(index, _)| index)
+    }
+}
+
+/// A consistent hash router using the `sender` field of a `NetMessage` to decide the member
+pub type SenderConsistentHashRouting<H> = ConsistentHashRouting<NetMessage, ActorPath, H>;
+/// A consistent hash router using the `sender` field of a `NetMessage` to decide the member
+///
+/// Uses Rust's default hasher.
+pub type SenderDefaultConsistentHashRouting =
+    SenderConsistentHashRouting<BuildHasherDefault<std::collections::hash_map::DefaultHasher>>;
+
+impl<H: BuildHasher + Default + Clone> Default for SenderConsistentHashRouting<H> {
+    fn default() -> Self {
+        let hasher_builder = H::default();
+        Self::new(hasher_builder, NetMessage::sender)
+    }
+}
+
+impl<R, M, T, H> RoutingPolicy<R, M> for ConsistentHashRouting<M, T, H>
+where
+    R: TypedRoutee<M> + RouteeIdentity,
+    M: fmt::Debug + 'static,
+    T: Hash + 'static,
+    H: BuildHasher + Clone + Send + Sync + 'static,
+{
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
+        match self.get_member(&msg, members) {
+            Some(index) => {
+                trace!(logger, "Routing msg to member at index={}", index);
+                members[index].tell(msg);
+            }
+            None => warn!(
+                logger,
+                "Dropping msg, as the group has no members: {:?}", msg
+            ),
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
+        let cloned: ConsistentHashRouting<M, T, H> = self.clone();
         Box::new(cloned)
     }
 
-    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        None
+    }
+
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        Some(self)
+    }
+}
+
+/// The load of a member as seen by load-aware policies
+///
+/// Members with an unknown mailbox length are considered maximally loaded.
+fn member_load<R: TypedRoutee<M>, M>(member: &R) -> usize {
+    member.mailbox_len().unwrap_or(usize::MAX)
+}
+
+/// A router that picks the member with the fewest queued messages
+///
+/// Ties are broken in a round-robin manner,
+/// so members with the same load (or an unknown load) share the traffic evenly.
+///
+/// This policy checks the mailbox of every member on each message.
+/// For large groups, consider [PowerOfTwoChoicesRouting] instead.
+#[derive(Debug)]
+pub struct LeastLoadedRouting {
+    offset: AtomicUsize,
+}
+impl LeastLoadedRouting {
+    /// Create a new instance
+    pub fn new() -> Self {
+        LeastLoadedRouting {
+            offset: AtomicUsize::new(0),
+        }
+    }
+
+    /// Provides the index of the least loaded member in `members`
+    ///
+    /// Returns `None` if there are no `members`.
+    pub fn get_member<R: TypedRoutee<M>, M>(&self, members: &[&R]) -> Option<usize> {
+        if members.is_empty() {
+            return None;
+        }
+        let start = self.offset.fetch_add(1, Ordering::Relaxed) % members.len();
+        (start..members.len())
+            .chain(0..start)
+            .min_by_key(|index| member_load(members[*index]))
+    }
+}
+impl Default for LeastLoadedRouting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<R: TypedRoutee<M>, M: fmt::Debug> RoutingPolicy<R, M> for LeastLoadedRouting {
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
+        match self.get_member(members) {
+            Some(index) => {
+                trace!(
+                    logger,
+                    "Routing msg to least loaded member at index={}",
+                    index
+                );
+                members[index].tell(msg);
+            }
+            None => warn!(
+                logger,
+                "Dropping msg, as the group has no members: {:?}", msg
+            ),
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
+        let offset = self.offset.load(Ordering::Relaxed);
+        let routing = LeastLoadedRouting {
+            offset: AtomicUsize::new(offset),
+        };
+        Box::new(routing)
+    }
+
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         None
     }
 
-    fn select(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        Some(self)
+    }
+}
+
+/// A router that samples two members at random and picks the one with fewer queued messages
+///
+/// This "power of two choices" strategy only checks two mailboxes per message,
+/// while still avoiding overloaded members almost as well as [LeastLoadedRouting].
+#[derive(Debug)]
+pub struct PowerOfTwoChoicesRouting {
+    state: AtomicU64,
+}
+impl PowerOfTwoChoicesRouting {
+    /// Create a new instance with a fixed seed
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    /// Create a new instance whose random choices are derived from `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        PowerOfTwoChoicesRouting {
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    // splitmix64, which is good enough for picking members and trivially thread-safe
+    fn next_random(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Provides the index of the less loaded of two randomly chosen members in `members`
+    ///
+    /// Returns `None` if there are no `members`.
+    pub fn get_member<R: TypedRoutee<M>, M>(&self, members: &[&R]) -> Option<usize> {
+        match members.len() {
+            0 => None,
+            1 => Some(0),
+            length => {
+                let random = self.next_random();
+                let first = (random % (length as u64)) as usize;
+                // pick a different second member
+                let offset = ((random >> 32) % (length as u64 - 1)) as usize + 1;
+                let second = (first + offset) % length;
+                if member_load(members[second]) < member_load(members[first]) {
+                    Some(second)
+                } else {
+                    Some(first)
+                }
+            }
+        }
+    }
+}
+impl Default for PowerOfTwoChoicesRouting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<R: TypedRoutee<M>, M: fmt::Debug> RoutingPolicy<R, M> for PowerOfTwoChoicesRouting {
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
+        match self.get_member(members) {
+            Some(index) => {
+                trace!(
+                    logger,
+                    "Routing msg to less loaded member at index={}",
+                    index
+                );
+                members[index].tell(msg);
+            }
+            None => warn!(
+                logger,
+                "Dropping msg, as the group has no members: {:?}", msg
+            ),
+        }
+    }
+
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
+        let state = self.state.load(Ordering::Relaxed);
+        Box::new(PowerOfTwoChoicesRouting::with_seed(state))
+    }
+
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
+        None
+    }
+
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         Some(self)
     }
 }
@@ -276,8 +670,8 @@ impl Default for BroadcastRouting {
         BroadcastRouting
     }
 }
-impl RoutingPolicy<DynActorRef, NetMessage> for BroadcastRouting {
-    fn route(&self, members: &[&DynActorRef], msg: NetMessage, logger: &KompactLogger) {
+impl<R: TypedRoutee<M>, M: TryClone + fmt::Debug> RoutingPolicy<R, M> for BroadcastRouting {
+    fn route(&self, members: &[&R], msg: M, logger: &KompactLogger) {
         trace!(logger, "Trying to broadcast message: {:?}", msg);
         let res = members
             .iter()
@@ -288,15 +682,15 @@ impl RoutingPolicy<DynActorRef, NetMessage> for BroadcastRouting {
         }
     }
 
-    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync> {
+    fn boxed_clone(&self) -> Box<dyn RoutingPolicy<R, M> + Send + Sync> {
         Box::new(*self)
     }
 
-    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn broadcast(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         Some(self)
     }
 
-    fn select(&self) -> Option<&(dyn RoutingPolicy<DynActorRef, NetMessage> + Send + Sync)> {
+    fn select(&self) -> Option<&(dyn RoutingPolicy<R, M> + Send + Sync)> {
         None
     }
 }
@@ -324,6 +718,24 @@ mod tests {
             let group = RoutingGroup::new(Vec::new(), &router);
             println!("Group: {:?}", group);
         }
+        {
+            let router = SenderDefaultConsistentHashRouting::default();
+            println!("Router: {:?}", router);
+            let group = RoutingGroup::new(Vec::new(), &router);
+            println!("Group: {:?}", group);
+        }
+        {
+            let router = LeastLoadedRouting::default();
+            println!("Router: {:?}", router);
+            let group = RoutingGroup::new(Vec::new(), &router);
+            println!("Group: {:?}", group);
+        }
+        {
+            let router = PowerOfTwoChoicesRouting::default();
+            println!("Router: {:?}", router);
+            let group = RoutingGroup::new(Vec::new(), &router);
+            println!("Group: {:?}", group);
+        }
         {
             let router = BroadcastRouting::default();
             println!("Router: {:?}", router);
@@ -506,6 +918,214 @@ mod tests {
         system.shutdown().expect("shutdown");
     }
 
+    #[test]
+    fn consistent_hash_routing() {
+        let system = KompactConfig::default().build().expect("system");
+
+        let receivers: Vec<Arc<Component<ReceiverComponent>>> = (0..GROUP_SIZE)
+            .map(|_i| system.create(ReceiverComponent::default))
+            .collect();
+        let receiver_refs: Vec<DynActorRef> =
+            receivers.iter().map(|c| c.actor_ref().dyn_ref()).collect();
+        receivers.iter().for_each(|c| system.start(c));
+
+        let router = SenderDefaultConsistentHashRouting::default();
+        let group = RoutingGroup::new(receiver_refs.iter().collect(), &router);
+        let group_ref: ActorPath = NamedPath::with_system(
+            system.system_path(),
+            vec!["routing_group".to_string(), "?".to_string()],
+        )
+        .into();
+        let source_ref = system.deadletter_path();
+
+        for _i in 0..NUM_MESSAGES {
+            let msg = NetMessage::with_box(
+                CountMe::SER_ID,
+                source_ref.clone(),
+                group_ref.clone(),
+                Box::new(CountMe),
+            );
+            group.route(msg, system.logger());
+        }
+        std::thread::sleep(SLEEP_TIME);
+        assert_eq!(NUM_MESSAGES, total_count(&receivers));
+        let counts = individual_count(&receivers);
+        assert!(counts.contains(&NUM_MESSAGES));
+
+        // the same sender stays with its member when another one leaves
+        let target = counts.iter().position(|&v| v == NUM_MESSAGES).unwrap();
+        let remaining: Vec<&DynActorRef> = receiver_refs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != (target + 1) % GROUP_SIZE)
+            .map(|(_, member)| member)
+            .collect();
+        let group = RoutingGroup::new(remaining, &router);
+        let msg = NetMessage::with_box(CountMe::SER_ID, source_ref, group_ref, Box::new(CountMe));
+        group.route(msg, system.logger());
+        std::thread::sleep(SLEEP_TIME);
+        assert_eq!(NUM_MESSAGES + 1, individual_count(&receivers)[target]);
+
+        system.shutdown().expect("shutdown");
+    }
+
+    struct TestMember(usize);
+    impl RouteeIdentity for TestMember {
+        fn hash_identity(&self, mut state: &mut dyn Hasher) {
+            self.0.hash(&mut state)
+        }
+    }
+
+    #[test]
+    fn consistent_hash_reassignment() {
+        const NUM_MEMBERS: usize = 10;
+        const NUM_KEYS: u64 = 10_000;
+
+        let router: ConsistentHashRouting<u64, u64, DefaultHasherBuilder> =
+            ConsistentHashRouting::new(DefaultHasherBuilder, |key| key);
+        let members: Vec<TestMember> = (0..=NUM_MEMBERS).map(TestMember).collect();
+        let assign = |group: &[&TestMember]| -> Vec<usize> {
+            (0..NUM_KEYS)
+                .map(|key| group[router.get_member(&key, group).unwrap()].0)
+                .collect()
+        };
+        let group: Vec<&TestMember> = members[..NUM_MEMBERS].iter().collect();
+        let before = assign(&group);
+        for member in 0..NUM_MEMBERS {
+            let share = before.iter().filter(|&&m| m == member).count() as u64;
+            assert!(share > NUM_KEYS / (2 * NUM_MEMBERS as u64));
+        }
+
+        // removing a member only moves its own keys
+        let removed = 3;
+        let shrunk: Vec<&TestMember> = group.iter().copied().filter(|m| m.0 != removed).collect();
+        let after = assign(&shrunk);
+        for (old, new) in before.iter().zip(after.iter()) {
+            if *old == removed {
+                assert_ne!(*new, removed);
+            } else {
+                assert_eq!(old, new);
+            }
+        }
+
+        // adding a member only moves keys onto it, about 1/n of them
+        let grown: Vec<&TestMember> = members.iter().collect();
+        let after = assign(&grown);
+        let mut moved = 0;
+        for (old, new) in before.iter().zip(after.iter()) {
+            if old != new {
+                assert_eq!(*new, NUM_MEMBERS);
+                moved += 1;
+            }
+        }
+        let expected = NUM_KEYS / (NUM_MEMBERS as u64 + 1);
+        assert!(
+            moved > expected / 2 && moved < expected * 2,
+            "moved={}",
+            moved
+        );
+
+        assert_eq!(router.get_member::<TestMember>(&0, &[]), None);
+    }
+
+    fn queued_routing_msg(system: &KompactSystem) -> NetMessage {
+        let group_ref: ActorPath = NamedPath::with_system(
+            system.system_path(),
+            vec!["routing_group".to_string(), "?".to_string()],
+        )
+        .into();
+        NetMessage::with_box(
+            CountMe::SER_ID,
+            system.deadletter_path(),
+            group_ref,
+            Box::new(CountMe),
+        )
+    }
+
+    #[test]
+    fn least_loaded_routing() {
+        let system = KompactConfig::default().build().expect("system");
+
+        // receivers are only started at the end, so messages stay in their mailboxes
+        let receivers: Vec<Arc<Component<ReceiverComponent>>> = (0..GROUP_SIZE)
+            .map(|_i| system.create(ReceiverComponent::default))
+            .collect();
+        let receiver_refs: Vec<DynActorRef> =
+            receivers.iter().map(|c| c.actor_ref().dyn_ref()).collect();
+        for (index, member) in receiver_refs.iter().enumerate() {
+            for _i in index..(GROUP_SIZE - 1) {
+                member.tell(queued_routing_msg(&system));
+            }
+        }
+        let mailbox_lens =
+            || -> Vec<Option<usize>> { receiver_refs.iter().map(|r| r.mailbox_len()).collect() };
+        assert_eq!(mailbox_lens(), vec![Some(2), Some(1), Some(0)]);
+
+        let router = LeastLoadedRouting::default();
+        let group = RoutingGroup::new(receiver_refs.iter().collect(), &router);
+        group.route(queued_routing_msg(&system), system.logger());
+        assert_eq!(mailbox_lens(), vec![Some(2), Some(1), Some(1)]);
+        group.route(queued_routing_msg(&system), system.logger());
+        group.route(queued_routing_msg(&system), system.logger());
+        assert_eq!(mailbox_lens(), vec![Some(2), Some(2), Some(2)]);
+
+        receivers.iter().for_each(|c| system.start(c));
+        std::thread::sleep(SLEEP_TIME);
+        assert_eq!(individual_count(&receivers), vec![2, 2, 2]);
+
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn power_of_two_choices_routing() {
+        const LARGE_GROUP_SIZE: usize = 8;
+
+        let system = KompactConfig::default().build().expect("system");
+
+        let receivers: Vec<Arc<Component<ReceiverComponent>>> = (0..LARGE_GROUP_SIZE)
+            .map(|_i| system.create(ReceiverComponent::default))
+            .collect();
+        let receiver_refs: Vec<DynActorRef> =
+            receivers.iter().map(|c| c.actor_ref().dyn_ref()).collect();
+        let mailbox_lens = || -> Vec<usize> {
+            receiver_refs
+                .iter()
+                .map(|r| r.mailbox_len().expect("allocated"))
+                .collect()
+        };
+
+        // with two members, both are always compared
+        let router = PowerOfTwoChoicesRouting::default();
+        for _i in 0..NUM_MESSAGES {
+            receiver_refs[0].tell(queued_routing_msg(&system));
+        }
+        let pair = RoutingGroup::new(receiver_refs[..2].iter().collect(), &router);
+        for _i in 0..NUM_MESSAGES {
+            pair.route(queued_routing_msg(&system), system.logger());
+        }
+        assert_eq!(&mailbox_lens()[..2], &[NUM_MESSAGES, NUM_MESSAGES]);
+
+        let rest = RoutingGroup::new(receiver_refs[2..].iter().collect(), &router);
+        for _i in 0..(NUM_MESSAGES * LARGE_GROUP_SIZE) {
+            rest.route(queued_routing_msg(&system), system.logger());
+        }
+        let loads = mailbox_lens();
+        let (min, max) = (
+            *loads[2..].iter().min().unwrap(),
+            *loads[2..].iter().max().unwrap(),
+        );
+        assert!(max - min <= 3, "unbalanced loads: {:?}", loads);
+
+        receivers.iter().for_each(|c| system.start(c));
+        std::thread::sleep(SLEEP_TIME);
+        assert_eq!(
+            NUM_MESSAGES * (2 + LARGE_GROUP_SIZE),
+            total_count(&receivers)
+        );
+
+        system.shutdown().expect("shutdown");
+    }
+
     #[test]
     fn broadcast_routing() {
         let system = KompactConfig::default().build().expect("system");
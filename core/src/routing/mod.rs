@@ -1,4 +1,7 @@
 pub mod groups;
+pub mod remote;
+pub mod scatter_gather;
+pub mod typed;
 
 #[cfg(test)]
 pub(crate) mod test_helpers {
@@ -0,0 +1,539 @@
+//! Support for sender-side routing over (remote) actor paths
+
+use super::groups::{Routee, RouteeIdentity, RoutingPolicy};
+use crate::{
+    actors::{ActorPath, DispatcherRef, Dispatching},
+    dispatch::{NetworkStatus, NetworkStatusPort, NetworkStatusRequest},
+    messaging::NetMessage,
+    net::SocketAddr,
+    prelude::*,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+/// The default delay before the first attempt to reconnect to an unreachable system
+pub const DEFAULT_MIN_REDIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The default maximum delay between attempts to reconnect to an unreachable system
+pub const DEFAULT_MAX_REDIAL_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A member of a [RemoteRouter]
+///
+/// Wraps the [ActorPath] of the member together with the dispatcher used to reach it.
+#[derive(Clone, Debug)]
+pub struct RemoteMember {
+    path: ActorPath,
+    dispatcher: DispatcherRef,
+}
+impl RemoteMember {
+    /// Create a new member for `path`, which is reached via `dispatcher`
+    pub fn new(path: ActorPath, dispatcher: DispatcherRef) -> Self {
+        RemoteMember { path, dispatcher }
+    }
+
+    /// The path of this member
+    pub fn path(&self) -> &ActorPath {
+        &self.path
+    }
+}
+impl Dispatching for RemoteMember {
+    fn dispatcher_ref(&self) -> DispatcherRef {
+        self.dispatcher.clone()
+    }
+}
+impl RouteeIdentity for RemoteMember {
+    fn hash_identity(&self, mut state: &mut dyn Hasher) {
+        self.path.hash(&mut state)
+    }
+}
+impl Routee for RemoteMember {
+    fn deliver(&self, msg: NetMessage) {
+        // keep the original sender, so members can reply directly to it
+        self.path.forward_with_original_sender(msg, self)
+    }
+}
+
+/// Messages used to manage the membership of a [RemoteRouter]
+#[derive(Debug)]
+pub enum RemoteRouterMsg {
+    /// Add the path to the members of the group
+    AddMember(ActorPath),
+    /// Remove the path from the members of the group
+    RemoveMember(ActorPath),
+    /// Reply with the paths of all members currently being routed to
+    ActiveMembers(KPromise<Vec<ActorPath>>),
+}
+
+/// A sender-side router over a set of actor paths, usually spread over multiple remote systems
+///
+/// Any message sent to the router's path is routed to the members
+/// according to the router's [policy](RoutingPolicy), without changing its sender.
+///
+/// The router tracks the connections of the local system via its
+/// [network status port](NetworkStatusPort), which must be connected with
+/// [connect_network_status_port](KompactSystem::connect_network_status_port).
+/// When the connection to a system is [dropped](NetworkStatus::ConnectionDropped),
+/// all members on that system are excluded from routing, until the connection
+/// is [established](NetworkStatus::ConnectionEstablished) again.
+/// Since the dispatcher gives up on a dropped connection, the router itself
+/// [requests](NetworkStatusRequest::ConnectSystem) a new connection to the system,
+/// for as long as it has members there. The attempts are delayed exponentially,
+/// starting at [DEFAULT_MIN_REDIAL_BACKOFF] and doubling up to [DEFAULT_MAX_REDIAL_BACKOFF],
+/// unless configured otherwise via [with_redial_backoff](RemoteRouter::with_redial_backoff).
+///
+/// Membership can be changed at runtime via [RemoteRouterMsg].
+///
+/// # Example
+///
+/// ```
+/// use kompact::{prelude::*, routing::{groups::RoundRobinRouting, remote::RemoteRouter}};
+/// # use std::time::Duration;
+///
+/// let mut cfg = KompactConfig::default();
+/// cfg.system_components(DeadletterBox::new, NetworkConfig::default().build());
+/// let system = cfg.build().expect("system");
+/// # let remote_path: ActorPath =
+/// #     system.system_path().into_named_with_string("some/actor").expect("path").into();
+/// // `remote_path` is the path of an actor on some other system
+/// let router = system.create(|| RemoteRouter::new(RoundRobinRouting::new(), vec![remote_path]));
+/// router.on_definition(|r| system.connect_network_status_port(&mut r.network_status_port));
+/// let router_path = system
+///     .register(&router)
+///     .wait_expect(Duration::from_millis(1000), "router never registered");
+/// system.start(&router);
+/// // messages sent to `router_path` are now routed to the members
+/// # system.shutdown().expect("shutdown");
+/// ```
+#[derive(ComponentDefinition)]
+pub struct RemoteRouter {
+    ctx: ComponentContext<Self>,
+    /// The network status port, which must be connected to the dispatcher
+    pub network_status_port: RequiredPort<NetworkStatusPort>,
+    policy: Box<dyn RoutingPolicy<RemoteMember, NetMessage> + Send + Sync>,
+    members: Vec<ActorPath>,
+    unreachable: FxHashSet<SocketAddr>,
+    redials: FxHashMap<SocketAddr, Redial>,
+    min_redial_backoff: Duration,
+    max_redial_backoff: Duration,
+    active: Vec<RemoteMember>,
+}
+
+/// The reconnection attempts to an unreachable system
+#[derive(Debug)]
+struct Redial {
+    system: SystemPath,
+    attempts: usize,
+    timer: ScheduledTimer,
+}
+
+impl RemoteRouter {
+    /// Create a new router routing over `members` with the given `policy`
+    pub fn new<P, I>(policy: P, members: I) -> Self
+    where
+        P: RoutingPolicy<RemoteMember, NetMessage> + Send + Sync + 'static,
+        I: IntoIterator<Item = ActorPath>,
+    {
+        let mut router = RemoteRouter {
+            ctx: ComponentContext::uninitialised(),
+            network_status_port: RequiredPort::uninitialised(),
+            policy: Box::new(policy),
+            members: Vec::new(),
+            unreachable: FxHashSet::default(),
+            redials: FxHashMap::default(),
+            min_redial_backoff: DEFAULT_MIN_REDIAL_BACKOFF,
+            max_redial_backoff: DEFAULT_MAX_REDIAL_BACKOFF,
+            active: Vec::new(),
+        };
+        for member in members {
+            router.add_member(member);
+        }
+        router
+    }
+
+    /// Delay reconnection attempts exponentially, from `min_backoff` up to `max_backoff`
+    pub fn with_redial_backoff(self, min_backoff: Duration, max_backoff: Duration) -> Self {
+        RemoteRouter {
+            min_redial_backoff: min_backoff,
+            max_redial_backoff: max_backoff,
+            ..self
+        }
+    }
+
+    /// The delay before the `attempt`-th reconnection attempt to a system (counting from 1)
+    pub fn redial_backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31) as u32;
+        self.min_redial_backoff
+            .checked_mul(1u32 << exponent)
+            .map_or(self.max_redial_backoff, |backoff| {
+                backoff.min(self.max_redial_backoff)
+            })
+    }
+
+    /// The paths of all configured members, reachable or not
+    pub fn members(&self) -> &[ActorPath] {
+        &self.members
+    }
+
+    /// The paths of all members currently being routed to
+    pub fn active_members(&self) -> Vec<ActorPath> {
+        self.active.iter().map(|m| m.path().clone()).collect()
+    }
+
+    fn add_member(&mut self, path: ActorPath) -> bool {
+        if self.members.contains(&path) {
+            false
+        } else {
+            self.members.push(path);
+            true
+        }
+    }
+
+    fn has_members_on(&self, addr: &SocketAddr) -> bool {
+        self.members
+            .iter()
+            .any(|path| &path.system().socket_address() == addr)
+    }
+
+    fn is_reachable(&self, path: &ActorPath) -> bool {
+        match path.system().protocol() {
+            Transport::Local => true,
+            _ => !self.unreachable.contains(&path.system().socket_address()),
+        }
+    }
+
+    fn refresh_active(&mut self) {
+        let dispatcher = self.dispatcher_ref();
+        self.active = self
+            .members
+            .iter()
+            .filter(|path| self.is_reachable(path))
+            .map(|path| RemoteMember::new(path.clone(), dispatcher.clone()))
+            .collect();
+        debug!(
+            self.log(),
+            "Routing over {} of {} members",
+            self.active.len(),
+            self.members.len()
+        );
+    }
+
+    fn set_reachable(&mut self, system: &SystemPath, reachable: bool) {
+        let addr = system.socket_address();
+        let changed = if reachable {
+            self.unreachable.remove(&addr)
+        } else {
+            self.unreachable.insert(addr)
+        };
+        let affected = self.has_members_on(&addr);
+        if changed && affected {
+            info!(
+                self.log(),
+                "Members on system {} are {}",
+                system,
+                if reachable {
+                    "reachable again"
+                } else {
+                    "unreachable"
+                }
+            );
+            self.refresh_active();
+        }
+        if reachable {
+            self.stop_redial(&addr);
+        } else {
+            self.start_redial(system);
+        }
+    }
+
+    fn start_redial(&mut self, system: &SystemPath) {
+        let addr = system.socket_address();
+        if !self.redials.contains_key(&addr) && self.has_members_on(&addr) {
+            let timer = self.schedule_redial(addr, 1);
+            let redial = Redial {
+                system: system.clone(),
+                attempts: 0,
+                timer,
+            };
+            self.redials.insert(addr, redial);
+        }
+    }
+
+    fn stop_redial(&mut self, addr: &SocketAddr) {
+        if let Some(redial) = self.redials.remove(addr) {
+            self.cancel_timer(redial.timer);
+        }
+    }
+
+    fn schedule_redial(&mut self, addr: SocketAddr, attempt: usize) -> ScheduledTimer {
+        self.schedule_once(self.redial_backoff(attempt), move |router, _| {
+            router.redial(addr)
+        })
+    }
+
+    fn redial(&mut self, addr: SocketAddr) -> Handled {
+        if !self.unreachable.contains(&addr) || !self.has_members_on(&addr) {
+            // reachable again or no members left to reach
+            self.redials.remove(&addr);
+        } else if let Some(mut redial) = self.redials.remove(&addr) {
+            redial.attempts += 1;
+            debug!(
+                self.log(),
+                "Reconnecting to system {}, attempt {}", redial.system, redial.attempts
+            );
+            self.network_status_port
+                .trigger(NetworkStatusRequest::ConnectSystem(redial.system.clone()));
+            redial.timer = self.schedule_redial(addr, redial.attempts + 1);
+            self.redials.insert(addr, redial);
+        }
+        Handled::Ok
+    }
+}
+
+impl ComponentLifecycle for RemoteRouter {
+    fn on_start(&mut self) -> Handled {
+        self.refresh_active();
+        Handled::Ok
+    }
+}
+
+impl Require<NetworkStatusPort> for RemoteRouter {
+    fn handle(&mut self, event: NetworkStatus) -> Handled {
+        match event {
+            NetworkStatus::ConnectionDropped(system) => self.set_reachable(&system, false),
+            NetworkStatus::ConnectionEstablished(system, _) => self.set_reachable(&system, true),
+            _ => (),
+        }
+        Handled::Ok
+    }
+}
+
+impl Actor for RemoteRouter {
+    type Message = RemoteRouterMsg;
+
+    fn receive_local(&mut self, msg: Self::Message) -> Handled {
+        match msg {
+            RemoteRouterMsg::AddMember(path) => {
+                let system = path.system().clone();
+                if self.add_member(path) {
+                    self.refresh_active();
+                    if self.unreachable.contains(&system.socket_address()) {
+                        self.start_redial(&system);
+                    }
+                }
+            }
+            RemoteRouterMsg::RemoveMember(path) => {
+                let before = self.members.len();
+                self.members.retain(|member| member != &path);
+                if self.members.len() != before {
+                    self.refresh_active();
+                }
+            }
+            RemoteRouterMsg::ActiveMembers(promise) => {
+                promise
+                    .fulfil(self.active_members())
+                    .unwrap_or_else(|e| warn!(self.log(), "Could not reply with members: {:?}", e));
+            }
+        }
+        Handled::Ok
+    }
+
+    fn receive_network(&mut self, msg: NetMessage) -> Handled {
+        if self.active.is_empty() {
+            warn!(
+                self.log(),
+                "Dropping message from {}, as no member is reachable", msg.sender
+            );
+        } else {
+            let members: Vec<&RemoteMember> = self.active.iter().collect();
+            self.policy.route(&members, msg, self.log());
+        }
+        Handled::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::groups::RoundRobinRouting;
+    use std::{sync::Arc, time::Duration};
+
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+
+    fn system_path(port: u16) -> SystemPath {
+        SystemPath::new(Transport::Tcp, "127.0.0.1".parse().unwrap(), port)
+    }
+
+    fn member(system: &SystemPath, name: &str) -> ActorPath {
+        system
+            .clone()
+            .into_named_with_string(name)
+            .expect("named path")
+            .into()
+    }
+
+    fn notify(router: &Arc<Component<RemoteRouter>>, event: NetworkStatus) {
+        let handled = router.on_definition(|r| r.handle(event));
+        assert!(matches!(handled, Handled::Ok));
+    }
+
+    #[test]
+    fn members_follow_connection_status() {
+        let system = KompactConfig::default().build().expect("system");
+        let system_a = system_path(1234);
+        let system_b = system_path(1235);
+        let a1 = member(&system_a, "a/1");
+        let a2 = member(&system_a, "a/2");
+        let b1 = member(&system_b, "b/1");
+        let router = system.create(|| {
+            RemoteRouter::new(
+                RoundRobinRouting::new(),
+                vec![a1.clone(), a2.clone(), b1.clone(), a1.clone()],
+            )
+        });
+        system
+            .start_notify(&router)
+            .wait_timeout(TIMEOUT)
+            .expect("router never started");
+        let active = || {
+            router
+                .actor_ref()
+                .ask_with(RemoteRouterMsg::ActiveMembers)
+                .wait_timeout(TIMEOUT)
+                .expect("members")
+        };
+        assert_eq!(active(), vec![a1.clone(), a2.clone(), b1.clone()]);
+
+        notify(&router, NetworkStatus::ConnectionDropped(system_a.clone()));
+        assert_eq!(active(), vec![b1.clone()]);
+        // members of unreachable systems are still kept
+        assert_eq!(router.on_definition(|r| r.members().len()), 3);
+
+        router
+            .actor_ref()
+            .tell(RemoteRouterMsg::RemoveMember(b1.clone()));
+        assert!(active().is_empty());
+
+        let session = crate::net::SessionId::new_unique();
+        notify(
+            &router,
+            NetworkStatus::ConnectionEstablished(system_a, session),
+        );
+        assert_eq!(active(), vec![a1, a2]);
+
+        router
+            .actor_ref()
+            .tell(RemoteRouterMsg::AddMember(b1.clone()));
+        notify(&router, NetworkStatus::ConnectionDropped(system_b));
+        assert_eq!(active().len(), 2);
+
+        system.shutdown().expect("shutdown");
+    }
+
+    /// Stands in for the dispatcher, which only manages to connect on the `connect_after`-th request
+    #[derive(ComponentDefinition)]
+    struct FlakyDispatcher {
+        ctx: ComponentContext<Self>,
+        network_status_port: ProvidedPort<NetworkStatusPort>,
+        connect_after: usize,
+        requests: Vec<SystemPath>,
+    }
+    impl FlakyDispatcher {
+        fn new(connect_after: usize) -> Self {
+            FlakyDispatcher {
+                ctx: ComponentContext::uninitialised(),
+                network_status_port: ProvidedPort::uninitialised(),
+                connect_after,
+                requests: Vec::new(),
+            }
+        }
+    }
+    ignore_lifecycle!(FlakyDispatcher);
+    impl Provide<NetworkStatusPort> for FlakyDispatcher {
+        fn handle(&mut self, request: NetworkStatusRequest) -> Handled {
+            if let NetworkStatusRequest::ConnectSystem(system) = request {
+                self.requests.push(system.clone());
+                if self.requests.len() == self.connect_after {
+                    let session = crate::net::SessionId::new_unique();
+                    self.network_status_port
+                        .trigger(NetworkStatus::ConnectionEstablished(system, session));
+                }
+            }
+            Handled::Ok
+        }
+    }
+    impl Actor for FlakyDispatcher {
+        type Message = ();
+
+        fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+            unimplemented!("Only used locally");
+        }
+    }
+
+    #[test]
+    fn redial_backoff_doubles_up_to_max() {
+        let router = RemoteRouter::new(RoundRobinRouting::new(), Vec::new())
+            .with_redial_backoff(Duration::from_millis(10), Duration::from_millis(50));
+        assert_eq!(router.redial_backoff(1), Duration::from_millis(10));
+        assert_eq!(router.redial_backoff(3), Duration::from_millis(40));
+        assert_eq!(router.redial_backoff(4), Duration::from_millis(50));
+        assert_eq!(router.redial_backoff(100), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn dropped_members_are_redialled() {
+        const CONNECT_AFTER: usize = 3;
+        let system = KompactConfig::default().build().expect("system");
+        let system_a = system_path(1234);
+        let system_b = system_path(1235);
+        let a1 = member(&system_a, "a/1");
+        let b1 = member(&system_b, "b/1");
+        let dispatcher = system.create(|| FlakyDispatcher::new(CONNECT_AFTER));
+        let router = system.create(|| {
+            RemoteRouter::new(RoundRobinRouting::new(), vec![a1.clone(), b1.clone()])
+                .with_redial_backoff(Duration::from_millis(5), Duration::from_millis(20))
+        });
+        biconnect_components::<NetworkStatusPort, _, _>(&dispatcher, &router).expect("connection");
+        system
+            .start_notify(&dispatcher)
+            .wait_timeout(TIMEOUT)
+            .expect("dispatcher never started");
+        system
+            .start_notify(&router)
+            .wait_timeout(TIMEOUT)
+            .expect("router never started");
+        let active = || {
+            router
+                .actor_ref()
+                .ask_with(RemoteRouterMsg::ActiveMembers)
+                .wait_timeout(TIMEOUT)
+                .expect("members")
+        };
+
+        notify(&router, NetworkStatus::ConnectionDropped(system_a.clone()));
+        assert_eq!(active(), vec![b1.clone()]);
+
+        // no one else asks for the connection, yet the member comes back
+        let deadline = std::time::Instant::now() + TIMEOUT;
+        while active().len() < 2 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "member was never re-added"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(active(), vec![a1, b1]);
+        assert!(router.on_definition(|r| r.redials.is_empty()));
+        // the router stops redialling once the connection is established
+        std::thread::sleep(Duration::from_millis(50));
+        let requests = dispatcher.on_definition(|d| d.requests.clone());
+        assert_eq!(requests, vec![system_a; CONNECT_AFTER]);
+
+        system.shutdown().expect("shutdown");
+    }
+}
@@ -0,0 +1,590 @@
+//! Support for scatter-gather requests over routing groups
+//!
+//! A scatter-gather request sends the same request to every member of a group
+//! and collects their replies until the [gather policy](GatherPolicy) is satisfied
+//! or a timeout expires, whichever happens first.
+//!
+//! Requests to local actor references are sent with [scatter_gather],
+//! while requests to (possibly remote) broadcast paths are sent with
+//! [scatter_gather](crate::prelude::KompactSystem::scatter_gather) on the system.
+
+use super::groups::TypedRoutee;
+use crate::{
+    actors::{ActorPath, NamedPath, BROADCAST_MARKER, SELECT_MARKER},
+    messaging::{NetMessage, PathEntry},
+    prelude::*,
+    utils::{promise, Ask, KPromise},
+};
+use futures::{stream::FuturesUnordered, FutureExt, StreamExt};
+use std::{future::Future, time::Duration};
+use uuid::Uuid;
+
+/// How many replies a scatter-gather request waits for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GatherPolicy {
+    /// Wait for a reply from every member
+    All,
+    /// Wait for replies from (at least) the given number of members
+    Quorum(usize),
+    /// Wait for the first reply from any member
+    First,
+}
+
+impl GatherPolicy {
+    /// The number of replies required to satisfy this policy in a group of `members`
+    pub fn required(&self, members: usize) -> usize {
+        match self {
+            GatherPolicy::All => members,
+            GatherPolicy::Quorum(n) => *n,
+            GatherPolicy::First => 1,
+        }
+    }
+}
+
+/// The outcome of a scatter-gather request
+///
+/// Members are identified by a key of type `K`, e.g. their actor reference or path.
+#[derive(Clone, Debug)]
+pub struct Gathered<K, T> {
+    /// The replies received before the request completed, in order of arrival
+    pub replies: Vec<(K, T)>,
+    /// The members that had not replied when the request completed
+    ///
+    /// With a policy other than [All](GatherPolicy::All) this includes members
+    /// that simply weren't waited for anymore.
+    pub missing: Vec<K>,
+    /// `true` if the [gather policy](GatherPolicy) was satisfied before the timeout
+    pub complete: bool,
+}
+
+impl<K, T> Gathered<K, T> {
+    /// Drop the member keys and return only the replies
+    pub fn into_replies(self) -> Vec<T> {
+        self.replies.into_iter().map(|(_, reply)| reply).collect()
+    }
+}
+
+fn is_done(policy: GatherPolicy, members: usize, replies: usize) -> bool {
+    replies >= policy.required(members) || replies == members
+}
+
+/// Send a copy of `request` to each of the `members` and gather their responses
+///
+/// The returned future completes once `policy` is satisfied, every member has responded,
+/// or `timeout` has expired. Members that dropped the request without responding
+/// are reported as missing.
+///
+/// The requests are sent immediately, but the returned future must be driven to completion
+/// (e.g. via [spawn_local](ComponentDefinition::spawn_local), [KompactSystem::spawn],
+/// or [block_on](crate::prelude::block_on)) to collect the responses.
+///
+/// # Example
+///
+/// ```
+/// use kompact::{prelude::*, routing::scatter_gather::{scatter_gather, GatherPolicy}};
+/// use std::time::Duration;
+///
+/// #[derive(ComponentDefinition)]
+/// struct Worker {
+///     ctx: ComponentContext<Self>
+/// }
+/// # impl Worker {
+/// #     fn new() -> Worker {
+/// #         Worker {
+/// #             ctx: ComponentContext::uninitialised()
+/// #         }
+/// #     }
+/// # }
+/// # ignore_lifecycle!(Worker);
+/// impl Actor for Worker {
+///     type Message = Ask<u64, u64>;
+///
+///     fn receive_local(&mut self, msg: Self::Message) -> Handled {
+///         msg.complete(|num| num * 2).expect("completion");
+///         Handled::Ok
+///     }
+/// #    fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+/// #        unimplemented!("We don't care about this.");
+/// #    }
+/// }
+///
+/// let system = KompactConfig::default().build().expect("system");
+/// let workers: Vec<ActorRef<Ask<u64, u64>>> = (0..3)
+///     .map(|_| {
+///         let worker = system.create(Worker::new);
+///         system.start(&worker);
+///         worker.actor_ref()
+///     })
+///     .collect();
+/// let gathered = block_on(scatter_gather(
+///     workers,
+///     21u64,
+///     GatherPolicy::All,
+///     Duration::from_millis(1000),
+/// ));
+/// assert!(gathered.complete);
+/// assert!(gathered.missing.is_empty());
+/// assert_eq!(gathered.into_replies(), vec![42u64; 3]);
+/// # system.shutdown().expect("shutdown");
+/// ```
+pub fn scatter_gather<R, Request, Response, I>(
+    members: I,
+    request: Request,
+    policy: GatherPolicy,
+    timeout: Duration,
+) -> impl Future<Output = Gathered<R, Response>> + Send + 'static
+where
+    I: IntoIterator<Item = R>,
+    R: TypedRoutee<Ask<Request, Response>> + Send + 'static,
+    Request: MessageBounds + Clone,
+    Response: Send + Sized + 'static,
+{
+    let pending: Vec<(R, KFuture<Response>)> = members
+        .into_iter()
+        .map(|member| {
+            let (promise, future) = promise::<Response>();
+            member.tell(Ask::new(promise, request.clone()));
+            (member, future)
+        })
+        .collect();
+    gather(pending, policy, timeout)
+}
+
+async fn gather<K, T>(
+    pending: Vec<(K, KFuture<T>)>,
+    policy: GatherPolicy,
+    timeout: Duration,
+) -> Gathered<K, T>
+where
+    T: Send + Sized,
+{
+    let num_members = pending.len();
+    let mut keys: Vec<Option<K>> = Vec::with_capacity(num_members);
+    let mut responses: FuturesUnordered<_> = pending
+        .into_iter()
+        .enumerate()
+        .map(|(index, (key, future))| {
+            keys.push(Some(key));
+            future.map(move |res| (index, res))
+        })
+        .collect();
+    let mut replies: Vec<(usize, T)> = Vec::with_capacity(num_members);
+    let collect = async {
+        while !is_done(policy, num_members, replies.len()) {
+            match responses.next().await {
+                Some((index, Ok(reply))) => replies.push((index, reply)),
+                // the member dropped the request, so it will never answer
+                Some((_index, Err(_))) => (),
+                None => break,
+            }
+        }
+    };
+    // on timeout we simply go with what we have
+    let _ = async_std::future::timeout(timeout, collect).await;
+    let complete = replies.len() >= policy.required(num_members);
+    let replies: Vec<(K, T)> = replies
+        .into_iter()
+        .map(|(index, reply)| (keys[index].take().expect("replied only once"), reply))
+        .collect();
+    let missing: Vec<K> = keys.into_iter().flatten().collect();
+    Gathered {
+        replies,
+        missing,
+        complete,
+    }
+}
+
+/// A temporary actor that scatters a request to a (broadcast) path and gathers the replies
+///
+/// Created via [KompactSystem::scatter_gather].
+#[derive(ComponentDefinition)]
+pub(crate) struct ScatterGather {
+    ctx: ComponentContext<Self>,
+    target: ActorPath,
+    request: Option<Box<dyn Serialisable>>,
+    policy: GatherPolicy,
+    timeout: Duration,
+    members: Option<Vec<(ActorPath, Uuid)>>,
+    replies: Vec<(usize, NetMessage)>,
+    promise: Option<KPromise<Gathered<ActorPath, NetMessage>>>,
+    timer: Option<ScheduledTimer>,
+}
+
+impl ScatterGather {
+    pub(crate) fn new(
+        target: ActorPath,
+        request: Box<dyn Serialisable>,
+        policy: GatherPolicy,
+        timeout: Duration,
+        promise: KPromise<Gathered<ActorPath, NetMessage>>,
+    ) -> Self {
+        ScatterGather {
+            ctx: ComponentContext::uninitialised(),
+            target,
+            request: Some(request),
+            policy,
+            timeout,
+            members: None,
+            replies: Vec::new(),
+            promise: Some(promise),
+            timer: None,
+        }
+    }
+
+    /// The prefix under which all members of a named `target` are registered
+    fn member_prefix(target: &NamedPath) -> NamedPath {
+        let mut path = target.clone_path();
+        let is_marker =
+            |s: &String| s.len() == 1 && s.starts_with([BROADCAST_MARKER, SELECT_MARKER]);
+        if path.last().map(is_marker).unwrap_or(false) {
+            path.pop();
+        }
+        NamedPath::with_system(target.system().clone(), path)
+    }
+
+    fn scatter(&mut self, members: Vec<(ActorPath, Uuid)>) {
+        if self.promise.is_none() {
+            return; // already timed out
+        }
+        debug!(
+            self.log(),
+            "Scattering request to {} members of {}",
+            members.len(),
+            self.target
+        );
+        if let Some(request) = self.request.take() {
+            if !members.is_empty() {
+                self.target.tell(request, self);
+            }
+        }
+        self.members = Some(members);
+        self.check_done();
+    }
+
+    fn member_index(&self, sender: &ActorPath) -> Option<usize> {
+        self.members
+            .as_ref()?
+            .iter()
+            .position(|(path, id)| match sender {
+                ActorPath::Unique(unique) => unique.id() == *id,
+                ActorPath::Named(_) => sender == path,
+            })
+    }
+
+    fn check_done(&mut self) {
+        if let Some(members) = self.members.as_ref() {
+            if is_done(self.policy, members.len(), self.replies.len()) {
+                self.finish();
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(promise) = self.promise.take() {
+            if let Some(timer) = self.timer.take() {
+                self.cancel_timer(timer);
+            }
+            // if the members were never listed, the request was never sent
+            let scattered = self.members.is_some();
+            let members = self.members.take().unwrap_or_default();
+            let complete = scattered && self.replies.len() >= self.policy.required(members.len());
+            let mut keys: Vec<Option<ActorPath>> =
+                members.into_iter().map(|(path, _)| Some(path)).collect();
+            let replies: Vec<(ActorPath, NetMessage)> = self
+                .replies
+                .drain(..)
+                .map(|(index, reply)| (keys[index].take().expect("replied only once"), reply))
+                .collect();
+            let missing: Vec<ActorPath> = keys.into_iter().flatten().collect();
+            let gathered = Gathered {
+                replies,
+                missing,
+                complete,
+            };
+            promise
+                .fulfil(gathered)
+                .unwrap_or_else(|e| warn!(self.log(), "Could not return replies: {:?}", e));
+            // the gatherer is not needed anymore
+            let deregistration = self
+                .ctx
+                .system()
+                .deregister_unique(&self.ctx.typed_component());
+            self.spawn_local(move |async_self| async move {
+                if let Ok(Err(e)) = deregistration.await {
+                    debug!(async_self.log(), "Could not deregister: {:?}", e);
+                }
+                Handled::DieNow
+            });
+        }
+    }
+}
+
+impl ComponentLifecycle for ScatterGather {
+    fn on_start(&mut self) -> Handled {
+        let timer = self.schedule_once(self.timeout, |c, _| {
+            c.timer = None;
+            debug!(c.log(), "Scatter-gather request to {} timed out", c.target);
+            c.finish();
+            Handled::Ok
+        });
+        self.timer = Some(timer);
+        // replies are sent to our unique path, so it must be registered
+        let registration = self.ctx.system().register(&self.ctx.typed_component());
+        let (listing, single) = match &self.target {
+            ActorPath::Named(named) => (
+                Some(self.ctx.system().list_paths(Self::member_prefix(named))),
+                None,
+            ),
+            ActorPath::Unique(unique) => (None, Some((self.target.clone(), unique.id()))),
+        };
+        self.spawn_local(move |mut async_self| async move {
+            match registration.await {
+                Ok(Ok(_)) => (),
+                res => {
+                    warn!(async_self.log(), "Could not register gatherer: {:?}", res);
+                    return Handled::Ok;
+                }
+            }
+            let members = match listing {
+                Some(listing) => match listing.await {
+                    Ok(entries) => entries
+                        .into_iter()
+                        .filter_map(|entry| match entry.entry {
+                            PathEntry::Actor(id) => Some((entry.path.into(), id)),
+                            PathEntry::Policy => None,
+                        })
+                        .collect(),
+                    Err(e) => {
+                        warn!(
+                            async_self.log(),
+                            "Could not list the members of {}: {}", async_self.target, e
+                        );
+                        return Handled::Ok;
+                    }
+                },
+                None => single.into_iter().collect(),
+            };
+            async_self.scatter(members);
+            Handled::Ok
+        });
+        Handled::Ok
+    }
+}
+
+impl Actor for ScatterGather {
+    type Message = Never;
+
+    fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+        unreachable!("Never type is empty")
+    }
+
+    fn receive_network(&mut self, msg: NetMessage) -> Handled {
+        if self.promise.is_none() {
+            debug!(self.log(), "Dropping late reply from {}", msg.sender);
+        } else {
+            match self.member_index(&msg.sender) {
+                Some(index) if !self.replies.iter().any(|(i, _)| *i == index) => {
+                    self.replies.push((index, msg));
+                    self.check_done();
+                }
+                Some(_) => debug!(self.log(), "Dropping repeated reply from {}", msg.sender),
+                None => warn!(
+                    self.log(),
+                    "Dropping reply from {}, which is not a member of {}", msg.sender, self.target
+                ),
+            }
+        }
+        Handled::Ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routing::{
+        groups::{BroadcastRouting, RoundRobinRouting},
+        test_helpers::*,
+        typed::ActorRefRouter,
+    };
+
+    const GROUP_SIZE: usize = 3;
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+    const SHORT_TIMEOUT: Duration = Duration::from_millis(200);
+
+    /// Doubles requests, unless it is silent, in which case it holds on to them without replying
+    #[derive(ComponentDefinition)]
+    struct Doubler {
+        ctx: ComponentContext<Self>,
+        silent: bool,
+        held: Vec<Ask<u64, u64>>,
+    }
+    impl Doubler {
+        fn new(silent: bool) -> Self {
+            Doubler {
+                ctx: ComponentContext::uninitialised(),
+                silent,
+                held: Vec::new(),
+            }
+        }
+    }
+    ignore_lifecycle!(Doubler);
+    impl Actor for Doubler {
+        type Message = Ask<u64, u64>;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            if self.silent {
+                self.held.push(msg);
+            } else {
+                msg.complete(|num| num * 2).expect("completion");
+            }
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, msg: NetMessage) -> Handled {
+            if !self.silent {
+                let sender = msg.sender.clone();
+                let num: u64 = msg.try_deserialise::<u64, u64>().expect("u64");
+                sender.tell(num * 2, self);
+            }
+            Handled::Ok
+        }
+    }
+
+    fn doublers(system: &KompactSystem, silent: usize) -> Vec<Arc<Component<Doubler>>> {
+        (0..GROUP_SIZE)
+            .map(|i| system.create(move || Doubler::new(i < silent)))
+            .collect()
+    }
+
+    #[test]
+    fn local_scatter_gather() {
+        let system = KompactConfig::default().build().expect("system");
+        let components = doublers(&system, 1);
+        components.iter().for_each(|c| system.start(c));
+        let refs: Vec<ActorRef<Ask<u64, u64>>> = components.iter().map(|c| c.actor_ref()).collect();
+
+        let gathered = block_on(scatter_gather(
+            refs.clone(),
+            1u64,
+            GatherPolicy::All,
+            SHORT_TIMEOUT,
+        ));
+        assert!(!gathered.complete);
+        assert_eq!(gathered.replies.len(), GROUP_SIZE - 1);
+        assert_eq!(gathered.missing, vec![refs[0].clone()]);
+
+        let gathered = block_on(scatter_gather(
+            refs[1..].to_vec(),
+            2u64,
+            GatherPolicy::All,
+            TIMEOUT,
+        ));
+        assert!(gathered.complete);
+        assert!(gathered.missing.is_empty());
+        assert_eq!(gathered.into_replies(), vec![4u64; GROUP_SIZE - 1]);
+
+        let gathered = block_on(scatter_gather(
+            refs.clone(),
+            3u64,
+            GatherPolicy::Quorum(2),
+            TIMEOUT,
+        ));
+        assert!(gathered.complete);
+        assert_eq!(gathered.into_replies(), vec![6u64; 2]);
+
+        let router = ActorRefRouter::new(
+            RoundRobinRouting::new(),
+            refs[..1].to_vec(),
+            system.logger().clone(),
+        );
+        let gathered = block_on(router.scatter_gather(4u64, GatherPolicy::First, SHORT_TIMEOUT));
+        assert!(!gathered.complete);
+        assert!(gathered.replies.is_empty());
+
+        // dropped requests are reported as missing without waiting for the timeout
+        drop(components[0].on_definition(|c| std::mem::take(&mut c.held)));
+        let gathered = block_on(scatter_gather(
+            refs.clone(),
+            5u64,
+            GatherPolicy::All,
+            TIMEOUT,
+        ));
+        assert_eq!(gathered.missing, vec![refs[0].clone()]);
+        drop(components[0].on_definition(|c| std::mem::take(&mut c.held)));
+
+        // no members is trivially complete
+        let gathered = block_on(scatter_gather(
+            Vec::<ActorRef<Ask<u64, u64>>>::new(),
+            6u64,
+            GatherPolicy::All,
+            TIMEOUT,
+        ));
+        assert!(gathered.complete);
+
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn path_scatter_gather() {
+        let system = new_kompact_system();
+        let components = doublers(&system, 1);
+        let group = system
+            .set_routing_policy(BroadcastRouting, "doublers", false)
+            .wait_expect(TIMEOUT, "Could not register policy");
+        let paths: Vec<ActorPath> = components
+            .iter()
+            .enumerate()
+            .map(|(i, c)| system.register_by_alias(c, format!("doublers/doubler{}", i)))
+            .map(|f| f.wait_expect(TIMEOUT, "Could not register component"))
+            .collect();
+        components.iter().for_each(|c| system.start(c));
+
+        let gathered = system
+            .scatter_gather(group.clone(), 1u64, GatherPolicy::All, SHORT_TIMEOUT)
+            .wait_timeout(TIMEOUT)
+            .expect("gathered");
+        assert!(!gathered.complete);
+        assert_eq!(gathered.missing, vec![paths[0].clone()]);
+        let mut responders: Vec<ActorPath> =
+            gathered.replies.iter().map(|(p, _)| p.clone()).collect();
+        responders.sort();
+        assert_eq!(responders, paths[1..].to_vec());
+        for (_, reply) in gathered.replies {
+            assert_eq!(reply.try_deserialise::<u64, u64>().expect("u64"), 2);
+        }
+
+        let broadcast_path: ActorPath = (group.clone().unwrap_named() / '*').into();
+        let gathered = system
+            .scatter_gather(broadcast_path, 2u64, GatherPolicy::Quorum(2), TIMEOUT)
+            .wait_timeout(SHORT_TIMEOUT)
+            .expect("gathered before the timeout");
+        assert!(gathered.complete);
+        assert_eq!(gathered.replies.len(), 2);
+
+        // unique paths are groups of one
+        let unique = system
+            .register(&components[1])
+            .wait_expect(TIMEOUT, "Could not register component");
+        let gathered = system
+            .scatter_gather(unique.clone(), 3u64, GatherPolicy::First, TIMEOUT)
+            .wait_timeout(SHORT_TIMEOUT)
+            .expect("gathered before the timeout");
+        assert!(gathered.complete);
+        assert_eq!(gathered.replies[0].0, unique);
+
+        // unknown groups have no members
+        let empty: ActorPath = system
+            .system_path()
+            .into_named_with_string("nobody/*")
+            .expect("path")
+            .into();
+        let gathered = system
+            .scatter_gather(empty, 4u64, GatherPolicy::First, TIMEOUT)
+            .wait_timeout(SHORT_TIMEOUT)
+            .expect("gathered before the timeout");
+        assert!(!gathered.complete);
+        assert!(gathered.replies.is_empty() && gathered.missing.is_empty());
+
+        system.shutdown().expect("shutdown");
+    }
+}
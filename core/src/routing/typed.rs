@@ -0,0 +1,402 @@
+//! Support for routing typed messages over local actor references
+
+use super::{
+    groups::{RoutingPolicy, TypedRoutee},
+    scatter_gather::{self, GatherPolicy, Gathered},
+};
+use crate::{
+    actors::{ActorRef, AdaptedQueueContainer, MessageBounds, Receiver, Recipient},
+    utils::{promise, Ask, KFuture, KPromise},
+    KompactLogger,
+};
+#[allow(unused_imports)]
+use slog::{crit, debug, error, info, trace, warn};
+use std::{fmt, future::Future, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// A router over a set of local members, which routes typed messages without serialisation
+///
+/// The router can be used in place of a single actor reference:
+/// every message passed to [tell](TypedRouter::tell) is sent to the members
+/// selected by the router's [policy](RoutingPolicy).
+/// As a [Receiver], it also provides [recipients](Receiver::recipient) that route their messages.
+///
+/// Clones of a router share the policy (and its state, e.g. the current round-robin index),
+/// so they can be handed out freely.
+///
+/// # Example
+///
+/// ```
+/// use kompact::{prelude::*, routing::{groups::RoundRobinRouting, typed::ActorRefRouter}};
+/// use std::time::Duration;
+///
+/// #[derive(ComponentDefinition)]
+/// struct Worker {
+///     ctx: ComponentContext<Self>
+/// }
+/// # impl Worker {
+/// #     fn new() -> Worker {
+/// #         Worker {
+/// #             ctx: ComponentContext::uninitialised()
+/// #         }
+/// #     }
+/// # }
+/// # ignore_lifecycle!(Worker);
+/// impl Actor for Worker {
+///     type Message = Ask<u64, String>;
+///
+///     fn receive_local(&mut self, msg: Self::Message) -> Handled {
+///         msg.complete(|num| format!("{}", num)).expect("completion");
+///         Handled::Ok
+///     }
+/// #    fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+/// #        unimplemented!("We don't care about this.");
+/// #    }
+/// }
+///
+/// let system = KompactConfig::default().build().expect("system");
+/// let workers: Vec<ActorRef<Ask<u64, String>>> = (0..3)
+///     .map(|_| {
+///         let worker = system.create(Worker::new);
+///         system.start(&worker);
+///         worker.actor_ref()
+///     })
+///     .collect();
+/// let router = ActorRefRouter::new(RoundRobinRouting::new(), workers, system.logger().clone());
+/// // use `router` wherever a single `ActorRef<Ask<u64, String>>` would be used
+/// let response = router
+///     .ask(42u64)
+///     .wait_timeout(Duration::from_millis(1000))
+///     .expect("response");
+/// assert_eq!("42".to_string(), response);
+/// # system.shutdown().expect("shutdown");
+/// ```
+pub struct TypedRouter<R, M> {
+    members: Vec<R>,
+    policy: Arc<dyn RoutingPolicy<R, M> + Send + Sync>,
+    logger: KompactLogger,
+}
+
+/// A router over [ActorRef] members
+pub type ActorRefRouter<M> = TypedRouter<ActorRef<M>, M>;
+/// A router over [Recipient] members
+pub type RecipientRouter<M> = TypedRouter<Recipient<M>, M>;
+
+impl<R: TypedRoutee<M>, M> TypedRouter<R, M> {
+    /// Create a new router over `members` with the given `policy`
+    ///
+    /// The `logger` is handed to the policy for debugging and routing errors.
+    pub fn new<P>(policy: P, members: Vec<R>, logger: KompactLogger) -> Self
+    where
+        P: RoutingPolicy<R, M> + Send + Sync + 'static,
+    {
+        TypedRouter {
+            members,
+            policy: Arc::new(policy),
+            logger,
+        }
+    }
+
+    /// The members of this router
+    pub fn members(&self) -> &[R] {
+        &self.members
+    }
+
+    /// Create a new router with the same policy as this one, but over `members`
+    ///
+    /// The policy state is shared with this router.
+    pub fn with_members(&self, members: Vec<R>) -> Self {
+        TypedRouter {
+            members,
+            policy: self.policy.clone(),
+            logger: self.logger.clone(),
+        }
+    }
+
+    /// Send message `v` to the member(s) selected by the policy
+    ///
+    /// If the router has no members, the message is dropped.
+    pub fn tell<I>(&self, v: I) -> ()
+    where
+        I: Into<M>,
+    {
+        if self.members.is_empty() {
+            warn!(self.logger, "Dropping msg, as the router has no members");
+        } else {
+            let members: Vec<&R> = self.members.iter().collect();
+            self.policy.route(&members, v.into(), &self.logger);
+        }
+    }
+
+    /// Helper to create messages that expect a response via a future instead of a message
+    ///
+    /// See [ActorRef::ask_with] for details.
+    pub fn ask_with<Response, F>(&self, f: F) -> KFuture<Response>
+    where
+        Response: Send + Sized,
+        F: FnOnce(KPromise<Response>) -> M,
+    {
+        let (promise, future) = promise::<Response>();
+        self.tell(f(promise));
+        future
+    }
+}
+
+impl<R, Request, Response> TypedRouter<R, Ask<Request, Response>>
+where
+    R: TypedRoutee<Ask<Request, Response>>,
+    Request: MessageBounds,
+    Response: Send + Sized,
+{
+    /// Send `request` to the member selected by the policy and wait for its response via a future
+    ///
+    /// See [ActorRef::ask](ActorRef#method.ask-1) for details.
+    pub fn ask(&self, request: Request) -> KFuture<Response> {
+        self.ask_with(|promise| Ask::new(promise, request))
+    }
+
+    /// Send `request` to *all* members, bypassing the policy, and gather their responses
+    ///
+    /// See [scatter_gather](scatter_gather::scatter_gather) for details.
+    pub fn scatter_gather(
+        &self,
+        request: Request,
+        policy: GatherPolicy,
+        timeout: Duration,
+    ) -> impl Future<Output = Gathered<R, Response>> + Send + 'static
+    where
+        R: Clone + Send + 'static,
+        Request: Clone,
+        Response: 'static,
+    {
+        scatter_gather::scatter_gather(self.members.clone(), request, policy, timeout)
+    }
+}
+
+impl<R, M, T> Receiver<T> for TypedRouter<R, M>
+where
+    R: TypedRoutee<M> + Clone + fmt::Debug + Send + Sync + 'static,
+    M: 'static,
+    T: Into<M> + fmt::Debug + 'static,
+{
+    fn recipient(&self) -> Recipient<T> {
+        let adapter = RouterAdapter {
+            router: self.clone(),
+            convert: Into::into,
+        };
+        Recipient::from(Box::new(adapter))
+    }
+}
+
+/// Routes the messages sent to a [Recipient] over a [TypedRouter], after converting them
+struct RouterAdapter<R, M, T> {
+    router: TypedRouter<R, M>,
+    convert: fn(T) -> M,
+}
+impl<R, M, T> AdaptedQueueContainer<T> for RouterAdapter<R, M, T>
+where
+    R: TypedRoutee<M> + Clone + fmt::Debug + Send + Sync + 'static,
+    M: 'static,
+    T: 'static,
+{
+    fn id(&self) -> Option<Uuid> {
+        // a router is not a component
+        None
+    }
+
+    fn mailbox_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn enqueue_into(&self, value: T) {
+        self.router.tell((self.convert)(value))
+    }
+
+    fn box_clone(&self) -> Box<dyn AdaptedQueueContainer<T>> {
+        Box::new(RouterAdapter {
+            router: self.router.clone(),
+            convert: self.convert,
+        })
+    }
+}
+impl<R: fmt::Debug, M, T> fmt::Debug for RouterAdapter<R, M, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouterAdapter")
+            .field("router", &self.router)
+            .finish()
+    }
+}
+
+// Because the derive macro adds the wrong trait bound for this -.-
+impl<R: Clone, M> Clone for TypedRouter<R, M> {
+    fn clone(&self) -> Self {
+        TypedRouter {
+            members: self.members.clone(),
+            policy: self.policy.clone(),
+            logger: self.logger.clone(),
+        }
+    }
+}
+
+impl<R: fmt::Debug, M> fmt::Debug for TypedRouter<R, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedRouter")
+            .field("members", &self.members)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        routing::groups::{BroadcastRouting, LeastLoadedRouting, RoundRobinRouting},
+    };
+    use std::time::Duration;
+
+    const GROUP_SIZE: usize = 3;
+    const NUM_MESSAGES: usize = 30;
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+
+    #[derive(Debug)]
+    enum CounterMsg {
+        Increment,
+        Get(Ask<(), usize>),
+    }
+    impl From<()> for CounterMsg {
+        fn from(_: ()) -> Self {
+            CounterMsg::Increment
+        }
+    }
+
+    #[derive(ComponentDefinition)]
+    struct Counter {
+        ctx: ComponentContext<Self>,
+        count: usize,
+    }
+    impl Counter {
+        fn new() -> Self {
+            Counter {
+                ctx: ComponentContext::uninitialised(),
+                count: 0,
+            }
+        }
+    }
+    ignore_lifecycle!(Counter);
+    impl Actor for Counter {
+        type Message = CounterMsg;
+
+        fn receive_local(&mut self, msg: Self::Message) -> Handled {
+            match msg {
+                CounterMsg::Increment => self.count += 1,
+                CounterMsg::Get(ask) => ask.reply(self.count).expect("reply"),
+            }
+            Handled::Ok
+        }
+
+        fn receive_network(&mut self, _msg: NetMessage) -> Handled {
+            unimplemented!("Only used locally");
+        }
+    }
+
+    fn counts(counters: &[Arc<Component<Counter>>]) -> Vec<usize> {
+        counters
+            .iter()
+            .map(|c| {
+                c.actor_ref()
+                    .ask_with(|promise| CounterMsg::Get(Ask::new(promise, ())))
+                    .wait_timeout(TIMEOUT)
+                    .expect("count")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn actor_ref_router() {
+        let system = KompactConfig::default().build().expect("system");
+        let counters: Vec<Arc<Component<Counter>>> = (0..GROUP_SIZE)
+            .map(|_i| system.create(Counter::new))
+            .collect();
+        counters.iter().for_each(|c| system.start(c));
+        let refs: Vec<ActorRef<CounterMsg>> = counters.iter().map(|c| c.actor_ref()).collect();
+
+        let router = ActorRefRouter::new(RoundRobinRouting::new(), refs, system.logger().clone());
+        // clones share the round-robin index
+        let cloned = router.clone();
+        for i in 0..NUM_MESSAGES {
+            if i % 2 == 0 {
+                router.tell(());
+            } else {
+                cloned.tell(CounterMsg::Increment);
+            }
+        }
+        assert_eq!(
+            counts(&counters),
+            vec![NUM_MESSAGES / GROUP_SIZE; GROUP_SIZE]
+        );
+
+        let count = router
+            .ask_with(|promise| CounterMsg::Get(Ask::new(promise, ())))
+            .wait_timeout(TIMEOUT)
+            .expect("count");
+        assert_eq!(count, NUM_MESSAGES / GROUP_SIZE);
+
+        // the router can stand in wherever a receiver is expected
+        fn tell_via<T: Receiver<()>>(target: &T) {
+            target.recipient().tell(());
+        }
+        for _i in 0..GROUP_SIZE {
+            tell_via(&router);
+        }
+        assert_eq!(
+            counts(&counters),
+            vec![NUM_MESSAGES / GROUP_SIZE + 1; GROUP_SIZE]
+        );
+
+        // no members is not an error
+        router.with_members(Vec::new()).tell(());
+
+        system.shutdown().expect("shutdown");
+    }
+
+    #[test]
+    fn recipient_router() {
+        let system = KompactConfig::default().build().expect("system");
+        let counters: Vec<Arc<Component<Counter>>> = (0..GROUP_SIZE)
+            .map(|_i| system.create(Counter::new))
+            .collect();
+        let recipients: Vec<Recipient<()>> =
+            counters.iter().map(|c| c.actor_ref().recipient()).collect();
+        // fill up the first mailbox before the counters are started
+        for _i in 0..GROUP_SIZE {
+            recipients[0].tell(());
+        }
+
+        let router = RecipientRouter::new(
+            LeastLoadedRouting::new(),
+            recipients,
+            system.logger().clone(),
+        );
+        for _i in 0..(2 * GROUP_SIZE) {
+            router.tell(());
+        }
+        let mailbox_lens: Vec<Option<usize>> =
+            router.members().iter().map(|r| r.mailbox_len()).collect();
+        assert_eq!(mailbox_lens, vec![Some(GROUP_SIZE); GROUP_SIZE]);
+
+        counters.iter().for_each(|c| system.start(c));
+        assert_eq!(counts(&counters), vec![GROUP_SIZE; GROUP_SIZE]);
+
+        let broadcast = TypedRouter::new(
+            BroadcastRouting,
+            router.members().to_vec(),
+            system.logger().clone(),
+        );
+        broadcast.tell(());
+        assert_eq!(counts(&counters), vec![GROUP_SIZE + 1; GROUP_SIZE]);
+
+        system.shutdown().expect("shutdown");
+    }
+}
@@ -129,6 +129,75 @@ The default value is `auto`.
     "#,
         version = "0.11"
     }
+
+    kompact_config! {
+        MAILBOX_CAPACITY,
+        key = "kompact.runtime.mailbox-capacity",
+        type = UsizeValue,
+        default = 0,
+        doc = r#"The default capacity of component mailboxes.
+
+Once a component's mailbox holds this many messages, any further messages are handled according to the [overflow strategy](crate::config_keys::system::MAILBOX_OVERFLOW), until the component has caught up.
+Each of the component's port queues is bounded by the same capacity.
+The default can be overridden for individual components via [create_with_mailbox](crate::prelude::KompactSystem::create_with_mailbox).
+System components always have unbounded mailboxes.
+
+# Legal Values
+
+- `0`: Mailboxes are unbounded.
+- Any positive number of messages.
+
+# Default
+
+The default value is `0`, i.e. mailboxes are unbounded.
+    "#,
+        version = "0.11"
+    }
+
+    kompact_config! {
+        MAILBOX_OVERFLOW,
+        key = "kompact.runtime.mailbox-overflow",
+        type = StringValue,
+        default = "drop-new".to_string(),
+        validate = |value| crate::prelude::OverflowStrategy::CONFIG_VALUES.contains(&value.as_ref()),
+        doc = r#"What happens to messages sent to a full mailbox.
+
+This only applies when a [mailbox capacity](crate::config_keys::system::MAILBOX_CAPACITY) is configured.
+
+# Legal Values
+
+- `drop-new`: Drop the new message (see [DropNew](crate::prelude::OverflowStrategy::DropNew)).
+- `drop-oldest`: Drop the oldest message in the mailbox (see [DropOldest](crate::prelude::OverflowStrategy::DropOldest)).
+- `deadletters`: Redirect the new message to the deadletter box (see [Deadletters](crate::prelude::OverflowStrategy::Deadletters)).
+- `fail`: Leave full mailboxes to `try_tell` and drop messages sent with `tell` (see [Fail](crate::prelude::OverflowStrategy::Fail)).
+
+# Default
+
+The default value is `drop-new`.
+    "#,
+        version = "0.11"
+    }
+
+    kompact_config! {
+        STASH_CAPACITY,
+        key = "kompact.runtime.stash-capacity",
+        type = UsizeValue,
+        default = 1000,
+        validate = |value| *value > 0,
+        doc = r#"The maximum number of messages a component can [stash](crate::prelude::ComponentContext::stash).
+
+Stashing further messages fails, until the component [unstashes](crate::prelude::ComponentContext::unstash_all) them again.
+
+# Legal Values
+
+Any positive number of messages.
+
+# Default
+
+The default value is `1000`.
+    "#,
+        version = "0.11"
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -160,6 +229,8 @@ pub struct KompactConfig {
     pub(crate) throughput: usize,
     pub(crate) msg_priority: f32,
     pub(crate) threads: usize,
+    pub(crate) mailbox: MailboxConfig,
+    pub(crate) stash_capacity: usize,
     pub(crate) timer_builder: Rc<TimerBuilder>,
     pub(crate) scheduler_builder: Rc<SchedulerBuilder>,
     pub(crate) sc_builder: Rc<ScBuilder>,
@@ -176,6 +247,8 @@ impl fmt::Debug for KompactConfig {
             throughput={},
             msg_priority={},
             threads={},
+            mailbox={:?},
+            stash_capacity={},
             timer_builder=<function>,
             scheduler_builder=<function>,
             sc_builder=<function>,
@@ -186,6 +259,8 @@ impl fmt::Debug for KompactConfig {
             self.throughput,
             self.msg_priority,
             self.threads,
+            self.mailbox,
+            self.stash_capacity,
             self.root_logger,
             self.config_sources,
         )
@@ -222,6 +297,8 @@ impl KompactConfig {
             throughput: 0,
             msg_priority: 0.0,
             threads: 1,
+            mailbox: MailboxConfig::unbounded(),
+            stash_capacity: 1000,
             timer_builder: Rc::new(|| DefaultTimer::new_timer_component()),
             scheduler_builder: Rc::new(|t| {
                 ExecutorScheduler::from(crossbeam_workstealing_pool::small_pool(t))
@@ -550,6 +627,8 @@ impl KompactConfig {
         self.throughput = conf.get_or_default(&keys::THROUGHPUT)?;
         self.msg_priority = conf.get_or_default(&keys::MESSAGE_PRIORITY)?;
         self.threads = conf.get_or_default(&keys::THREADS)?;
+        self.mailbox = MailboxConfig::from_config(conf)?;
+        self.stash_capacity = conf.get_or_default(&keys::STASH_CAPACITY)?;
         let scheduler_option = conf.get_or_default(&keys::SCHEDULER)?;
         match scheduler_option.as_ref() {
             "auto" => {
@@ -609,6 +688,8 @@ impl Default for KompactConfig {
             throughput: 0,
             msg_priority: 0.0,
             threads: 1,
+            mailbox: MailboxConfig::unbounded(),
+            stash_capacity: 1000,
             timer_builder: Rc::new(|| DefaultTimer::new_timer_component()),
             scheduler_builder,
             sc_builder: Rc::new(|sys, dead_prom, disp_prom| {
@@ -4,21 +4,35 @@ use super::*;
 use crate::utils::erased::CreateErased;
 use crate::{
     messaging::{
+        ActorIdentity,
+        Deregistration,
         DispatchEnvelope,
         MsgEnvelope,
+        NetMessage,
         PathResolvable,
+        RegisteredPath,
         RegistrationEnvelope,
         RegistrationError,
         RegistrationResult,
     },
-    prelude::NetworkStatusPort,
-    routing::groups::StorePolicy,
-    supervision::{ComponentSupervisor, ListenEvent, SupervisionPort, SupervisorMsg},
+    prelude::{NetworkBackend, NetworkStatusPort},
+    routing::{
+        groups::StorePolicy,
+        scatter_gather::{GatherPolicy, Gathered, ScatterGather},
+    },
+    supervision::{
+        ComponentSupervisor,
+        FaultCounters,
+        FaultStatistics,
+        ListenEvent,
+        SupervisionPort,
+        SupervisorMsg,
+    },
     timer::timer_manager::{CanCancelTimers, TimerRefFactory},
 };
 use hocon::{Hocon, HoconLoader};
 use oncemutex::{OnceMutex, OnceMutexGuard};
-use std::{any::TypeId, fmt, sync::Mutex};
+use std::{any::TypeId, fmt, sync::Mutex, time::Duration};
 
 /// A Kompact system is a collection of components and services
 ///
@@ -122,8 +136,6 @@ impl KompactSystem {
         Ok(sys)
     }
 
-    #[allow(dead_code)]
-    // Only used in testing
     pub(crate) fn get_system_components(&self) -> &dyn SystemComponents {
         self.inner.get_internal_components().get_system_components()
     }
@@ -214,6 +226,91 @@ impl KompactSystem {
         c
     }
 
+    /// Create a new component with a custom mailbox
+    ///
+    /// This behaves like [create](KompactSystem::create), but the component's mailbox
+    /// uses the given `mailbox` configuration, instead of the system's [default](KompactSystem::default_mailbox).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::TestComponent1;
+    ///
+    /// let system = KompactConfig::default().build().expect("system");
+    /// let c = system.create_with_mailbox(
+    ///     TestComponent1::new,
+    ///     MailboxConfig::bounded(1024, OverflowStrategy::DropOldest),
+    /// );
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn create_with_mailbox<C, F>(&self, f: F, mailbox: MailboxConfig) -> Arc<Component<C>>
+    where
+        F: FnOnce() -> C,
+        C: ComponentDefinition + 'static,
+    {
+        self.inner.assert_active();
+        let c = Arc::new(
+            Component::new(self.clone(), f(), self.supervision_port()).with_mailbox(mailbox),
+        );
+        unsafe {
+            let cd = &mut c.mutable_core.lock().unwrap().definition;
+            let cc: Arc<dyn CoreContainer> = c.clone() as Arc<dyn CoreContainer>;
+            cd.setup(c.clone());
+            c.core().set_component(cc);
+        }
+        c
+    }
+
+    /// Create a new component supervised by `parent`
+    ///
+    /// The child is killed whenever `parent` is killed or faults,
+    /// and faults of the child are handled according to the
+    /// [supervision strategy](Component::set_supervision_strategy) of `parent`.
+    /// If `parent` is already dead when the child is started,
+    /// the child is killed immediately.
+    ///
+    /// Newly created components are not started automatically.
+    /// Use [start](KompactSystem::start) or
+    /// [start_notify](KompactSystem::start_notify) to start a newly
+    /// created component, once it is connected properly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// # let system = KompactConfig::default().build().expect("system");
+    /// let parent = system.create(TestComponent1::new);
+    /// parent.set_supervision_strategy(SupervisionStrategy::OneForAll);
+    /// let child = system.create_child(&parent, TestComponent1::new);
+    /// system.start(&parent);
+    /// system.start_notify(&child)
+    ///       .wait_timeout(Duration::from_millis(1000))
+    ///       .expect("child never started!");
+    /// // killing the parent also kills the child
+    /// system.kill(parent);
+    /// # child.wait_ended();
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn create_child<C, F>(
+        &self,
+        parent: &Arc<impl CoreContainer + ?Sized>,
+        f: F,
+    ) -> Arc<Component<C>>
+    where
+        F: FnOnce() -> C,
+        C: ComponentDefinition + 'static,
+    {
+        let c = self.create(f);
+        let parent = parent.core().component();
+        unsafe {
+            c.core().set_parent(&parent);
+        }
+        c
+    }
+
     /// Create a new component from type-erased definition
     ///
     /// Since components are shared between threads, the created component
@@ -623,6 +720,284 @@ impl KompactSystem {
         self.inner.set_routing_policy(policy.into(), path, update)
     }
 
+    /// Attempts to remove the actor registered with the given alias
+    ///
+    /// The returned future will contain the named [ActorPath](ActorPath)
+    /// that was removed, once it is completed by the dispatcher.
+    ///
+    /// The entry is removed immediately, instead of waiting for the reaper to notice
+    /// a deallocated component. Deregistration fails with
+    /// [NotFound](RegistrationError::NotFound), if no actor is registered with the alias.
+    /// Routing policies and actors registered under the alias are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// system.register_by_alias(&c, "test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// system.deregister_alias("test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to deregister TestComponent1");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn deregister_alias<A>(&self, alias: A) -> KFuture<RegistrationResult>
+    where
+        A: Into<String>,
+    {
+        self.inner.assert_active();
+        let path = PathResolvable::Alias(alias.into());
+        self.inner.deregister(Deregistration::Actor(path))
+    }
+
+    /// Attempts to remove the unique registration of the provided component
+    ///
+    /// The returned future will contain the unique [ActorPath](ActorPath)
+    /// that was removed, once it is completed by the dispatcher.
+    ///
+    /// Deregistration fails with [NotFound](RegistrationError::NotFound),
+    /// if the component was not registered. Aliases of the component are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let (c, registration_future) = system.create_and_register(TestComponent1::new);
+    /// registration_future.wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1");
+    /// system.deregister_unique(&c)
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to deregister TestComponent1");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn deregister_unique(&self, c: &dyn UniqueRegistrable) -> KFuture<RegistrationResult> {
+        self.inner.assert_active();
+        let path = PathResolvable::ActorId(c.component_id());
+        self.inner.deregister(Deregistration::Actor(path))
+    }
+
+    /// Attempts to remove the routing policy at `path`
+    ///
+    /// The returned future will contain the named [ActorPath](ActorPath)
+    /// of the removed policy, once it is completed by the dispatcher.
+    ///
+    /// Deregistration fails with [NotFound](RegistrationError::NotFound),
+    /// if there is no policy at `path`. Actors registered under `path` are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// use kompact::routing::groups::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// system.set_routing_policy(BroadcastRouting::default(), "broadcast-me", false)
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to set broadcast policy");
+    /// system.remove_routing_policy("broadcast-me")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to remove broadcast policy");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn remove_routing_policy(&self, path: &str) -> KFuture<RegistrationResult> {
+        self.inner.assert_active();
+        self.inner.remove_routing_policy(path)
+    }
+
+    /// Asks the dispatcher of `path`'s system what is registered at `path`
+    ///
+    /// The returned future completes with the [ActorIdentity](ActorIdentity) of the path,
+    /// which is either [NotFound](ActorIdentity::NotFound), the unique path of a single actor,
+    /// or the unique paths of all members behind a broadcast, select, or policy path.
+    ///
+    /// Remote paths are resolved by the remote system's dispatcher.
+    /// If the remote system becomes unreachable, the future fails instead,
+    /// as it does if no answer arrives within the
+    /// [identify timeout](crate::prelude::NetworkConfig::set_identify_timeout).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// let named_path = system.register_by_alias(&c, "test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// let identity = system.identify(named_path)
+    ///     .wait_timeout(Duration::from_millis(1000))
+    ///     .expect("identity");
+    /// assert_eq!(identity, ActorIdentity::Actor(system.actor_path_for(&c)));
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn identify(&self, path: ActorPath) -> KFuture<ActorIdentity> {
+        self.inner.assert_active();
+        let (promise, future) = utils::promise();
+        self.inner
+            .dispatcher_ref()
+            .tell(DispatchEnvelope::Identify { path, promise });
+        future
+    }
+
+    /// Lists all named paths registered at or below `prefix`
+    ///
+    /// The returned future completes with one [RegisteredPath](RegisteredPath) per actor
+    /// or routing policy in the prefix's subtree, sorted by path.
+    /// Pass a prefix without any path segments to list everything registered in a system.
+    ///
+    /// Remote prefixes are listed by the remote system's dispatcher.
+    /// If the remote system becomes unreachable, the future fails instead,
+    /// as it does if no answer arrives within the
+    /// [identify timeout](crate::prelude::NetworkConfig::set_identify_timeout).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// system.register_by_alias(&c, "test/c")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// let prefix = system.system_path().into_named_with_string("test").expect("named path");
+    /// let entries = system.list_paths(prefix)
+    ///     .wait_timeout(Duration::from_millis(1000))
+    ///     .expect("entries");
+    /// assert_eq!(entries.len(), 1);
+    /// assert_eq!(entries[0].entry, PathEntry::Actor(c.id()));
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn list_paths(&self, prefix: NamedPath) -> KFuture<Vec<RegisteredPath>> {
+        self.inner.assert_active();
+        let (promise, future) = utils::promise();
+        self.inner
+            .dispatcher_ref()
+            .tell(DispatchEnvelope::ListPaths { prefix, promise });
+        future
+    }
+
+    /// Send `request` to every member of the group at `target` and gather their replies
+    ///
+    /// The `target` is usually a broadcast path, such as `group/*`, or the path of a broadcast policy.
+    /// The members of the group are the actors [listed](KompactSystem::list_paths) under it.
+    /// If `target` is a unique path, it is treated as a group with a single member.
+    ///
+    /// The request is sent by a temporary actor, which collects the members' replies
+    /// until `policy` is satisfied, every member has replied, or `timeout` has expired.
+    /// Replies are matched to members via their sender, so members must reply
+    /// to the sender of the request with their own path.
+    ///
+    /// The returned future contains the replies by member path,
+    /// together with the members that didn't reply in time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::{prelude::*, routing::{groups::BroadcastRouting, scatter_gather::GatherPolicy}};
+    /// use std::time::Duration;
+    ///
+    /// #[derive(ComponentDefinition)]
+    /// struct Responder {
+    ///     ctx: ComponentContext<Self>,
+    ///     value: u64,
+    /// }
+    /// # impl Responder {
+    /// #     fn new(value: u64) -> Responder {
+    /// #         Responder {
+    /// #             ctx: ComponentContext::uninitialised(),
+    /// #             value,
+    /// #         }
+    /// #     }
+    /// # }
+    /// # ignore_lifecycle!(Responder);
+    /// impl Actor for Responder {
+    ///     type Message = Never;
+    ///
+    /// #    fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+    /// #        unimplemented!("We don't care about this.");
+    /// #    }
+    ///     fn receive_network(&mut self, msg: NetMessage) -> Handled {
+    ///         msg.sender.tell(self.value, self);
+    ///         Handled::Ok
+    ///     }
+    /// }
+    ///
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let group = system
+    ///     .set_routing_policy(BroadcastRouting::default(), "group", false)
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to set policy");
+    /// for i in 0..3u64 {
+    ///     let responder = system.create(move || Responder::new(i));
+    ///     system.register_by_alias(&responder, format!("group/responder{}", i))
+    ///         .wait_expect(Duration::from_millis(1000), "Failed to register Responder");
+    ///     system.start(&responder);
+    /// }
+    /// let gathered = system
+    ///     .scatter_gather(group, (), GatherPolicy::All, Duration::from_millis(1000))
+    ///     .wait();
+    /// assert!(gathered.complete);
+    /// let mut values: Vec<u64> = gathered
+    ///     .into_replies()
+    ///     .into_iter()
+    ///     .map(|reply| reply.try_deserialise::<u64, u64>().expect("u64"))
+    ///     .collect();
+    /// values.sort();
+    /// assert_eq!(values, vec![0, 1, 2]);
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    pub fn scatter_gather<B>(
+        &self,
+        target: ActorPath,
+        request: B,
+        policy: GatherPolicy,
+        timeout: Duration,
+    ) -> KFuture<Gathered<ActorPath, NetMessage>>
+    where
+        B: Into<Box<dyn Serialisable>>,
+    {
+        self.inner.assert_active();
+        let (promise, future) = utils::promise();
+        let request = request.into();
+        let gatherer =
+            self.create(move || ScatterGather::new(target, request, policy, timeout, promise));
+        self.start(&gatherer);
+        future
+    }
+
     /// Start a component
     ///
     /// A component only handles events/messages once it is started.
@@ -891,6 +1266,21 @@ impl KompactSystem {
         self.inner.max_messages
     }
 
+    /// Return the configured default mailbox for new components
+    ///
+    /// See also [MAILBOX_CAPACITY](crate::config_keys::system::MAILBOX_CAPACITY)
+    /// and [MAILBOX_OVERFLOW](crate::config_keys::system::MAILBOX_OVERFLOW).
+    pub fn default_mailbox(&self) -> MailboxConfig {
+        self.inner.mailbox
+    }
+
+    /// Return the maximum number of messages a component can stash
+    ///
+    /// See also [STASH_CAPACITY](crate::config_keys::system::STASH_CAPACITY).
+    pub fn stash_capacity(&self) -> usize {
+        self.inner.stash_capacity
+    }
+
     /// Wait for the Kompact system to be terminated
     ///
     /// Suspends this thread until the system is terminated
@@ -991,6 +1381,15 @@ impl KompactSystem {
         });
     }
 
+    /// Returns the dispatcher, unless the system is shutting down
+    pub(crate) fn active_dispatcher_ref(&self) -> Option<DispatcherRef> {
+        if self.inner.is_active() {
+            Some(self.inner.dispatcher_ref())
+        } else {
+            None
+        }
+    }
+
     /// Return the system path of this Kompact system
     ///
     /// The system path forms a prefix for every [ActorPath](prelude::ActorPath).
@@ -999,6 +1398,15 @@ impl KompactSystem {
         self.inner.system_path()
     }
 
+    /// Return the backend driving this system's network
+    ///
+    /// Returns `None` if the system has no network,
+    /// and [NetworkBackend::Mio] if the configured backend could not be set up.
+    pub fn network_backend(&self) -> Option<NetworkBackend> {
+        self.inner.assert_active();
+        self.get_system_components().network_backend()
+    }
+
     /// Generate an unique path for the given component
     ///
     /// Produces a unique id [ActorPath](prelude::ActorPath) for `component`
@@ -1050,6 +1458,14 @@ impl KompactSystem {
         self.inner.supervision_port()
     }
 
+    /// Returns the fault counters of all components in this system
+    ///
+    /// This is meant for monitoring purposes, to detect components that fault repeatedly.
+    /// See [RestartPolicy](crate::prelude::RestartPolicy) for limiting restarts.
+    pub fn fault_statistics(&self) -> FaultStatistics {
+        self.inner.fault_statistics()
+    }
+
     /// The remote path for the deadletter box
     pub fn deadletter_path(&self) -> ActorPath {
         ActorPath::Named(NamedPath::with_system(self.system_path(), Vec::new()))
@@ -1328,6 +1744,187 @@ pub trait SystemHandle: Dispatching + CanCancelTimers {
     where
         P: Into<StorePolicy>;
 
+    /// Attempts to remove the actor registered with the given alias
+    ///
+    /// The returned future will contain the named [ActorPath](ActorPath)
+    /// that was removed, once it is completed by the dispatcher.
+    ///
+    /// The entry is removed immediately, instead of waiting for the reaper to notice
+    /// a deallocated component. Deregistration fails with
+    /// [NotFound](crate::prelude::RegistrationError::NotFound), if no actor is registered with the alias.
+    /// Routing policies and actors registered under the alias are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// system.register_by_alias(&c, "test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// system.deregister_alias("test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to deregister TestComponent1");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn deregister_alias<A>(&self, alias: A) -> KFuture<RegistrationResult>
+    where
+        A: Into<String>;
+
+    /// Attempts to remove the unique registration of the provided component
+    ///
+    /// The returned future will contain the unique [ActorPath](ActorPath)
+    /// that was removed, once it is completed by the dispatcher.
+    ///
+    /// Deregistration fails with [NotFound](crate::prelude::RegistrationError::NotFound),
+    /// if the component was not registered. Aliases of the component are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let (c, registration_future) = system.create_and_register(TestComponent1::new);
+    /// registration_future.wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1");
+    /// system.deregister_unique(&c)
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to deregister TestComponent1");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn deregister_unique(&self, c: &dyn UniqueRegistrable) -> KFuture<RegistrationResult>;
+
+    /// Attempts to remove the routing policy at `path`
+    ///
+    /// The returned future will contain the named [ActorPath](ActorPath)
+    /// of the removed policy, once it is completed by the dispatcher.
+    ///
+    /// Deregistration fails with [NotFound](crate::prelude::RegistrationError::NotFound),
+    /// if there is no policy at `path`. Actors registered under `path` are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// use kompact::routing::groups::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// system.set_routing_policy(BroadcastRouting::default(), "broadcast-me", false)
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to set broadcast policy");
+    /// system.remove_routing_policy("broadcast-me")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to remove broadcast policy");
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn remove_routing_policy(&self, path: &str) -> KFuture<RegistrationResult>;
+
+    /// Asks the dispatcher of `path`'s system what is registered at `path`
+    ///
+    /// The returned future completes with the [ActorIdentity](crate::prelude::ActorIdentity) of the path,
+    /// which is either [NotFound](crate::prelude::ActorIdentity::NotFound), the unique path of a single actor,
+    /// or the unique paths of all members behind a broadcast, select, or policy path.
+    ///
+    /// Remote paths are resolved by the remote system's dispatcher.
+    /// If the remote system becomes unreachable, the future fails instead,
+    /// as it does if no answer arrives within the
+    /// [identify timeout](crate::prelude::NetworkConfig::set_identify_timeout).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// let named_path = system.register_by_alias(&c, "test")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// let identity = system.identify(named_path)
+    ///     .wait_timeout(Duration::from_millis(1000))
+    ///     .expect("identity");
+    /// assert_eq!(identity, ActorIdentity::Actor(system.actor_path_for(&c)));
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn identify(&self, path: ActorPath) -> KFuture<ActorIdentity>;
+
+    /// Lists all named paths registered at or below `prefix`
+    ///
+    /// The returned future completes with one [RegisteredPath](crate::prelude::RegisteredPath)
+    /// per actor or routing policy in the prefix's subtree, sorted by path.
+    /// Pass a prefix without any path segments to list everything registered in a system.
+    ///
+    /// Remote prefixes are listed by the remote system's dispatcher.
+    /// If the remote system becomes unreachable, the future fails instead,
+    /// as it does if no answer arrives within the
+    /// [identify timeout](crate::prelude::NetworkConfig::set_identify_timeout).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use kompact::prelude::*;
+    /// # use kompact::doctest_helpers::*;
+    /// use std::time::Duration;
+    /// let mut cfg = KompactConfig::new();
+    /// cfg.system_components(DeadletterBox::new, {
+    ///     let net_config = NetworkConfig::new("127.0.0.1:0".parse().expect("Address should work"));
+    ///     net_config.build()
+    /// });
+    /// let system = cfg.build().expect("KompactSystem");
+    /// let c = system.create(TestComponent1::new);
+    /// system.register_by_alias(&c, "test/c")
+    ///     .wait_expect(Duration::from_millis(1000), "Failed to register TestComponent1 by alias");
+    /// let prefix = system.system_path().into_named_with_string("test").expect("named path");
+    /// let entries = system.list_paths(prefix)
+    ///     .wait_timeout(Duration::from_millis(1000))
+    ///     .expect("entries");
+    /// assert_eq!(entries.len(), 1);
+    /// assert_eq!(entries[0].entry, PathEntry::Actor(c.id()));
+    /// # system.shutdown().expect("shutdown");
+    /// ```
+    fn list_paths(&self, prefix: NamedPath) -> KFuture<Vec<RegisteredPath>>;
+
+    /// Send `request` to every member of the group at `target` and gather their replies
+    ///
+    /// The `target` is usually a broadcast path, such as `group/*`, or the path of a broadcast policy.
+    /// The members of the group are the actors [listed](SystemHandle::list_paths) under it.
+    /// If `target` is a unique path, it is treated as a group with a single member.
+    ///
+    /// The request is sent by a temporary actor, which collects the members' replies
+    /// until `policy` is satisfied, every member has replied, or `timeout` has expired.
+    /// Replies are matched to members via their sender, so members must reply
+    /// to the sender of the request with their own path.
+    ///
+    /// See [KompactSystem::scatter_gather] for an example.
+    fn scatter_gather<B>(
+        &self,
+        target: ActorPath,
+        request: B,
+        policy: GatherPolicy,
+        timeout: Duration,
+    ) -> KFuture<Gathered<ActorPath, NetMessage>>
+    where
+        B: Into<Box<dyn Serialisable>>;
+
     /// Start a component
     ///
     /// A component only handles events/messages once it is started.
@@ -1575,6 +2172,10 @@ pub trait SystemComponents: Send + Sync + 'static {
     }
     /// Allow subscribing to `NetworkStatusUpdate` messages
     fn connect_network_status_port(&self, required: &mut RequiredPort<NetworkStatusPort>) -> ();
+    /// Returns the backend driving the network, if the system has a running one
+    fn network_backend(&self) -> Option<NetworkBackend> {
+        None
+    }
 }
 
 impl dyn SystemComponents {
@@ -1608,6 +2209,7 @@ pub trait TimerComponent: TimerRefFactory + Send + Sync {
 struct InternalComponents {
     supervisor: Arc<Component<ComponentSupervisor>>,
     supervision_port: ProvidedRef<SupervisionPort>,
+    fault_counters: Arc<FaultCounters>,
     system_components: Box<dyn SystemComponents>,
 }
 
@@ -1616,10 +2218,12 @@ impl InternalComponents {
         supervisor: Arc<Component<ComponentSupervisor>>,
         system_components: Box<dyn SystemComponents>,
     ) -> InternalComponents {
-        let supervision_port = supervisor.on_definition(|s| s.supervision.share());
+        let (supervision_port, fault_counters) =
+            supervisor.on_definition(|s| (s.supervision.share(), s.fault_counters()));
         InternalComponents {
             supervisor,
             supervision_port,
+            fault_counters,
             system_components,
         }
     }
@@ -1649,6 +2253,10 @@ impl InternalComponents {
         self.supervision_port.clone()
     }
 
+    fn fault_statistics(&self) -> FaultStatistics {
+        self.fault_counters.statistics()
+    }
+
     fn stop(&self, system: &KompactSystem) -> () {
         let (p, f) = utils::promise();
         self.supervision_port
@@ -1674,6 +2282,8 @@ struct KompactRuntime {
     label: String,
     throughput: usize,
     max_messages: usize,
+    mailbox: MailboxConfig,
+    stash_capacity: usize,
     timer: Box<dyn TimerComponent>,
     internal_components: OnceMutex<Option<InternalComponents>>,
     logger: KompactLogger,
@@ -1691,6 +2301,8 @@ impl KompactRuntime {
             label: conf.label,
             throughput: conf.throughput,
             max_messages: mm,
+            mailbox: conf.mailbox,
+            stash_capacity: conf.stash_capacity,
             timer: (conf.timer_builder)(),
             internal_components: OnceMutex::new(None),
             logger,
@@ -1790,6 +2402,34 @@ impl KompactRuntime {
         future
     }
 
+    fn deregister(&self, deregistration: Deregistration) -> KFuture<RegistrationResult> {
+        debug!(
+            self.logger(),
+            "Requesting deregistration of {:?}", deregistration
+        );
+        let (promise, future) = utils::promise();
+        let dispatcher = self.dispatcher_ref();
+        let envelope = MsgEnvelope::Typed(DispatchEnvelope::Registration(
+            RegistrationEnvelope::deregistration_with_promise(deregistration, promise),
+        ));
+        dispatcher.enqueue(envelope);
+        future
+    }
+
+    fn remove_routing_policy(&self, path: &str) -> KFuture<RegistrationResult> {
+        let parsed_path = crate::actors::parse_path(path);
+        match crate::actors::validate_insert_path(&parsed_path) {
+            Ok(_) => self.deregister(Deregistration::Policy(parsed_path)),
+            Err(e) => {
+                let (promise, future) = utils::promise();
+                promise
+                    .fulfil(Err(RegistrationError::InvalidPath(e)))
+                    .expect("should fulfil promise");
+                future
+            }
+        }
+    }
+
     fn deadletter_ref(&self) -> ActorRef<Never> {
         match *self.internal_components {
             Some(ref sc) => sc.deadletter_ref(),
@@ -1825,6 +2465,13 @@ impl KompactRuntime {
         }
     }
 
+    fn fault_statistics(&self) -> FaultStatistics {
+        match *self.internal_components {
+            Some(ref ic) => ic.fault_statistics(),
+            None => panic!("KompactRuntime was not properly initialised!"),
+        }
+    }
+
     fn timer_ref(&self) -> timer::TimerRef {
         self.timer.timer_ref()
     }
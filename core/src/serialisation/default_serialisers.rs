@@ -36,6 +36,21 @@ pub mod serialisation_ids {
     /// Id for a `()` (unit type) serialiser.
     pub const UNIT: SerId = 8;
 
+    /// Id for the death-watch protocol between dispatchers.
+    pub const WATCH: SerId = 9;
+
+    /// Id for a [Terminated](crate::prelude::Terminated) notification.
+    pub const TERMINATED: SerId = 10;
+
+    /// Id for the actor identification protocol between dispatchers.
+    pub const IDENTIFY: SerId = 11;
+
+    /// Id for a [MembershipEvent](crate::prelude::MembershipEvent) notification.
+    pub const MEMBERSHIP: SerId = 12;
+
+    /// Id for the membership watch protocol between dispatchers.
+    pub const MEMBERSHIP_WATCH: SerId = 13;
+
     /// Id for the Serde serialiser
     pub const SERDE: SerId = 19;
 
@@ -248,6 +248,31 @@ pub fn deserialise_chunk_lease(mut buffer: ChunkLease) -> Result<NetMessage, Ser
     Ok(envelope)
 }
 
+/// Extracts a [NetMessage](NetMessage) with an already deserialised `receiver` from the provided buffer
+///
+/// This expects the format from [serialise_msg](serialise_msg),
+/// and only reads the sender and the payload.
+/// The receiver's serialised path, `receiver_len` bytes long, is skipped.
+pub(crate) fn deserialise_chunk_lease_to(
+    mut buffer: ChunkLease,
+    receiver: ActorPath,
+    receiver_len: usize,
+) -> Result<NetMessage, SerError> {
+    let src = ActorPath::deserialise(&mut buffer)?;
+    if buffer.remaining() < receiver_len {
+        return Err(SerError::InvalidData(format!(
+            "Could not skip {} bytes for the receiver",
+            receiver_len
+        )));
+    }
+    buffer.advance(receiver_len);
+    let ser_id = buffer.get_ser_id();
+
+    let envelope = NetMessage::with_chunk_ref(ser_id, src, receiver, buffer.into_chunk_ref());
+
+    Ok(envelope)
+}
+
 /// Extracts a [NetMessage](NetMessage) from the provided buffer
 ///
 /// This expects the format from [serialise_msg](serialise_msg).
@@ -1,15 +1,21 @@
 use super::prelude::*;
 use crate::{
-    component::ContextSystemHandle,
+    component::{ComponentCore, ContextSystemHandle, Registrations, Snapshot, StoredSnapshot},
     utils::{Completable, KPromise},
     ControlEvent,
     KompactLogger,
 };
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     fmt,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+        Mutex,
+        Weak,
+    },
+    time::{Duration, Instant},
 };
 use uuid::Uuid;
 
@@ -37,21 +43,213 @@ impl ListenEvent {
     }
 }
 
+/// How a component handles faults of its children
+///
+/// Children are created via [create_child](crate::prelude::ComponentContext::create_child)
+/// and the strategy is set on the parent via
+/// [set_supervision_strategy](crate::prelude::ComponentContext::set_supervision_strategy).
+/// Components without a parent are always recovered [one-for-one](SupervisionStrategy::OneForOne).
+///
+/// *Restarting* a component that did not fault itself means killing it
+/// and then running its recovery function, just as if it had faulted.
+/// The [fault](FaultContext::fault) it is given is a `String` describing the
+/// fault of its sibling.
+///
+/// Independent of the strategy, all children of a component are killed
+/// whenever the component is killed or faults itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// Only recover the faulty child
+    OneForOne,
+    /// Recover the faulty child and restart all its siblings
+    OneForAll,
+    /// Recover the faulty child and restart all siblings that were started after it
+    RestForOne,
+    /// Treat the fault as a fault of the parent
+    ///
+    /// The faulty child is not recovered itself. Instead the parent is killed
+    /// (taking all its children with it) and its own recovery function is invoked
+    /// with the child's fault, according to the strategy of the parent's parent.
+    Escalate,
+}
+
+impl Default for SupervisionStrategy {
+    fn default() -> Self {
+        SupervisionStrategy::OneForOne
+    }
+}
+
+/// What to do when a component exceeds the restart limit of its [RestartPolicy]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartLimitAction {
+    /// Do not recover the component anymore
+    GiveUp,
+    /// Treat the fault as a fault of the component's parent
+    ///
+    /// See [SupervisionStrategy::Escalate].
+    /// Components without a parent give up instead.
+    Escalate,
+    /// Shut down the whole Kompact system
+    ShutdownSystem,
+}
+
+impl Default for RestartLimitAction {
+    fn default() -> Self {
+        RestartLimitAction::GiveUp
+    }
+}
+
+/// Limits how often, and how quickly, a component is recovered from faults
+///
+/// A component is only recovered, if it was recovered fewer than `max_restarts`
+/// times within the last `within` period. Otherwise the `limit_action` is taken.
+///
+/// Each restart within the period is delayed exponentially longer,
+/// starting at `min_backoff` and doubling up to `max_backoff`.
+/// By default restarts are not delayed at all.
+///
+/// The policy is set via [set_restart_policy](crate::prelude::ComponentContext::set_restart_policy)
+/// and is inherited by all components created by the recovery handler,
+/// together with the number of restarts so far, unless they set a policy of their own.
+///
+/// # Example
+///
+/// ```
+/// # use kompact::prelude::*;
+/// use std::time::Duration;
+///
+/// let policy = RestartPolicy::new(5, Duration::from_secs(60))
+///     .with_backoff(Duration::from_millis(10), Duration::from_secs(1))
+///     .with_limit_action(RestartLimitAction::Escalate);
+/// assert_eq!(policy.backoff(1), Duration::from_millis(10));
+/// assert_eq!(policy.backoff(3), Duration::from_millis(40));
+/// assert_eq!(policy.backoff(100), Duration::from_secs(1));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// The maximum number of restarts within the period
+    pub max_restarts: usize,
+    /// The length of the period
+    pub within: Duration,
+    /// The delay before the first restart within the period
+    pub min_backoff: Duration,
+    /// The maximum delay before a restart
+    pub max_backoff: Duration,
+    /// What to do when the limit is exceeded
+    pub limit_action: RestartLimitAction,
+}
+
+impl RestartPolicy {
+    /// Allow at most `max_restarts` restarts `within` the given period, without any backoff
+    ///
+    /// When the limit is exceeded, the component is not recovered anymore.
+    pub fn new(max_restarts: usize, within: Duration) -> Self {
+        RestartPolicy {
+            max_restarts,
+            within,
+            min_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            limit_action: RestartLimitAction::default(),
+        }
+    }
+
+    /// Delay restarts exponentially, from `min_backoff` up to `max_backoff`
+    pub fn with_backoff(self, min_backoff: Duration, max_backoff: Duration) -> Self {
+        RestartPolicy {
+            min_backoff,
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Take `action` when the restart limit is exceeded
+    pub fn with_limit_action(self, action: RestartLimitAction) -> Self {
+        RestartPolicy {
+            limit_action: action,
+            ..self
+        }
+    }
+
+    /// The delay before the `restart`-th restart within the period (counting from 1)
+    pub fn backoff(&self, restart: usize) -> Duration {
+        let exponent = restart.saturating_sub(1).min(31) as u32;
+        self.min_backoff
+            .checked_mul(1u32 << exponent)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Fault counters of a Kompact system, for monitoring purposes
+///
+/// See [fault_statistics](crate::prelude::KompactSystem::fault_statistics).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FaultStatistics {
+    /// The number of component faults so far
+    pub faults: usize,
+    /// The number of recovery handlers run so far
+    pub recoveries: usize,
+    /// The number of times a restart limit was exceeded so far
+    pub limits_exceeded: usize,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct FaultCounters {
+    faults: AtomicUsize,
+    recoveries: AtomicUsize,
+    limits_exceeded: AtomicUsize,
+}
+
+impl FaultCounters {
+    pub(crate) fn statistics(&self) -> FaultStatistics {
+        FaultStatistics {
+            faults: self.faults.load(Ordering::Relaxed),
+            recoveries: self.recoveries.load(Ordering::Relaxed),
+            limits_exceeded: self.limits_exceeded.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// Information about the fault that occurred
 pub struct FaultContext {
     /// The id of the component that faulted
     pub component_id: Uuid,
+    /// The id of the faulty component's parent, if it has one
+    pub parent_id: Option<Uuid>,
     /// The concrete error produced by [catch_unwind](std::panic::catch_unwind)
     pub fault: Box<dyn Any + Send>,
+    /// The number of faults of this component and all the components it replaced,
+    /// including this one
+    ///
+    /// This is only known to the supervisor, so it is always 0 in the recovery function
+    /// and only set when the recovery handler is run.
+    pub fault_count: usize,
+    parent: Option<Weak<dyn CoreContainer>>,
+    restart_policy: Option<RestartPolicy>,
+    snapshot: Option<StoredSnapshot>,
+    registrations: Registrations,
 }
 impl FaultContext {
-    pub(crate) fn new(component_id: Uuid, fault: Box<dyn Any + Send>) -> Self {
+    pub(crate) fn new(core: &ComponentCore, fault: Box<dyn Any + Send>) -> Self {
         FaultContext {
-            component_id,
+            component_id: *core.id(),
+            parent_id: core.parent_id(),
             fault,
+            fault_count: 0,
+            parent: core.parent().map(|parent| Arc::downgrade(&parent)),
+            restart_policy: core.restart_policy(),
+            snapshot: core.last_snapshot(),
+            registrations: core.registrations(),
         }
     }
 
+    /// The faulty component's parent, if it is still alive
+    fn live_parent(&self) -> Option<Arc<dyn CoreContainer>> {
+        self.parent
+            .as_ref()
+            .and_then(|parent| parent.upgrade())
+            .filter(|parent| !parent.core().is_terminated())
+    }
+
     /// Produce a [Recoverhandler](RecoveryHandler) with `f` describing
     /// the actions to take in order to recover from this fault
     ///
@@ -62,6 +260,9 @@ impl FaultContext {
     /// If you need to perform a complicated setup sequence, consider starting
     /// a temporary component to drive the futures involved, instead of handling
     /// everything in the recovery handler.
+    ///
+    /// If the faulty component had a parent, all components created via the provided
+    /// system handle take its place in the hierarchy, i.e. they become children of that parent.
     pub fn recover_with<F>(self, f: F) -> RecoveryHandler
     where
         F: FnOnce(Self, ContextSystemHandle, &KompactLogger) + Send + 'static,
@@ -99,6 +300,50 @@ impl FaultContext {
             system.start(&cd);
         })
     }
+
+    /// Create and start an instance of `C` from the last [snapshot](Snapshot) of the faulty component
+    ///
+    /// The new instance takes over all named paths the faulty component was registered under,
+    /// and is registered under its own unique path, if the faulty component was.
+    /// It keeps the snapshot as its own last good snapshot, until it stores a new one.
+    /// If no snapshot was stored, the new instance is [restored](Snapshot::restore) from `None`.
+    pub fn restore_snapshot<C>(self) -> RecoveryHandler
+    where
+        C: Snapshot,
+    {
+        self.recover_with(move |ctx, system, logger| {
+            let snapshot = ctx.snapshot;
+            let state = snapshot.as_ref().and_then(|snapshot| {
+                snapshot
+                    .restore::<C::State, C::State>()
+                    .map_err(|e| warn!(logger, "Could not restore snapshot: {}", e))
+                    .ok()
+            });
+            info!(
+                logger,
+                "Restoring a {} {} to replace instance with id={}",
+                C::type_name(),
+                if state.is_some() {
+                    "from a snapshot"
+                } else {
+                    "without a snapshot"
+                },
+                ctx.component_id
+            );
+            let cd = system.create(move || C::restore(state));
+            let Registrations { unique, named } = ctx.registrations;
+            if unique {
+                system.reassign(&cd, PathResolvable::ActorId(cd.id()));
+            }
+            for path in named {
+                system.reassign(&cd, PathResolvable::Segments(path));
+            }
+            if let Some(snapshot) = snapshot {
+                cd.core().store_snapshot(snapshot);
+            }
+            system.start(&cd);
+        })
+    }
 }
 impl fmt::Debug for FaultContext {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -114,6 +359,8 @@ impl fmt::Debug for FaultContext {
         };
         f.debug_struct("RecoveryHandler")
             .field("component_id", &self.component_id)
+            .field("parent_id", &self.parent_id)
+            .field("fault_count", &self.fault_count)
             .field("action", &error_msg)
             .finish()
     }
@@ -168,11 +415,62 @@ pub(crate) enum SupervisorMsg {
     Shutdown(Arc<Mutex<KPromise<()>>>),
 }
 
+/// The restart history of a component and all the components it replaced
+#[derive(Clone, Debug, Default)]
+struct Lineage {
+    policy: Option<RestartPolicy>,
+    faults: usize,
+    restarts: VecDeque<Instant>,
+}
+
+impl Lineage {
+    /// Returns the number of faults so far
+    fn record_fault(&mut self) -> usize {
+        self.faults += 1;
+        self.faults
+    }
+
+    /// Returns the delay before the next restart, or the action to take if restarting is not allowed
+    fn next_restart(&mut self, now: Instant) -> Result<Duration, RestartLimitAction> {
+        match self.policy {
+            Some(ref policy) => {
+                while let Some(restart) = self.restarts.front() {
+                    if now.duration_since(*restart) >= policy.within {
+                        self.restarts.pop_front();
+                    } else {
+                        break;
+                    }
+                }
+                if self.restarts.len() >= policy.max_restarts {
+                    Err(policy.limit_action)
+                } else {
+                    self.restarts.push_back(now);
+                    Ok(policy.backoff(self.restarts.len()))
+                }
+            }
+            None => Ok(Duration::ZERO),
+        }
+    }
+}
+
+/// A recovery that is run once the component has been killed
+enum PendingRecovery {
+    /// The component is restarted because of a fault of one of its siblings
+    Restart(RecoveryHandler),
+    /// The component is treated as faulty, e.g. because one of its children escalated a fault
+    Faulty(RecoveryHandler),
+}
+
 #[derive(ComponentDefinition, Actor)]
 pub(crate) struct ComponentSupervisor {
     ctx: ComponentContext<ComponentSupervisor>,
     pub(crate) supervision: ProvidedPort<SupervisionPort>,
     children: HashMap<Uuid, Arc<dyn CoreContainer>>,
+    // the supervision hierarchy, mapping parents to their children in order of starting
+    hierarchy: HashMap<Uuid, Vec<Uuid>>,
+    pending_recoveries: HashMap<Uuid, PendingRecovery>,
+    lineages: HashMap<Uuid, Lineage>,
+    counters: Arc<FaultCounters>,
     listeners: HashMap<Uuid, Vec<(ListenEvent, KPromise<()>)>>,
     shutdown: Option<KPromise<()>>,
 }
@@ -183,11 +481,242 @@ impl ComponentSupervisor {
             ctx: ComponentContext::uninitialised(),
             supervision: ProvidedPort::uninitialised(),
             children: HashMap::new(),
+            hierarchy: HashMap::new(),
+            pending_recoveries: HashMap::new(),
+            lineages: HashMap::new(),
+            counters: Arc::new(FaultCounters::default()),
             listeners: HashMap::new(),
             shutdown: None,
         }
     }
 
+    pub(crate) fn fault_counters(&self) -> Arc<FaultCounters> {
+        self.counters.clone()
+    }
+
+    fn adopt(&mut self, c: &Arc<dyn CoreContainer>) -> () {
+        let id = c.id();
+        if let Some(parent_id) = c.core().parent_id() {
+            match c.core().parent() {
+                Some(ref parent) if !parent.core().is_terminated() => {
+                    let siblings = self.hierarchy.entry(parent_id).or_default();
+                    if !siblings.contains(&id) {
+                        siblings.push(id);
+                    }
+                }
+                _ => {
+                    debug!(
+                        self.ctx.log(),
+                        "Child {} started after its parent {} died. Killing it immediately!",
+                        id,
+                        parent_id
+                    );
+                    c.enqueue_control(ControlEvent::Kill);
+                }
+            }
+        }
+    }
+
+    /// Remove `id` from the children of `parent_id`
+    ///
+    /// Returns the siblings started before and after `id`.
+    fn detach(&mut self, id: &Uuid, parent_id: Option<Uuid>) -> (Vec<Uuid>, Vec<Uuid>) {
+        if let Some(siblings) = parent_id.and_then(|parent_id| self.hierarchy.get_mut(&parent_id)) {
+            if let Some(index) = siblings.iter().position(|sibling| sibling == id) {
+                let after = siblings.split_off(index + 1);
+                siblings.pop();
+                let before = siblings.clone();
+                siblings.extend(after.iter().cloned());
+                return (before, after);
+            }
+        }
+        (Vec::new(), Vec::new())
+    }
+
+    /// Kill all children of `id`, without recovering them
+    fn kill_children(&mut self, id: &Uuid) -> () {
+        if let Some(children) = self.hierarchy.remove(id) {
+            debug!(
+                self.ctx.log(),
+                "Killing {} children of Component({}).",
+                children.len(),
+                id
+            );
+            for child_id in children {
+                self.pending_recoveries.remove(&child_id);
+                if let Some(child) = self.children.get(&child_id) {
+                    child.enqueue_control(ControlEvent::Kill);
+                }
+            }
+        }
+    }
+
+    /// Apply the supervision strategy of the faulty component's parent
+    fn handle_fault(&mut self, handler: RecoveryHandler, siblings: (Vec<Uuid>, Vec<Uuid>)) -> () {
+        let mut handler = handler;
+        let id = handler.ctx.component_id;
+        let parent = handler.ctx.live_parent();
+        let strategy = parent
+            .as_ref()
+            .map(|parent| parent.core().supervision_strategy())
+            .unwrap_or_default();
+        // escalated faults are counted as well, before the parent faults in turn
+        self.counters.faults.fetch_add(1, Ordering::Relaxed);
+        if let (SupervisionStrategy::Escalate, Some(parent)) = (strategy, parent.as_ref()) {
+            debug!(
+                self.ctx.log(),
+                "Escalating fault of Component({}) to Component({}).",
+                id,
+                parent.id()
+            );
+            self.lineages.remove(&id);
+            self.escalate(parent, handler.ctx.fault);
+            return;
+        }
+        let mut lineage = self.lineages.remove(&id).unwrap_or_default();
+        if let Some(policy) = handler.ctx.restart_policy.take() {
+            lineage.policy = Some(policy);
+        }
+        handler.ctx.fault_count = lineage.record_fault();
+        let delay = match lineage.next_restart(Instant::now()) {
+            Ok(delay) => delay,
+            Err(action) => return self.restart_limit_exceeded(handler, parent, action),
+        };
+        let (before, after) = siblings;
+        match strategy {
+            SupervisionStrategy::OneForAll => {
+                for sibling in before.iter().chain(after.iter()) {
+                    self.restart(sibling, &id);
+                }
+            }
+            SupervisionStrategy::RestForOne => {
+                for sibling in after.iter() {
+                    self.restart(sibling, &id);
+                }
+            }
+            _ => (),
+        }
+        if delay.is_zero() {
+            self.recover(handler, lineage);
+        } else {
+            debug!(
+                self.ctx.log(),
+                "Recovering Component({}) in {:?}.", id, delay
+            );
+            self.schedule_once(delay, move |supervisor, _timer| {
+                if supervisor.shutdown.is_none() {
+                    supervisor.recover(handler, lineage);
+                }
+                Handled::Ok
+            });
+        }
+    }
+
+    fn restart_limit_exceeded(
+        &mut self,
+        handler: RecoveryHandler,
+        parent: Option<Arc<dyn CoreContainer>>,
+        action: RestartLimitAction,
+    ) -> () {
+        let id = handler.ctx.component_id;
+        self.counters
+            .limits_exceeded
+            .fetch_add(1, Ordering::Relaxed);
+        match (action, parent) {
+            (RestartLimitAction::Escalate, Some(parent)) => {
+                warn!(
+                    self.ctx.log(),
+                    "Component({}) exceeded its restart limit. Escalating to Component({}).",
+                    id,
+                    parent.id()
+                );
+                self.escalate(&parent, handler.ctx.fault);
+            }
+            (RestartLimitAction::ShutdownSystem, _) => {
+                error!(
+                    self.ctx.log(),
+                    "Component({}) exceeded its restart limit. Shutting down the system!", id
+                );
+                self.ctx.system().shutdown_async();
+            }
+            _ => {
+                error!(
+                    self.ctx.log(),
+                    "Component({}) exceeded its restart limit. Giving up on it!", id
+                );
+            }
+        }
+    }
+
+    /// Kill `id` and run its recovery function, once it is dead
+    fn restart(&mut self, id: &Uuid, faulty_sibling: &Uuid) -> () {
+        if let Some(c) = self.children.get(id) {
+            debug!(
+                self.ctx.log(),
+                "Restarting Component({}) due to fault of sibling {}.", id, faulty_sibling
+            );
+            let fault = format!("Sibling {} faulted", faulty_sibling);
+            let ctx = FaultContext::new(c.core(), Box::new(fault));
+            if let Some(handler) = c.core().recovery_handler(ctx) {
+                self.pending_recoveries
+                    .insert(*id, PendingRecovery::Restart(handler));
+            }
+            c.enqueue_control(ControlEvent::Kill);
+        }
+    }
+
+    /// Kill `parent` and handle `fault` as its own, once it is dead
+    fn escalate(&mut self, parent: &Arc<dyn CoreContainer>, fault: Box<dyn Any + Send>) -> () {
+        let ctx = FaultContext::new(parent.core(), fault);
+        if let Some(handler) = parent.core().recovery_handler(ctx) {
+            self.pending_recoveries
+                .insert(parent.id(), PendingRecovery::Faulty(handler));
+        }
+        parent.enqueue_control(ControlEvent::Kill);
+    }
+
+    fn run_pending_recovery(&mut self, pending: PendingRecovery, siblings: (Vec<Uuid>, Vec<Uuid>)) {
+        if self.shutdown.is_some() {
+            warn!(
+                self.log(),
+                "Not running recovery handler due to ongoing shutdown."
+            );
+            return;
+        }
+        match pending {
+            PendingRecovery::Restart(handler) => {
+                let lineage = self
+                    .lineages
+                    .remove(&handler.ctx.component_id)
+                    .unwrap_or_default();
+                self.recover(handler, lineage)
+            }
+            PendingRecovery::Faulty(handler) => self.handle_fault(handler, siblings),
+        }
+    }
+
+    fn recover(&mut self, handler: RecoveryHandler, lineage: Lineage) -> () {
+        let parent = handler.ctx.live_parent();
+        if handler.ctx.parent_id.is_some() && parent.is_none() {
+            debug!(
+                self.ctx.log(),
+                "Not recovering Component({}), as its parent is dead.", handler.ctx.component_id
+            );
+            return;
+        }
+        // replacements take the place of the faulty component in the hierarchy
+        let replacements = Arc::new(Mutex::new(Vec::new()));
+        let system = self
+            .ctx
+            .context_system()
+            .for_recovery(parent, replacements.clone());
+        self.counters.recoveries.fetch_add(1, Ordering::Relaxed);
+        handler.recover(system, self.ctx.log());
+        for replacement in replacements.lock().unwrap().drain(..) {
+            self.lineages.insert(replacement, lineage.clone());
+        }
+    }
+
     fn notify_listeners<S>(&mut self, id: &Uuid, selector: S)
     where
         S: Fn(&ListenEvent) -> bool,
@@ -256,6 +785,7 @@ impl Provide<SupervisionPort> for ComponentSupervisor {
             SupervisorMsg::Started(c) => {
                 let id = c.id();
                 self.children.insert(id, c.clone());
+                self.adopt(&c);
                 debug!(self.ctx.log(), "Component({}) was started.", id);
                 self.notify_listeners(&id, |l| matches!(l, ListenEvent::Started(_)));
                 if self.shutdown.is_some() {
@@ -271,6 +801,10 @@ impl Provide<SupervisionPort> for ComponentSupervisor {
                 self.notify_listeners(&id, |l| matches!(l, ListenEvent::Stopped(_)));
             }
             SupervisorMsg::Killed(id) => {
+                let parent_id = self.children.get(&id).and_then(|c| c.core().parent_id());
+                let siblings = self.detach(&id, parent_id);
+                self.kill_children(&id);
+                let pending = self.pending_recoveries.remove(&id);
                 match self.children.remove(&id) {
                     Some(carc) => {
                         let count = Arc::strong_count(&carc);
@@ -289,6 +823,12 @@ impl Provide<SupervisionPort> for ComponentSupervisor {
                     }
                     None => warn!(self.ctx.log(), "An untracked Component({}) was killed.", id),
                 }
+                match pending {
+                    Some(pending) => self.run_pending_recovery(pending, siblings),
+                    None => {
+                        self.lineages.remove(&id);
+                    }
+                }
                 self.shutdown_if_no_more_children()
             }
             SupervisorMsg::Faulty(recover_handler) => {
@@ -298,18 +838,18 @@ impl Provide<SupervisionPort> for ComponentSupervisor {
                     "Component({}) has been marked as faulty.", id
                 );
                 self.drop_listeners(&id); // will never be fulfilled
+                let siblings = self.detach(&id, recover_handler.ctx.parent_id);
+                self.kill_children(&id);
                 match self.children.remove(&id) {
                     Some(carc) => drop(carc),
                     None => warn!(self.ctx.log(), "Component({}) faulted during start!.", id),
                 }
-                if self.shutdown.is_none() {
-                    recover_handler.recover(self.ctx.context_system(), self.ctx.log());
-                } else {
-                    warn!(
-                        self.log(),
-                        "Not running recovery handler due to ongoing shutdown."
-                    );
-                }
+                // a component that was already being restarted uses the recovery we took from it
+                let pending = self
+                    .pending_recoveries
+                    .remove(&id)
+                    .unwrap_or(PendingRecovery::Faulty(recover_handler));
+                self.run_pending_recovery(pending, siblings);
                 self.shutdown_if_no_more_children()
             }
             SupervisorMsg::Listen(amp, event) => match Arc::try_unwrap(amp) {
@@ -356,3 +896,41 @@ impl Provide<SupervisionPort> for ComponentSupervisor {
         Handled::Ok
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lineage_limits_restarts_within_period() {
+        let within = Duration::from_secs(10);
+        let policy = RestartPolicy::new(2, within)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(150))
+            .with_limit_action(RestartLimitAction::ShutdownSystem);
+        let mut lineage = Lineage {
+            policy: Some(policy),
+            ..Lineage::default()
+        };
+        let start = Instant::now();
+        assert_eq!(lineage.next_restart(start), Ok(Duration::from_millis(100)));
+        assert_eq!(
+            lineage.next_restart(start + Duration::from_secs(1)),
+            Ok(Duration::from_millis(150))
+        );
+        assert_eq!(
+            lineage.next_restart(start + Duration::from_secs(2)),
+            Err(RestartLimitAction::ShutdownSystem)
+        );
+        // the first restart has left the period
+        assert_eq!(
+            lineage.next_restart(start + within),
+            Ok(Duration::from_millis(150))
+        );
+
+        let mut unlimited = Lineage::default();
+        for _ in 0..10 {
+            assert_eq!(unlimited.next_restart(start), Ok(Duration::ZERO));
+        }
+        assert_eq!(unlimited.record_fault(), 1);
+    }
+}
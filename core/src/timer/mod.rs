@@ -1,9 +1,11 @@
+use std::{fmt, time::Duration};
 use uuid::Uuid;
 
 use hierarchical_hash_wheel_timer::{
     thread_timer::{TimerRef as GenericTimerRef, TimerWithThread as GenericTimerWithThread},
     OneshotState,
     PeriodicState,
+    Timer as GenericTimer,
     TimerEntry as GenericTimerEntry,
     TimerReturn as GenericTimerReturn,
 };
@@ -53,16 +55,50 @@ pub type TimerRef = GenericTimerRef<Uuid, ActorRefState, ActorRefState>;
 /// The concrete vairant of timer thread used in Kompact
 pub type TimerWithThread = GenericTimerWithThread<Uuid, ActorRefState, ActorRefState>;
 
+/// Runs `action` on the timer thread once `timeout` has passed
+///
+/// The `action` must be quick, as it holds up all other timers.
+pub(crate) fn run_once<F>(timer: &mut TimerRef, timeout: Duration, action: F) -> ()
+where
+    F: FnOnce() + Send + 'static,
+{
+    let state = ActorRefState {
+        id: Uuid::new_v4(),
+        receiver: TimerReceiver::Action(Box::new(action)),
+    };
+    timer.schedule_once(timeout, state);
+}
+
 /// The necessary state for Kompact timers
 #[derive(Debug)]
 pub struct ActorRefState {
     id: Uuid,
-    receiver: TimerActorRef,
+    receiver: TimerReceiver,
 }
 
 impl ActorRefState {
     fn new(id: Uuid, receiver: TimerActorRef) -> Self {
-        ActorRefState { id, receiver }
+        ActorRefState {
+            id,
+            receiver: TimerReceiver::Actor(receiver),
+        }
+    }
+}
+
+/// Whatever is triggered when a timer expires
+enum TimerReceiver {
+    /// A component, which runs the timer's handler itself
+    Actor(TimerActorRef),
+    /// An action run directly on the timer thread, only used for one-shot timers
+    Action(Box<dyn FnOnce() + Send>),
+}
+
+impl fmt::Debug for TimerReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerReceiver::Actor(receiver) => f.debug_tuple("Actor").field(receiver).finish(),
+            TimerReceiver::Action(_) => f.write_str("Action(<fn>)"),
+        }
     }
 }
 
@@ -74,8 +110,13 @@ impl OneshotState for ActorRefState {
     }
 
     fn trigger(self) -> () {
-        // Ignore the Result, as we are anyway not trying to reschedule this timer
-        let _res = self.receiver.enqueue(Timeout(self.id));
+        match self.receiver {
+            TimerReceiver::Actor(receiver) => {
+                // Ignore the Result, as we are anyway not trying to reschedule this timer
+                let _res = receiver.enqueue(Timeout(self.id));
+            }
+            TimerReceiver::Action(action) => action(),
+        }
     }
 }
 
@@ -87,10 +128,13 @@ impl PeriodicState for ActorRefState {
     }
 
     fn trigger(self) -> GenericTimerReturn<Self> {
-        // Ignore the Result, as we are anyway not trying to reschedule this timer
-        match self.receiver.enqueue(Timeout(self.id)) {
-            Ok(_) => GenericTimerReturn::Reschedule(self),
-            Err(_) => GenericTimerReturn::Cancel, // Queue has probably been deallocated, so no point in trying this over and over again
+        match self.receiver {
+            TimerReceiver::Actor(ref receiver) => match receiver.enqueue(Timeout(self.id)) {
+                Ok(_) => GenericTimerReturn::Reschedule(self),
+                Err(_) => GenericTimerReturn::Cancel, // Queue has probably been deallocated, so no point in trying this over and over again
+            },
+            // Actions are only ever scheduled once
+            TimerReceiver::Action(_) => GenericTimerReturn::Cancel,
         }
     }
 }
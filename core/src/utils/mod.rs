@@ -91,14 +91,39 @@ pub fn biconnect_components<P, C1, C2>(
     provider: &Arc<Component<C1>>,
     requirer: &Arc<Component<C2>>,
 ) -> Result<TwoWayChannel<P, C1, C2>, TryDualLockError>
+where
+    P: Port + 'static,
+    C1: ComponentDefinition + Sized + 'static + Provide<P> + ProvideRef<P>,
+    C2: ComponentDefinition + Sized + 'static + Require<P> + RequireRef<P>,
+{
+    biconnect_components_with_interceptors(provider, requirer, InterceptorChain::new())
+}
+
+/// Connect two components on their instances of port type `P`, passing all events through `interceptors`.
+///
+/// Requests from the requirer pass through the chain's request interceptors before reaching the provider,
+/// and indications from the provider pass through its indication interceptors before reaching the requirer.
+/// Other connections of the same ports are not affected.
+///
+/// The component providing the port must be given as first argument, and the requiring component second.
+///
+/// This function can fail with a [`TryDualLockError`](enum.TryDualLockError.html), if one of the mutexes can not be acquired immediately.
+///
+/// See [PortInterceptor](crate::prelude::PortInterceptor) for an example.
+pub fn biconnect_components_with_interceptors<P, C1, C2>(
+    provider: &Arc<Component<C1>>,
+    requirer: &Arc<Component<C2>>,
+    interceptors: InterceptorChain<P>,
+) -> Result<TwoWayChannel<P, C1, C2>, TryDualLockError>
 where
     P: Port + 'static,
     C1: ComponentDefinition + Sized + 'static + Provide<P> + ProvideRef<P>,
     C2: ComponentDefinition + Sized + 'static + Require<P> + RequireRef<P>,
 {
     let (provided_ref, required_ref) = on_dual_definition(provider, requirer, |prov, req| {
-        let prov_share: ProvidedRef<P> = prov.provided_ref();
-        let req_share: RequiredRef<P> = req.required_ref();
+        let prov_share: ProvidedRef<P> =
+            prov.provided_ref().with_interceptors(interceptors.clone());
+        let req_share: RequiredRef<P> = req.required_ref().with_interceptors(interceptors);
         ProvideRef::connect_to_required(prov, req_share.clone());
         RequireRef::connect_to_provided(req, prov_share.clone());
         (prov_share, req_share)
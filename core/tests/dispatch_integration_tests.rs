@@ -1,5 +1,12 @@
 use crossbeam_channel::Receiver as Rcv;
-use kompact::{prelude::*, prelude_test::net_test_helpers::*};
+use kompact::{
+    prelude::*,
+    prelude_test::net_test_helpers::*,
+    routing::{
+        groups::RoundRobinRouting,
+        remote::{RemoteRouter, RemoteRouterMsg},
+    },
+};
 use std::{net::SocketAddr, sync::Arc, thread, time::Duration};
 
 const REGISTRATION_TIMEOUT: Duration = Duration::from_millis(1000);
@@ -217,6 +224,36 @@ impl NetworkStatusReceiver {
             }
         }
     }
+
+    fn expect_peer_throttled(&self, timeout: Duration) -> (ThrottledPeer, TrafficDirection) {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(NetworkStatus::PeerThrottled(peer, direction)) => (peer, direction),
+            Ok(other_status) => {
+                panic!(
+                    "unexpected network status {:?} waiting for PeerThrottled",
+                    other_status
+                )
+            }
+            Err(_) => {
+                panic!("ConnectionStatus timeout waiting for PeerThrottled")
+            }
+        }
+    }
+
+    fn expect_peer_unthrottled(&self, timeout: Duration) -> (ThrottledPeer, TrafficDirection) {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(NetworkStatus::PeerUnthrottled(peer, direction)) => (peer, direction),
+            Ok(other_status) => {
+                panic!(
+                    "unexpected network status {:?} waiting for PeerUnthrottled",
+                    other_status
+                )
+            }
+            Err(_) => {
+                panic!("ConnectionStatus timeout waiting for PeerUnthrottled")
+            }
+        }
+    }
 }
 
 #[test]
@@ -253,6 +290,125 @@ fn named_registration() {
         .expect("Kompact didn't shut down properly");
 }
 
+#[test]
+fn named_deregistration() {
+    const ACTOR_NAME: &str = "ponger";
+    let system = system_from_network_config(NetworkConfig::default());
+
+    let ponger = system.create(PongerAct::new_lazy);
+    system.start(&ponger);
+
+    let registered_path = system
+        .register_by_alias(&ponger, ACTOR_NAME)
+        .wait_expect(REGISTRATION_TIMEOUT, "Registration never completed.");
+
+    let removed_path = system
+        .deregister_alias(ACTOR_NAME)
+        .wait_expect(REGISTRATION_TIMEOUT, "Deregistration never completed.");
+    assert_eq!(registered_path, removed_path);
+
+    let res = system
+        .deregister_alias(ACTOR_NAME)
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Deregistration never completed.");
+    assert_eq!(
+        res,
+        Err(RegistrationError::NotFound),
+        "Repeated deregistration should fail."
+    );
+
+    // The alias is free again right away
+    system
+        .register_by_alias(&ponger, ACTOR_NAME)
+        .wait_expect(REGISTRATION_TIMEOUT, "Registration never completed.");
+
+    system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn unique_deregistration() {
+    let system = system_from_network_config(NetworkConfig::default());
+
+    let (ponger, ponger_path) = start_ponger(&system, PongerAct::new_lazy());
+
+    let removed_path = system
+        .deregister_unique(&ponger)
+        .wait_expect(REGISTRATION_TIMEOUT, "Deregistration never completed.");
+    assert_eq!(ponger_path, removed_path);
+
+    let res = system
+        .deregister_unique(&ponger)
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Deregistration never completed.");
+    assert_eq!(res, Err(RegistrationError::NotFound));
+
+    system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn routing_policy_removal() {
+    use kompact::routing::groups::BroadcastRouting;
+
+    let system = system_from_network_config(NetworkConfig::default());
+
+    let ponger = system.create(PongerAct::new_lazy);
+    system.start(&ponger);
+
+    let policy_path = system
+        .set_routing_policy(BroadcastRouting, "group", false)
+        .wait_expect(REGISTRATION_TIMEOUT, "Policy registration never completed.");
+    system
+        .register_by_alias(&ponger, "group/ponger")
+        .wait_expect(REGISTRATION_TIMEOUT, "Registration never completed.");
+
+    // Policies and actors are only removed by the matching call
+    let res = system
+        .deregister_alias("group")
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Deregistration never completed.");
+    assert_eq!(res, Err(RegistrationError::NotFound));
+    let res = system
+        .remove_routing_policy("group/ponger")
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Policy removal never completed.");
+    assert_eq!(res, Err(RegistrationError::NotFound));
+
+    let removed_path = system
+        .remove_routing_policy("group")
+        .wait_expect(REGISTRATION_TIMEOUT, "Policy removal never completed.");
+    assert_eq!(policy_path, removed_path);
+
+    // The member stays registered
+    let res = system
+        .register_by_alias(&ponger, "group/ponger")
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Registration never completed.");
+    assert_eq!(res, Err(RegistrationError::DuplicateEntry));
+
+    system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
 // Sets up two KompactSystems with 2x Pingers and Pongers. One Ponger is registered by UUID,
 // the other by a custom name. One Pinger communicates with the UUID-registered Ponger,
 // the other with the named Ponger. Both sets are expected to exchange PING_COUNT ping-pong
@@ -355,6 +511,102 @@ fn remote_delivery_bigger_than_buffer_messages_lazy_tcp() {
         .expect("Kompact didn't shut down properly");
 }
 
+#[test]
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+// Same as remote_delivery_bigger_than_buffer_messages_lazy_tcp, but with both systems on io_uring
+fn remote_delivery_bigger_than_buffer_messages_io_uring() {
+    let mut buf_cfg = BufferConfig::default();
+    buf_cfg.chunk_size(SMALL_CHUNK_SIZE);
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_buffer_config(buf_cfg);
+    net_cfg.set_backend(NetworkBackend::IoUring);
+    let ponger_system = system_from_network_config(net_cfg.clone());
+    let pinger_system = system_from_network_config(net_cfg);
+    // Make sure neither system silently fell back to mio
+    assert_eq!(
+        ponger_system.network_backend(),
+        Some(NetworkBackend::IoUring)
+    );
+    assert_eq!(
+        pinger_system.network_backend(),
+        Some(NetworkBackend::IoUring)
+    );
+
+    let (ponger, ponger_path) = start_big_ponger(&ponger_system, BigPongerAct::new_lazy());
+
+    let (pinger, all_pongs_received_future) = start_big_pinger(
+        &pinger_system,
+        BigPingerAct::new_lazy(ponger_path, SMALL_CHUNK_SIZE),
+    );
+
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Should complete");
+
+    pinger_system
+        .stop_notify(&pinger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Pinger never stopped!");
+    ponger_system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger never died!");
+
+    pinger.on_definition(|c| {
+        assert_eq!(c.count, PING_COUNT);
+    });
+
+    pinger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+// Pings from a system on io_uring to one on mio, so both backends have to agree on the framing
+fn remote_delivery_between_io_uring_and_mio() {
+    let mut uring_cfg = NetworkConfig::default();
+    uring_cfg.set_backend(NetworkBackend::IoUring);
+    let pinger_system = system_from_network_config(uring_cfg);
+    let ponger_system = system_from_network_config(NetworkConfig::default());
+    assert_eq!(
+        pinger_system.network_backend(),
+        Some(NetworkBackend::IoUring)
+    );
+    assert_eq!(ponger_system.network_backend(), Some(NetworkBackend::Mio));
+
+    let (ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_eager());
+    let (pinger, all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_eager(ponger_path));
+
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+
+    pinger_system
+        .stop_notify(&pinger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Pinger never stopped!");
+    ponger_system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger never died!");
+
+    pinger.on_definition(|c| {
+        assert_eq!(c.count, PING_COUNT);
+    });
+
+    pinger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
 #[test]
 // Sets up two KompactSystems, one with a BigPinger and one with a BigPonger.
 // BigPonger will validate the BigPing messages on reception, BigPinger counts replies
@@ -1331,73 +1583,227 @@ fn network_status_port_close_connection_closed_connection() {
 }
 
 #[test]
-fn network_status_port_open_close_open() {
-    let mut net_cfg = NetworkConfig::default();
-    net_cfg.set_max_connection_retry_attempts(2);
-    net_cfg.set_connection_retry_interval(1000);
-    let initiator_system = system_from_network_config(net_cfg.clone());
-    let receiver_system = system_from_network_config(net_cfg);
+fn network_status_port_idle_connection_closed() {
+    let mut pinger_cfg = NetworkConfig::default();
+    pinger_cfg.set_idle_timeout(Some(500));
+    let ponger_system = system_from_network_config(NetworkConfig::default());
+    let pinger_system = system_from_network_config(pinger_cfg);
 
-    let system_path = receiver_system.system_path();
-    // Create a status_counter which will listen to the status port and count messages received
-    let (status_counter, status_receiver) = start_status_counter(&initiator_system);
+    let (_, ponger_status_receiver) = start_status_counter(&ponger_system);
+    let (pinger_system_status_counter, pinger_status_receiver) =
+        start_status_counter(&pinger_system);
 
-    status_counter.on_definition(|sc| {
-        sc.send_status_request(NetworkStatusRequest::ConnectSystem(system_path.clone()));
-    });
-    status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    let (_, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
+    let (_, all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_lazy(ponger_path.clone()));
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
 
-    status_counter.on_definition(|sc| {
-        assert_eq!(sc.connection_established, 1);
-        assert_eq!(sc.connection_closed, 0);
-        sc.send_status_request(NetworkStatusRequest::DisconnectSystem(system_path.clone()));
-    });
-    status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
-    status_counter.on_definition(|sc| {
-        assert_eq!(sc.connection_established, 1);
-        assert_eq!(sc.connection_closed, 1);
-        sc.send_status_request(NetworkStatusRequest::ConnectSystem(system_path.clone()));
-    });
-    status_receiver.expect_connection_established(DROP_CONNECTION_TIMEOUT);
-    status_counter.on_definition(|sc| {
-        assert_eq!(sc.connection_established, 2);
+    ponger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+
+    // Nothing is sent anymore, so the pinger closes the connection
+    ponger_status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
+    pinger_status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
+    pinger_system_status_counter.on_definition(|sc| {
         assert_eq!(sc.connection_closed, 1);
-        assert_eq!(sc.connected_systems[0], sc.disconnected_systems[0]);
-        assert_ne!(sc.connected_systems[0], sc.connected_systems[1]);
     });
 
-    initiator_system
+    // The connection is re-established for new messages
+    let (_, all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_lazy(ponger_path));
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+
+    ponger_system
         .shutdown()
         .expect("Kompact didn't shut down properly");
-    receiver_system
+    pinger_system
         .shutdown()
         .expect("Kompact didn't shut down properly");
 }
 
 #[test]
-fn network_status_port_block_unblock_system() {
-    let mut net_cfg = NetworkConfig::default();
-    net_cfg.set_max_connection_retry_attempts(CONNECTION_RETRY_ATTEMPTS);
-    net_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
-
-    let ponger_system = system_from_network_config(net_cfg.clone());
-    let pinger_system = system_from_network_config(net_cfg);
-
-    // Create a status_counter which will listen to the status port and count messages received
-    let (status_counter, ponger_status_receiver) = start_status_counter(&ponger_system);
-    let (_, pinger_status_receiver) = start_status_counter(&pinger_system);
-
-    let (ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
+fn network_status_port_max_connections_evicts_idlest() {
+    let mut ponger_cfg = NetworkConfig::default();
+    ponger_cfg.set_max_connections(Some(1));
+    ponger_cfg.set_connection_limit_policy(ConnectionLimitPolicy::EvictIdlest);
+    let ponger_system = system_from_network_config(ponger_cfg);
+    let first_pinger_system = system_from_network_config(NetworkConfig::default());
+    let second_pinger_system = system_from_network_config(NetworkConfig::default());
 
-    let pinger = start_ping_stream(&pinger_system, &ponger_path);
+    let (ponger_system_status_counter, ponger_status_receiver) =
+        start_status_counter(&ponger_system);
+    let (_, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
 
+    let (_, all_pongs_received_future) = start_pinger(
+        &first_pinger_system,
+        PingerAct::new_lazy(ponger_path.clone()),
+    );
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
     ponger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
-    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
 
-    let pinger_sys_path = pinger_system.system_path();
-    status_counter.on_definition(|sc| {
-        sc.send_status_request(NetworkStatusRequest::BlockSystem(pinger_sys_path.clone()));
-    });
+    // The second connection pushes out the first one
+    let (_, all_pongs_received_future) =
+        start_pinger(&second_pinger_system, PingerAct::new_lazy(ponger_path));
+    ponger_status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
+    ponger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+
+    let first_pinger_sys_path = first_pinger_system.system_path();
+    ponger_system_status_counter.on_definition(|sc| {
+        assert_eq!(sc.connection_closed, 1);
+        assert!(sc
+            .disconnected_systems
+            .iter()
+            .any(|(path, _)| path == &first_pinger_sys_path));
+    });
+
+    ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    first_pinger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    second_pinger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn network_status_port_max_connections_rejects() {
+    let mut pinger_cfg = NetworkConfig::default();
+    pinger_cfg.set_max_connections(Some(1));
+    pinger_cfg.set_connection_limit_policy(ConnectionLimitPolicy::Reject);
+    pinger_cfg.set_max_connection_retry_attempts(2);
+    pinger_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
+    let pinger_system = system_from_network_config(pinger_cfg);
+    let first_ponger_system = system_from_network_config(NetworkConfig::default());
+    let second_ponger_system = system_from_network_config(NetworkConfig::default());
+
+    let (pinger_system_status_counter, pinger_status_receiver) =
+        start_status_counter(&pinger_system);
+    let (_, first_ponger_path) = start_ponger(&first_ponger_system, PongerAct::new_lazy());
+    let (_, second_ponger_path) = start_ponger(&second_ponger_system, PongerAct::new_lazy());
+
+    // Close a first connection to the second system, so it is re-requested later
+    let (_, all_pongs_received_future) = start_pinger(
+        &pinger_system,
+        PingerAct::new_lazy(second_ponger_path.clone()),
+    );
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    let second_system_path = second_ponger_system.system_path();
+    pinger_system_status_counter.on_definition(|sc| {
+        sc.send_status_request(NetworkStatusRequest::DisconnectSystem(second_system_path));
+    });
+    pinger_status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
+
+    let (_, all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_lazy(first_ponger_path));
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+
+    // Reconnecting is refused until the dispatcher gives up on the queued messages
+    let (_, _all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_lazy(second_ponger_path));
+    pinger_status_receiver.expect_connection_dropped(DROP_CONNECTION_TIMEOUT);
+    pinger_system_status_counter.on_definition(|sc| {
+        assert_eq!(sc.connection_established, 2);
+        assert_eq!(sc.connection_closed, 1);
+        assert_eq!(sc.connection_dropped, 1);
+    });
+
+    pinger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    first_ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    second_ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn network_status_port_open_close_open() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_max_connection_retry_attempts(2);
+    net_cfg.set_connection_retry_interval(1000);
+    let initiator_system = system_from_network_config(net_cfg.clone());
+    let receiver_system = system_from_network_config(net_cfg);
+
+    let system_path = receiver_system.system_path();
+    // Create a status_counter which will listen to the status port and count messages received
+    let (status_counter, status_receiver) = start_status_counter(&initiator_system);
+
+    status_counter.on_definition(|sc| {
+        sc.send_status_request(NetworkStatusRequest::ConnectSystem(system_path.clone()));
+    });
+    status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+
+    status_counter.on_definition(|sc| {
+        assert_eq!(sc.connection_established, 1);
+        assert_eq!(sc.connection_closed, 0);
+        sc.send_status_request(NetworkStatusRequest::DisconnectSystem(system_path.clone()));
+    });
+    status_receiver.expect_connection_closed(CONNECTION_STATUS_TIMEOUT);
+    status_counter.on_definition(|sc| {
+        assert_eq!(sc.connection_established, 1);
+        assert_eq!(sc.connection_closed, 1);
+        sc.send_status_request(NetworkStatusRequest::ConnectSystem(system_path.clone()));
+    });
+    status_receiver.expect_connection_established(DROP_CONNECTION_TIMEOUT);
+    status_counter.on_definition(|sc| {
+        assert_eq!(sc.connection_established, 2);
+        assert_eq!(sc.connection_closed, 1);
+        assert_eq!(sc.connected_systems[0], sc.disconnected_systems[0]);
+        assert_ne!(sc.connected_systems[0], sc.connected_systems[1]);
+    });
+
+    initiator_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    receiver_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn network_status_port_block_unblock_system() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_max_connection_retry_attempts(CONNECTION_RETRY_ATTEMPTS);
+    net_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
+
+    let ponger_system = system_from_network_config(net_cfg.clone());
+    let pinger_system = system_from_network_config(net_cfg);
+
+    // Create a status_counter which will listen to the status port and count messages received
+    let (status_counter, ponger_status_receiver) = start_status_counter(&ponger_system);
+    let (_, pinger_status_receiver) = start_status_counter(&pinger_system);
+
+    let (ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
+
+    let pinger = start_ping_stream(&pinger_system, &ponger_path);
+
+    ponger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    pinger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+
+    let pinger_sys_path = pinger_system.system_path();
+    status_counter.on_definition(|sc| {
+        sc.send_status_request(NetworkStatusRequest::BlockSystem(pinger_sys_path.clone()));
+    });
 
     ponger_status_receiver.expect_blocked_system(CONNECTION_STATUS_TIMEOUT);
     pinger_status_receiver.expect_connection_lost(CONNECTION_STATUS_TIMEOUT);
@@ -1505,6 +1911,59 @@ fn network_status_port_block_unblock_ip() {
     let _ = pinger_system.shutdown();
 }
 
+#[test]
+fn network_status_port_inbound_rate_limit() {
+    let mut limit = RateLimit::new(RateLimitScope::System, 4, 0);
+    limit.burst(1, 0);
+    let mut ponger_cfg = NetworkConfig::default();
+    ponger_cfg.set_inbound_rate_limit(Some(limit));
+
+    let ponger_system = system_from_network_config(ponger_cfg);
+    let pinger_system = system_from_network_config(NetworkConfig::default());
+
+    let (_, ponger_status_receiver) = start_status_counter(&ponger_system);
+    let (ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_eager());
+    let (pinger, all_pongs_received_future) =
+        start_pinger(&pinger_system, PingerAct::new_eager(ponger_path));
+
+    ponger_status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    let pinger_sys_path = pinger_system.system_path();
+    assert_eq!(
+        ponger_status_receiver.expect_peer_throttled(CONNECTION_STATUS_TIMEOUT),
+        (
+            ThrottledPeer::System(pinger_sys_path.clone()),
+            TrafficDirection::Inbound
+        )
+    );
+
+    // Delayed pings are still delivered, just not all at once
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    pinger.on_definition(|c| {
+        assert_eq!(c.count, PING_COUNT);
+    });
+    assert_eq!(
+        ponger_status_receiver.expect_peer_unthrottled(CONNECTION_STATUS_TIMEOUT),
+        (
+            ThrottledPeer::System(pinger_sys_path),
+            TrafficDirection::Inbound
+        )
+    );
+
+    pinger_system
+        .kill_notify(pinger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Pinger never stopped!");
+    ponger_system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger never died!");
+
+    let _ = ponger_system.shutdown();
+    let _ = pinger_system.shutdown();
+}
+
 #[test]
 // Sets up three KompactSystems: One with a BigPonger, one with a BigPinger with big pings
 // and one with a BigPinger with small pings. The big Pings are sent first and occupies
@@ -1585,3 +2044,676 @@ fn remote_delivery_overflow_network_thread_buffers() {
         .shutdown()
         .expect("Kompact didn't shut down properly");
 }
+
+const WATCH_TIMEOUT: Duration = Duration::from_millis(10_000);
+
+fn start_watcher(system: &KompactSystem, target: ActorPath) -> KFuture<ActorPath> {
+    let mut watcher_actor = WatcherAct::new(target);
+    let terminated_future = watcher_actor.terminated_future();
+    let (watcher, reg_future) = system.create_and_register(move || watcher_actor);
+    reg_future.wait_expect(REGISTRATION_TIMEOUT, "Watcher failed to register!");
+    system.start(&watcher);
+    terminated_future
+}
+
+#[test]
+fn remote_watch_notifies_when_actor_is_killed() {
+    let watcher_system = system_from_network_config(NetworkConfig::default());
+    let ponger_system = system_from_network_config(NetworkConfig::default());
+
+    let (ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
+    let terminated_future = start_watcher(&watcher_system, ponger_path.clone());
+    // Make sure the watch request has arrived before killing the target
+    thread::sleep(PING_INTERVAL);
+
+    ponger_system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+    let terminated = terminated_future
+        .wait_timeout(WATCH_TIMEOUT)
+        .expect("Watcher was not notified");
+    assert_eq!(terminated, ponger_path);
+
+    watcher_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn local_watch_notifies_as_soon_as_actor_is_killed() {
+    // Well below the reaper interval, so the notification must come from the kill itself
+    const LOCAL_WATCH_TIMEOUT: Duration = Duration::from_millis(250);
+    let system = system_from_network_config(NetworkConfig::default());
+
+    let (ponger, ponger_path) = start_ponger(&system, PongerAct::new_lazy());
+    let terminated_future = start_watcher(&system, ponger_path.clone());
+    // Make sure the watch request has arrived before killing the target
+    thread::sleep(PING_INTERVAL);
+
+    system
+        .kill_notify(ponger)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+    let terminated = terminated_future
+        .wait_timeout(LOCAL_WATCH_TIMEOUT)
+        .expect("Watcher was not notified");
+    assert_eq!(terminated, ponger_path);
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn remote_watch_notifies_when_actor_is_not_registered() {
+    let watcher_system = system_from_network_config(NetworkConfig::default());
+    let ponger_system = system_from_network_config(NetworkConfig::default());
+
+    let missing_path: ActorPath = ponger_system
+        .system_path()
+        .into_named_with_string("missing")
+        .expect("named path")
+        .into();
+    let terminated = start_watcher(&watcher_system, missing_path.clone())
+        .wait_timeout(WATCH_TIMEOUT)
+        .expect("Watcher was not notified");
+    assert_eq!(terminated, missing_path);
+
+    watcher_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    ponger_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn remote_watch_notifies_when_system_is_unreachable() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_max_connection_retry_attempts(CONNECTION_RETRY_ATTEMPTS);
+    net_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
+    let watcher_system = system_from_network_config(net_cfg);
+    let ponger_system = system_from_network_config(NetworkConfig::default());
+
+    let (_ponger, ponger_path) = start_ponger(&ponger_system, PongerAct::new_lazy());
+    let terminated_future = start_watcher(&watcher_system, ponger_path.clone());
+    // Lost connections are only detected while there is traffic
+    let _ = start_ping_stream(&watcher_system, &ponger_path);
+    thread::sleep(PING_INTERVAL);
+
+    ponger_system
+        .kill_system()
+        .expect("Kompact didn't die properly");
+    let terminated = terminated_future
+        .wait_timeout(DROP_CONNECTION_TIMEOUT + WATCH_TIMEOUT)
+        .expect("Watcher was not notified");
+    assert_eq!(terminated, ponger_path);
+
+    watcher_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+const IDENTIFY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+#[test]
+fn remote_identify() {
+    let system = system_from_network_config(NetworkConfig::default());
+    let remote_system = system_from_network_config(NetworkConfig::default());
+
+    let (ponger1, ponger1_path) = start_ponger(&remote_system, PongerAct::new_lazy());
+    let (ponger2, ponger2_path) = start_ponger(&remote_system, PongerAct::new_lazy());
+    let named_path = remote_system
+        .register_by_alias(&ponger1, "pongers/1")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+    remote_system
+        .register_by_alias(&ponger2, "pongers/2")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+
+    let identify = |path: ActorPath| {
+        system
+            .identify(path)
+            .wait_timeout(IDENTIFY_TIMEOUT)
+            .expect("Identify never completed")
+    };
+    let remote_path = |path: &str| -> ActorPath {
+        remote_system
+            .system_path()
+            .into_named_with_string(path)
+            .expect("named path")
+            .into()
+    };
+
+    assert_eq!(
+        identify(named_path),
+        ActorIdentity::Actor(ponger1_path.clone())
+    );
+    assert_eq!(
+        identify(ponger2_path.clone()),
+        ActorIdentity::Actor(ponger2_path.clone())
+    );
+    assert_eq!(identify(remote_path("missing")), ActorIdentity::NotFound);
+
+    let broadcast = identify(remote_path("pongers/*"));
+    assert_eq!(broadcast.member_count(), 2);
+    assert!(broadcast.unique_paths().contains(&ponger1_path));
+    assert!(broadcast.unique_paths().contains(&ponger2_path));
+    assert_eq!(identify(remote_path("pongers/?")).member_count(), 2);
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn remote_identify_fails_when_unanswered() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_identify_timeout(500);
+    let system = system_from_network_config(net_cfg);
+
+    // Nothing is listening there, so the connection is retried for much longer than the timeout
+    let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port");
+    let path: ActorPath = SystemPath::with_socket(Transport::Tcp, free_port)
+        .into_named_with_string("missing")
+        .expect("named path")
+        .into();
+    let start = std::time::Instant::now();
+    system
+        .identify(path)
+        .wait_timeout(IDENTIFY_TIMEOUT)
+        .expect_err("Identify should fail");
+    assert!(start.elapsed() < IDENTIFY_TIMEOUT, "Identify never failed");
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn remote_list_paths() {
+    use kompact::routing::groups::BroadcastRouting;
+
+    let system = system_from_network_config(NetworkConfig::default());
+    let remote_system = system_from_network_config(NetworkConfig::default());
+
+    let (ponger1, _) = start_ponger(&remote_system, PongerAct::new_lazy());
+    let (ponger2, _) = start_ponger(&remote_system, PongerAct::new_lazy());
+    remote_system
+        .set_routing_policy(BroadcastRouting, "pongers", false)
+        .wait_expect(REGISTRATION_TIMEOUT, "Policy registration never completed.");
+    remote_system
+        .register_by_alias(&ponger1, "pongers/1")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+    remote_system
+        .register_by_alias(&ponger2, "pongers/2")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+    remote_system
+        .register_by_alias(&ponger1, "other")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+
+    let list = |path: &str| -> Vec<RegisteredPath> {
+        let prefix = remote_system
+            .system_path()
+            .into_named_with_string(path)
+            .expect("named path");
+        system
+            .list_paths(prefix)
+            .wait_timeout(IDENTIFY_TIMEOUT)
+            .expect("Listing never completed")
+    };
+    let entry = |path: &str, entry: PathEntry| RegisteredPath {
+        path: remote_system
+            .system_path()
+            .into_named_with_string(path)
+            .expect("named path"),
+        entry,
+    };
+
+    assert_eq!(
+        list("pongers"),
+        vec![
+            entry("pongers", PathEntry::Policy),
+            entry("pongers/1", PathEntry::Actor(ponger1.id())),
+            entry("pongers/2", PathEntry::Actor(ponger2.id())),
+        ]
+    );
+    assert_eq!(
+        list("pongers/2"),
+        vec![entry("pongers/2", PathEntry::Actor(ponger2.id()))]
+    );
+    assert!(list("missing").is_empty());
+
+    let root = remote_system
+        .system_path()
+        .into_named_with_vec(Vec::new())
+        .expect("named path");
+    let all = system
+        .list_paths(root)
+        .wait_timeout(IDENTIFY_TIMEOUT)
+        .expect("Listing never completed");
+    assert!(all.contains(&entry("other", PathEntry::Actor(ponger1.id()))));
+    assert!(all.contains(&entry("pongers/1", PathEntry::Actor(ponger1.id()))));
+    // The dispatcher's alias is reserved for the system
+    assert!(all
+        .iter()
+        .all(|registered| !registered.path.path_ref()[0].starts_with('$')));
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+fn start_remote_router(
+    system: &KompactSystem,
+    members: Vec<ActorPath>,
+) -> (Arc<Component<RemoteRouter>>, ActorPath) {
+    let router = system.create(move || {
+        RemoteRouter::new(RoundRobinRouting::new(), members)
+            .with_redial_backoff(PING_INTERVAL / 5, PING_INTERVAL)
+    });
+    router.on_definition(|c| system.connect_network_status_port(&mut c.network_status_port));
+    let router_path = system
+        .register(&router)
+        .wait_expect(REGISTRATION_TIMEOUT, "Router failed to register!");
+    system
+        .start_notify(&router)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Router never started!");
+    (router, router_path)
+}
+
+fn active_members(router: &Arc<Component<RemoteRouter>>) -> Vec<ActorPath> {
+    router
+        .actor_ref()
+        .ask_with(RemoteRouterMsg::ActiveMembers)
+        .wait_timeout(REGISTRATION_TIMEOUT)
+        .expect("Router never replied")
+}
+
+#[test]
+fn remote_router_round_robin() {
+    let system = system_from_network_config(NetworkConfig::default());
+    let remote_system_1 = system_from_network_config(NetworkConfig::default());
+    let remote_system_2 = system_from_network_config(NetworkConfig::default());
+
+    let (ponger_1, ponger_1_path) = start_ponger(&remote_system_1, PongerAct::new_lazy());
+    let (ponger_2, ponger_2_path) = start_ponger(&remote_system_2, PongerAct::new_lazy());
+    let (_router, router_path) = start_remote_router(&system, vec![ponger_1_path, ponger_2_path]);
+
+    // the pongers reply directly to the pinger, bypassing the router
+    let (_pinger, all_pongs_received_future) =
+        start_pinger(&system, PingerAct::new_lazy(router_path));
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+
+    ponger_1.on_definition(|c| assert_eq!(c.count, PING_COUNT / 2));
+    ponger_2.on_definition(|c| assert_eq!(c.count, PING_COUNT / 2));
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system_1
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system_2
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+#[ignore]
+// Routes over pongers on two remote systems, kills one of them and checks that its ponger
+// is no longer routed to once the connection is dropped. Then boots a new system with an
+// identical network config and named registration, and checks that the router reconnects
+// on its own and routes to the ponger again.
+fn remote_router_drops_and_readds_members() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_max_connection_retry_attempts(CONNECTION_RETRY_ATTEMPTS);
+    net_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
+    let system = system_from_network_config(net_cfg);
+    let stable_system = system_from_network_config(NetworkConfig::default());
+    let failing_system_1 = system_from_network_config(NetworkConfig::default());
+    let failing_system_port = failing_system_1.system_path().port();
+    let (_status_counter, status_receiver) = start_status_counter(&system);
+
+    let (_stable_ponger, stable_path) = start_ponger(&stable_system, PongerAct::new_lazy());
+    let (failing_ponger, _) = start_ponger(&failing_system_1, PongerAct::new_lazy());
+    let failing_path: ActorPath = failing_system_1
+        .register_by_alias(&failing_ponger, "custom_name")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+
+    let (router, router_path) =
+        start_remote_router(&system, vec![stable_path.clone(), failing_path.clone()]);
+    // PingStream ensures that the network layer detects failures
+    let _ping_stream = start_ping_stream(&system, &router_path);
+    status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    assert_eq!(
+        active_members(&router),
+        vec![stable_path.clone(), failing_path.clone()]
+    );
+
+    failing_system_1.kill_system().ok();
+    status_receiver.expect_connection_lost(CONNECTION_STATUS_TIMEOUT);
+    status_receiver.expect_connection_dropped(DROP_CONNECTION_TIMEOUT);
+    thread::sleep(PING_INTERVAL);
+    assert_eq!(active_members(&router), vec![stable_path.clone()]);
+
+    let (_pinger_1, all_pongs_received_future_1) =
+        start_pinger(&system, PingerAct::new_lazy(router_path.clone()));
+    all_pongs_received_future_1
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+
+    let mut addr: SocketAddr = "127.0.0.1:0".parse().expect("Address should work");
+    addr.set_port(failing_system_port);
+    let failing_system_2 = system_from_network_config(NetworkConfig::new(addr));
+    let (failing_ponger, _) = start_ponger(&failing_system_2, PongerAct::new_lazy());
+    failing_system_2
+        .register_by_alias(&failing_ponger, "custom_name")
+        .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+    status_receiver.expect_connection_established(CONNECTION_STATUS_TIMEOUT);
+    thread::sleep(PING_INTERVAL);
+    assert_eq!(active_members(&router), vec![stable_path, failing_path]);
+
+    let (_pinger_2, all_pongs_received_future_2) =
+        start_pinger(&system, PingerAct::new_lazy(router_path));
+    all_pongs_received_future_2
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    failing_ponger.on_definition(|c| assert!(c.count > 0));
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    stable_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    failing_system_2
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn remote_delivery_with_consistent_hash_policy() {
+    use kompact::routing::groups::SenderDefaultConsistentHashRouting;
+    const GROUP_SIZE: usize = 3;
+
+    let system = system_from_network_config(NetworkConfig::default());
+    let remote_system = system_from_network_config(NetworkConfig::default());
+
+    let group_path = remote_system
+        .set_routing_policy(
+            SenderDefaultConsistentHashRouting::default(),
+            "group",
+            false,
+        )
+        .wait_expect(REGISTRATION_TIMEOUT, "Policy registration never completed.");
+    let pongers: Vec<Arc<Component<PongerAct>>> = (0..GROUP_SIZE)
+        .map(|i| {
+            let (ponger, _) = start_ponger(&remote_system, PongerAct::new_lazy());
+            remote_system
+                .register_by_alias(&ponger, format!("group/ponger{}", i))
+                .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!");
+            ponger
+        })
+        .collect();
+
+    // all messages from the same sender end up at the same member
+    let (_pinger, all_pongs_received_future) =
+        start_pinger(&system, PingerAct::new_lazy(group_path));
+    all_pongs_received_future
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for ping pong to complete");
+    let counts: Vec<u64> = pongers
+        .iter()
+        .map(|ponger| ponger.on_definition(|c| c.count))
+        .collect();
+    assert_eq!(counts.iter().sum::<u64>(), PING_COUNT);
+    assert!(counts.contains(&PING_COUNT));
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+/// Replies to every `u64` with the same number
+#[derive(ComponentDefinition)]
+struct EchoAct {
+    ctx: ComponentContext<Self>,
+}
+impl EchoAct {
+    fn new() -> Self {
+        EchoAct {
+            ctx: ComponentContext::uninitialised(),
+        }
+    }
+}
+ignore_lifecycle!(EchoAct);
+impl Actor for EchoAct {
+    type Message = Never;
+
+    fn receive_local(&mut self, _msg: Self::Message) -> Handled {
+        unimplemented!();
+    }
+
+    fn receive_network(&mut self, msg: NetMessage) -> Handled {
+        let sender = msg.sender.clone();
+        match msg.try_deserialise::<u64, u64>() {
+            Ok(num) => sender.tell(num, self),
+            Err(e) => error!(self.log(), "Error deserialising u64: {:?}", e),
+        }
+        Handled::Ok
+    }
+}
+
+#[test]
+fn remote_scatter_gather() {
+    use kompact::routing::{groups::BroadcastRouting, scatter_gather::GatherPolicy};
+    const GROUP_SIZE: usize = 3;
+    const GATHER_TIMEOUT: Duration = Duration::from_millis(2000);
+
+    let system = system_from_network_config(NetworkConfig::default());
+    let remote_system = system_from_network_config(NetworkConfig::default());
+
+    let group_path = remote_system
+        .set_routing_policy(BroadcastRouting, "group", false)
+        .wait_expect(REGISTRATION_TIMEOUT, "Policy registration never completed.");
+    let echoes: Vec<Arc<Component<EchoAct>>> = (0..GROUP_SIZE)
+        .map(|i| {
+            let echo = remote_system.create(EchoAct::new);
+            remote_system
+                .register_by_alias(&echo, format!("group/echo{}", i))
+                .wait_expect(REGISTRATION_TIMEOUT, "Echo failed to register!");
+            echo
+        })
+        .collect();
+    // the first echo is never started, so it can't answer
+    echoes[1..].iter().for_each(|e| remote_system.start(e));
+    let silent_path: ActorPath = remote_system
+        .system_path()
+        .into_named_with_string("group/echo0")
+        .expect("path")
+        .into();
+
+    let gathered = system
+        .scatter_gather(group_path.clone(), 1u64, GatherPolicy::All, GATHER_TIMEOUT)
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for the gathered replies");
+    assert!(!gathered.complete);
+    assert_eq!(gathered.missing, vec![silent_path]);
+    assert_eq!(gathered.replies.len(), GROUP_SIZE - 1);
+    for (_, reply) in gathered.replies {
+        assert_eq!(reply.try_deserialise::<u64, u64>().expect("u64"), 1);
+    }
+
+    let broadcast_path: ActorPath = (group_path.unwrap_named() / '*').into();
+    let gathered = system
+        .scatter_gather(broadcast_path, 2u64, GatherPolicy::First, GATHER_TIMEOUT)
+        .wait_timeout(PINGPONG_TIMEOUT)
+        .expect("Time out waiting for the gathered replies");
+    assert!(gathered.complete);
+    assert_eq!(gathered.replies.len(), 1);
+    assert_eq!(gathered.missing.len(), GROUP_SIZE - 1);
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    remote_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+fn start_membership_watcher(
+    system: &KompactSystem,
+    prefix: NamedPath,
+) -> Arc<Component<MembershipWatcherAct>> {
+    let (watcher, reg_future) =
+        system.create_and_register(move || MembershipWatcherAct::new(prefix));
+    reg_future.wait_expect(REGISTRATION_TIMEOUT, "Watcher failed to register!");
+    system.start(&watcher);
+    watcher
+}
+
+/// Waits until the watcher knows about exactly `expected` members and returns them
+fn wait_for_members(
+    watcher: &Arc<Component<MembershipWatcherAct>>,
+    expected: usize,
+    timeout: Duration,
+) -> Vec<NamedPath> {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        let members = watcher.on_definition(|c| c.members.clone());
+        if members.len() == expected {
+            return members;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "Expected {} members, but got {:?}",
+            expected,
+            members
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn register_members(
+    system: &KompactSystem,
+    names: &[&str],
+) -> Vec<(Arc<Component<PongerAct>>, NamedPath)> {
+    names
+        .iter()
+        .map(|name| {
+            let (ponger, _) = start_ponger(system, PongerAct::new_lazy());
+            let path = system
+                .register_by_alias(&ponger, *name)
+                .wait_expect(REGISTRATION_TIMEOUT, "Ponger failed to register!")
+                .unwrap_named();
+            (ponger, path)
+        })
+        .collect()
+}
+
+#[test]
+fn membership_watch_follows_registrations() {
+    let system = system_from_network_config(NetworkConfig::default());
+    let group_system = system_from_network_config(NetworkConfig::default());
+    let prefix = group_system
+        .system_path()
+        .into_named_with_string("group")
+        .expect("named path");
+    let local_prefix = system
+        .system_path()
+        .into_named_with_string("group")
+        .expect("named path");
+
+    let mut members = register_members(&group_system, &["group/a", "group/b", "other/c"]);
+    let remote_watcher = start_membership_watcher(&system, prefix.clone());
+    let local_watcher = start_membership_watcher(&group_system, prefix.clone());
+    // watching an empty group is fine
+    let empty_watcher = start_membership_watcher(&system, local_prefix);
+    for watcher in [&remote_watcher, &local_watcher] {
+        let known = wait_for_members(watcher, 2, WATCH_TIMEOUT);
+        assert_eq!(known, vec![members[0].1.clone(), members[1].1.clone()]);
+    }
+
+    members.extend(register_members(&group_system, &["group/sub/d"]));
+    for watcher in [&remote_watcher, &local_watcher] {
+        let known = wait_for_members(watcher, 3, WATCH_TIMEOUT);
+        assert_eq!(known[2], members[3].1);
+    }
+
+    group_system
+        .deregister_alias("group/a")
+        .wait_expect(REGISTRATION_TIMEOUT, "Deregistration failed");
+    let (killed, killed_path) = members.remove(1);
+    group_system
+        .kill_notify(killed)
+        .wait_timeout(STOP_COMPONENT_TIMEOUT)
+        .expect("Ponger did not die");
+    for watcher in [&remote_watcher, &local_watcher] {
+        let known = wait_for_members(watcher, 1, WATCH_TIMEOUT);
+        assert!(!known.contains(&killed_path));
+    }
+
+    // unwatching stops the notifications
+    local_watcher.on_definition(|c| c.ctx().unwatch_members(prefix.clone()));
+    let events = local_watcher.on_definition(|c| c.events);
+    register_members(&group_system, &["group/e"]);
+    wait_for_members(&remote_watcher, 2, WATCH_TIMEOUT);
+    thread::sleep(PING_INTERVAL);
+    assert_eq!(local_watcher.on_definition(|c| c.events), events);
+    assert_eq!(empty_watcher.on_definition(|c| c.events), 0);
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+    group_system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
+
+#[test]
+fn membership_watch_removes_members_of_unreachable_systems() {
+    let mut net_cfg = NetworkConfig::default();
+    net_cfg.set_max_connection_retry_attempts(CONNECTION_RETRY_ATTEMPTS);
+    net_cfg.set_connection_retry_interval(CONNECTION_RETRY_INTERVAL);
+    let system = system_from_network_config(net_cfg);
+    let group_system = system_from_network_config(NetworkConfig::default());
+    let prefix = group_system
+        .system_path()
+        .into_named_with_string("group")
+        .expect("named path");
+
+    let members = register_members(&group_system, &["group/a", "group/b"]);
+    let watcher = start_membership_watcher(&system, prefix);
+    wait_for_members(&watcher, 2, WATCH_TIMEOUT);
+    // Lost connections are only detected while there is traffic
+    let _ = start_ping_stream(&system, &members[0].1.clone().into());
+    thread::sleep(PING_INTERVAL);
+
+    group_system
+        .kill_system()
+        .expect("Kompact didn't die properly");
+    wait_for_members(&watcher, 0, DROP_CONNECTION_TIMEOUT + WATCH_TIMEOUT);
+
+    system
+        .shutdown()
+        .expect("Kompact didn't shut down properly");
+}
@@ -12,6 +12,7 @@ const DATA_SIZES: [usize; 4] = [1, 7, 100, 10000];
 const MAX_DEPTH: usize = 5;
 const NAME_PREFIX: &str = "node";
 const ROUTER_PREFIX: &str = "route-me";
+const RCU_PREFIX: &str = "rcu";
 
 pub fn insert_benches(c: &mut Criterion) {
     let mut g = c.benchmark_group("Inserts");
@@ -31,6 +32,24 @@ pub fn insert_benches(c: &mut Criterion) {
     g.finish();
 }
 
+pub fn rcu_insert_benches(c: &mut Criterion) {
+    let mut g = c.benchmark_group("RcuInserts");
+    for data_size in DATA_SIZES.iter() {
+        g.throughput(Throughput::Elements(1));
+        g.bench_with_input(
+            BenchmarkId::new("SequenceTrie", data_size),
+            data_size,
+            |b, &size| tests::bench_rcu_insert(b, sequence_store::ActorStore::new(), size),
+        );
+        g.bench_with_input(
+            BenchmarkId::new("PathTrie", data_size),
+            data_size,
+            |b, &size| tests::bench_rcu_insert(b, ActorStore::new(), size),
+        );
+    }
+    g.finish();
+}
+
 pub fn lookup_benches(c: &mut Criterion) {
     let mut g = c.benchmark_group("Lookups");
     for data_size in DATA_SIZES.iter() {
@@ -45,6 +64,11 @@ pub fn lookup_benches(c: &mut Criterion) {
             data_size,
             |b, &size| tests::bench_lookup(b, ActorStore::new(), size),
         );
+        g.bench_with_input(
+            BenchmarkId::new("PathBytes", data_size),
+            data_size,
+            |b, &size| tests::bench_bytes_lookup(b, size),
+        );
     }
     g.finish();
 }
@@ -157,6 +181,33 @@ mod tests {
         data_set.system.shutdown().expect("shutdown");
     }
 
+    /// Copy-on-write insert of a single new path into a store of `store_size` entries,
+    /// as done by the dispatcher for every registration
+    pub fn bench_rcu_insert<L>(b: &mut Bencher, mut store: L, store_size: usize)
+    where
+        L: ActorLookup,
+    {
+        let mut data_set = load_data(store_size);
+        for (path, aref) in data_set.paths.iter() {
+            let _ = store.insert(path.clone(), aref.clone());
+        }
+        let actor_ref = data_set.actors[0].actor_ref().dyn_ref();
+        let mut counter: usize = 0;
+        b.iter_with_large_drop(|| {
+            counter += 1;
+            let path = PathResolvable::Segments(vec![format!("{}{}", RCU_PREFIX, counter)]);
+            let mut next = store.clone();
+            let res = next.insert(path, actor_ref.clone());
+            let _ = black_box(res);
+            next
+        });
+        drop(actor_ref);
+        drop(store);
+        data_set.paths.clear();
+        data_set.actors.clear();
+        data_set.system.shutdown().expect("shutdown");
+    }
+
     pub fn bench_lookup<L>(b: &mut Bencher, mut store: L, store_size: usize)
     where
         L: ActorLookup,
@@ -188,6 +239,37 @@ mod tests {
         data_set.system.shutdown().expect("shutdown");
     }
 
+    /// Lookups on the serialised path bytes, as they arrive from the wire
+    pub fn bench_bytes_lookup(b: &mut Bencher, store_size: usize) {
+        let mut data_set = load_data(store_size);
+        let mut store = ActorStore::new();
+        for (path, aref) in data_set.paths.iter() {
+            let _ = store.insert(path.clone(), aref.clone());
+        }
+        let paths: Vec<Vec<u8>> = data_set
+            .paths
+            .iter()
+            .map(|(path, _)| {
+                if let PathResolvable::Segments(ref segments) = path {
+                    segments.join("/").into_bytes()
+                } else {
+                    unreachable!("We only put in segments!")
+                }
+            })
+            .collect();
+        b.iter(|| {
+            for path in paths.iter() {
+                let res = store.get_by_path_bytes(path);
+                let _ = black_box(res);
+            }
+        });
+        drop(paths);
+        drop(store);
+        data_set.paths.clear();
+        data_set.actors.clear();
+        data_set.system.shutdown().expect("shutdown");
+    }
+
     pub fn bench_group_lookup<L>(b: &mut Bencher, mut store: L, store_size: usize)
     where
         L: ActorLookup,
@@ -252,6 +334,7 @@ mod tests {
 criterion_group!(
     actor_store_benches,
     insert_benches,
+    rcu_insert_benches,
     lookup_benches,
     group_lookup_benches,
     cleanup_benches
@@ -270,6 +270,24 @@ impl ActorLookup for ActorStore {
         existed
     }
 
+    fn remove_actor_by_named_path(&mut self, path: &[String]) -> bool {
+        if let Some(ActorTreeEntry::Ref(_)) = self.name_map.get(path) {
+            self.name_map.remove(path);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_routing_policy(&mut self, path: &[String]) -> bool {
+        if let Some(ActorTreeEntry::Policy(_)) = self.name_map.get(path) {
+            self.name_map.remove(path);
+            true
+        } else {
+            false
+        }
+    }
+
     fn cleanup(&mut self) -> usize {
         self.remove_deallocated_entries()
     }
@@ -0,0 +1,103 @@
+#![recursion_limit = "128"]
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// A macro to derive [FsmState](FsmState) implementations for enums of states
+///
+/// Every variant of the enum is a declared state, and is named after the variant
+/// in transition logs. Only fieldless enums are supported.
+#[proc_macro_derive(FsmState)]
+pub fn fsm_state(input: TokenStream) -> TokenStream {
+    // Parse the input stream
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    // Build the impl
+    let gen = impl_fsm_state(&ast);
+
+    //println!("Derived code:\n{}", gen.clone().into_string());
+
+    // Return the generated impl
+    gen.into()
+}
+
+fn impl_fsm_state(ast: &syn::DeriveInput) -> TokenStream2 {
+    let name = &ast.ident;
+    if let syn::Data::Enum(ref data) = ast.data {
+        let variants: Vec<&syn::Ident> = data
+            .variants
+            .iter()
+            .map(|variant| {
+                if let syn::Fields::Unit = variant.fields {
+                    &variant.ident
+                } else {
+                    panic!("#[derive(FsmState)] is only defined for enums without fields!");
+                }
+            })
+            .collect();
+        let names: Vec<String> = variants.iter().map(|v| v.to_string()).collect();
+        let generics = &ast.generics;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics FsmState for #name #ty_generics #where_clause {
+
+                const STATES: &'static [Self] = &[#(#name::#variants),*];
+
+                fn name(&self) -> &'static str {
+                    match self {
+                        #(#name::#variants => #names),*
+                    }
+                }
+            }
+        }
+    } else {
+        panic!("#[derive(FsmState)] is only defined for enums, not for structs or unions!");
+    }
+}
+
+/// A macro to derive [Actor](Actor) implementations for [finite state machines](FiniteStateMachine)
+///
+/// The derived implementation dispatches local and network messages
+/// to the handlers of the machine's current state.
+#[proc_macro_derive(FsmActor)]
+pub fn fsm_actor(input: TokenStream) -> TokenStream {
+    // Parse the input stream
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    // Build the impl
+    let gen = impl_fsm_actor(&ast);
+
+    //println!("Derived code:\n{}", gen.clone().into_string());
+
+    // Return the generated impl
+    gen.into()
+}
+
+fn impl_fsm_actor(ast: &syn::DeriveInput) -> TokenStream2 {
+    let name = &ast.ident;
+    if let syn::Data::Struct(_) = ast.data {
+        let generics = &ast.generics;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics Actor for #name #ty_generics #where_clause {
+
+                type Message = <Self as FiniteStateMachine>::Message;
+
+                fn receive_local(&mut self, msg: <Self as FiniteStateMachine>::Message) -> Handled {
+                    FiniteStateMachine::fsm_receive_local(self, msg)
+                }
+
+                fn receive_network(&mut self, msg: NetMessage) -> Handled {
+                    FiniteStateMachine::fsm_receive_network(self, msg)
+                }
+            }
+        }
+    } else {
+        //Nope. This is an Enum. We cannot handle these!
+        panic!("#[derive(FsmActor)] is only defined for structs, not for enums!");
+    }
+}